@@ -1,9 +1,17 @@
 use crate::modules::{
-    AppCacheScanOptions, AppCacheScanProgress, AppCacheScanResult,
+    AppCacheScanOptions, AppCacheScanProgress, AppCacheScanResult, AppDefinitionList,
+    AppDefinitionRegistry, AppCacheFile, CleanCategory,
     start_app_cache_scan, get_app_cache_progress, get_app_cache_result,
     pause_app_cache_scan, resume_app_cache_scan, cancel_app_cache_scan,
     clear_app_cache_result,
 };
+use crate::modules::app_cache::export::{
+    self, ImageExportOptions, ImageExportProgress, ImageExportResult,
+};
+use crate::modules::app_cache::retention::{
+    self, RetentionApplyResult, RetentionConfig, RetentionPolicy, RetentionPreview,
+};
+use crate::modules::app_cache::{conversation_files, summarize_conversations, ConversationSummary};
 use tauri::{command, AppHandle};
 
 #[command]
@@ -52,3 +60,133 @@ pub async fn app_cache_scan_result(scan_id: String) -> Result<Option<AppCacheSca
 pub async fn app_cache_scan_clear(scan_id: String) -> Result<(), String> {
     clear_app_cache_result(&scan_id).await
 }
+
+#[command]
+pub async fn app_cache_definitions() -> Result<AppDefinitionList, String> {
+    Ok(AppDefinitionRegistry::load().to_list())
+}
+
+#[command]
+pub async fn app_cache_conversation_summary(
+    scan_id: String,
+) -> Result<Vec<ConversationSummary>, String> {
+    let result = get_app_cache_result(&scan_id)
+        .await
+        .ok_or_else(|| "未找到应用缓存扫描结果".to_string())?;
+    Ok(summarize_conversations(&result.files))
+}
+
+#[command]
+pub async fn app_cache_conversation_files(
+    scan_id: String,
+    app: String,
+    account_id: Option<String>,
+    conversation: String,
+    categories: Vec<String>,
+) -> Result<Vec<AppCacheFile>, String> {
+    let result = get_app_cache_result(&scan_id)
+        .await
+        .ok_or_else(|| "未找到应用缓存扫描结果".to_string())?;
+    let categories: Vec<CleanCategory> = categories
+        .iter()
+        .filter_map(|c| CleanCategory::from_str(c))
+        .collect();
+    Ok(conversation_files(
+        &result.files,
+        &app,
+        account_id.as_deref(),
+        &conversation,
+        &categories,
+    ))
+}
+
+#[command]
+pub async fn app_cache_retention_get_config() -> Result<RetentionConfig, String> {
+    Ok(RetentionConfig::load())
+}
+
+#[command]
+pub async fn app_cache_retention_save_config(config: RetentionConfig) -> Result<(), String> {
+    config.save()
+}
+
+/// 预览保留策略将处理的文件，给定 `scan_id` 时基于该次扫描结果，否则重新扫描；
+/// 未给定策略时使用已保存的策略
+#[command]
+pub async fn app_cache_retention_preview(
+    scan_id: Option<String>,
+    policies: Option<Vec<RetentionPolicy>>,
+) -> Result<RetentionPreview, String> {
+    let policies = policies.unwrap_or_else(|| RetentionConfig::load().policies);
+    let files = match scan_id {
+        Some(scan_id) => {
+            get_app_cache_result(&scan_id)
+                .await
+                .ok_or_else(|| "未找到应用缓存扫描结果".to_string())?
+                .files
+        }
+        None => {
+            let scan_policies = policies.clone();
+            tokio::task::spawn_blocking(move || retention::collect_policy_files(&scan_policies))
+                .await
+                .map_err(|e| e.to_string())?
+        }
+    };
+    Ok(retention::preview_retention(
+        &files,
+        &policies,
+        retention::now_millis(),
+    ))
+}
+
+/// 立即执行保留策略，未给定策略时使用已保存的策略
+#[command]
+pub async fn app_cache_retention_apply(
+    policies: Option<Vec<RetentionPolicy>>,
+) -> Result<Vec<RetentionApplyResult>, String> {
+    let policies = policies.unwrap_or_else(|| RetentionConfig::load().policies);
+    for policy in &policies {
+        policy.validate()?;
+    }
+    Ok(retention::run_retention(policies).await)
+}
+
+/// 把选中的文件解码后导出到指定目录，返回导出任务的 id
+#[command]
+pub async fn app_cache_export_start(
+    app: AppHandle,
+    destination: String,
+    files: Vec<AppCacheFile>,
+) -> Result<String, String> {
+    export::start_export(app, ImageExportOptions { destination, files }).await
+}
+
+#[command]
+pub async fn app_cache_export_pause(scan_id: String) -> Result<(), String> {
+    export::pause_export(&scan_id).await
+}
+
+#[command]
+pub async fn app_cache_export_resume(scan_id: String) -> Result<(), String> {
+    export::resume_export(&scan_id).await
+}
+
+#[command]
+pub async fn app_cache_export_cancel(scan_id: String) -> Result<(), String> {
+    export::cancel_export(&scan_id).await
+}
+
+#[command]
+pub async fn app_cache_export_progress(scan_id: String) -> Result<Option<ImageExportProgress>, String> {
+    Ok(export::get_progress(&scan_id).await)
+}
+
+#[command]
+pub async fn app_cache_export_result(scan_id: String) -> Result<Option<ImageExportResult>, String> {
+    Ok(export::get_result(&scan_id).await)
+}
+
+#[command]
+pub async fn app_cache_export_clear(scan_id: String) -> Result<(), String> {
+    export::clear_export(&scan_id).await
+}
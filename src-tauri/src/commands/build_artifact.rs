@@ -0,0 +1,44 @@
+//! 开发项目构建产物扫描命令
+
+use tauri::{command, AppHandle};
+use crate::modules::build_artifact::{
+    self, BuildArtifactScanOptions, BuildArtifactScanProgress, BuildArtifactScanResult,
+};
+
+#[command]
+pub async fn build_artifact_scan_start(
+    app: AppHandle,
+    options: Option<BuildArtifactScanOptions>,
+) -> Result<String, String> {
+    build_artifact::start_scan(app, options.unwrap_or_default()).await
+}
+
+#[command]
+pub async fn build_artifact_scan_pause(scan_id: String) -> Result<(), String> {
+    build_artifact::pause_scan(&scan_id).await
+}
+
+#[command]
+pub async fn build_artifact_scan_resume(scan_id: String) -> Result<(), String> {
+    build_artifact::resume_scan(&scan_id).await
+}
+
+#[command]
+pub async fn build_artifact_scan_cancel(scan_id: String) -> Result<(), String> {
+    build_artifact::cancel_scan(&scan_id).await
+}
+
+#[command]
+pub async fn build_artifact_scan_get_progress(scan_id: String) -> Result<Option<BuildArtifactScanProgress>, String> {
+    Ok(build_artifact::get_progress(&scan_id).await)
+}
+
+#[command]
+pub async fn build_artifact_scan_get_result(scan_id: String) -> Result<Option<BuildArtifactScanResult>, String> {
+    Ok(build_artifact::get_result(&scan_id).await)
+}
+
+#[command]
+pub async fn build_artifact_scan_clear(scan_id: String) -> Result<(), String> {
+    build_artifact::clear_scan(&scan_id).await
+}
@@ -7,15 +7,19 @@ use uuid::Uuid;
 use futures::future::BoxFuture;
 
 use crate::models::{
-    CleanOptions, CleanPreview, CleanProgress, CleanResult, CleanStatus,
+    CleanIoOptions, CleanOptions, CleanPreview, CleanProgress, CleanResult, CleanStatus,
     ProtectedFile, DiskTidyError, ErrorResponse,
-    GarbageFile, DuplicateGroup,
+    GarbageFile, DuplicateGroup, GitPathStatus, RiskFactor, FileHolder,
+    SecureDeleteOptions, SecureDeleteWarning,
+    ArchiveOptions, ArchiveResult, ArchiveManifest, RestoreResult,
     EVENT_CLEAN_PROGRESS, EVENT_CLEAN_COMPLETE,
 };
 use crate::modules::cleaner::{
     CleanerExecutor, SafetyChecker, RecycleBin, RecycleBinInfo,
     CleanReportGenerator, CleanReportData,
 };
+use crate::modules::cleaner::secure_erase::storage_warnings;
+use crate::modules::cleaner::archive::{self, read_manifest};
 
 pub struct CleanManager {
     cleans: Arc<RwLock<std::collections::HashMap<String, CleanState>>>,
@@ -24,6 +28,8 @@ pub struct CleanManager {
 struct CleanState {
     status: CleanStatus,
     cancelled: bool,
+    /// 正在运行的执行器的取消标志，取消时通知执行器停止处理剩余文件
+    executor_cancelled: Option<Arc<RwLock<bool>>>,
 }
 
 impl CleanManager {
@@ -47,6 +53,10 @@ pub struct CleanOptionsJson {
     pub secure_pass_count: u8,
     #[serde(default)]
     pub confirmed: bool,
+    #[serde(default)]
+    pub secure: SecureDeleteOptions,
+    #[serde(default)]
+    pub io: CleanIoOptions,
 }
 
 impl Default for CleanOptionsJson {
@@ -56,6 +66,8 @@ impl Default for CleanOptionsJson {
             secure_delete: false,
             secure_pass_count: 3,
             confirmed: false,
+            secure: SecureDeleteOptions::default(),
+            io: CleanIoOptions::default(),
         }
     }
 }
@@ -68,9 +80,12 @@ impl CleanOptionsJson {
         if self.secure_delete && self.secure_pass_count == 0 {
             return Err("安全删除的覆盖次数必须大于 0".to_string());
         }
-        if self.secure_pass_count > 35 {
+        if self.secure_pass_count > 35 || self.secure.patterns.len() > 35 {
             return Err("安全删除的覆盖次数不能超过 35 次".to_string());
         }
+        if self.io.max_concurrency == 0 || self.io.per_device_concurrency == 0 {
+            return Err("并发数必须大于 0".to_string());
+        }
         Ok(())
     }
 }
@@ -81,6 +96,8 @@ impl From<CleanOptionsJson> for CleanOptions {
             move_to_recycle_bin: opts.move_to_recycle_bin,
             secure_delete: opts.secure_delete,
             secure_pass_count: opts.secure_pass_count,
+            secure: opts.secure,
+            io: opts.io,
         }
     }
 }
@@ -177,6 +194,7 @@ pub async fn clean_files(
         cleans.insert(clean_id.clone(), CleanState {
             status: CleanStatus::Running,
             cancelled: false,
+            executor_cancelled: None,
         });
     }
 
@@ -244,6 +262,40 @@ pub async fn clean_duplicates(
     clean_files(files, options, app, manager).await
 }
 
+/// 重新清理上次因被占用而跳过的文件，用户关闭相关程序后调用
+#[tauri::command]
+pub async fn retry_in_use_files(
+    result: CleanResult,
+    options: Option<CleanOptionsJson>,
+    app: AppHandle,
+    manager: State<'_, CleanManager>,
+) -> Result<String, ErrorResponse> {
+    clean_files(result.in_use_paths(), options, app, manager).await
+}
+
+#[tauri::command]
+pub async fn check_files_in_use(
+    paths: Vec<String>,
+) -> Result<Vec<InUseFileJson>, String> {
+    let index = CleanerExecutor::new().snapshot_in_use().await;
+    let mut in_use = Vec::new();
+
+    for path in paths {
+        let processes = index.holders_of(&PathBuf::from(&path));
+        if !processes.is_empty() {
+            in_use.push(InUseFileJson { path, processes });
+        }
+    }
+
+    Ok(in_use)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InUseFileJson {
+    pub path: String,
+    pub processes: Vec<FileHolder>,
+}
+
 #[tauri::command]
 pub async fn clean_cancel(
     clean_id: String,
@@ -253,6 +305,9 @@ pub async fn clean_cancel(
     if let Some(state) = cleans.get_mut(&clean_id) {
         state.cancelled = true;
         state.status = CleanStatus::Cancelled;
+        if let Some(flag) = &state.executor_cancelled {
+            *flag.write().await = true;
+        }
     }
     Ok(())
 }
@@ -295,14 +350,31 @@ pub async fn check_file_safety(
         safe_to_delete: result.safe_to_delete,
         risk_level: format!("{:?}", result.risk_level),
         reason: result.reason,
+        git_status: result.git_status,
+        risk_score: result.risk_score,
+        risk_factors: result.risk_factors,
     })
 }
 
+/// 检查安全删除的目标是否位于覆写不可靠的存储上
+#[tauri::command]
+pub async fn get_secure_delete_warnings(
+    paths: Vec<String>,
+) -> Result<Vec<SecureDeleteWarning>, ErrorResponse> {
+    let paths: Vec<PathBuf> = paths.iter().map(PathBuf::from).collect();
+    tokio::task::spawn_blocking(move || storage_warnings(&paths))
+        .await
+        .map_err(|e| ErrorResponse::from(DiskTidyError::Unknown(e.to_string())))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SafetyCheckResultJson {
     pub safe_to_delete: bool,
     pub risk_level: String,
     pub reason: Option<String>,
+    pub git_status: Option<GitPathStatus>,
+    pub risk_score: u8,
+    pub risk_factors: Vec<RiskFactor>,
 }
 
 #[tauri::command]
@@ -330,6 +402,50 @@ pub async fn export_report_html(
     generator.export_html(&report)
 }
 
+/// 将文件打包归档到指定位置，校验后删除原文件
+#[tauri::command]
+pub async fn archive_files(
+    files: Vec<String>,
+    options: ArchiveOptions,
+) -> Result<ArchiveResult, ErrorResponse> {
+    let files: Vec<PathBuf> = files.iter().map(PathBuf::from).collect();
+    CleanerExecutor::new()
+        .archive(files, &options)
+        .await
+        .map_err(ErrorResponse::from)
+}
+
+/// 读取归档中的清单，供选择要恢复的文件
+#[tauri::command]
+pub async fn read_archive_manifest(
+    archive_path: String,
+) -> Result<ArchiveManifest, ErrorResponse> {
+    tokio::task::spawn_blocking(move || read_manifest(&PathBuf::from(archive_path)))
+        .await
+        .map_err(|e| ErrorResponse::from(DiskTidyError::Unknown(e.to_string())))?
+        .map_err(|e| ErrorResponse::from(DiskTidyError::IoError(e)))
+}
+
+/// 将归档中选中的文件恢复到原位置，`entries` 为空时恢复全部
+#[tauri::command]
+pub async fn restore_archive(
+    archive_path: String,
+    entries: Option<Vec<String>>,
+    overwrite: bool,
+) -> Result<RestoreResult, ErrorResponse> {
+    tokio::task::spawn_blocking(move || {
+        archive::restore_archive(
+            &PathBuf::from(archive_path),
+            entries.as_deref(),
+            overwrite,
+            &SafetyChecker::new(),
+        )
+    })
+    .await
+    .map_err(|e| ErrorResponse::from(DiskTidyError::Unknown(e.to_string())))?
+    .map_err(|e| ErrorResponse::from(DiskTidyError::IoError(e)))
+}
+
 /// 将文件移动到回收站
 #[tauri::command]
 pub async fn move_files_to_recycle_bin(
@@ -380,11 +496,12 @@ async fn perform_clean(
     });
 
     {
-        let cleans = cleans.read().await;
-        if let Some(state) = cleans.get(&clean_id) {
+        let mut cleans = cleans.write().await;
+        if let Some(state) = cleans.get_mut(&clean_id) {
             if state.cancelled {
                 return Ok(());
             }
+            state.executor_cancelled = Some(executor.cancel_flag());
         }
     }
 
@@ -0,0 +1,14 @@
+use crate::modules::cleaner::CleanerExecutor;
+use crate::modules::cleanup_planner::{
+    CleanupPlan, CleanupPlanInput, CleanupPlanOptions, CleanupPlanner,
+};
+
+#[tauri::command]
+pub async fn cleanup_plan_create(
+    input: CleanupPlanInput,
+    options: CleanupPlanOptions,
+) -> CleanupPlan {
+    let mut planner = CleanupPlanner::with_options(options);
+    planner.set_open_files(CleanerExecutor::new().snapshot_in_use().await);
+    planner.plan(&input)
+}
@@ -4,11 +4,12 @@ use tauri::AppHandle;
 
 use crate::models::{
     DuplicateAnalysisResult, DuplicateDetectorOptions, DuplicateGroup, GarbageAnalysisResult,
-    GarbageCategory, LargeFile, LargeFileAnalysisResult, LargeFileAnalyzerOptions,
+    GarbageCategory, IgnoredPath, LargeFile, LargeFileAnalysisResult, LargeFileAnalyzerOptions,
     LargeFileDetails,
 };
 use crate::modules::file_analyzer::{
     cancel_junk_file_scan, clear_junk_file_scan_result, get_junk_category_files,
+    get_junk_ignored_paths,
     get_junk_file_scan_progress, get_junk_file_scan_result, pause_junk_file_scan,
     resume_junk_file_scan, start_junk_file_scan, DuplicateDetector, GarbageDetector,
     GarbageDetectorOptions, JunkCategoryFilesResponse, JunkFileDetector, JunkFileScanProgress,
@@ -110,6 +111,7 @@ pub async fn analyze_garbage_by_category(
         categories,
         high_risk_count,
         duration_ms: 0,
+        ignored_paths: detector.take_ignored_paths(),
     })
 }
 
@@ -557,5 +559,10 @@ pub async fn junk_file_category_files(
     get_junk_category_files(&scan_id, &file_type, offset, limit).await
 }
 
+#[tauri::command]
+pub async fn junk_file_scan_ignored_paths(scan_id: String) -> Vec<IgnoredPath> {
+    get_junk_ignored_paths(&scan_id).await
+}
+
 // 确保前端 camelCase 参数名能够正确映射
 // Tauri 会自动处理 scanId -> scan_id, fileType -> file_type 的转换
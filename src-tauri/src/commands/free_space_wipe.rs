@@ -0,0 +1,44 @@
+//! 空闲空间擦除命令
+
+use tauri::{command, AppHandle};
+use crate::modules::free_space_wipe::{
+    self, FreeSpaceWipeConfig, FreeSpaceWipeProgress, FreeSpaceWipeResult,
+};
+
+#[command]
+pub async fn free_space_wipe_start(
+    app: AppHandle,
+    config: FreeSpaceWipeConfig,
+) -> Result<String, String> {
+    free_space_wipe::start_wipe(app, config).await
+}
+
+#[command]
+pub async fn free_space_wipe_pause(scan_id: String) -> Result<(), String> {
+    free_space_wipe::pause_wipe(&scan_id).await
+}
+
+#[command]
+pub async fn free_space_wipe_resume(scan_id: String) -> Result<(), String> {
+    free_space_wipe::resume_wipe(&scan_id).await
+}
+
+#[command]
+pub async fn free_space_wipe_cancel(scan_id: String) -> Result<(), String> {
+    free_space_wipe::cancel_wipe(&scan_id).await
+}
+
+#[command]
+pub async fn free_space_wipe_get_progress(scan_id: String) -> Result<Option<FreeSpaceWipeProgress>, String> {
+    Ok(free_space_wipe::get_progress(&scan_id).await)
+}
+
+#[command]
+pub async fn free_space_wipe_get_result(scan_id: String) -> Result<Option<FreeSpaceWipeResult>, String> {
+    Ok(free_space_wipe::get_result(&scan_id).await)
+}
+
+#[command]
+pub async fn free_space_wipe_clear(scan_id: String) -> Result<(), String> {
+    free_space_wipe::clear_wipe(&scan_id).await
+}
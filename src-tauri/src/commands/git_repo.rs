@@ -0,0 +1,10 @@
+use crate::modules::git_repo::{scan_git_repositories, GitRepoScanOptions, GitRepoScanResult};
+
+#[tauri::command]
+pub async fn analyze_git_repositories(
+    options: GitRepoScanOptions,
+) -> Result<GitRepoScanResult, String> {
+    tokio::task::spawn_blocking(move || scan_git_repositories(&options))
+        .await
+        .map_err(|e| format!("Git 仓库分析失败: {}", e))
+}
@@ -9,3 +9,9 @@ pub mod large_file;
 pub mod software_residue;
 pub mod driver;
 pub mod file_classifier;
+pub mod cleanup_planner;
+pub mod smart_clean;
+pub mod build_artifact;
+pub mod package_cache;
+pub mod git_repo;
+pub mod free_space_wipe;
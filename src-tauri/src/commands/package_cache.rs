@@ -0,0 +1,13 @@
+use crate::modules::package_cache::{
+    PackageCacheAnalysisResult, PackageCacheAnalyzer, PackageCacheOptions,
+};
+
+#[tauri::command]
+pub async fn analyze_package_caches(
+    options: Option<PackageCacheOptions>,
+) -> Result<PackageCacheAnalysisResult, String> {
+    let analyzer = PackageCacheAnalyzer::with_options(options.unwrap_or_default());
+    tokio::task::spawn_blocking(move || analyzer.analyze())
+        .await
+        .map_err(|e| format!("包管理器缓存分析失败: {}", e))
+}
@@ -3,8 +3,9 @@ use tauri::State;
 use tokio::sync::Mutex;
 use serde::{Deserialize, Serialize};
 
-use crate::models::settings::{AppSettings, CleanRule};
+use crate::models::settings::{AppSettings, CleanRule, WhitelistSettings};
 use crate::models::file_analyzer::JunkCategory;
+use crate::modules::risk_score;
 use crate::modules::settings::{SettingsManager, SettingsUpdate, RuleEngine};
 
 pub struct SettingsState {
@@ -14,8 +15,11 @@ pub struct SettingsState {
 
 impl SettingsState {
     pub fn new() -> Self {
+        let manager = SettingsManager::new();
+        // 启动时按保存的白名单配置风险模型，之后创建的检查器都会使用
+        risk_score::install_saved_config(manager.risk_model_config());
         Self {
-            manager: Arc::new(Mutex::new(SettingsManager::new())),
+            manager: Arc::new(Mutex::new(manager)),
             rule_engine: Arc::new(Mutex::new(RuleEngine::new())),
         }
     }
@@ -102,7 +106,29 @@ pub async fn settings_import(
 ) -> Result<(), String> {
     let manager = state.manager.lock().await;
     manager.import_settings(&json)
-        .map_err(|e| format!("{}: {}", e.error_code(), e))
+        .map_err(|e| format!("{}: {}", e.error_code(), e))?;
+    risk_score::install_saved_config(manager.risk_model_config());
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn whitelist_get(
+    state: State<'_, SettingsState>,
+) -> Result<WhitelistSettings, String> {
+    let manager = state.manager.lock().await;
+    Ok(manager.get_whitelist())
+}
+
+#[tauri::command]
+pub async fn whitelist_update(
+    whitelist: WhitelistSettings,
+    state: State<'_, SettingsState>,
+) -> Result<WhitelistSettings, String> {
+    let manager = state.manager.lock().await;
+    let saved = manager.update_whitelist(whitelist)
+        .map_err(|e| format!("{}: {}", e.error_code(), e))?;
+    risk_score::install_saved_config(manager.risk_model_config());
+    Ok(saved)
 }
 
 #[tauri::command]
@@ -0,0 +1,61 @@
+use tauri::{command, AppHandle, State};
+
+use crate::commands::cleaner::{clean_files, CleanManager, CleanOptionsJson};
+use crate::models::{DiskTidyError, ErrorResponse};
+use crate::modules::smart_clean::{
+    cancel_smart_clean_scan, clear_smart_clean_result, get_smart_clean_plan,
+    get_smart_clean_progress, start_smart_clean_scan, SmartCleanOptions, SmartCleanPlan,
+    SmartCleanProgress,
+};
+
+#[command]
+pub async fn smart_clean_start(
+    app: AppHandle,
+    options: Option<SmartCleanOptions>,
+) -> Result<String, String> {
+    start_smart_clean_scan(app, options.unwrap_or_default()).await
+}
+
+#[command]
+pub async fn smart_clean_progress(scan_id: String) -> Result<Option<SmartCleanProgress>, String> {
+    Ok(get_smart_clean_progress(&scan_id).await)
+}
+
+#[command]
+pub async fn smart_clean_result(scan_id: String) -> Result<Option<SmartCleanPlan>, String> {
+    Ok(get_smart_clean_plan(&scan_id).await)
+}
+
+#[command]
+pub async fn smart_clean_cancel(scan_id: String) -> Result<(), String> {
+    cancel_smart_clean_scan(&scan_id).await
+}
+
+#[command]
+pub async fn smart_clean_clear(scan_id: String) -> Result<(), String> {
+    clear_smart_clean_result(&scan_id).await
+}
+
+/// 执行智能清理计划，`excluded_paths` 为用户在审阅时取消勾选的项
+#[command]
+pub async fn smart_clean_execute(
+    scan_id: String,
+    excluded_paths: Option<Vec<String>>,
+    options: Option<CleanOptionsJson>,
+    app: AppHandle,
+    manager: State<'_, CleanManager>,
+) -> Result<String, ErrorResponse> {
+    let plan = get_smart_clean_plan(&scan_id)
+        .await
+        .ok_or_else(|| DiskTidyError::InvalidParameter {
+            message: format!("未找到智能清理计划: {}", scan_id),
+        })?;
+
+    let files: Vec<String> = plan
+        .paths(&excluded_paths.unwrap_or_default())
+        .into_iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+
+    clean_files(files, options, app, manager).await
+}
@@ -2,8 +2,14 @@ use std::sync::Arc;
 use tauri::State;
 use tokio::sync::RwLock;
 
+use crate::modules::cleaner::safe_remove::{remove_nofollow, resolve_delete_target};
+use crate::modules::software_residue::inventory::{self, SoftwareFootprint};
+use crate::modules::software_residue::registry::{
+    self, RegBackup, RegBackupFile, RegImportSummary,
+};
 use crate::modules::software_residue::{
-    ResidueScanOptions, ResidueScanProgress, ResidueScanResult, SoftwareResidueScanner,
+    ResidueScanOptions, ResidueScanProgress, ResidueScanResult, ResidueType,
+    SoftwareResidueScanner,
 };
 
 pub struct ResidueScanState {
@@ -130,25 +136,23 @@ pub async fn residue_delete_items(
     let mut deleted_count = 0;
     let mut deleted_size: u64 = 0;
     let mut failed_items: Vec<FailedItem> = Vec::new();
+    let mut registry_items = Vec::new();
 
     for result in results.iter() {
         for item in result.items.iter() {
             if item_ids.contains(&item.id) {
-                if item.residue_type == crate::modules::software_residue::ResidueType::RegistryKey {
-                    failed_items.push(FailedItem {
-                        id: item.id.clone(),
-                        path: item.path.clone(),
-                        error: "注册表项暂不支持删除".to_string(),
-                    });
+                if item.residue_type == ResidueType::RegistryKey {
+                    registry_items.push(item);
                     continue;
                 }
 
                 let path = std::path::Path::new(&item.path);
-                if path.exists() {
+                if std::fs::symlink_metadata(path).is_ok() {
+                    // 残留项可能是文件或目录，统一按不跟随符号链接的方式删除
                     let delete_result = if move_to_recycle_bin {
                         move_to_recycle_bin_internal(path)
                     } else {
-                        std::fs::remove_dir_all(path).map(|_| ())
+                        resolve_delete_target(path).and_then(|target| remove_nofollow(&target))
                     };
 
                     match delete_result {
@@ -169,20 +173,81 @@ pub async fn residue_delete_items(
         }
     }
 
+    let mut registry_backup = None;
+    if !registry_items.is_empty() {
+        match (registry::system_registry(), RegBackup::default_dir()) {
+            (Some(backend), Some(backup_dir)) => {
+                let paths: Vec<String> = registry_items.iter().map(|i| i.path.clone()).collect();
+                let outcome = registry::delete_keys_with_backup(
+                    backend.as_ref(),
+                    &RegBackup::new(backup_dir),
+                    &paths,
+                );
+                deleted_count += outcome.deleted.len() as u32;
+                for (path, error) in outcome.failed {
+                    if let Some(item) = registry_items.iter().find(|i| i.path == path) {
+                        failed_items.push(FailedItem {
+                            id: item.id.clone(),
+                            path,
+                            error,
+                        });
+                    }
+                }
+                registry_backup = outcome
+                    .backup_path
+                    .map(|p| p.to_string_lossy().to_string());
+            }
+            _ => {
+                failed_items.extend(registry_items.iter().map(|item| FailedItem {
+                    id: item.id.clone(),
+                    path: item.path.clone(),
+                    error: "当前系统不支持删除注册表项".to_string(),
+                }));
+            }
+        }
+    }
+
     Ok(DeleteResidueResult {
         deleted_count,
         deleted_size,
         failed_count: failed_items.len() as u32,
         failed_items,
+        registry_backup,
     })
 }
 
+#[tauri::command]
+pub async fn residue_registry_backups() -> Result<Vec<RegBackupFile>, String> {
+    Ok(RegBackup::default_dir()
+        .map(|dir| RegBackup::new(dir).list())
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn residue_restore_registry(backup_path: String) -> Result<RegImportSummary, String> {
+    let backend = registry::system_registry().ok_or("当前系统不支持注册表")?;
+    let backup_dir = RegBackup::default_dir().ok_or("无法获取注册表备份目录")?;
+    RegBackup::new(backup_dir)
+        .restore(backend.as_ref(), std::path::Path::new(&backup_path))
+        .map_err(|e| e.to_string())
+}
+
+/// 已安装软件及其占用，按总占用从大到小排列
+#[tauri::command]
+pub async fn residue_software_inventory() -> Result<Vec<SoftwareFootprint>, String> {
+    tokio::task::spawn_blocking(inventory::software_inventory)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct DeleteResidueResult {
     pub deleted_count: u32,
     pub deleted_size: u64,
     pub failed_count: u32,
     pub failed_items: Vec<FailedItem>,
+    /// 删除注册表项前导出的备份文件
+    pub registry_backup: Option<String>,
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -232,5 +297,5 @@ fn move_to_recycle_bin_internal(path: &std::path::Path) -> Result<(), std::io::E
 
 #[cfg(not(windows))]
 fn move_to_recycle_bin_internal(path: &std::path::Path) -> Result<(), std::io::Error> {
-    std::fs::remove_dir_all(path)
+    resolve_delete_target(path).and_then(|target| remove_nofollow(&target))
 }
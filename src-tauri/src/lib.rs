@@ -15,6 +15,10 @@ pub fn run() {
         .manage(commands::cleaner::CleanManager::new())
         .manage(commands::settings::SettingsState::new())
         .manage(commands::software_residue::ResidueScanState::new())
+        .setup(|app| {
+            modules::app_cache::retention::spawn_retention_scheduler(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             commands::system::system_get_info,
             commands::system::system_get_disks,
@@ -34,15 +38,37 @@ pub fn run() {
             commands::cleaner::clean_files,
             commands::cleaner::clean_garbage_files,
             commands::cleaner::clean_duplicates,
+            commands::cleaner::retry_in_use_files,
+            commands::cleaner::check_files_in_use,
+            commands::cleaner::get_secure_delete_warnings,
             commands::cleaner::clean_cancel,
             commands::cleaner::clean_status,
             commands::cleaner::empty_recycle_bin,
             commands::cleaner::get_recycle_bin_info,
             commands::cleaner::check_file_safety,
             commands::cleaner::move_files_to_recycle_bin,
+            commands::cleaner::archive_files,
+            commands::cleaner::read_archive_manifest,
+            commands::cleaner::restore_archive,
             commands::cleaner::generate_clean_report,
             commands::cleaner::export_report_json,
             commands::cleaner::export_report_html,
+            commands::cleanup_planner::cleanup_plan_create,
+            commands::smart_clean::smart_clean_start,
+            commands::smart_clean::smart_clean_progress,
+            commands::smart_clean::smart_clean_result,
+            commands::smart_clean::smart_clean_cancel,
+            commands::smart_clean::smart_clean_clear,
+            commands::smart_clean::smart_clean_execute,
+            commands::build_artifact::build_artifact_scan_start,
+            commands::build_artifact::build_artifact_scan_pause,
+            commands::build_artifact::build_artifact_scan_resume,
+            commands::build_artifact::build_artifact_scan_cancel,
+            commands::build_artifact::build_artifact_scan_get_progress,
+            commands::build_artifact::build_artifact_scan_get_result,
+            commands::build_artifact::build_artifact_scan_clear,
+            commands::package_cache::analyze_package_caches,
+            commands::git_repo::analyze_git_repositories,
             commands::file_analyzer::analyze_garbage_files,
             commands::file_analyzer::analyze_garbage_by_category,
             commands::file_analyzer::get_garbage_categories,
@@ -62,12 +88,15 @@ pub fn run() {
             commands::file_analyzer::junk_file_scan_result,
             commands::file_analyzer::junk_file_scan_clear,
             commands::file_analyzer::junk_file_category_files,
+            commands::file_analyzer::junk_file_scan_ignored_paths,
             commands::settings::settings_get,
             commands::settings::settings_update,
             commands::settings::settings_update_partial,
             commands::settings::settings_reset,
             commands::settings::settings_export,
             commands::settings::settings_import,
+            commands::settings::whitelist_get,
+            commands::settings::whitelist_update,
             commands::settings::rule_list,
             commands::settings::rule_get,
             commands::settings::rule_add,
@@ -83,6 +112,20 @@ pub fn run() {
             commands::app_cache::app_cache_scan_progress,
             commands::app_cache::app_cache_scan_result,
             commands::app_cache::app_cache_scan_clear,
+            commands::app_cache::app_cache_definitions,
+            commands::app_cache::app_cache_conversation_summary,
+            commands::app_cache::app_cache_conversation_files,
+            commands::app_cache::app_cache_retention_get_config,
+            commands::app_cache::app_cache_retention_save_config,
+            commands::app_cache::app_cache_retention_preview,
+            commands::app_cache::app_cache_retention_apply,
+            commands::app_cache::app_cache_export_start,
+            commands::app_cache::app_cache_export_pause,
+            commands::app_cache::app_cache_export_resume,
+            commands::app_cache::app_cache_export_cancel,
+            commands::app_cache::app_cache_export_progress,
+            commands::app_cache::app_cache_export_result,
+            commands::app_cache::app_cache_export_clear,
             commands::app_config::get_app_config,
             commands::app_config::set_app_path,
             commands::app_config::remove_app_path,
@@ -96,6 +139,13 @@ pub fn run() {
             commands::large_file::large_file_scan_get_progress,
             commands::large_file::large_file_scan_get_result,
             commands::large_file::large_file_scan_clear,
+            commands::free_space_wipe::free_space_wipe_start,
+            commands::free_space_wipe::free_space_wipe_pause,
+            commands::free_space_wipe::free_space_wipe_resume,
+            commands::free_space_wipe::free_space_wipe_cancel,
+            commands::free_space_wipe::free_space_wipe_get_progress,
+            commands::free_space_wipe::free_space_wipe_get_result,
+            commands::free_space_wipe::free_space_wipe_clear,
             commands::software_residue::residue_scan_start,
             commands::software_residue::residue_scan_pause,
             commands::software_residue::residue_scan_resume,
@@ -104,6 +154,9 @@ pub fn run() {
             commands::software_residue::residue_scan_result,
             commands::software_residue::residue_scan_clear,
             commands::software_residue::residue_delete_items,
+            commands::software_residue::residue_registry_backups,
+            commands::software_residue::residue_restore_registry,
+            commands::software_residue::residue_software_inventory,
             commands::file_classifier::classify_files,
             commands::file_classifier::classify_disk,
             commands::file_classifier::start_classify_files,
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use super::scan::IgnoredPath;
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub enum CleanMode {
@@ -52,6 +54,16 @@ pub enum RiskLevel {
 }
 
 impl RiskLevel {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "low" => Some(Self::Low),
+            "medium" => Some(Self::Medium),
+            "high" => Some(Self::High),
+            "critical" => Some(Self::Critical),
+            _ => None,
+        }
+    }
+
     pub fn display_name(&self) -> &str {
         match self {
             Self::Low => "低风险",
@@ -92,6 +104,33 @@ pub struct LargeFile {
     pub created_time: i64,
     pub file_type: String,
     pub extension: String,
+    #[serde(default)]
+    pub git_status: Option<GitPathStatus>,
+}
+
+/// 文件在 Git 工作区中的状态
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum GitPathStatus {
+    /// 已被索引跟踪
+    Tracked,
+    /// 未跟踪且未被忽略
+    Untracked,
+    /// 被 .gitignore 忽略，通常是可重新生成的构建产物
+    Ignored,
+    /// 位于 .git 目录内的仓库数据
+    GitDir,
+}
+
+impl GitPathStatus {
+    pub fn display_name(&self) -> &str {
+        match self {
+            Self::Tracked => "已跟踪",
+            Self::Untracked => "未跟踪",
+            Self::Ignored => "已忽略",
+            Self::GitDir => "仓库数据",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -164,6 +203,10 @@ pub struct CleanOptions {
     pub move_to_recycle_bin: bool,
     pub secure_delete: bool,
     pub secure_pass_count: u8,
+    #[serde(default)]
+    pub secure: SecureDeleteOptions,
+    #[serde(default)]
+    pub io: CleanIoOptions,
 }
 
 impl Default for CleanOptions {
@@ -172,6 +215,129 @@ impl Default for CleanOptions {
             move_to_recycle_bin: true,
             secure_delete: false,
             secure_pass_count: 3,
+            secure: SecureDeleteOptions::default(),
+            io: CleanIoOptions::default(),
+        }
+    }
+}
+
+/// 安全删除的覆写方案
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SecureDeleteScheme {
+    /// 一遍随机数据
+    Random,
+    /// DoD 5220.22-M：0x00、0xFF、随机数据各一遍
+    Dod5220,
+    /// 使用 `patterns` 中的每一项各覆写一遍；未指定时按 `secure_pass_count`
+    /// 循环使用 0x00、0xFF、0xAA
+    Custom,
+}
+
+impl SecureDeleteScheme {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            SecureDeleteScheme::Random => "随机数据（1 遍）",
+            SecureDeleteScheme::Dod5220 => "DoD 5220.22-M（3 遍）",
+            SecureDeleteScheme::Custom => "自定义",
+        }
+    }
+}
+
+/// 一遍覆写写入的数据
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum OverwritePattern {
+    /// 重复写入同一个字节
+    Byte(u8),
+    /// 随机数据
+    Random,
+}
+
+/// 安全删除设置
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SecureDeleteOptions {
+    pub scheme: SecureDeleteScheme,
+    /// 自定义方案的各遍数据
+    pub patterns: Vec<OverwritePattern>,
+    /// 每遍写入并同步后读回校验
+    pub verify: bool,
+    /// 删除前将文件名重命名为随机名称的次数，避免原文件名残留在目录项中
+    pub rename_count: u8,
+    /// 覆写到最后一个块的末尾，清除文件末尾之后残留的旧数据（文件松弛空间）
+    pub wipe_slack: bool,
+}
+
+impl Default for SecureDeleteOptions {
+    fn default() -> Self {
+        Self {
+            scheme: SecureDeleteScheme::Custom,
+            patterns: Vec::new(),
+            verify: false,
+            rename_count: 3,
+            wipe_slack: false,
+        }
+    }
+}
+
+/// 覆写不可靠的存储类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SecureDeleteWarningKind {
+    /// 固态硬盘：磨损均衡会把写入重定向到新的闪存块
+    SolidState,
+    /// 写时复制文件系统：覆写写入新的数据块，原数据块不会被改写
+    CopyOnWrite,
+    /// 日志文件系统：文件名等元数据可能残留在日志中
+    Journaling,
+}
+
+impl SecureDeleteWarningKind {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            SecureDeleteWarningKind::SolidState => "固态硬盘",
+            SecureDeleteWarningKind::CopyOnWrite => "写时复制文件系统",
+            SecureDeleteWarningKind::Journaling => "日志文件系统",
+        }
+    }
+}
+
+/// 安全删除的目标位于覆写不可靠的存储上
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecureDeleteWarning {
+    /// 该存储上的第一个目标路径
+    pub path: String,
+    pub kind: SecureDeleteWarningKind,
+    /// 文件系统名称，无法识别时为空
+    pub file_system: String,
+    pub message: String,
+}
+
+/// 清理时的并发与限速设置
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CleanIoOptions {
+    /// 同时处理的路径数上限
+    pub max_concurrency: usize,
+    /// 同一设备上同时处理的路径数上限，避免机械硬盘来回寻道
+    pub per_device_concurrency: usize,
+    /// 每秒最多处理的字节数，0 表示不限速
+    pub max_bytes_per_sec: u64,
+    /// 每秒最多处理的路径数，0 表示不限速
+    pub max_ops_per_sec: u64,
+    /// 以后台优先级执行删除，Linux 上使用 idle I/O 调度类并调低 nice 值
+    pub background_priority: bool,
+}
+
+impl Default for CleanIoOptions {
+    fn default() -> Self {
+        Self {
+            max_concurrency: 8,
+            per_device_concurrency: 4,
+            max_bytes_per_sec: 0,
+            max_ops_per_sec: 0,
+            background_priority: false,
         }
     }
 }
@@ -183,17 +349,217 @@ pub struct CleanResult {
     pub cleaned_files: u64,
     pub failed_files: u64,
     pub skipped_files: u64,
+    /// 尝试清理的路径的表观大小之和，目录按内容递归统计
     pub total_size: u64,
+    /// 已清理路径的表观大小之和
     pub cleaned_size: u64,
+    /// 永久删除后实际释放的磁盘空间（分配大小）
+    #[serde(default)]
+    pub freed_size: u64,
+    /// 移入回收站的空间（分配大小），仍占用同一卷，清空回收站后才会释放
+    #[serde(default)]
+    pub trashed_size: u64,
+    /// 平均每秒清理的字节数（表观大小）
+    #[serde(default)]
+    pub bytes_per_sec: u64,
+    /// 安全删除的目标位于覆写不可靠的存储上时的提示
+    #[serde(default)]
+    pub secure_delete_warnings: Vec<SecureDeleteWarning>,
     pub errors: Vec<CleanError>,
     pub duration_ms: u64,
 }
 
+/// 一个路径占用的空间，目录为其全部内容之和
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EntrySize {
+    /// 文件内容的字节数
+    pub apparent: u64,
+    /// 实际分配的磁盘空间
+    pub allocated: u64,
+    /// 删除后可以释放的磁盘空间，不含在别处仍有硬链接的文件
+    pub reclaimable: u64,
+    pub file_count: u64,
+}
+
+impl CleanResult {
+    /// 因被其他进程占用而跳过的路径，关闭相关程序后可重新清理
+    pub fn in_use_paths(&self) -> Vec<String> {
+        self.errors
+            .iter()
+            .filter(|e| !e.processes.is_empty())
+            .map(|e| e.path.clone())
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CleanError {
     pub path: String,
     pub error_code: String,
     pub error_message: String,
+    /// 占用该文件的进程，文件未被占用时为空
+    #[serde(default)]
+    pub processes: Vec<FileHolder>,
+}
+
+/// 进程持有文件的方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FileHoldKind {
+    /// 通过文件描述符打开
+    Open,
+    /// 映射到进程地址空间，如正在使用的动态库
+    Mapped,
+}
+
+impl FileHoldKind {
+    pub fn display_name(&self) -> &str {
+        match self {
+            Self::Open => "打开",
+            Self::Mapped => "映射",
+        }
+    }
+}
+
+/// 占用文件的进程
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FileHolder {
+    pub pid: u32,
+    pub process_name: String,
+    /// 被占用的具体文件，删除目录时为目录中的某个文件
+    pub path: String,
+    pub kind: FileHoldKind,
+}
+
+impl FileHolder {
+    pub fn describe(&self) -> String {
+        format!(
+            "{} (PID {}) {}",
+            self.process_name,
+            self.pid,
+            self.kind.display_name()
+        )
+    }
+}
+
+/// 归档文件的格式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ArchiveFormat {
+    TarZst,
+    Zip,
+}
+
+impl ArchiveFormat {
+    pub fn display_name(&self) -> &str {
+        match self {
+            Self::TarZst => "tar.zst",
+            Self::Zip => "zip",
+        }
+    }
+
+    pub fn extension(&self) -> &str {
+        match self {
+            Self::TarZst => ".tar.zst",
+            Self::Zip => ".zip",
+        }
+    }
+
+    /// 根据文件名判断格式
+    pub fn from_path(path: &std::path::Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+        if name.ends_with(".tar.zst") {
+            Some(Self::TarZst)
+        } else if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+/// 归档（代替删除）设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveOptions {
+    /// 归档文件存放的目录，例如外接硬盘上的路径
+    pub destination: String,
+    pub format: ArchiveFormat,
+    /// 压缩级别，tar.zst 为 1-22，zip 为 0-9，超出范围时取边界值
+    #[serde(default = "default_compression_level")]
+    pub compression_level: i32,
+    /// 校验归档成功后删除原文件
+    #[serde(default = "default_remove_originals")]
+    pub remove_originals: bool,
+}
+
+fn default_compression_level() -> i32 {
+    3
+}
+
+fn default_remove_originals() -> bool {
+    true
+}
+
+impl Default for ArchiveOptions {
+    fn default() -> Self {
+        Self {
+            destination: String::new(),
+            format: ArchiveFormat::TarZst,
+            compression_level: default_compression_level(),
+            remove_originals: default_remove_originals(),
+        }
+    }
+}
+
+/// 归档清单中的一个文件
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ArchiveManifestEntry {
+    /// 归档前的完整路径，恢复时写回此处
+    pub original_path: String,
+    /// 在归档中的条目名
+    pub archive_path: String,
+    pub size: u64,
+    /// 修改时间（Unix 时间戳，秒）
+    pub modified_time: i64,
+    /// 修改时间的纳秒部分，恢复时与秒一起还原
+    #[serde(default)]
+    pub modified_nanos: u32,
+    /// 文件内容的 SHA-256
+    pub sha256: String,
+}
+
+/// 嵌入在归档中的清单
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub version: u32,
+    pub created_at: i64,
+    pub hostname: String,
+    pub format: ArchiveFormat,
+    pub entries: Vec<ArchiveManifestEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveResult {
+    pub archive_path: String,
+    pub format: ArchiveFormat,
+    pub archived_files: u64,
+    /// 归档前文件的总大小
+    pub archived_size: u64,
+    /// 归档文件的大小
+    pub archive_size: u64,
+    /// 校验后删除的原路径数
+    pub removed_paths: u64,
+    pub errors: Vec<CleanError>,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestoreResult {
+    pub restored_files: u64,
+    pub restored_size: u64,
+    /// 目标位置已存在且未选择覆盖而跳过的文件
+    pub skipped_files: u64,
+    pub errors: Vec<CleanError>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -245,6 +611,8 @@ pub struct GarbageAnalysisResult {
     pub categories: HashMap<String, CategoryStats>,
     pub high_risk_count: u64,
     pub duration_ms: u64,
+    #[serde(default)]
+    pub ignored_paths: Vec<IgnoredPath>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -263,6 +631,8 @@ pub struct LargeFileAnalysisResult {
     pub files: Vec<LargeFile>,
     pub threshold: u64,
     pub duration_ms: u64,
+    #[serde(default)]
+    pub ignored_paths: Vec<IgnoredPath>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -274,6 +644,8 @@ pub struct DuplicateAnalysisResult {
     pub wasted_space: u64,
     pub groups: Vec<DuplicateGroup>,
     pub duration_ms: u64,
+    #[serde(default)]
+    pub ignored_paths: Vec<IgnoredPath>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
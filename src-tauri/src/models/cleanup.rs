@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+use super::cleaner::RiskLevel;
+use super::risk::RiskFactor;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum CleanupSource {
+    Garbage,
+    Junk,
+    AppCache,
+    Duplicate,
+    LargeFile,
+    Residue,
+}
+
+impl CleanupSource {
+    pub fn display_name(&self) -> &str {
+        match self {
+            Self::Garbage => "垃圾文件",
+            Self::Junk => "无用文件",
+            Self::AppCache => "应用缓存",
+            Self::Duplicate => "重复文件",
+            Self::LargeFile => "大文件",
+            Self::Residue => "软件残留",
+        }
+    }
+}
+
+/// 各分析器结果归一化后的清理候选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupCandidate {
+    pub path: String,
+    pub size: u64,
+    pub source: CleanupSource,
+    pub risk_level: RiskLevel,
+    pub regenerable: bool,
+    pub modified_time: i64,
+    pub reason: String,
+    /// 同一路径还被哪些分析器报告过
+    #[serde(default)]
+    pub also_reported_by: Vec<CleanupSource>,
+    /// 风险模型给出的 0-100 分数
+    #[serde(default)]
+    pub risk_score: u8,
+    /// 影响风险分数的因素，按影响大小排序
+    #[serde(default)]
+    pub risk_factors: Vec<RiskFactor>,
+}
+
+impl CleanupCandidate {
+    pub fn age_days(&self, now: i64) -> u64 {
+        if self.modified_time <= 0 || self.modified_time > now {
+            return 0;
+        }
+        ((now - self.modified_time) / 86400) as u64
+    }
+}
+
+/// 因位于已选目录内而被合并的候选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergedCandidate {
+    pub path: String,
+    pub size: u64,
+    pub source: CleanupSource,
+    pub merged_into: String,
+}
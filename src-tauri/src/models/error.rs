@@ -54,6 +54,18 @@ pub enum DiskTidyError {
     #[error("路径受保护: {path}")]
     ProtectedPath { path: String },
 
+    #[error("路径中存在符号链接，拒绝跟随: {path}")]
+    SymlinkRefused { path: String },
+
+    #[error("图片解码失败: {path}（{reason}）")]
+    ImageDecodeFailed { path: String, reason: String },
+
+    #[error("注册表操作失败: {path}（{reason}）")]
+    RegistryOperationFailed { path: String, reason: String },
+
+    #[error("注册表文件格式错误: 第 {line} 行（{reason}）")]
+    RegFileInvalid { line: usize, reason: String },
+
     #[error("哈希计算失败: {path}")]
     HashCalculationFailed { path: String },
 
@@ -93,6 +105,10 @@ impl DiskTidyError {
             Self::FileNotFound { .. } => "E015",
             Self::FileInUse { .. } => "E016",
             Self::ProtectedPath { .. } => "E017",
+            Self::SymlinkRefused { .. } => "E023",
+            Self::ImageDecodeFailed { .. } => "E024",
+            Self::RegistryOperationFailed { .. } => "E025",
+            Self::RegFileInvalid { .. } => "E026",
             Self::HashCalculationFailed { .. } => "E018",
             Self::HashCalculationTimeout { .. } => "E022",
             Self::InvalidParameter { .. } => "E019",
@@ -130,6 +146,10 @@ impl From<DiskTidyError> for ErrorResponse {
             DiskTidyError::FileNotFound { .. } => (5003, error.to_string()),
             DiskTidyError::FileInUse { .. } => (5004, error.to_string()),
             DiskTidyError::ProtectedPath { .. } => (5005, error.to_string()),
+            DiskTidyError::SymlinkRefused { .. } => (5011, error.to_string()),
+            DiskTidyError::ImageDecodeFailed { .. } => (5012, error.to_string()),
+            DiskTidyError::RegistryOperationFailed { .. } => (5013, error.to_string()),
+            DiskTidyError::RegFileInvalid { .. } => (5014, error.to_string()),
             DiskTidyError::HashCalculationFailed { .. } => (5006, error.to_string()),
             DiskTidyError::HashCalculationTimeout { .. } => (5010, error.to_string()),
             DiskTidyError::InvalidParameter { .. } => (5007, error.to_string()),
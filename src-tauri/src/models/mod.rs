@@ -5,6 +5,8 @@ pub mod file_analyzer;
 pub mod error;
 pub mod events;
 pub mod settings;
+pub mod cleanup;
+pub mod risk;
 
 pub use system::*;
 pub use scan::*;
@@ -13,3 +15,5 @@ pub use file_analyzer::*;
 pub use error::*;
 pub use events::*;
 pub use settings::*;
+pub use cleanup::*;
+pub use risk::*;
@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+
+use super::cleaner::RiskLevel;
+
+/// 风险评分所依据的信号
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum RiskSignal {
+    Location,
+    Age,
+    LastAccess,
+    FileType,
+    Regenerable,
+    InUse,
+    Git,
+    Whitelist,
+    RecentModification,
+    Category,
+    Protection,
+}
+
+impl RiskSignal {
+    pub fn display_name(&self) -> &str {
+        match self {
+            Self::Location => "所在位置",
+            Self::Age => "文件年龄",
+            Self::LastAccess => "最近访问",
+            Self::FileType => "文件类型",
+            Self::Regenerable => "可重新生成",
+            Self::InUse => "正在使用",
+            Self::Git => "Git 跟踪",
+            Self::Whitelist => "白名单",
+            Self::RecentModification => "最近修改",
+            Self::Category => "分析器分类",
+            Self::Protection => "安全规则",
+        }
+    }
+}
+
+/// 一项对风险分数的贡献，正数提高风险，负数降低风险
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RiskFactor {
+    pub signal: RiskSignal,
+    pub contribution: i32,
+    pub description: String,
+}
+
+/// 风险评估结果：0-100 的分数、对应的风险等级以及按影响大小排序的因素
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskAssessment {
+    pub score: u8,
+    pub risk_level: RiskLevel,
+    pub factors: Vec<RiskFactor>,
+}
+
+impl RiskAssessment {
+    /// 安全规则直接判定的结果，只包含一项说明
+    pub fn fixed(risk_level: RiskLevel, description: &str) -> Self {
+        let score = match risk_level {
+            RiskLevel::Low => 15,
+            RiskLevel::Medium => 45,
+            RiskLevel::High => 80,
+            RiskLevel::Critical => 100,
+        };
+        Self {
+            score,
+            risk_level,
+            factors: vec![RiskFactor {
+                signal: RiskSignal::Protection,
+                contribution: 0,
+                description: description.to_string(),
+            }],
+        }
+    }
+
+    /// 用于界面展示的简短说明，取影响最大的几项因素
+    pub fn summary(&self, limit: usize) -> String {
+        self.factors
+            .iter()
+            .filter(|f| f.contribution != 0 || f.signal == RiskSignal::Protection)
+            .take(limit)
+            .map(|f| f.description.clone())
+            .collect::<Vec<_>>()
+            .join("，")
+    }
+}
+
+/// 各信号的权重，即该信号取最大值时对分数的影响
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RiskWeights {
+    /// 没有任何信号时的基础分
+    pub base: i32,
+    pub location: i32,
+    pub age: i32,
+    pub last_access: i32,
+    pub file_type: i32,
+    pub regenerable: i32,
+    pub in_use: i32,
+    pub git: i32,
+    pub whitelist: i32,
+    pub recent_modification: i32,
+    pub category: i32,
+}
+
+impl Default for RiskWeights {
+    fn default() -> Self {
+        Self {
+            base: 40,
+            location: 30,
+            age: 15,
+            last_access: 15,
+            file_type: 20,
+            regenerable: 25,
+            in_use: 40,
+            git: 30,
+            whitelist: 40,
+            recent_modification: 20,
+            category: 40,
+        }
+    }
+}
+
+impl RiskWeights {
+    pub fn weight(&self, signal: RiskSignal) -> i32 {
+        match signal {
+            RiskSignal::Location => self.location,
+            RiskSignal::Age => self.age,
+            RiskSignal::LastAccess => self.last_access,
+            RiskSignal::FileType => self.file_type,
+            RiskSignal::Regenerable => self.regenerable,
+            RiskSignal::InUse => self.in_use,
+            RiskSignal::Git => self.git,
+            RiskSignal::Whitelist => self.whitelist,
+            RiskSignal::RecentModification => self.recent_modification,
+            RiskSignal::Category => self.category,
+            RiskSignal::Protection => 0,
+        }
+    }
+}
+
+/// 分数到风险等级的分界
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RiskThresholds {
+    /// 低于该分数为低风险
+    pub medium: u8,
+    /// 低于该分数为中等风险，否则为高风险
+    pub high: u8,
+}
+
+impl Default for RiskThresholds {
+    fn default() -> Self {
+        Self {
+            medium: 30,
+            high: 60,
+        }
+    }
+}
+
+impl RiskThresholds {
+    /// 极高风险只由安全规则判定，评分最高给出高风险
+    pub fn level(&self, score: u8) -> RiskLevel {
+        if score < self.medium {
+            RiskLevel::Low
+        } else if score < self.high {
+            RiskLevel::Medium
+        } else {
+            RiskLevel::High
+        }
+    }
+}
@@ -53,6 +53,18 @@ pub struct ScanResult {
     pub categories: Vec<FileCategory>,
     pub status: ScanStatus,
     pub duration: u64,
+    #[serde(default)]
+    pub ignored_paths: Vec<IgnoredPath>,
+}
+
+/// 被 `.disktidyignore` 排除的路径及排除它的规则
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IgnoredPath {
+    pub path: String,
+    pub is_dir: bool,
+    pub ignore_file: String,
+    pub line: usize,
+    pub pattern: String,
 }
 
 pub const MAX_FILES_PER_CATEGORY: usize = 100;
@@ -121,6 +133,7 @@ impl ScanResult {
             categories: Vec::new(),
             status: ScanStatus::Idle,
             duration: 0,
+            ignored_paths: Vec::new(),
         }
     }
 }
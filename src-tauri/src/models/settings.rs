@@ -1,8 +1,11 @@
 use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::OnceLock;
 
 use super::cleaner::CleanMode;
 use super::file_analyzer::JunkCategory;
 use super::scan::ScanMode;
+use crate::utils::path_match::PathPrefix;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppSettings {
@@ -66,6 +69,15 @@ pub struct WhitelistPath {
     pub description: String,
     pub enabled: bool,
     pub added_time: u64,
+    /// 首次比较时解析的路径前缀，之后的比较直接复用
+    #[serde(skip)]
+    prefix: OnceLock<PathPrefix>,
+}
+
+impl WhitelistPath {
+    fn prefix(&self) -> &PathPrefix {
+        self.prefix.get_or_init(|| PathPrefix::new(&self.path))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -96,6 +108,7 @@ impl WhitelistSettings {
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap_or_default()
                 .as_secs(),
+            prefix: OnceLock::new(),
         });
     }
 
@@ -124,9 +137,10 @@ impl WhitelistSettings {
         if !self.enabled {
             return false;
         }
+        let path = Path::new(path);
         self.paths
             .iter()
-            .any(|p| p.enabled && path.to_lowercase().starts_with(&p.path.to_lowercase()))
+            .any(|p| p.enabled && p.prefix().contains(path))
     }
 
     pub fn is_extension_whitelisted(&self, extension: &str) -> bool {
@@ -0,0 +1,126 @@
+//! 按会话统计应用缓存
+//!
+//! 扫描结果中的每个文件都带有所属应用、账号和会话，这里把它们按会话与分类
+//! 汇总，便于找出占用空间最多的群聊并只清理该会话的文件。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::scanner::{AppCacheFile, CleanCategory};
+
+/// 一组文件的数量、大小与修改时间范围（毫秒时间戳）
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationStats {
+    pub file_count: u64,
+    pub total_size: u64,
+    pub earliest_modified: i64,
+    pub latest_modified: i64,
+}
+
+impl ConversationStats {
+    fn add(&mut self, file: &AppCacheFile) {
+        if self.file_count == 0 {
+            self.earliest_modified = file.modified_at;
+            self.latest_modified = file.modified_at;
+        } else {
+            self.earliest_modified = self.earliest_modified.min(file.modified_at);
+            self.latest_modified = self.latest_modified.max(file.modified_at);
+        }
+        self.file_count += 1;
+        self.total_size += file.size;
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationCategoryStats {
+    pub category: CleanCategory,
+    #[serde(flatten)]
+    pub stats: ConversationStats,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationSummary {
+    pub app: String,
+    pub account_id: Option<String>,
+    pub conversation: String,
+    #[serde(flatten)]
+    pub stats: ConversationStats,
+    /// 按大小降序
+    pub categories: Vec<ConversationCategoryStats>,
+}
+
+/// 按应用、账号和会话汇总文件，结果按总大小降序
+pub fn summarize_conversations(files: &[AppCacheFile]) -> Vec<ConversationSummary> {
+    let mut summaries: Vec<ConversationSummary> = Vec::new();
+    let mut index: HashMap<(&str, Option<&str>, &str), usize> = HashMap::new();
+
+    for file in files {
+        let key = (
+            file.app.as_str(),
+            file.account_id.as_deref(),
+            file.chat_object.as_str(),
+        );
+        let position = *index.entry(key).or_insert_with(|| {
+            summaries.push(ConversationSummary {
+                app: file.app.clone(),
+                account_id: file.account_id.clone(),
+                conversation: file.chat_object.clone(),
+                stats: ConversationStats::default(),
+                categories: Vec::new(),
+            });
+            summaries.len() - 1
+        });
+
+        let summary = &mut summaries[position];
+        summary.stats.add(file);
+        match summary
+            .categories
+            .iter_mut()
+            .find(|c| c.category == file.category)
+        {
+            Some(category) => category.stats.add(file),
+            None => {
+                let mut stats = ConversationStats::default();
+                stats.add(file);
+                summary.categories.push(ConversationCategoryStats {
+                    category: file.category.clone(),
+                    stats,
+                });
+            }
+        }
+    }
+
+    for summary in &mut summaries {
+        summary
+            .categories
+            .sort_by_key(|c| std::cmp::Reverse(c.stats.total_size));
+    }
+    summaries.sort_by(|a, b| {
+        b.stats
+            .total_size
+            .cmp(&a.stats.total_size)
+            .then_with(|| a.conversation.cmp(&b.conversation))
+    });
+    summaries
+}
+
+/// 选出某个会话的文件，`categories` 为空时不按分类过滤
+pub fn conversation_files(
+    files: &[AppCacheFile],
+    app: &str,
+    account_id: Option<&str>,
+    conversation: &str,
+    categories: &[CleanCategory],
+) -> Vec<AppCacheFile> {
+    files
+        .iter()
+        .filter(|f| {
+            f.app == app && f.account_id.as_deref() == account_id && f.chat_object == conversation
+        })
+        .filter(|f| categories.is_empty() || categories.contains(&f.category))
+        .cloned()
+        .collect()
+}
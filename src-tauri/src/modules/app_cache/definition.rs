@@ -0,0 +1,292 @@
+//! 应用缓存定义
+//!
+//! 每个应用用一份 JSON 描述：如何在根目录下找到账号目录、各清理分类对应哪些
+//! 目录与扩展名、哪些文件需要专门的解密处理，以及可选的风险等级。内置定义随
+//! 程序发布，用户可以在配置目录的 `app_definitions` 下放置同格式的文件来新增
+//! 应用或覆盖同 id 的内置定义，无需修改代码。
+//!
+//! 目录与账号模式按 `/` 分段，每段可使用 `*`、`?`、`[...]` 通配符。规则目录中的
+//! `{conversation}` 段匹配任意目录，并把目录名记为文件所属的会话。同一文件被
+//! 多个目录匹配时只记入先出现的规则，因此会话目录应写在更宽泛的目录之前。
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use super::scanner::CleanCategory;
+use crate::models::RiskLevel;
+use crate::utils::get_default_config_dir;
+
+/// 用户自定义应用定义所在的目录名（位于配置目录下）
+pub const USER_DEFINITIONS_DIR: &str = "app_definitions";
+
+const BUILTIN_DEFINITIONS: &[&str] = &[
+    include_str!("definitions/wechat.json"),
+    include_str!("definitions/dingtalk.json"),
+    include_str!("definitions/qq.json"),
+    include_str!("definitions/wework.json"),
+    include_str!("definitions/slack.json"),
+    include_str!("definitions/discord.json"),
+    include_str!("definitions/telegram.json"),
+    include_str!("definitions/vscode.json"),
+    include_str!("definitions/jetbrains.json"),
+    include_str!("definitions/spotify.json"),
+    include_str!("definitions/steam.json"),
+    include_str!("definitions/flatpak.json"),
+    include_str!("definitions/electron.json"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AppDefinition {
+    /// 唯一标识，用于扫描选项与路径配置，如 `wechat`
+    pub id: String,
+    /// 显示名称
+    pub name: String,
+    /// 未配置路径时尝试的默认根目录，支持 `{home}`、`{config}`、`{data}`、
+    /// `{cache}`、`{documents}`、`{appdata}`、`{localappdata}` 占位符
+    #[serde(default)]
+    pub roots: Vec<String>,
+    #[serde(default)]
+    pub accounts: AccountDiscovery,
+    pub rules: Vec<CacheRule>,
+}
+
+/// 在根目录下查找账号目录
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AccountDiscovery {
+    /// 账号目录相对于根目录的模式，为空时根目录本身即为唯一的账号目录
+    pub patterns: Vec<String>,
+    /// 排除的目录名模式
+    pub exclude: Vec<String>,
+    /// 账号目录中至少存在其中一项时才视为有效账号，为空时不检查
+    pub require_any: Vec<String>,
+}
+
+impl Default for AccountDiscovery {
+    fn default() -> Self {
+        Self {
+            patterns: vec!["*".to_string()],
+            exclude: Vec::new(),
+            require_any: Vec::new(),
+        }
+    }
+}
+
+/// 一个清理分类下的一组目录
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CacheRule {
+    pub category: CleanCategory,
+    /// 相对于账号目录的目录模式
+    pub paths: Vec<String>,
+    /// 只收集这些扩展名（不含点，小写），为空时收集全部文件
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    /// 是否扫描子目录
+    #[serde(default = "default_recursive")]
+    pub recursive: bool,
+    /// 匹配文件的加密格式
+    #[serde(default)]
+    pub encrypted: Option<EncryptedFormat>,
+    /// 覆盖按分类推断的风险等级
+    #[serde(default)]
+    pub risk: Option<RiskLevel>,
+}
+
+fn default_recursive() -> bool {
+    true
+}
+
+/// 需要专门处理的加密文件格式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EncryptedFormat {
+    /// 微信图片的异或加密 .dat 文件
+    WechatDat,
+}
+
+impl AppDefinition {
+    pub fn from_json(content: &str) -> Result<Self, String> {
+        let definition: Self =
+            serde_json::from_str(content).map_err(|e| format!("解析应用定义失败: {}", e))?;
+        definition.validate()?;
+        Ok(definition)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let valid_id = !self.id.is_empty()
+            && self
+                .id
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-');
+        if !valid_id {
+            return Err(format!(
+                "应用 id 只能包含小写字母、数字、下划线和连字符: {}",
+                self.id
+            ));
+        }
+        if self.rules.is_empty() {
+            return Err(format!("应用 {} 没有定义任何目录", self.id));
+        }
+
+        let patterns = self
+            .accounts
+            .patterns
+            .iter()
+            .chain(&self.accounts.require_any)
+            .chain(self.rules.iter().flat_map(|r| &r.paths));
+        for pattern in patterns {
+            if !is_relative_pattern(pattern) {
+                return Err(format!(
+                    "应用 {} 的路径必须是不含 .. 的相对路径: {}",
+                    self.id, pattern
+                ));
+            }
+        }
+        if self.rules.iter().any(|r| r.paths.is_empty()) {
+            return Err(format!("应用 {} 的规则缺少目录", self.id));
+        }
+
+        Ok(())
+    }
+
+    /// 定义中出现的清理分类，按首次出现的顺序
+    pub fn categories(&self) -> Vec<CleanCategory> {
+        let mut categories: Vec<CleanCategory> = Vec::new();
+        for rule in &self.rules {
+            if !categories.contains(&rule.category) {
+                categories.push(rule.category.clone());
+            }
+        }
+        categories
+    }
+
+    /// 展开默认根目录中的占位符，无法解析的根目录被忽略
+    pub fn default_roots(&self) -> Vec<PathBuf> {
+        self.roots.iter().filter_map(|r| expand_root(r)).collect()
+    }
+}
+
+fn is_relative_pattern(pattern: &str) -> bool {
+    !pattern.is_empty()
+        && Path::new(pattern)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+type DirResolver = fn() -> Option<PathBuf>;
+
+fn expand_root(root: &str) -> Option<PathBuf> {
+    let placeholders: [(&str, DirResolver); 7] = [
+        ("{home}", dirs::home_dir),
+        ("{config}", dirs::config_dir),
+        ("{data}", dirs::data_dir),
+        ("{cache}", dirs::cache_dir),
+        ("{documents}", dirs::document_dir),
+        ("{appdata}", dirs::data_dir),
+        ("{localappdata}", dirs::data_local_dir),
+    ];
+
+    let mut expanded = root.to_string();
+    for (placeholder, resolve) in placeholders {
+        if expanded.contains(placeholder) {
+            let value = resolve()?;
+            expanded = expanded.replace(placeholder, &value.to_string_lossy());
+        }
+    }
+    if expanded.contains('{') {
+        return None;
+    }
+    Some(PathBuf::from(expanded))
+}
+
+/// 返回给前端的应用定义列表
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppDefinitionList {
+    pub definitions: Vec<AppDefinition>,
+    pub errors: Vec<String>,
+}
+
+/// 已加载的应用定义，保持内置顺序，用户定义追加在后
+#[derive(Debug, Clone, Default)]
+pub struct AppDefinitionRegistry {
+    definitions: Vec<AppDefinition>,
+    /// 加载用户定义时的错误，不影响其他定义
+    pub errors: Vec<String>,
+}
+
+impl AppDefinitionRegistry {
+    pub fn builtin() -> Self {
+        let definitions = BUILTIN_DEFINITIONS
+            .iter()
+            .map(|content| AppDefinition::from_json(content).expect("内置应用定义无效"))
+            .collect();
+        Self {
+            definitions,
+            errors: Vec::new(),
+        }
+    }
+
+    /// 加载内置定义以及用户配置目录中的定义
+    pub fn load() -> Self {
+        let mut registry = Self::builtin();
+        if let Some(dir) = get_default_config_dir() {
+            registry.load_dir(&dir.join(USER_DEFINITIONS_DIR));
+        }
+        registry
+    }
+
+    /// 加载目录中的所有 `.json` 定义，同 id 的定义被替换
+    pub fn load_dir(&mut self, dir: &Path) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().map(|e| e == "json").unwrap_or(false))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let loaded = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| AppDefinition::from_json(&content));
+            match loaded {
+                Ok(definition) => self.insert(definition),
+                Err(e) => {
+                    log::warn!("Skipping app definition {}: {}", path.display(), e);
+                    self.errors.push(format!("{}: {}", path.display(), e));
+                }
+            }
+        }
+    }
+
+    pub fn insert(&mut self, definition: AppDefinition) {
+        match self.definitions.iter_mut().find(|d| d.id == definition.id) {
+            Some(existing) => *existing = definition,
+            None => self.definitions.push(definition),
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&AppDefinition> {
+        let id = id.to_lowercase();
+        self.definitions.iter().find(|d| d.id == id)
+    }
+
+    pub fn definitions(&self) -> &[AppDefinition] {
+        &self.definitions
+    }
+
+    pub fn ids(&self) -> Vec<String> {
+        self.definitions.iter().map(|d| d.id.clone()).collect()
+    }
+
+    pub fn to_list(&self) -> AppDefinitionList {
+        AppDefinitionList {
+            definitions: self.definitions.clone(),
+            errors: self.errors.clone(),
+        }
+    }
+}
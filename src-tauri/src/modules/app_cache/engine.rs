@@ -0,0 +1,397 @@
+//! 应用缓存扫描引擎
+//!
+//! 按应用定义查找账号目录并收集各分类的文件，不包含任何应用专属的逻辑。
+//! 扫描是同步的，调用方通过回调获取进度并决定是否继续。
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use uuid::Uuid;
+
+use super::definition::{AppDefinition, CacheRule, EncryptedFormat};
+use super::scanner::{AppCacheFile, CleanCategory};
+use crate::utils::{should_skip_file, PathSource, ResolvedAppPath, WeChatDatDecoder};
+
+/// 回调的返回值，`Stop` 时扫描立即结束
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanControl {
+    Continue,
+    Stop,
+}
+
+/// 每进入一个目录或收集到一个文件时调用，参数为当前路径与已收集的文件
+pub type ScanCallback<'a> = dyn FnMut(&Path, &[AppCacheFile]) -> ScanControl + 'a;
+
+/// 文件所属的账号与会话
+struct FileOwner<'a> {
+    account_id: Option<&'a str>,
+    account_path: &'a Path,
+    /// 会话标识，规则中没有会话目录时为账号名
+    chat_object: &'a str,
+}
+
+pub struct AppCacheEngine<'a> {
+    definition: &'a AppDefinition,
+    categories: &'a [CleanCategory],
+    /// 增量扫描时只收集此时间（Unix 秒）之后修改的文件
+    last_scan_timestamp: u64,
+}
+
+impl<'a> AppCacheEngine<'a> {
+    pub fn new(definition: &'a AppDefinition, categories: &'a [CleanCategory]) -> Self {
+        Self {
+            definition,
+            categories,
+            last_scan_timestamp: 0,
+        }
+    }
+
+    pub fn with_last_scan(mut self, last_scan_timestamp: u64) -> Self {
+        self.last_scan_timestamp = last_scan_timestamp;
+        self
+    }
+
+    /// 解析要扫描的账号目录
+    ///
+    /// 用户配置了路径时只使用该路径（不存在时不扫描），否则依次尝试定义中的
+    /// 默认根目录。多个根目录通过符号链接指向同一位置时只保留第一个。
+    pub fn resolve(&self, configured: Option<&Path>) -> Vec<ResolvedAppPath> {
+        match configured {
+            Some(root) if root.exists() => self.resolve_accounts(root, PathSource::UserConfig),
+            Some(_) => Vec::new(),
+            None => {
+                let mut seen: HashSet<PathBuf> = HashSet::new();
+                self.definition
+                    .default_roots()
+                    .iter()
+                    .filter(|root| root.exists())
+                    .flat_map(|root| self.resolve_accounts(root, PathSource::Default))
+                    .filter(|account| {
+                        let key = fs::canonicalize(&account.path)
+                            .unwrap_or_else(|_| account.path.clone());
+                        seen.insert(key)
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// 按定义中的账号模式查找根目录下的账号目录
+    pub fn resolve_accounts(&self, root: &Path, source: PathSource) -> Vec<ResolvedAppPath> {
+        let discovery = &self.definition.accounts;
+        if discovery.patterns.is_empty() {
+            return vec![ResolvedAppPath {
+                path: root.to_path_buf(),
+                source,
+                account_id: None,
+            }];
+        }
+
+        let mut accounts: Vec<PathBuf> = discovery
+            .patterns
+            .iter()
+            .flat_map(|pattern| expand_pattern(root, pattern))
+            .filter(|path| path.is_dir())
+            .filter(|path| {
+                let name = file_name(path);
+                !discovery
+                    .exclude
+                    .iter()
+                    .any(|pattern| glob_match::glob_match(pattern, &name))
+            })
+            .filter(|path| {
+                discovery.require_any.is_empty()
+                    || discovery
+                        .require_any
+                        .iter()
+                        .any(|marker| !expand_pattern(path, marker).is_empty())
+            })
+            .collect();
+        accounts.sort();
+        accounts.dedup();
+
+        accounts
+            .into_iter()
+            .map(|path| {
+                let account_id = path
+                    .strip_prefix(root)
+                    .map(|p| p.to_string_lossy().replace('\\', "/"))
+                    .unwrap_or_else(|_| file_name(&path));
+                ResolvedAppPath {
+                    path,
+                    source: source.clone(),
+                    account_id: Some(account_id),
+                }
+            })
+            .collect()
+    }
+
+    /// 扫描一个账号目录下所选分类的文件
+    pub fn scan_account(
+        &self,
+        account: &ResolvedAppPath,
+        files: &mut Vec<AppCacheFile>,
+        skipped_files: &mut u64,
+        callback: &mut ScanCallback,
+    ) -> ScanControl {
+        let account_name = account
+            .account_id
+            .clone()
+            .or_else(|| {
+                account
+                    .path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+            })
+            .unwrap_or_else(|| "未知".to_string());
+
+        // 会话目录通常位于更宽泛的目录之下，先出现的规则已收集的文件不再重复记入
+        let mut seen: HashSet<String> = HashSet::new();
+        for rule in &self.definition.rules {
+            if !self.categories.contains(&rule.category) {
+                continue;
+            }
+            for pattern in &rule.paths {
+                for (dir, conversation) in expand_with_conversation(&account.path, pattern) {
+                    if !dir.is_dir() {
+                        continue;
+                    }
+                    let owner = FileOwner {
+                        account_id: account.account_id.as_deref(),
+                        account_path: &account.path,
+                        chat_object: conversation.as_deref().unwrap_or(&account_name),
+                    };
+                    let start = files.len();
+                    let control = self.scan_dir(&dir, rule, &owner, files, skipped_files, callback);
+                    let collected = files.split_off(start);
+                    files.extend(collected.into_iter().filter(|f| seen.insert(f.path.clone())));
+                    if control == ScanControl::Stop {
+                        return ScanControl::Stop;
+                    }
+                }
+            }
+        }
+
+        ScanControl::Continue
+    }
+
+    fn scan_dir(
+        &self,
+        dir: &Path,
+        rule: &CacheRule,
+        owner: &FileOwner,
+        files: &mut Vec<AppCacheFile>,
+        skipped_files: &mut u64,
+        callback: &mut ScanCallback,
+    ) -> ScanControl {
+        let mut dirs_to_scan = vec![dir.to_path_buf()];
+
+        while let Some(current_dir) = dirs_to_scan.pop() {
+            if callback(&current_dir, files) == ScanControl::Stop {
+                return ScanControl::Stop;
+            }
+            let Ok(entries) = fs::read_dir(&current_dir) else {
+                continue;
+            };
+
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if should_skip_file(&path) {
+                    continue;
+                }
+                if path.is_dir() {
+                    if rule.recursive {
+                        dirs_to_scan.push(path);
+                    }
+                    continue;
+                }
+                if !path.is_file() || !matches_extension(&path, &rule.extensions) {
+                    continue;
+                }
+                if !modified_since(&path, self.last_scan_timestamp) {
+                    *skipped_files += 1;
+                    continue;
+                }
+
+                if let Some(file) = self.create_file(&path, rule, owner) {
+                    files.push(file);
+                    if callback(&path, files) == ScanControl::Stop {
+                        return ScanControl::Stop;
+                    }
+                }
+            }
+        }
+
+        ScanControl::Continue
+    }
+
+    fn create_file(
+        &self,
+        path: &Path,
+        rule: &CacheRule,
+        owner: &FileOwner,
+    ) -> Option<AppCacheFile> {
+        let mut file = create_app_cache_file(
+            path,
+            rule.category.clone(),
+            &self.definition.id,
+            owner.chat_object,
+        )?;
+        file.account_id = owner.account_id.map(str::to_string);
+        file.account_path = Some(owner.account_path.to_string_lossy().to_string());
+        file.risk_level = rule.risk.clone();
+
+        match rule.encrypted {
+            Some(EncryptedFormat::WechatDat) => {
+                file.is_encrypted = true;
+                match WeChatDatDecoder::inspect_dat_file(path) {
+                    Ok(info) => {
+                        file.original_format = Some(info.original_format.extension().to_string())
+                    }
+                    Err(reason) => file.decode_error = Some(reason),
+                }
+            }
+            None => {}
+        }
+
+        Some(file)
+    }
+}
+
+/// 目录模式中表示会话目录的段，匹配任意目录名并把目录名记录为会话标识
+pub const CONVERSATION_SEGMENT: &str = "{conversation}";
+
+/// 展开按 `/` 分段的模式，返回存在的路径（按路径排序）
+pub fn expand_pattern(base: &Path, pattern: &str) -> Vec<PathBuf> {
+    expand_with_conversation(base, pattern)
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect()
+}
+
+/// 展开模式，同时返回 `{conversation}` 段匹配到的目录名
+pub fn expand_with_conversation(base: &Path, pattern: &str) -> Vec<(PathBuf, Option<String>)> {
+    let mut current: Vec<(PathBuf, Option<String>)> = vec![(base.to_path_buf(), None)];
+
+    for segment in pattern.split('/').filter(|s| !s.is_empty() && *s != ".") {
+        let capture = segment == CONVERSATION_SEGMENT;
+        let glob = if capture { "*" } else { segment };
+        let mut next = Vec::new();
+        for (dir, conversation) in &current {
+            if is_glob(glob) {
+                let Ok(entries) = fs::read_dir(dir) else {
+                    continue;
+                };
+                for entry in entries.flatten() {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    if !glob_match::glob_match(glob, &name) {
+                        continue;
+                    }
+                    if capture && !entry.path().is_dir() {
+                        continue;
+                    }
+                    let conversation = if capture {
+                        Some(name)
+                    } else {
+                        conversation.clone()
+                    };
+                    next.push((entry.path(), conversation));
+                }
+            } else {
+                let path = dir.join(segment);
+                if fs::symlink_metadata(&path).is_ok() {
+                    next.push((path, conversation.clone()));
+                }
+            }
+        }
+        current = next;
+    }
+
+    current.sort();
+    current
+}
+
+fn is_glob(segment: &str) -> bool {
+    segment.contains(['*', '?', '['])
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn matches_extension(path: &Path, extensions: &[String]) -> bool {
+    if extensions.is_empty() {
+        return true;
+    }
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|s| s.to_lowercase())
+        .unwrap_or_default();
+    extensions.iter().any(|e| e.eq_ignore_ascii_case(&ext))
+}
+
+fn modified_since(path: &Path, last_scan_timestamp: u64) -> bool {
+    if last_scan_timestamp == 0 {
+        return true;
+    }
+
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() >= last_scan_timestamp)
+        .unwrap_or(true)
+}
+
+fn create_app_cache_file(
+    path: &Path,
+    category: CleanCategory,
+    app: &str,
+    chat_object: &str,
+) -> Option<AppCacheFile> {
+    let metadata = fs::metadata(path).ok()?;
+
+    let size = metadata.len();
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+
+    let modified_at = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0);
+
+    let created_at = metadata
+        .created()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(modified_at);
+
+    Some(AppCacheFile {
+        id: Uuid::new_v4().to_string(),
+        path: path.to_string_lossy().to_string(),
+        name,
+        size,
+        category,
+        app: app.to_string(),
+        chat_object: chat_object.to_string(),
+        account_id: None,
+        account_path: None,
+        created_at,
+        modified_at,
+        selected: false,
+        is_encrypted: false,
+        original_format: None,
+        decode_error: None,
+        risk_level: None,
+    })
+}
@@ -0,0 +1,592 @@
+//! 批量导出微信图片
+//!
+//! 清理聊天图片之前，把选中的文件解码后保存到指定目录，按
+//! `应用/账号/会话/年-月` 组织。`.dat` 文件按检测到的格式解码，其他文件原样
+//! 复制，导出的文件保留原修改时间。新版容器缺少密钥时只导出未加密部分中
+//! 能识别的图片，并在清单中标记。
+//!
+//! 导出目录中的 `disktidy-export-manifest.json` 记录每个导出文件的来源与
+//! SHA-256，再次导出到同一目录时内容相同的图片会被跳过。解码按批并行进行，
+//! 每批之间检查暂停与取消，进度通过扫描框架发送。
+
+use chrono::{Local, TimeZone};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+use super::retention::{file_directory_date, now_millis};
+use super::scanner::AppCacheFile;
+use crate::models::{CleanError, DiskTidyError, ScanStatus};
+use crate::modules::scanner_framework::{
+    ControlAction, ScanContext, ScanManager, ScanProgress as ScanProgressTrait,
+};
+use crate::utils::{DatKeys, WeChatDatDecoder};
+
+pub const EVENT_IMAGE_EXPORT_PROGRESS: &str = "app_cache:export_progress";
+pub const EVENT_IMAGE_EXPORT_COMPLETE: &str = "app_cache:export_complete";
+
+/// 导出目录中的清单文件名
+pub const EXPORT_MANIFEST_FILE: &str = "disktidy-export-manifest.json";
+const MANIFEST_VERSION: u32 = 1;
+/// 每批并行解码的文件数，批与批之间检查暂停与取消
+const EXPORT_BATCH_SIZE: usize = 32;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageExportOptions {
+    /// 导出目录，不存在时创建
+    pub destination: String,
+    pub files: Vec<AppCacheFile>,
+}
+
+/// 清单中的一条记录
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportManifestEntry {
+    pub source: String,
+    /// 相对导出目录的路径，以 `/` 分隔
+    pub output: String,
+    pub sha256: String,
+    pub size: u64,
+    /// 加密部分无法解密，只导出了未加密的部分
+    #[serde(default)]
+    pub partial: bool,
+    pub app: String,
+    pub account_id: Option<String>,
+    pub conversation: String,
+    /// 原文件的修改时间（毫秒时间戳）
+    pub modified_at: i64,
+    pub exported_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportManifest {
+    pub version: u32,
+    pub updated_at: i64,
+    pub entries: Vec<ExportManifestEntry>,
+}
+
+impl Default for ExportManifest {
+    fn default() -> Self {
+        Self {
+            version: MANIFEST_VERSION,
+            updated_at: 0,
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl ExportManifest {
+    /// 读取导出目录中的清单，不存在时返回空清单
+    pub fn load(destination: &Path) -> io::Result<Self> {
+        let path = destination.join(EXPORT_MANIFEST_FILE);
+        match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("导出清单无法解析: {}: {}", path.display(), e),
+                )
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// 先写入临时文件再替换，避免中断时留下不完整的清单
+    pub fn save(&self, destination: &Path) -> io::Result<PathBuf> {
+        let path = destination.join(EXPORT_MANIFEST_FILE);
+        let temp = destination.join(format!("{}.tmp", EXPORT_MANIFEST_FILE));
+        let content = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(&temp, content)?;
+        fs::rename(&temp, &path)?;
+        Ok(path)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageExportProgress {
+    pub scan_id: String,
+    pub current_path: String,
+    pub total_files: u64,
+    pub processed_files: u64,
+    pub exported_files: u64,
+    pub skipped_files: u64,
+    pub failed_files: u64,
+    pub percent: f32,
+    pub status: ScanStatus,
+}
+
+impl ImageExportProgress {
+    pub fn new(scan_id: &str, total_files: u64) -> Self {
+        Self {
+            scan_id: scan_id.to_string(),
+            current_path: String::new(),
+            total_files,
+            processed_files: 0,
+            exported_files: 0,
+            skipped_files: 0,
+            failed_files: 0,
+            percent: 0.0,
+            status: ScanStatus::Scanning,
+        }
+    }
+}
+
+impl ScanProgressTrait for ImageExportProgress {
+    fn set_status(&mut self, status: ScanStatus) {
+        self.status = status;
+    }
+
+    fn event_name() -> &'static str {
+        EVENT_IMAGE_EXPORT_PROGRESS
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageExportResult {
+    pub scan_id: String,
+    pub destination: String,
+    pub manifest_path: String,
+    pub exported_files: u64,
+    pub exported_size: u64,
+    /// 内容与已导出文件相同而跳过的文件数
+    pub skipped_files: u64,
+    pub errors: Vec<CleanError>,
+    pub cancelled: bool,
+    pub duration_ms: u64,
+}
+
+/// 导出过程中的统计
+#[derive(Debug, Clone, Default)]
+pub struct ExportStats {
+    pub current_path: String,
+    pub total_files: u64,
+    pub processed_files: u64,
+    pub exported_files: u64,
+    pub skipped_files: u64,
+    pub failed_files: u64,
+}
+
+/// 一次导出的结果
+#[derive(Debug, Clone, Default)]
+pub struct ExportSummary {
+    pub manifest_path: PathBuf,
+    pub exported_files: u64,
+    pub exported_size: u64,
+    pub skipped_files: u64,
+    pub errors: Vec<CleanError>,
+    pub cancelled: bool,
+}
+
+/// 解码后的文件内容
+struct DecodedImage {
+    data: Vec<u8>,
+    extension: String,
+    sha256: String,
+    partial: bool,
+}
+
+pub struct ImageExporter {
+    destination: PathBuf,
+}
+
+impl ImageExporter {
+    pub fn new(destination: impl Into<PathBuf>) -> Self {
+        Self {
+            destination: destination.into(),
+        }
+    }
+
+    /// 文件导出后所在的目录，相对导出目录
+    pub fn output_dir(file: &AppCacheFile) -> PathBuf {
+        let account = file.account_id.as_deref().unwrap_or("");
+        [
+            file.app.as_str(),
+            account,
+            file.chat_object.as_str(),
+            &export_month(file),
+        ]
+        .iter()
+        .map(|segment| sanitize_segment(segment))
+        .collect()
+    }
+
+    /// 执行导出，阻塞直到完成或取消
+    ///
+    /// `paused` 为真时在批与批之间等待；`on_progress` 在每处理完一个文件后调用。
+    pub fn run(
+        &self,
+        files: &[AppCacheFile],
+        cancelled: &AtomicBool,
+        paused: &AtomicBool,
+        on_progress: &mut dyn FnMut(&ExportStats),
+    ) -> io::Result<ExportSummary> {
+        fs::create_dir_all(&self.destination)?;
+        let mut manifest = ExportManifest::load(&self.destination)?;
+        let mut exported: HashSet<String> =
+            manifest.entries.iter().map(|e| e.sha256.clone()).collect();
+
+        let mut summary = ExportSummary::default();
+        let mut stats = ExportStats {
+            total_files: files.len() as u64,
+            ..Default::default()
+        };
+
+        for batch in files.chunks(EXPORT_BATCH_SIZE) {
+            while paused.load(Ordering::Relaxed) && !cancelled.load(Ordering::Relaxed) {
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            if cancelled.load(Ordering::Relaxed) {
+                summary.cancelled = true;
+                break;
+            }
+
+            let decoded: Vec<Result<DecodedImage, DiskTidyError>> =
+                batch.par_iter().map(decode_file).collect();
+
+            for (file, decoded) in batch.iter().zip(decoded) {
+                stats.current_path = file.path.clone();
+                stats.processed_files += 1;
+
+                let result = decoded.and_then(|image| {
+                    if exported.contains(&image.sha256) {
+                        return Ok(None);
+                    }
+                    let entry = self.write_image(file, &image)?;
+                    exported.insert(entry.sha256.clone());
+                    Ok(Some(entry))
+                });
+
+                match result {
+                    Ok(Some(entry)) => {
+                        stats.exported_files += 1;
+                        summary.exported_files += 1;
+                        summary.exported_size += entry.size;
+                        manifest.entries.push(entry);
+                    }
+                    Ok(None) => {
+                        stats.skipped_files += 1;
+                        summary.skipped_files += 1;
+                    }
+                    Err(error) => {
+                        log::warn!("Failed to export {}: {}", file.path, error);
+                        stats.failed_files += 1;
+                        summary.errors.push(CleanError {
+                            path: file.path.clone(),
+                            error_code: error.error_code().to_string(),
+                            error_message: error.to_string(),
+                            processes: Vec::new(),
+                        });
+                    }
+                }
+                on_progress(&stats);
+            }
+        }
+
+        manifest.updated_at = now_millis();
+        summary.manifest_path = manifest.save(&self.destination)?;
+        Ok(summary)
+    }
+
+    fn write_image(
+        &self,
+        file: &AppCacheFile,
+        image: &DecodedImage,
+    ) -> Result<ExportManifestEntry, DiskTidyError> {
+        let relative_dir = Self::output_dir(file);
+        let dir = self.destination.join(&relative_dir);
+        fs::create_dir_all(&dir)?;
+
+        let stem = Path::new(&file.name)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| file.name.clone());
+        let name = unique_name(&dir, &sanitize_segment(&stem), &image.extension);
+        let target = dir.join(&name);
+        fs::write(&target, &image.data)?;
+
+        let modified = source_modified(file);
+        if let Err(e) = File::options()
+            .write(true)
+            .open(&target)
+            .and_then(|f| f.set_modified(modified))
+        {
+            log::warn!("Failed to set modified time of {}: {}", target.display(), e);
+        }
+
+        let output = relative_dir
+            .join(&name)
+            .to_string_lossy()
+            .replace('\\', "/");
+        Ok(ExportManifestEntry {
+            source: file.path.clone(),
+            output,
+            sha256: image.sha256.clone(),
+            size: image.data.len() as u64,
+            partial: image.partial,
+            app: file.app.clone(),
+            account_id: file.account_id.clone(),
+            conversation: file.chat_object.clone(),
+            modified_at: file.modified_at,
+            exported_at: now_millis(),
+        })
+    }
+}
+
+/// 读取并解码文件，加密的 `.dat` 按检测到的格式解码，其他文件保留原内容
+fn decode_file(file: &AppCacheFile) -> Result<DecodedImage, DiskTidyError> {
+    let path = Path::new(&file.path);
+    let (data, extension, partial) = if file.is_encrypted {
+        let decoded = WeChatDatDecoder::decode_file(path, &DatKeys::default()).map_err(|e| {
+            DiskTidyError::ImageDecodeFailed {
+                path: file.path.clone(),
+                reason: e.to_string(),
+            }
+        })?;
+        (
+            decoded.data,
+            decoded.format.extension().to_string(),
+            decoded.partial,
+        )
+    } else {
+        let extension = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        (fs::read(path)?, extension, false)
+    };
+
+    let sha256 = format!("{:x}", Sha256::digest(&data));
+    Ok(DecodedImage {
+        data,
+        extension,
+        sha256,
+        partial,
+    })
+}
+
+/// 文件所属的月份，优先使用目录名中的日期
+fn export_month(file: &AppCacheFile) -> String {
+    let millis = file_directory_date(file).unwrap_or(file.modified_at);
+    Local
+        .timestamp_millis_opt(millis)
+        .single()
+        .map(|t| t.format("%Y-%m").to_string())
+        .unwrap_or_else(|| "未知日期".to_string())
+}
+
+/// 原文件的修改时间，读取失败时使用扫描时记录的时间
+fn source_modified(file: &AppCacheFile) -> SystemTime {
+    fs::metadata(&file.path)
+        .and_then(|m| m.modified())
+        .unwrap_or_else(|_| UNIX_EPOCH + Duration::from_millis(file.modified_at.max(0) as u64))
+}
+
+/// 把名称中不能用于文件名的字符替换为 `_`
+fn sanitize_segment(segment: &str) -> String {
+    let sanitized: String = segment
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let trimmed = sanitized.trim_matches(|c: char| c == '.' || c.is_whitespace());
+    if trimmed.is_empty() {
+        "未知".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// 目标目录中已存在同名文件时追加序号
+fn unique_name(dir: &Path, stem: &str, extension: &str) -> String {
+    let with_extension = |name: String| {
+        if extension.is_empty() {
+            name
+        } else {
+            format!("{}.{}", name, extension)
+        }
+    };
+
+    let mut name = with_extension(stem.to_string());
+    let mut index = 1;
+    while dir.join(&name).exists() {
+        name = with_extension(format!("{}-{}", stem, index));
+        index += 1;
+    }
+    name
+}
+
+lazy_static::lazy_static! {
+    static ref EXPORT_MANAGER: ScanManager<ImageExportProgress, ImageExportResult> = ScanManager::new();
+}
+
+pub async fn start_export(app: AppHandle, options: ImageExportOptions) -> Result<String, String> {
+    if options.destination.trim().is_empty() {
+        return Err("导出目录不能为空".to_string());
+    }
+    let scan_id = crate::models::generate_scan_id();
+    let progress = ImageExportProgress::new(&scan_id, options.files.len() as u64);
+    EXPORT_MANAGER
+        .start_scan_with_id(app, scan_id, progress, move |mut ctx| async move {
+            perform_export(&mut ctx, options).await
+        })
+        .await
+}
+
+pub async fn pause_export(scan_id: &str) -> Result<(), String> {
+    EXPORT_MANAGER.pause_scan(scan_id).await
+}
+
+pub async fn resume_export(scan_id: &str) -> Result<(), String> {
+    EXPORT_MANAGER.resume_scan(scan_id).await
+}
+
+pub async fn cancel_export(scan_id: &str) -> Result<(), String> {
+    EXPORT_MANAGER.cancel_scan(scan_id).await
+}
+
+pub async fn get_progress(scan_id: &str) -> Option<ImageExportProgress> {
+    EXPORT_MANAGER.get_progress(scan_id).await
+}
+
+pub async fn get_result(scan_id: &str) -> Option<ImageExportResult> {
+    EXPORT_MANAGER.get_result(scan_id).await
+}
+
+pub async fn clear_export(scan_id: &str) -> Result<(), String> {
+    EXPORT_MANAGER.clear_scan(scan_id).await
+}
+
+async fn perform_export(
+    ctx: &mut ScanContext<ImageExportProgress>,
+    options: ImageExportOptions,
+) -> Result<ImageExportResult, String> {
+    let start_instant = Instant::now();
+    let is_paused = Arc::new(AtomicBool::new(false));
+    let is_cancelled = Arc::new(AtomicBool::new(false));
+
+    let scan_id = ctx.scan_id.clone();
+    let destination = options.destination.clone();
+    let progress_store = EXPORT_MANAGER.get_progress_store();
+
+    let handle = {
+        let is_paused = is_paused.clone();
+        let is_cancelled = is_cancelled.clone();
+        let progress_store = progress_store.clone();
+        let scan_id = scan_id.clone();
+        let app = ctx.app.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let exporter = ImageExporter::new(&options.destination);
+            let mut last_update = Instant::now();
+            let update_interval = Duration::from_millis(200);
+
+            let mut on_progress = |stats: &ExportStats| {
+                let now = Instant::now();
+                if now.duration_since(last_update) < update_interval {
+                    return;
+                }
+                last_update = now;
+
+                let progress = ImageExportProgress {
+                    scan_id: scan_id.clone(),
+                    current_path: stats.current_path.clone(),
+                    total_files: stats.total_files,
+                    processed_files: stats.processed_files,
+                    exported_files: stats.exported_files,
+                    skipped_files: stats.skipped_files,
+                    failed_files: stats.failed_files,
+                    percent: if stats.total_files > 0 {
+                        (stats.processed_files as f64 / stats.total_files as f64 * 100.0) as f32
+                    } else {
+                        0.0
+                    },
+                    status: ScanStatus::Scanning,
+                };
+                let _ = app.emit(EVENT_IMAGE_EXPORT_PROGRESS, &progress);
+
+                if let Ok(mut store) = progress_store.try_write() {
+                    if let Some(p) = store.get_mut(&scan_id) {
+                        let status = p.status.clone();
+                        *p = ImageExportProgress { status, ..progress };
+                    }
+                }
+            };
+
+            exporter.run(&options.files, &is_cancelled, &is_paused, &mut on_progress)
+        })
+    };
+
+    loop {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        if ctx.check_control(&progress_store).await == ControlAction::Cancel {
+            // 等待导出线程写完当前批次和清单后再返回
+            is_cancelled.store(true, Ordering::Relaxed);
+        }
+
+        let paused = {
+            let store = progress_store.read().await;
+            store
+                .get(&scan_id)
+                .map(|p| p.status == ScanStatus::Paused)
+                .unwrap_or(false)
+        };
+        is_paused.store(paused, Ordering::Relaxed);
+
+        if handle.is_finished() {
+            break;
+        }
+    }
+
+    let summary = handle
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("Image export failed: {}", e))?;
+
+    let result = ImageExportResult {
+        scan_id: scan_id.clone(),
+        destination,
+        manifest_path: summary.manifest_path.to_string_lossy().to_string(),
+        exported_files: summary.exported_files,
+        exported_size: summary.exported_size,
+        skipped_files: summary.skipped_files,
+        errors: summary.errors,
+        cancelled: summary.cancelled,
+        duration_ms: start_instant.elapsed().as_millis() as u64,
+    };
+
+    {
+        let mut store = progress_store.write().await;
+        if let Some(p) = store.get_mut(&scan_id) {
+            p.exported_files = result.exported_files;
+            p.skipped_files = result.skipped_files;
+            p.failed_files = result.errors.len() as u64;
+            p.status = if result.cancelled {
+                ScanStatus::Cancelled
+            } else {
+                p.processed_files = p.total_files;
+                p.percent = 100.0;
+                ScanStatus::Completed
+            };
+        }
+    }
+
+    let _ = ctx.app.emit(EVENT_IMAGE_EXPORT_COMPLETE, &result);
+
+    Ok(result)
+}
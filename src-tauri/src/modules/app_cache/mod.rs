@@ -1,3 +1,14 @@
+pub mod conversation;
+pub mod definition;
+pub mod engine;
+pub mod export;
+pub mod retention;
 pub mod scanner;
 
+pub use conversation::{
+    conversation_files, summarize_conversations, ConversationCategoryStats, ConversationStats,
+    ConversationSummary,
+};
+pub use definition::{AppDefinition, AppDefinitionList, AppDefinitionRegistry};
+pub use engine::{AppCacheEngine, ScanControl};
 pub use scanner::*;
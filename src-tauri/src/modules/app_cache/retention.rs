@@ -0,0 +1,486 @@
+//! 聊天媒体的保留策略
+//!
+//! 策略按应用、分类和会话匹配文件，超过保留天数的文件按策略删除、移入回收站
+//! 或归档。策略按顺序匹配，每个文件只归属第一条匹配的策略；保留天数为空的
+//! 策略表示永久保留，可用于"从不清理文档"这类规则。
+//!
+//! 文件的时间优先取所在目录名中的日期（如 `2024-05`、`2024-05-17`），这是
+//! 应用收到消息的时间，不会因复制或同步而改变；目录名中没有日期时使用修改
+//! 时间。目录日期按该时间段的最后一刻计算，避免提前清理。
+
+use chrono::{Datelike, Local, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+use super::definition::AppDefinitionRegistry;
+use super::engine::{AppCacheEngine, ScanControl};
+use super::scanner::{AppCacheFile, CleanCategory};
+use crate::models::{ArchiveOptions, CleanError, CleanOptions};
+use crate::modules::cleaner::CleanerExecutor;
+use crate::utils::{get_app_paths_config, get_default_config_dir};
+
+const RETENTION_CONFIG_FILE: &str = "retention_policies.json";
+const MILLIS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+/// 定时任务检查是否到期的间隔
+const SCHEDULER_CHECK_INTERVAL_SECS: u64 = 15 * 60;
+
+pub const EVENT_RETENTION_APPLIED: &str = "app_cache:retention_applied";
+
+/// 超过保留期的文件的处理方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RetentionAction {
+    Delete,
+    MoveToRecycleBin,
+    Archive,
+}
+
+impl RetentionAction {
+    pub fn display_name(&self) -> &str {
+        match self {
+            Self::Delete => "永久删除",
+            Self::MoveToRecycleBin => "移入回收站",
+            Self::Archive => "归档",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionPolicy {
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// 应用 id，为空时匹配全部应用
+    #[serde(default)]
+    pub apps: Vec<String>,
+    /// 为空时匹配全部分类
+    #[serde(default)]
+    pub categories: Vec<CleanCategory>,
+    /// 会话标识，为空时匹配全部会话
+    #[serde(default)]
+    pub conversations: Vec<String>,
+    /// 保留最近多少天的文件，为空时永久保留
+    pub keep_days: Option<u32>,
+    pub action: RetentionAction,
+    /// 归档时的选项，`action` 为 `Archive` 时必须提供
+    #[serde(default)]
+    pub archive: Option<ArchiveOptions>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl RetentionPolicy {
+    pub fn matches(&self, file: &AppCacheFile) -> bool {
+        self.enabled
+            && (self.apps.is_empty() || self.apps.iter().any(|a| a.eq_ignore_ascii_case(&file.app)))
+            && (self.categories.is_empty() || self.categories.contains(&file.category))
+            && (self.conversations.is_empty() || self.conversations.contains(&file.chat_object))
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.action == RetentionAction::Archive
+            && self
+                .archive
+                .as_ref()
+                .map(|a| a.destination.is_empty())
+                .unwrap_or(true)
+        {
+            return Err(format!("策略 {} 未指定归档位置", self.name));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionSchedule {
+    pub enabled: bool,
+    pub interval_hours: u32,
+    /// 上次执行的时间（Unix 秒）
+    #[serde(default)]
+    pub last_run_at: Option<i64>,
+}
+
+impl Default for RetentionSchedule {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: 24,
+            last_run_at: None,
+        }
+    }
+}
+
+impl RetentionSchedule {
+    pub fn is_due(&self, now_secs: i64) -> bool {
+        if !self.enabled || self.interval_hours == 0 {
+            return false;
+        }
+        match self.last_run_at {
+            Some(last) => now_secs - last >= i64::from(self.interval_hours) * 3600,
+            None => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionConfig {
+    pub policies: Vec<RetentionPolicy>,
+    #[serde(default)]
+    pub schedule: RetentionSchedule,
+}
+
+impl RetentionConfig {
+    fn config_file() -> Option<PathBuf> {
+        get_default_config_dir().map(|d| d.join(RETENTION_CONFIG_FILE))
+    }
+
+    pub fn load() -> Self {
+        Self::config_file()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        for policy in &self.policies {
+            policy.validate()?;
+        }
+        let Some(path) = Self::config_file() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("创建配置目录失败: {}", e))?;
+        }
+        let content =
+            serde_json::to_string_pretty(self).map_err(|e| format!("序列化保留策略失败: {}", e))?;
+        fs::write(&path, content).map_err(|e| format!("保存保留策略失败: {}", e))
+    }
+}
+
+/// 计算文件年龄所依据的时间
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AgeSource {
+    DirectoryDate,
+    ModifiedTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionCandidate {
+    pub file: AppCacheFile,
+    /// 计算年龄所用的时间（毫秒时间戳）
+    pub reference_time: i64,
+    pub age_source: AgeSource,
+    pub age_days: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionPolicyPreview {
+    pub policy_id: String,
+    pub policy_name: String,
+    pub action: RetentionAction,
+    pub file_count: u64,
+    pub total_size: u64,
+    pub files: Vec<RetentionCandidate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionPreview {
+    /// 与策略顺序一致
+    pub policies: Vec<RetentionPolicyPreview>,
+    /// 未到期、被永久保留策略匹配或没有匹配任何策略的文件
+    pub kept_files: u64,
+    pub kept_size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionApplyResult {
+    pub policy_id: String,
+    pub action: RetentionAction,
+    pub processed_files: u64,
+    pub processed_size: u64,
+    /// 归档时生成的归档文件
+    pub archive_path: Option<String>,
+    pub errors: Vec<CleanError>,
+}
+
+/// 从路径中的目录名解析日期，返回该时间段最后一刻的毫秒时间戳
+///
+/// 支持 `YYYY-MM` 与 `YYYY-MM-DD`，从最近的目录开始查找，只查找 `root` 之下的
+/// 目录，账号目录及其上级目录的名称不作为日期。不识别不带连字符的纯数字目录名，
+/// 以免把 QQ 号之类的目录当作日期。
+pub fn directory_date(path: &Path, root: &Path) -> Option<i64> {
+    path.parent()?
+        .ancestors()
+        .take_while(|dir| *dir != root && dir.starts_with(root))
+        .filter_map(|dir| dir.file_name())
+        .find_map(|name| parse_period_end(&name.to_string_lossy()))
+}
+
+/// 文件所在目录的日期，没有记录账号目录时不使用目录日期
+pub fn file_directory_date(file: &AppCacheFile) -> Option<i64> {
+    let root = file.account_path.as_deref()?;
+    directory_date(Path::new(&file.path), Path::new(root))
+}
+
+fn parse_period_end(name: &str) -> Option<i64> {
+    if !name.is_ascii() {
+        return None;
+    }
+    let bytes = name.as_bytes();
+    let digits = match bytes.len() {
+        7 if bytes[4] == b'-' => format!("{}{}", &name[0..4], &name[5..7]),
+        10 if bytes[4] == b'-' && bytes[7] == b'-' => {
+            format!("{}{}{}", &name[0..4], &name[5..7], &name[8..10])
+        }
+        _ => return None,
+    };
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let year: i32 = digits[0..4].parse().ok()?;
+    let month: u32 = digits[4..6].parse().ok()?;
+    if !(2000..=2100).contains(&year) {
+        return None;
+    }
+
+    let next_start = if digits.len() == 8 {
+        let day: u32 = digits[6..8].parse().ok()?;
+        NaiveDate::from_ymd_opt(year, month, day)?.succ_opt()?
+    } else {
+        let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+        if first.month() == 12 {
+            NaiveDate::from_ymd_opt(year + 1, 1, 1)?
+        } else {
+            NaiveDate::from_ymd_opt(year, month + 1, 1)?
+        }
+    };
+    let start = Local
+        .from_local_datetime(&next_start.and_hms_opt(0, 0, 0)?)
+        .earliest()?;
+    Some(start.timestamp_millis() - 1)
+}
+
+/// 文件年龄所依据的时间：目录日期不晚于当前时间时使用目录日期，否则使用修改时间
+pub fn reference_time(file: &AppCacheFile, now_ms: i64) -> (i64, AgeSource) {
+    match file_directory_date(file) {
+        Some(date) if date <= now_ms => (date, AgeSource::DirectoryDate),
+        _ => (file.modified_at, AgeSource::ModifiedTime),
+    }
+}
+
+/// 计算每条策略将处理的文件，不修改任何文件
+pub fn preview_retention(
+    files: &[AppCacheFile],
+    policies: &[RetentionPolicy],
+    now_ms: i64,
+) -> RetentionPreview {
+    let mut previews: Vec<RetentionPolicyPreview> = policies
+        .iter()
+        .map(|p| RetentionPolicyPreview {
+            policy_id: p.id.clone(),
+            policy_name: p.name.clone(),
+            action: p.action,
+            file_count: 0,
+            total_size: 0,
+            files: Vec::new(),
+        })
+        .collect();
+    let mut kept_files = 0;
+    let mut kept_size = 0;
+
+    for file in files {
+        let matched = policies.iter().position(|p| p.matches(file));
+        let candidate = matched.and_then(|index| {
+            let keep_days = policies[index].keep_days?;
+            let (reference, source) = reference_time(file, now_ms);
+            let age_days = (now_ms - reference).max(0) / MILLIS_PER_DAY;
+            (age_days >= i64::from(keep_days)).then_some((
+                index,
+                RetentionCandidate {
+                    file: file.clone(),
+                    reference_time: reference,
+                    age_source: source,
+                    age_days: age_days as u64,
+                },
+            ))
+        });
+
+        match candidate {
+            Some((index, candidate)) => {
+                let preview = &mut previews[index];
+                preview.file_count += 1;
+                preview.total_size += file.size;
+                preview.files.push(candidate);
+            }
+            None => {
+                kept_files += 1;
+                kept_size += file.size;
+            }
+        }
+    }
+
+    RetentionPreview {
+        policies: previews,
+        kept_files,
+        kept_size,
+    }
+}
+
+/// 收集策略涉及的应用中的所有文件
+pub fn collect_policy_files(policies: &[RetentionPolicy]) -> Vec<AppCacheFile> {
+    let registry = AppDefinitionRegistry::load();
+    let paths_config = get_app_paths_config();
+    let enabled: Vec<&RetentionPolicy> = policies.iter().filter(|p| p.enabled).collect();
+    let mut files = Vec::new();
+
+    for definition in registry.definitions() {
+        let relevant = enabled.iter().any(|p| {
+            p.apps.is_empty()
+                || p.apps
+                    .iter()
+                    .any(|a| a.eq_ignore_ascii_case(&definition.id))
+        });
+        if !relevant {
+            continue;
+        }
+        let categories = definition.categories();
+        let engine = AppCacheEngine::new(definition, &categories);
+        let configured = paths_config.get_path(&definition.id);
+        let mut skipped = 0;
+        for account in engine.resolve(configured.as_deref()) {
+            engine.scan_account(&account, &mut files, &mut skipped, &mut |_, _| {
+                ScanControl::Continue
+            });
+        }
+    }
+
+    files
+}
+
+/// 按预览结果处理到期文件
+pub async fn apply_retention(
+    preview: &RetentionPreview,
+    policies: &[RetentionPolicy],
+) -> Vec<RetentionApplyResult> {
+    let mut results = Vec::new();
+
+    for policy_preview in preview.policies.iter().filter(|p| p.file_count > 0) {
+        let Some(policy) = policies.iter().find(|p| p.id == policy_preview.policy_id) else {
+            continue;
+        };
+        let paths: Vec<PathBuf> = policy_preview
+            .files
+            .iter()
+            .map(|c| PathBuf::from(&c.file.path))
+            .collect();
+        let mut result = RetentionApplyResult {
+            policy_id: policy.id.clone(),
+            action: policy.action,
+            processed_files: 0,
+            processed_size: 0,
+            archive_path: None,
+            errors: Vec::new(),
+        };
+
+        match policy.action {
+            RetentionAction::Delete | RetentionAction::MoveToRecycleBin => {
+                let executor = CleanerExecutor::with_options(CleanOptions {
+                    move_to_recycle_bin: policy.action == RetentionAction::MoveToRecycleBin,
+                    ..CleanOptions::default()
+                });
+                match executor.clean(paths).await {
+                    Ok(clean) => {
+                        result.processed_files = clean.cleaned_files;
+                        result.processed_size = clean.cleaned_size;
+                        result.errors = clean.errors;
+                    }
+                    Err(e) => result.errors.push(policy_error(&policy.name, &e)),
+                }
+            }
+            RetentionAction::Archive => {
+                let options = policy.archive.clone().unwrap_or_default();
+                match CleanerExecutor::new().archive(paths, &options).await {
+                    Ok(archive) => {
+                        result.processed_files = archive.archived_files;
+                        result.processed_size = archive.archived_size;
+                        result.archive_path = Some(archive.archive_path);
+                        result.errors = archive.errors;
+                    }
+                    Err(e) => result.errors.push(policy_error(&policy.name, &e)),
+                }
+            }
+        }
+
+        results.push(result);
+    }
+
+    results
+}
+
+fn policy_error(policy_name: &str, error: &crate::models::DiskTidyError) -> CleanError {
+    CleanError {
+        path: policy_name.to_string(),
+        error_code: error.error_code().to_string(),
+        error_message: error.to_string(),
+        processes: Vec::new(),
+    }
+}
+
+pub fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// 扫描并按策略处理到期文件
+pub async fn run_retention(policies: Vec<RetentionPolicy>) -> Vec<RetentionApplyResult> {
+    let scan_policies = policies.clone();
+    let files = tokio::task::spawn_blocking(move || collect_policy_files(&scan_policies))
+        .await
+        .unwrap_or_default();
+    let preview = preview_retention(&files, &policies, now_millis());
+    apply_retention(&preview, &policies).await
+}
+
+/// 启动定时执行保留策略的后台任务
+pub fn spawn_retention_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        // 配置保存失败时仍按内存中的执行时间计算下次执行，避免每次检查都重新执行
+        let mut last_run_at = None;
+        loop {
+            let mut config = RetentionConfig::load();
+            config.schedule.last_run_at = config.schedule.last_run_at.max(last_run_at);
+            let now_secs = now_millis() / 1000;
+            if config.schedule.is_due(now_secs) {
+                log::info!("Running scheduled retention policies");
+                let results = run_retention(config.policies.clone()).await;
+                last_run_at = Some(now_secs);
+                config.schedule.last_run_at = last_run_at;
+                if let Err(e) = config.save() {
+                    log::warn!("Failed to record retention run: {}", e);
+                }
+                let _ = app.emit(EVENT_RETENTION_APPLIED, &results);
+            }
+            tokio::time::sleep(std::time::Duration::from_secs(
+                SCHEDULER_CHECK_INTERVAL_SECS,
+            ))
+            .await;
+        }
+    });
+}
@@ -1,62 +1,22 @@
 use log::{debug, error};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::{Instant, UNIX_EPOCH};
+use std::time::Instant;
 use tauri::{AppHandle, Emitter};
 use tokio::sync::{watch, RwLock};
 use uuid::Uuid;
 
-use crate::utils::WeChatDatDecoder;
-use crate::utils::{
-    get_app_paths_config, get_cache_dir, should_skip_file, AppPathResolver, ResolvedAppPath,
-    ScanCache,
-};
+use super::definition::{AppDefinition, AppDefinitionRegistry};
+use super::engine::{AppCacheEngine, ScanControl};
+use crate::models::RiskLevel;
+use crate::utils::{DatDecodeError, WeChatDatDecoder};
+use crate::utils::{get_app_paths_config, get_cache_dir, ScanCache};
 
 pub const EVENT_APP_CACHE_PROGRESS: &str = "app_cache:progress";
 pub const EVENT_APP_CACHE_COMPLETE: &str = "app_cache:complete";
 
-pub const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "gif", "bmp", "webp"];
-pub const VIDEO_EXTENSIONS: &[&str] = &["mp4", "mov", "avi", "mkv", "wmv", "flv"];
-pub const DOC_EXTENSIONS: &[&str] = &[
-    "pdf", "doc", "docx", "xls", "xlsx", "ppt", "pptx", "txt", "zip", "rar", "7z",
-];
-pub const INSTALL_EXTENSIONS: &[&str] = &["apk", "ipa", "exe", "dmg"];
-pub const VOICE_EXTENSIONS: &[&str] = &["amr", "mp3", "wav"];
-pub const EMOJI_EXTENSIONS: &[&str] = &["gif", "png", "jpg"];
-
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "snake_case")]
-pub enum AppType {
-    Wechat,
-    Dingtalk,
-    Qq,
-    Wework,
-}
-
-impl AppType {
-    pub fn from_str(s: &str) -> Option<Self> {
-        match s.to_lowercase().as_str() {
-            "wechat" => Some(Self::Wechat),
-            "dingtalk" => Some(Self::Dingtalk),
-            "qq" => Some(Self::Qq),
-            "wework" => Some(Self::Wework),
-            _ => None,
-        }
-    }
-
-    pub fn to_str(&self) -> &'static str {
-        match self {
-            Self::Wechat => "wechat",
-            Self::Dingtalk => "dingtalk",
-            Self::Qq => "qq",
-            Self::Wework => "wework",
-        }
-    }
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum CleanCategory {
@@ -69,6 +29,8 @@ pub enum CleanCategory {
     EmojiCache,
     TempFiles,
     ThumbCache,
+    LogFiles,
+    ShaderCache,
 }
 
 impl CleanCategory {
@@ -83,6 +45,8 @@ impl CleanCategory {
             "emoji_cache" => Some(Self::EmojiCache),
             "temp_files" => Some(Self::TempFiles),
             "thumb_cache" => Some(Self::ThumbCache),
+            "log_files" => Some(Self::LogFiles),
+            "shader_cache" => Some(Self::ShaderCache),
             _ => None,
         }
     }
@@ -98,6 +62,8 @@ impl CleanCategory {
             Self::EmojiCache => "emoji_cache",
             Self::TempFiles => "temp_files",
             Self::ThumbCache => "thumb_cache",
+            Self::LogFiles => "log_files",
+            Self::ShaderCache => "shader_cache",
         }
     }
 
@@ -112,6 +78,8 @@ impl CleanCategory {
             Self::EmojiCache => "表情缓存",
             Self::TempFiles => "临时文件",
             Self::ThumbCache => "缩略图缓存",
+            Self::LogFiles => "日志文件",
+            Self::ShaderCache => "着色器缓存",
         }
     }
 
@@ -126,6 +94,8 @@ impl CleanCategory {
             Self::EmojiCache => "表情包缓存文件",
             Self::TempFiles => "临时文件，可安全清理",
             Self::ThumbCache => "图片和视频的缩略图缓存",
+            Self::LogFiles => "应用运行日志，可安全清理",
+            Self::ShaderCache => "游戏和显卡驱动编译的着色器缓存，清理后会重新生成",
         }
     }
 }
@@ -149,9 +119,19 @@ pub struct AppCacheFile {
     pub name: String,
     pub size: u64,
     pub category: CleanCategory,
-    pub app: AppType,
+    /// 应用定义的 id
+    pub app: String,
+    /// 会话标识（如微信 `msg/attach` 下的会话目录名），应用没有按会话存放时为账号名
     pub chat_object: String,
+    /// 所属账号
+    #[serde(default)]
+    pub account_id: Option<String>,
+    /// 所属账号目录，目录名中的日期只在该目录之下识别
+    #[serde(default)]
+    pub account_path: Option<String>,
+    /// 创建时间，Unix 毫秒时间戳
     pub created_at: i64,
+    /// 修改时间，Unix 毫秒时间戳
     pub modified_at: i64,
     #[serde(default)]
     pub selected: bool,
@@ -159,6 +139,19 @@ pub struct AppCacheFile {
     pub is_encrypted: bool,
     #[serde(default)]
     pub original_format: Option<String>,
+    /// 加密文件无法解码的原因
+    #[serde(default)]
+    pub decode_error: Option<DatDecodeError>,
+    /// 应用定义中指定的风险等级，未指定时按分类推断
+    #[serde(default)]
+    pub risk_level: Option<RiskLevel>,
+}
+
+impl AppCacheFile {
+    /// 以秒为单位的修改时间，供与其他分析器的结果比较
+    pub fn modified_secs(&self) -> i64 {
+        self.modified_at / 1000
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -283,22 +276,6 @@ async fn save_scan_cache(cache: &ScanCache) {
     }
 }
 
-fn should_scan_file_by_time(path: &Path, last_scan_timestamp: u64) -> bool {
-    if last_scan_timestamp == 0 {
-        return true;
-    }
-
-    if let Ok(metadata) = fs::metadata(path) {
-        if let Ok(modified) = metadata.modified() {
-            if let Ok(duration) = modified.duration_since(UNIX_EPOCH) {
-                return duration.as_secs() >= last_scan_timestamp;
-            }
-        }
-    }
-
-    true
-}
-
 pub async fn start_app_cache_scan(
     app: AppHandle,
     options: AppCacheScanOptions,
@@ -349,7 +326,6 @@ pub async fn start_app_cache_scan(
 
     Ok(scan_id)
 }
-
 async fn perform_app_cache_scan(
     app: &AppHandle,
     scan_id: &str,
@@ -359,7 +335,6 @@ async fn perform_app_cache_scan(
 ) -> Result<(), String> {
     let start_instant = Instant::now();
     let mut files: Vec<AppCacheFile> = Vec::new();
-    let mut last_update = 0u64;
     let mut skipped_files: u64 = 0;
 
     debug!(
@@ -375,15 +350,16 @@ async fn perform_app_cache_scan(
         }
     }
 
-    let apps: Vec<AppType> = options
+    let registry = AppDefinitionRegistry::load();
+    let definitions: Vec<AppDefinition> = options
         .apps
         .iter()
-        .filter_map(|s| AppType::from_str(s))
+        .filter_map(|id| registry.get(id).cloned())
         .collect();
 
     debug!(
         "[AppCacheScan] Parsed apps: {:?}",
-        apps.iter().map(|a| a.to_str()).collect::<Vec<_>>()
+        definitions.iter().map(|d| d.id.as_str()).collect::<Vec<_>>()
     );
 
     let categories: Vec<CleanCategory> = if options.categories.is_empty() {
@@ -395,6 +371,8 @@ async fn perform_app_cache_scan(
             CleanCategory::CacheData,
             CleanCategory::VoiceFiles,
             CleanCategory::EmojiCache,
+            CleanCategory::LogFiles,
+            CleanCategory::ShaderCache,
         ]
     } else {
         options
@@ -418,12 +396,12 @@ async fn perform_app_cache_scan(
         );
     }
 
-    let total_apps = apps.len() as u64;
-    let mut current_app_index = 0u64;
+    let total_apps = definitions.len() as u64;
     let is_incremental = last_scan_timestamp > 0;
+    let paths_config = get_app_paths_config();
 
-    for app_type in &apps {
-        debug!("[AppCacheScan] Processing app: {}", app_type.to_str());
+    for (current_app_index, definition) in definitions.into_iter().enumerate() {
+        debug!("[AppCacheScan] Processing app: {}", definition.id);
 
         if *cancel_receiver.borrow() {
             let mut progress_map = SCAN_PROGRESS.write().await;
@@ -433,69 +411,43 @@ async fn perform_app_cache_scan(
             return Ok(());
         }
 
-        while *pause_receiver.borrow() {
-            {
-                let mut progress_map = SCAN_PROGRESS.write().await;
-                if let Some(progress) = progress_map.get_mut(scan_id) {
-                    progress.status = AppCacheScanStatus::Paused;
-                    let _ = app.emit(EVENT_APP_CACHE_PROGRESS, progress.clone());
-                }
-            }
-            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-
-            if *cancel_receiver.borrow() {
-                let mut progress_map = SCAN_PROGRESS.write().await;
-                if let Some(progress) = progress_map.get_mut(scan_id) {
-                    progress.status = AppCacheScanStatus::Cancelled;
-                }
-                return Ok(());
-            }
-        }
-
         {
             let mut progress_map = SCAN_PROGRESS.write().await;
             if let Some(progress) = progress_map.get_mut(scan_id) {
-                progress.current_app = app_type.to_str().to_string();
+                progress.current_app = definition.id.clone();
                 progress.status = AppCacheScanStatus::Scanning;
             }
         }
 
-        let resolved_paths = resolve_app_paths(app_type);
-        debug!(
-            "[AppCacheScan] Resolved {} paths for {}",
-            resolved_paths.len(),
-            app_type.to_str()
-        );
+        let configured = paths_config.get_path(&definition.id);
+        let task = AppScanTask {
+            app: app.clone(),
+            scan_id: scan_id.to_string(),
+            definition,
+            categories: categories.clone(),
+            configured,
+            last_scan_timestamp,
+            start_instant,
+            current_app_index: current_app_index as u64,
+            total_apps,
+            pause_receiver: pause_receiver.clone(),
+            cancel_receiver: cancel_receiver.clone(),
+        };
 
-        for resolved in resolved_paths {
-            debug!(
-                "[AppCacheScan] Scanning path: {} (source: {:?})",
-                resolved.path.display(),
-                resolved.source
-            );
-            if resolved.path.exists() {
-                scan_app_path(
-                    &resolved.path,
-                    app_type,
-                    &categories,
-                    &mut files,
-                    scan_id,
-                    app,
-                    &mut last_update,
-                    &start_instant,
-                    current_app_index,
-                    total_apps,
-                    pause_receiver,
-                    cancel_receiver,
-                    last_scan_timestamp,
-                    &mut skipped_files,
-                    resolved.account_id.as_deref(),
-                )
-                .await;
+        let (scanned, skipped, control) =
+            tokio::task::spawn_blocking(move || task.run(files, skipped_files))
+                .await
+                .map_err(|e| format!("扫描任务异常: {}", e))?;
+        files = scanned;
+        skipped_files = skipped;
+
+        if control == ScanControl::Stop {
+            let mut progress_map = SCAN_PROGRESS.write().await;
+            if let Some(progress) = progress_map.get_mut(scan_id) {
+                progress.status = AppCacheScanStatus::Cancelled;
             }
+            return Ok(());
         }
-
-        current_app_index += 1;
     }
 
     let duration_ms = start_instant.elapsed().as_millis() as u64;
@@ -547,1687 +499,113 @@ async fn perform_app_cache_scan(
     Ok(())
 }
 
-fn resolve_app_paths(app: &AppType) -> Vec<ResolvedAppPath> {
-    let config = get_app_paths_config();
-    match app {
-        AppType::Wechat => {
-            AppPathResolver::resolve_wechat_paths(config.get_path("wechat").as_ref())
-        }
-        AppType::Qq => AppPathResolver::resolve_qq_paths(config.get_path("qq").as_ref()),
-        AppType::Dingtalk => {
-            AppPathResolver::resolve_dingtalk_paths(config.get_path("dingtalk").as_ref())
-        }
-        AppType::Wework => {
-            AppPathResolver::resolve_wework_paths(config.get_path("wework").as_ref())
-        }
-    }
-}
-
-#[allow(clippy::too_many_arguments)]
-async fn scan_app_path(
-    base_path: &Path,
-    app: &AppType,
-    categories: &[CleanCategory],
-    files: &mut Vec<AppCacheFile>,
-    scan_id: &str,
-    app_handle: &AppHandle,
-    last_update: &mut u64,
-    start_instant: &Instant,
-    current_app_index: u64,
-    total_apps: u64,
-    pause_receiver: &mut watch::Receiver<bool>,
-    cancel_receiver: &mut watch::Receiver<bool>,
+/// 在阻塞线程中扫描单个应用的所有账号目录
+struct AppScanTask {
+    app: AppHandle,
+    scan_id: String,
+    definition: AppDefinition,
+    categories: Vec<CleanCategory>,
+    configured: Option<PathBuf>,
     last_scan_timestamp: u64,
-    skipped_files: &mut u64,
-    account_id: Option<&str>,
-) {
-    match app {
-        AppType::Wechat => {
-            scan_wechat(
-                base_path,
-                categories,
-                files,
-                scan_id,
-                app_handle,
-                last_update,
-                start_instant,
-                current_app_index,
-                total_apps,
-                pause_receiver,
-                cancel_receiver,
-                last_scan_timestamp,
-                skipped_files,
-                account_id,
-            )
-            .await
-        }
-        AppType::Dingtalk => {
-            scan_dingtalk(
-                base_path,
-                categories,
-                files,
-                scan_id,
-                app_handle,
-                last_update,
-                start_instant,
-                current_app_index,
-                total_apps,
-                pause_receiver,
-                cancel_receiver,
-                last_scan_timestamp,
-                skipped_files,
-                account_id,
-            )
-            .await
-        }
-        AppType::Qq => {
-            scan_qq(
-                base_path,
-                categories,
-                files,
-                scan_id,
-                app_handle,
-                last_update,
-                start_instant,
-                current_app_index,
-                total_apps,
-                pause_receiver,
-                cancel_receiver,
-                last_scan_timestamp,
-                skipped_files,
-                account_id,
-            )
-            .await
-        }
-        AppType::Wework => {
-            scan_wework(
-                base_path,
-                categories,
-                files,
-                scan_id,
-                app_handle,
-                last_update,
-                start_instant,
-                current_app_index,
-                total_apps,
-                pause_receiver,
-                cancel_receiver,
-                last_scan_timestamp,
-                skipped_files,
-                account_id,
-            )
-            .await
-        }
-    }
-}
-
-#[allow(clippy::too_many_arguments)]
-async fn scan_wechat(
-    base_path: &Path,
-    categories: &[CleanCategory],
-    files: &mut Vec<AppCacheFile>,
-    scan_id: &str,
-    app_handle: &AppHandle,
-    last_update: &mut u64,
-    start_instant: &Instant,
+    start_instant: Instant,
     current_app_index: u64,
     total_apps: u64,
-    pause_receiver: &mut watch::Receiver<bool>,
-    cancel_receiver: &mut watch::Receiver<bool>,
-    last_scan_timestamp: u64,
-    skipped_files: &mut u64,
-    account_id: Option<&str>,
-) {
-    debug!(
-        "[AppCacheScan] scan_wechat called with base_path: {}",
-        base_path.display()
-    );
-
-    let chat_object = account_id
-        .unwrap_or_else(|| {
-            base_path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("未知")
-        })
-        .to_string();
-
-    let cache_dir = base_path.join("cache");
-    let msg_dir = base_path.join("msg");
-    let temp_dir = base_path.join("temp");
-
-    if categories.contains(&CleanCategory::ChatImages) {
-        scan_wechat_chat_images(
-            &cache_dir,
-            &msg_dir,
-            files,
-            scan_id,
-            app_handle,
-            last_update,
-            start_instant,
-            current_app_index,
-            total_apps,
-            pause_receiver,
-            cancel_receiver,
-            last_scan_timestamp,
-            skipped_files,
-            &chat_object,
-        )
-        .await;
-    }
-
-    if categories.contains(&CleanCategory::VideoFiles) {
-        let video_dir = msg_dir.join("video");
-        scan_wechat_video_files(
-            &video_dir,
-            files,
-            scan_id,
-            app_handle,
-            last_update,
-            start_instant,
-            current_app_index,
-            total_apps,
-            pause_receiver,
-            cancel_receiver,
-            last_scan_timestamp,
-            skipped_files,
-            &chat_object,
-        )
-        .await;
-    }
-
-    if categories.contains(&CleanCategory::DocumentFiles) {
-        let file_dir = msg_dir.join("file");
-        scan_wechat_document_files(
-            &file_dir,
-            files,
-            scan_id,
-            app_handle,
-            last_update,
-            start_instant,
-            current_app_index,
-            total_apps,
-            pause_receiver,
-            cancel_receiver,
-            last_scan_timestamp,
-            skipped_files,
-            &chat_object,
-        )
-        .await;
-    }
-
-    if categories.contains(&CleanCategory::CacheData) {
-        scan_wechat_cache_data(
-            &cache_dir,
-            files,
-            scan_id,
-            app_handle,
-            last_update,
-            start_instant,
-            current_app_index,
-            total_apps,
-            pause_receiver,
-            cancel_receiver,
-            last_scan_timestamp,
-            skipped_files,
-            &chat_object,
-        )
-        .await;
-    }
-
-    if categories.contains(&CleanCategory::EmojiCache) {
-        scan_wechat_emoji_cache(
-            &cache_dir,
-            files,
-            scan_id,
-            app_handle,
-            last_update,
-            start_instant,
-            current_app_index,
-            total_apps,
-            pause_receiver,
-            cancel_receiver,
-            last_scan_timestamp,
-            skipped_files,
-            &chat_object,
-        )
-        .await;
-    }
-
-    if categories.contains(&CleanCategory::TempFiles) {
-        scan_directory_async(
-            &temp_dir,
-            CleanCategory::TempFiles,
-            AppType::Wechat,
-            &chat_object,
-            files,
-            true,
-            scan_id,
-            app_handle,
-            last_update,
-            start_instant,
-            current_app_index,
-            total_apps,
-            pause_receiver,
-            cancel_receiver,
-            last_scan_timestamp,
-            skipped_files,
-        )
-        .await;
-    }
-
-    if categories.contains(&CleanCategory::ThumbCache) {
-        scan_wechat_thumb_cache(
-            &cache_dir,
-            files,
-            scan_id,
-            app_handle,
-            last_update,
-            start_instant,
-            current_app_index,
-            total_apps,
-            pause_receiver,
-            cancel_receiver,
-            last_scan_timestamp,
-            skipped_files,
-            &chat_object,
-        )
-        .await;
-    }
-}
-
-#[allow(clippy::too_many_arguments)]
-async fn scan_wechat_chat_images(
-    cache_dir: &Path,
-    msg_dir: &Path,
-    files: &mut Vec<AppCacheFile>,
-    scan_id: &str,
-    app_handle: &AppHandle,
-    last_update: &mut u64,
-    start_instant: &Instant,
-    current_app_index: u64,
-    total_apps: u64,
-    pause_receiver: &mut watch::Receiver<bool>,
-    cancel_receiver: &mut watch::Receiver<bool>,
-    last_scan_timestamp: u64,
-    skipped_files: &mut u64,
-    chat_object: &str,
-) {
-    let attach_dir = msg_dir.join("attach");
-    if attach_dir.exists() {
-        if let Ok(entries) = fs::read_dir(&attach_dir) {
-            for entry in entries.flatten() {
-                let session_dir = entry.path();
-                if session_dir.is_dir() {
-                    scan_wechat_attach_session(
-                        &session_dir,
-                        files,
-                        scan_id,
-                        app_handle,
-                        last_update,
-                        start_instant,
-                        current_app_index,
-                        total_apps,
-                        pause_receiver,
-                        cancel_receiver,
-                        last_scan_timestamp,
-                        skipped_files,
-                        chat_object,
-                    )
-                    .await;
-                }
-            }
-        }
-    }
+    pause_receiver: watch::Receiver<bool>,
+    cancel_receiver: watch::Receiver<bool>,
+}
+
+impl AppScanTask {
+    fn run(
+        self,
+        mut files: Vec<AppCacheFile>,
+        mut skipped_files: u64,
+    ) -> (Vec<AppCacheFile>, u64, ScanControl) {
+        let engine = AppCacheEngine::new(&self.definition, &self.categories)
+            .with_last_scan(self.last_scan_timestamp);
+        let accounts = engine.resolve(self.configured.as_deref());
+        debug!(
+            "[AppCacheScan] Resolved {} paths for {}",
+            accounts.len(),
+            self.definition.id
+        );
 
-    if cache_dir.exists() {
-        if let Ok(month_entries) = fs::read_dir(cache_dir) {
-            for month_entry in month_entries.flatten() {
-                let month_dir = month_entry.path();
-                if month_dir.is_dir() {
-                    let msg_cache_dir = month_dir.join("Message");
-                    if msg_cache_dir.exists() {
-                        if let Ok(session_entries) = fs::read_dir(&msg_cache_dir) {
-                            for session_entry in session_entries.flatten() {
-                                let session_dir = session_entry.path();
-                                if session_dir.is_dir() {
-                                    let image_temp = session_dir.join("ImageTemp");
-                                    if image_temp.exists() {
-                                        scan_directory_async(
-                                            &image_temp,
-                                            CleanCategory::ChatImages,
-                                            AppType::Wechat,
-                                            chat_object,
-                                            files,
-                                            true,
-                                            scan_id,
-                                            app_handle,
-                                            last_update,
-                                            start_instant,
-                                            current_app_index,
-                                            total_apps,
-                                            pause_receiver,
-                                            cancel_receiver,
-                                            last_scan_timestamp,
-                                            skipped_files,
-                                        )
-                                        .await;
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+        let mut last_update = files.len() as u64;
+        let mut callback = |path: &Path, files: &[AppCacheFile]| {
+            if self.wait_while_paused() == ScanControl::Stop {
+                return ScanControl::Stop;
             }
-        }
-    }
-}
-
-#[allow(clippy::too_many_arguments)]
-async fn scan_wechat_attach_session(
-    session_dir: &Path,
-    files: &mut Vec<AppCacheFile>,
-    _scan_id: &str,
-    _app_handle: &AppHandle,
-    _last_update: &mut u64,
-    _start_instant: &Instant,
-    _current_app_index: u64,
-    _total_apps: u64,
-    _pause_receiver: &mut watch::Receiver<bool>,
-    _cancel_receiver: &mut watch::Receiver<bool>,
-    last_scan_timestamp: u64,
-    skipped_files: &mut u64,
-    chat_object: &str,
-) {
-    let mut dirs_to_scan = vec![session_dir.to_path_buf()];
-
-    while let Some(current_dir) = dirs_to_scan.pop() {
-        if let Ok(entries) = fs::read_dir(&current_dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.is_dir() {
-                    dirs_to_scan.push(path);
-                } else if path.extension().map(|e| e == "dat").unwrap_or(false) {
-                    if should_skip_file(&path) {
-                        *skipped_files += 1;
-                        continue;
-                    }
-                    if let Ok(metadata) = fs::metadata(&path) {
-                        let modified = metadata.modified().ok();
-                        let created = metadata.created().ok();
-                        let modified_ts = modified
-                            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
-                            .map(|d| d.as_secs() as i64);
-                        let created_ts = created
-                            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
-                            .map(|d| d.as_secs() as i64);
-
-                        if let Some(modified_time) = modified_ts {
-                            if modified_time < last_scan_timestamp as i64 {
-                                *skipped_files += 1;
-                                continue;
-                            }
-                        }
-
-                        let file = AppCacheFile {
-                            id: Uuid::new_v4().to_string(),
-                            path: path.to_string_lossy().to_string(),
-                            name: path
-                                .file_name()
-                                .map(|n| n.to_string_lossy().to_string())
-                                .unwrap_or_default(),
-                            size: metadata.len(),
-                            category: CleanCategory::ChatImages,
-                            app: AppType::Wechat,
-                            chat_object: chat_object.to_string(),
-                            created_at: created_ts.unwrap_or(0),
-                            modified_at: modified_ts.unwrap_or(0),
-                            selected: false,
-                            is_encrypted: true,
-                            original_format: None,
-                        };
-                        files.push(file);
-                    }
-                }
+            let scanned_files = files.len() as u64;
+            if scanned_files >= last_update + PROGRESS_UPDATE_INTERVAL {
+                last_update = scanned_files;
+                self.update_progress(path, files);
             }
-        }
-    }
-}
-
-#[allow(clippy::too_many_arguments)]
-async fn scan_wechat_video_files(
-    video_dir: &Path,
-    files: &mut Vec<AppCacheFile>,
-    scan_id: &str,
-    app_handle: &AppHandle,
-    last_update: &mut u64,
-    start_instant: &Instant,
-    current_app_index: u64,
-    total_apps: u64,
-    pause_receiver: &mut watch::Receiver<bool>,
-    cancel_receiver: &mut watch::Receiver<bool>,
-    last_scan_timestamp: u64,
-    skipped_files: &mut u64,
-    chat_object: &str,
-) {
-    if !video_dir.exists() {
-        return;
-    }
+            ScanControl::Continue
+        };
 
-    if let Ok(month_entries) = fs::read_dir(video_dir) {
-        for month_entry in month_entries.flatten() {
-            let month_dir = month_entry.path();
-            if month_dir.is_dir() {
-                scan_files_by_extension_async(
-                    &month_dir,
-                    VIDEO_EXTENSIONS,
-                    CleanCategory::VideoFiles,
-                    AppType::Wechat,
-                    chat_object,
-                    files,
-                    scan_id,
-                    app_handle,
-                    last_update,
-                    start_instant,
-                    current_app_index,
-                    total_apps,
-                    pause_receiver,
-                    cancel_receiver,
-                    last_scan_timestamp,
-                    skipped_files,
-                    false,
-                )
-                .await;
+        for account in &accounts {
+            debug!(
+                "[AppCacheScan] Scanning path: {} (source: {:?})",
+                account.path.display(),
+                account.source
+            );
+            let control = engine.scan_account(account, &mut files, &mut skipped_files, &mut callback);
+            if control == ScanControl::Stop {
+                return (files, skipped_files, ScanControl::Stop);
             }
         }
-    }
-}
 
-#[allow(clippy::too_many_arguments)]
-async fn scan_wechat_document_files(
-    file_dir: &Path,
-    files: &mut Vec<AppCacheFile>,
-    scan_id: &str,
-    app_handle: &AppHandle,
-    last_update: &mut u64,
-    start_instant: &Instant,
-    current_app_index: u64,
-    total_apps: u64,
-    pause_receiver: &mut watch::Receiver<bool>,
-    cancel_receiver: &mut watch::Receiver<bool>,
-    last_scan_timestamp: u64,
-    skipped_files: &mut u64,
-    chat_object: &str,
-) {
-    if !file_dir.exists() {
-        return;
+        (files, skipped_files, ScanControl::Continue)
     }
 
-    if let Ok(month_entries) = fs::read_dir(file_dir) {
-        for month_entry in month_entries.flatten() {
-            let month_dir = month_entry.path();
-            if month_dir.is_dir() {
-                scan_files_by_extension_async(
-                    &month_dir,
-                    DOC_EXTENSIONS,
-                    CleanCategory::DocumentFiles,
-                    AppType::Wechat,
-                    chat_object,
-                    files,
-                    scan_id,
-                    app_handle,
-                    last_update,
-                    start_instant,
-                    current_app_index,
-                    total_apps,
-                    pause_receiver,
-                    cancel_receiver,
-                    last_scan_timestamp,
-                    skipped_files,
-                    false,
-                )
-                .await;
-                scan_files_by_extension_async(
-                    &month_dir,
-                    INSTALL_EXTENSIONS,
-                    CleanCategory::InstallPackages,
-                    AppType::Wechat,
-                    chat_object,
-                    files,
-                    scan_id,
-                    app_handle,
-                    last_update,
-                    start_instant,
-                    current_app_index,
-                    total_apps,
-                    pause_receiver,
-                    cancel_receiver,
-                    last_scan_timestamp,
-                    skipped_files,
-                    false,
-                )
-                .await;
+    fn wait_while_paused(&self) -> ScanControl {
+        let mut paused_reported = false;
+        loop {
+            if *self.cancel_receiver.borrow() {
+                return ScanControl::Stop;
             }
-        }
-    }
-}
-
-#[allow(clippy::too_many_arguments)]
-async fn scan_wechat_cache_data(
-    cache_dir: &Path,
-    files: &mut Vec<AppCacheFile>,
-    scan_id: &str,
-    app_handle: &AppHandle,
-    last_update: &mut u64,
-    start_instant: &Instant,
-    current_app_index: u64,
-    total_apps: u64,
-    pause_receiver: &mut watch::Receiver<bool>,
-    cancel_receiver: &mut watch::Receiver<bool>,
-    last_scan_timestamp: u64,
-    skipped_files: &mut u64,
-    chat_object: &str,
-) {
-    if !cache_dir.exists() {
-        return;
-    }
-
-    if let Ok(month_entries) = fs::read_dir(cache_dir) {
-        for month_entry in month_entries.flatten() {
-            let month_dir = month_entry.path();
-            if month_dir.is_dir() {
-                let http_resource = month_dir.join("HttpResource");
-                if http_resource.exists() {
-                    scan_directory_async(
-                        &http_resource,
-                        CleanCategory::CacheData,
-                        AppType::Wechat,
-                        chat_object,
-                        files,
-                        true,
-                        scan_id,
-                        app_handle,
-                        last_update,
-                        start_instant,
-                        current_app_index,
-                        total_apps,
-                        pause_receiver,
-                        cancel_receiver,
-                        last_scan_timestamp,
-                        skipped_files,
-                    )
-                    .await;
-                }
-
-                let weapp_icon = month_dir.join("WeAppIcon");
-                if weapp_icon.exists() {
-                    scan_directory_async(
-                        &weapp_icon,
-                        CleanCategory::CacheData,
-                        AppType::Wechat,
-                        chat_object,
-                        files,
-                        true,
-                        scan_id,
-                        app_handle,
-                        last_update,
-                        start_instant,
-                        current_app_index,
-                        total_apps,
-                        pause_receiver,
-                        cancel_receiver,
-                        last_scan_timestamp,
-                        skipped_files,
-                    )
-                    .await;
-                }
-
-                let msg_cache_dir = month_dir.join("Message");
-                if msg_cache_dir.exists() {
-                    if let Ok(session_entries) = fs::read_dir(&msg_cache_dir) {
-                        for session_entry in session_entries.flatten() {
-                            let session_dir = session_entry.path();
-                            if session_dir.is_dir() {
-                                let file_temp = session_dir.join("FileTemp");
-                                if file_temp.exists() {
-                                    scan_directory_async(
-                                        &file_temp,
-                                        CleanCategory::CacheData,
-                                        AppType::Wechat,
-                                        chat_object,
-                                        files,
-                                        true,
-                                        scan_id,
-                                        app_handle,
-                                        last_update,
-                                        start_instant,
-                                        current_app_index,
-                                        total_apps,
-                                        pause_receiver,
-                                        cancel_receiver,
-                                        last_scan_timestamp,
-                                        skipped_files,
-                                    )
-                                    .await;
-                                }
-                            }
-                        }
-                    }
-                }
+            if !*self.pause_receiver.borrow() {
+                return ScanControl::Continue;
             }
-        }
-    }
-}
-
-#[allow(clippy::too_many_arguments)]
-async fn scan_wechat_emoji_cache(
-    cache_dir: &Path,
-    files: &mut Vec<AppCacheFile>,
-    scan_id: &str,
-    app_handle: &AppHandle,
-    last_update: &mut u64,
-    start_instant: &Instant,
-    current_app_index: u64,
-    total_apps: u64,
-    pause_receiver: &mut watch::Receiver<bool>,
-    cancel_receiver: &mut watch::Receiver<bool>,
-    last_scan_timestamp: u64,
-    skipped_files: &mut u64,
-    chat_object: &str,
-) {
-    if !cache_dir.exists() {
-        return;
-    }
-
-    if let Ok(month_entries) = fs::read_dir(cache_dir) {
-        for month_entry in month_entries.flatten() {
-            let month_dir = month_entry.path();
-            if month_dir.is_dir() {
-                let emoticon_dir = month_dir.join("Emoticon");
-                if emoticon_dir.exists() {
-                    scan_directory_async(
-                        &emoticon_dir,
-                        CleanCategory::EmojiCache,
-                        AppType::Wechat,
-                        chat_object,
-                        files,
-                        true,
-                        scan_id,
-                        app_handle,
-                        last_update,
-                        start_instant,
-                        current_app_index,
-                        total_apps,
-                        pause_receiver,
-                        cancel_receiver,
-                        last_scan_timestamp,
-                        skipped_files,
-                    )
-                    .await;
+            if !paused_reported {
+                paused_reported = true;
+                let mut progress_map = SCAN_PROGRESS.blocking_write();
+                if let Some(progress) = progress_map.get_mut(&self.scan_id) {
+                    progress.status = AppCacheScanStatus::Paused;
+                    let _ = self.app.emit(EVENT_APP_CACHE_PROGRESS, progress.clone());
                 }
             }
+            std::thread::sleep(std::time::Duration::from_millis(100));
         }
     }
-}
 
-#[allow(clippy::too_many_arguments)]
-async fn scan_wechat_thumb_cache(
-    cache_dir: &Path,
-    files: &mut Vec<AppCacheFile>,
-    scan_id: &str,
-    app_handle: &AppHandle,
-    last_update: &mut u64,
-    start_instant: &Instant,
-    current_app_index: u64,
-    total_apps: u64,
-    pause_receiver: &mut watch::Receiver<bool>,
-    cancel_receiver: &mut watch::Receiver<bool>,
-    last_scan_timestamp: u64,
-    skipped_files: &mut u64,
-    chat_object: &str,
-) {
-    if !cache_dir.exists() {
-        return;
-    }
+    fn update_progress(&self, current_path: &Path, files: &[AppCacheFile]) {
+        let mut progress_map = SCAN_PROGRESS.blocking_write();
+        if let Some(progress) = progress_map.get_mut(&self.scan_id) {
+            let scanned_size: u64 = files.iter().map(|f| f.size).sum();
+            progress.current_path = current_path.to_string_lossy().to_string();
+            progress.scanned_files = files.len() as u64;
+            progress.scanned_size = scanned_size;
 
-    if let Ok(month_entries) = fs::read_dir(cache_dir) {
-        for month_entry in month_entries.flatten() {
-            let month_dir = month_entry.path();
-            if month_dir.is_dir() {
-                let msg_cache_dir = month_dir.join("Message");
-                if msg_cache_dir.exists() {
-                    if let Ok(session_entries) = fs::read_dir(&msg_cache_dir) {
-                        for session_entry in session_entries.flatten() {
-                            let session_dir = session_entry.path();
-                            if session_dir.is_dir() {
-                                let thumb_dir = session_dir.join("Thumb");
-                                if thumb_dir.exists() {
-                                    scan_directory_async(
-                                        &thumb_dir,
-                                        CleanCategory::ThumbCache,
-                                        AppType::Wechat,
-                                        chat_object,
-                                        files,
-                                        true,
-                                        scan_id,
-                                        app_handle,
-                                        last_update,
-                                        start_instant,
-                                        current_app_index,
-                                        total_apps,
-                                        pause_receiver,
-                                        cancel_receiver,
-                                        last_scan_timestamp,
-                                        skipped_files,
-                                    )
-                                    .await;
-                                }
-                            }
-                        }
-                    }
-                }
-
-                let sns_dir = month_dir.join("Sns");
-                if sns_dir.exists() {
-                    let sns_img = sns_dir.join("Img");
-                    if sns_img.exists() {
-                        scan_directory_async(
-                            &sns_img,
-                            CleanCategory::ThumbCache,
-                            AppType::Wechat,
-                            chat_object,
-                            files,
-                            true,
-                            scan_id,
-                            app_handle,
-                            last_update,
-                            start_instant,
-                            current_app_index,
-                            total_apps,
-                            pause_receiver,
-                            cancel_receiver,
-                            last_scan_timestamp,
-                            skipped_files,
-                        )
-                        .await;
-                    }
-                }
-            }
-        }
-    }
-}
-
-#[allow(clippy::too_many_arguments)]
-async fn scan_dingtalk(
-    base_path: &Path,
-    categories: &[CleanCategory],
-    files: &mut Vec<AppCacheFile>,
-    scan_id: &str,
-    app_handle: &AppHandle,
-    last_update: &mut u64,
-    start_instant: &Instant,
-    current_app_index: u64,
-    total_apps: u64,
-    pause_receiver: &mut watch::Receiver<bool>,
-    cancel_receiver: &mut watch::Receiver<bool>,
-    last_scan_timestamp: u64,
-    skipped_files: &mut u64,
-    account_id: Option<&str>,
-) {
-    let chat_object = account_id
-        .unwrap_or_else(|| {
-            base_path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("未知")
-        })
-        .to_string();
-
-    if categories.contains(&CleanCategory::ChatImages) {
-        let image_path = base_path.join("Image");
-        scan_files_by_extension_async(
-            &image_path,
-            IMAGE_EXTENSIONS,
-            CleanCategory::ChatImages,
-            AppType::Dingtalk,
-            &chat_object,
-            files,
-            scan_id,
-            app_handle,
-            last_update,
-            start_instant,
-            current_app_index,
-            total_apps,
-            pause_receiver,
-            cancel_receiver,
-            last_scan_timestamp,
-            skipped_files,
-            false,
-        )
-        .await;
-        let image_path2 = base_path.join("images");
-        scan_files_by_extension_async(
-            &image_path2,
-            IMAGE_EXTENSIONS,
-            CleanCategory::ChatImages,
-            AppType::Dingtalk,
-            &chat_object,
-            files,
-            scan_id,
-            app_handle,
-            last_update,
-            start_instant,
-            current_app_index,
-            total_apps,
-            pause_receiver,
-            cancel_receiver,
-            last_scan_timestamp,
-            skipped_files,
-            false,
-        )
-        .await;
-    }
-
-    if categories.contains(&CleanCategory::VideoFiles) {
-        let video_path = base_path.join("Video");
-        scan_files_by_extension_async(
-            &video_path,
-            VIDEO_EXTENSIONS,
-            CleanCategory::VideoFiles,
-            AppType::Dingtalk,
-            &chat_object,
-            files,
-            scan_id,
-            app_handle,
-            last_update,
-            start_instant,
-            current_app_index,
-            total_apps,
-            pause_receiver,
-            cancel_receiver,
-            last_scan_timestamp,
-            skipped_files,
-            false,
-        )
-        .await;
-    }
-
-    if categories.contains(&CleanCategory::DocumentFiles) {
-        let file_path = base_path.join("File");
-        scan_files_by_extension_async(
-            &file_path,
-            DOC_EXTENSIONS,
-            CleanCategory::DocumentFiles,
-            AppType::Dingtalk,
-            &chat_object,
-            files,
-            scan_id,
-            app_handle,
-            last_update,
-            start_instant,
-            current_app_index,
-            total_apps,
-            pause_receiver,
-            cancel_receiver,
-            last_scan_timestamp,
-            skipped_files,
-            false,
-        )
-        .await;
-    }
-
-    if categories.contains(&CleanCategory::CacheData) {
-        let cache_path = base_path.join("Cache");
-        scan_directory_async(
-            &cache_path,
-            CleanCategory::CacheData,
-            AppType::Dingtalk,
-            &chat_object,
-            files,
-            true,
-            scan_id,
-            app_handle,
-            last_update,
-            start_instant,
-            current_app_index,
-            total_apps,
-            pause_receiver,
-            cancel_receiver,
-            last_scan_timestamp,
-            skipped_files,
-        )
-        .await;
-        let temp_path = base_path.join("Temp");
-        scan_directory_async(
-            &temp_path,
-            CleanCategory::CacheData,
-            AppType::Dingtalk,
-            &chat_object,
-            files,
-            true,
-            scan_id,
-            app_handle,
-            last_update,
-            start_instant,
-            current_app_index,
-            total_apps,
-            pause_receiver,
-            cancel_receiver,
-            last_scan_timestamp,
-            skipped_files,
-        )
-        .await;
-    }
-
-    if categories.contains(&CleanCategory::VoiceFiles) {
-        let voice_path = base_path.join("Voice");
-        scan_files_by_extension_async(
-            &voice_path,
-            VOICE_EXTENSIONS,
-            CleanCategory::VoiceFiles,
-            AppType::Dingtalk,
-            &chat_object,
-            files,
-            scan_id,
-            app_handle,
-            last_update,
-            start_instant,
-            current_app_index,
-            total_apps,
-            pause_receiver,
-            cancel_receiver,
-            last_scan_timestamp,
-            skipped_files,
-            false,
-        )
-        .await;
-    }
-}
-
-#[allow(clippy::too_many_arguments)]
-async fn scan_qq(
-    base_path: &Path,
-    categories: &[CleanCategory],
-    files: &mut Vec<AppCacheFile>,
-    scan_id: &str,
-    app_handle: &AppHandle,
-    last_update: &mut u64,
-    start_instant: &Instant,
-    current_app_index: u64,
-    total_apps: u64,
-    pause_receiver: &mut watch::Receiver<bool>,
-    cancel_receiver: &mut watch::Receiver<bool>,
-    last_scan_timestamp: u64,
-    skipped_files: &mut u64,
-    account_id: Option<&str>,
-) {
-    let chat_object = account_id
-        .unwrap_or_else(|| {
-            base_path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("未知")
-        })
-        .to_string();
-
-    if categories.contains(&CleanCategory::ChatImages) {
-        let image_path = base_path.join("Image");
-        scan_files_by_extension_async(
-            &image_path,
-            IMAGE_EXTENSIONS,
-            CleanCategory::ChatImages,
-            AppType::Qq,
-            &chat_object,
-            files,
-            scan_id,
-            app_handle,
-            last_update,
-            start_instant,
-            current_app_index,
-            total_apps,
-            pause_receiver,
-            cancel_receiver,
-            last_scan_timestamp,
-            skipped_files,
-            false,
-        )
-        .await;
-        let image_path2 = base_path.join("FileRecv/Image");
-        scan_files_by_extension_async(
-            &image_path2,
-            IMAGE_EXTENSIONS,
-            CleanCategory::ChatImages,
-            AppType::Qq,
-            &chat_object,
-            files,
-            scan_id,
-            app_handle,
-            last_update,
-            start_instant,
-            current_app_index,
-            total_apps,
-            pause_receiver,
-            cancel_receiver,
-            last_scan_timestamp,
-            skipped_files,
-            false,
-        )
-        .await;
-    }
-
-    if categories.contains(&CleanCategory::VideoFiles) {
-        let video_path = base_path.join("Video");
-        scan_files_by_extension_async(
-            &video_path,
-            VIDEO_EXTENSIONS,
-            CleanCategory::VideoFiles,
-            AppType::Qq,
-            &chat_object,
-            files,
-            scan_id,
-            app_handle,
-            last_update,
-            start_instant,
-            current_app_index,
-            total_apps,
-            pause_receiver,
-            cancel_receiver,
-            last_scan_timestamp,
-            skipped_files,
-            false,
-        )
-        .await;
-    }
-
-    if categories.contains(&CleanCategory::DocumentFiles) {
-        let file_path = base_path.join("FileRecv");
-        scan_files_by_extension_async(
-            &file_path,
-            DOC_EXTENSIONS,
-            CleanCategory::DocumentFiles,
-            AppType::Qq,
-            &chat_object,
-            files,
-            scan_id,
-            app_handle,
-            last_update,
-            start_instant,
-            current_app_index,
-            total_apps,
-            pause_receiver,
-            cancel_receiver,
-            last_scan_timestamp,
-            skipped_files,
-            false,
-        )
-        .await;
-    }
-
-    if categories.contains(&CleanCategory::CacheData) {
-        let cache_path = base_path.join("Cache");
-        scan_directory_async(
-            &cache_path,
-            CleanCategory::CacheData,
-            AppType::Qq,
-            &chat_object,
-            files,
-            true,
-            scan_id,
-            app_handle,
-            last_update,
-            start_instant,
-            current_app_index,
-            total_apps,
-            pause_receiver,
-            cancel_receiver,
-            last_scan_timestamp,
-            skipped_files,
-        )
-        .await;
-    }
-
-    if categories.contains(&CleanCategory::VoiceFiles) {
-        let voice_path = base_path.join("Audio");
-        scan_files_by_extension_async(
-            &voice_path,
-            VOICE_EXTENSIONS,
-            CleanCategory::VoiceFiles,
-            AppType::Qq,
-            &chat_object,
-            files,
-            scan_id,
-            app_handle,
-            last_update,
-            start_instant,
-            current_app_index,
-            total_apps,
-            pause_receiver,
-            cancel_receiver,
-            last_scan_timestamp,
-            skipped_files,
-            false,
-        )
-        .await;
-    }
-
-    if categories.contains(&CleanCategory::EmojiCache) {
-        let emoji_path = base_path.join("Emoji");
-        scan_files_by_extension_async(
-            &emoji_path,
-            EMOJI_EXTENSIONS,
-            CleanCategory::EmojiCache,
-            AppType::Qq,
-            &chat_object,
-            files,
-            scan_id,
-            app_handle,
-            last_update,
-            start_instant,
-            current_app_index,
-            total_apps,
-            pause_receiver,
-            cancel_receiver,
-            last_scan_timestamp,
-            skipped_files,
-            false,
-        )
-        .await;
-    }
-}
-
-#[allow(clippy::too_many_arguments)]
-async fn scan_wework(
-    base_path: &Path,
-    categories: &[CleanCategory],
-    files: &mut Vec<AppCacheFile>,
-    scan_id: &str,
-    app_handle: &AppHandle,
-    last_update: &mut u64,
-    start_instant: &Instant,
-    current_app_index: u64,
-    total_apps: u64,
-    pause_receiver: &mut watch::Receiver<bool>,
-    cancel_receiver: &mut watch::Receiver<bool>,
-    last_scan_timestamp: u64,
-    skipped_files: &mut u64,
-    account_id: Option<&str>,
-) {
-    let chat_object = account_id
-        .unwrap_or_else(|| {
-            base_path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("未知")
-        })
-        .to_string();
-
-    if categories.contains(&CleanCategory::ChatImages) {
-        let image_path = base_path.join("Image");
-        scan_files_by_extension_async(
-            &image_path,
-            IMAGE_EXTENSIONS,
-            CleanCategory::ChatImages,
-            AppType::Wework,
-            &chat_object,
-            files,
-            scan_id,
-            app_handle,
-            last_update,
-            start_instant,
-            current_app_index,
-            total_apps,
-            pause_receiver,
-            cancel_receiver,
-            last_scan_timestamp,
-            skipped_files,
-            false,
-        )
-        .await;
-    }
-
-    if categories.contains(&CleanCategory::VideoFiles) {
-        let video_path = base_path.join("Video");
-        scan_files_by_extension_async(
-            &video_path,
-            VIDEO_EXTENSIONS,
-            CleanCategory::VideoFiles,
-            AppType::Wework,
-            &chat_object,
-            files,
-            scan_id,
-            app_handle,
-            last_update,
-            start_instant,
-            current_app_index,
-            total_apps,
-            pause_receiver,
-            cancel_receiver,
-            last_scan_timestamp,
-            skipped_files,
-            false,
-        )
-        .await;
-    }
-
-    if categories.contains(&CleanCategory::DocumentFiles) {
-        let file_path = base_path.join("File");
-        scan_files_by_extension_async(
-            &file_path,
-            DOC_EXTENSIONS,
-            CleanCategory::DocumentFiles,
-            AppType::Wework,
-            &chat_object,
-            files,
-            scan_id,
-            app_handle,
-            last_update,
-            start_instant,
-            current_app_index,
-            total_apps,
-            pause_receiver,
-            cancel_receiver,
-            last_scan_timestamp,
-            skipped_files,
-            false,
-        )
-        .await;
-    }
-
-    if categories.contains(&CleanCategory::CacheData) {
-        let cache_path = base_path.join("Cache");
-        scan_directory_async(
-            &cache_path,
-            CleanCategory::CacheData,
-            AppType::Wework,
-            &chat_object,
-            files,
-            true,
-            scan_id,
-            app_handle,
-            last_update,
-            start_instant,
-            current_app_index,
-            total_apps,
-            pause_receiver,
-            cancel_receiver,
-            last_scan_timestamp,
-            skipped_files,
-        )
-        .await;
-    }
-
-    if categories.contains(&CleanCategory::VoiceFiles) {
-        let voice_path = base_path.join("Voice");
-        scan_files_by_extension_async(
-            &voice_path,
-            VOICE_EXTENSIONS,
-            CleanCategory::VoiceFiles,
-            AppType::Wework,
-            &chat_object,
-            files,
-            scan_id,
-            app_handle,
-            last_update,
-            start_instant,
-            current_app_index,
-            total_apps,
-            pause_receiver,
-            cancel_receiver,
-            last_scan_timestamp,
-            skipped_files,
-            false,
-        )
-        .await;
-    }
-}
-
-#[allow(clippy::too_many_arguments)]
-async fn scan_files_by_extension_async(
-    dir: &Path,
-    extensions: &[&str],
-    category: CleanCategory,
-    app: AppType,
-    chat_object: &str,
-    files: &mut Vec<AppCacheFile>,
-    scan_id: &str,
-    app_handle: &AppHandle,
-    last_update: &mut u64,
-    start_instant: &Instant,
-    current_app_index: u64,
-    total_apps: u64,
-    pause_receiver: &mut watch::Receiver<bool>,
-    cancel_receiver: &mut watch::Receiver<bool>,
-    last_scan_timestamp: u64,
-    skipped_files: &mut u64,
-    check_encrypted: bool,
-) {
-    if !dir.exists() {
-        return;
-    }
-
-    scan_files_by_extension_recursive(
-        dir,
-        extensions,
-        category,
-        app,
-        chat_object,
-        files,
-        scan_id,
-        app_handle,
-        last_update,
-        start_instant,
-        current_app_index,
-        total_apps,
-        pause_receiver,
-        cancel_receiver,
-        last_scan_timestamp,
-        skipped_files,
-        check_encrypted,
-    )
-    .await;
-}
-
-#[allow(clippy::too_many_arguments)]
-async fn scan_files_by_extension_recursive(
-    dir: &Path,
-    extensions: &[&str],
-    category: CleanCategory,
-    app: AppType,
-    chat_object: &str,
-    files: &mut Vec<AppCacheFile>,
-    scan_id: &str,
-    app_handle: &AppHandle,
-    last_update: &mut u64,
-    start_instant: &Instant,
-    current_app_index: u64,
-    total_apps: u64,
-    pause_receiver: &mut watch::Receiver<bool>,
-    cancel_receiver: &mut watch::Receiver<bool>,
-    last_scan_timestamp: u64,
-    skipped_files: &mut u64,
-    check_encrypted: bool,
-) {
-    if !dir.exists() {
-        return;
-    }
-
-    if let Ok(entries) = fs::read_dir(dir) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-
-            if should_skip_file(&path) {
-                continue;
-            }
-
-            if *cancel_receiver.borrow() {
-                return;
-            }
-
-            while *pause_receiver.borrow() {
-                {
-                    let mut progress_map = SCAN_PROGRESS.write().await;
-                    if let Some(progress) = progress_map.get_mut(scan_id) {
-                        progress.status = AppCacheScanStatus::Paused;
-                        let _ = app_handle.emit(EVENT_APP_CACHE_PROGRESS, progress.clone());
-                    }
-                }
-                tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-
-                if *cancel_receiver.borrow() {
-                    return;
-                }
-            }
-
-            if path.is_dir() {
-                Box::pin(scan_files_by_extension_recursive(
-                    &path,
-                    extensions,
-                    category.clone(),
-                    app.clone(),
-                    chat_object,
-                    files,
-                    scan_id,
-                    app_handle,
-                    last_update,
-                    start_instant,
-                    current_app_index,
-                    total_apps,
-                    pause_receiver,
-                    cancel_receiver,
-                    last_scan_timestamp,
-                    skipped_files,
-                    check_encrypted,
-                ))
-                .await;
+            let elapsed = self.start_instant.elapsed().as_secs_f64();
+            progress.speed = if elapsed > 0.0 {
+                scanned_size as f64 / elapsed
             } else {
-                if !should_scan_file_by_time(&path, last_scan_timestamp) {
-                    *skipped_files += 1;
-                    continue;
-                }
-
-                let ext = path
-                    .extension()
-                    .and_then(|e| e.to_str())
-                    .map(|s| s.to_lowercase())
-                    .unwrap_or_default();
-
-                if extensions.iter().any(|&e| e == ext) {
-                    if let Some(mut file) =
-                        create_app_cache_file(&path, category.clone(), app.clone(), chat_object)
-                    {
-                        if check_encrypted && ext == "dat" {
-                            if let Some(info) = WeChatDatDecoder::analyze_dat_file(&path) {
-                                file.is_encrypted = true;
-                                file.original_format =
-                                    Some(info.original_format.extension().to_string());
-                            }
-                        }
-
-                        files.push(file);
-
-                        let current_count = files.len() as u64;
-                        if current_count - *last_update >= PROGRESS_UPDATE_INTERVAL {
-                            *last_update = current_count;
-                            update_progress(
-                                scan_id,
-                                app_handle,
-                                &path.display().to_string(),
-                                current_count,
-                                files.iter().map(|f| f.size).sum(),
-                                start_instant,
-                                current_app_index,
-                                total_apps,
-                            )
-                            .await;
-                        }
-                    }
-                }
-            }
-        }
-    }
-}
-
-#[allow(clippy::too_many_arguments)]
-async fn scan_directory_async(
-    dir: &Path,
-    category: CleanCategory,
-    app: AppType,
-    chat_object: &str,
-    files: &mut Vec<AppCacheFile>,
-    recursive: bool,
-    scan_id: &str,
-    app_handle: &AppHandle,
-    last_update: &mut u64,
-    start_instant: &Instant,
-    current_app_index: u64,
-    total_apps: u64,
-    pause_receiver: &mut watch::Receiver<bool>,
-    cancel_receiver: &mut watch::Receiver<bool>,
-    last_scan_timestamp: u64,
-    skipped_files: &mut u64,
-) {
-    if !dir.exists() {
-        return;
-    }
-
-    if let Ok(entries) = fs::read_dir(dir) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-
-            if should_skip_file(&path) {
-                continue;
-            }
-
-            if *cancel_receiver.borrow() {
-                return;
-            }
-
-            while *pause_receiver.borrow() {
-                {
-                    let mut progress_map = SCAN_PROGRESS.write().await;
-                    if let Some(progress) = progress_map.get_mut(scan_id) {
-                        progress.status = AppCacheScanStatus::Paused;
-                        let _ = app_handle.emit(EVENT_APP_CACHE_PROGRESS, progress.clone());
-                    }
-                }
-                tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-
-                if *cancel_receiver.borrow() {
-                    return;
-                }
-            }
+                0.0
+            };
+            progress.status = AppCacheScanStatus::Scanning;
 
-            if path.is_dir() && recursive {
-                Box::pin(scan_directory_async(
-                    &path,
-                    category.clone(),
-                    app.clone(),
-                    chat_object,
-                    files,
-                    true,
-                    scan_id,
-                    app_handle,
-                    last_update,
-                    start_instant,
-                    current_app_index,
-                    total_apps,
-                    pause_receiver,
-                    cancel_receiver,
-                    last_scan_timestamp,
-                    skipped_files,
-                ))
-                .await;
-            } else if path.is_file() {
-                if !should_scan_file_by_time(&path, last_scan_timestamp) {
-                    *skipped_files += 1;
-                    continue;
-                }
+            let app_progress = if self.total_apps > 0 {
+                (self.current_app_index as f32 / self.total_apps as f32) * 100.0
+            } else {
+                0.0
+            };
+            progress.percent = app_progress.min(99.0);
 
-                if let Some(file) =
-                    create_app_cache_file(&path, category.clone(), app.clone(), chat_object)
-                {
-                    files.push(file);
-
-                    let current_count = files.len() as u64;
-                    if current_count - *last_update >= PROGRESS_UPDATE_INTERVAL {
-                        *last_update = current_count;
-                        update_progress(
-                            scan_id,
-                            app_handle,
-                            &path.display().to_string(),
-                            current_count,
-                            files.iter().map(|f| f.size).sum(),
-                            start_instant,
-                            current_app_index,
-                            total_apps,
-                        )
-                        .await;
-                    }
-                }
-            }
+            let _ = self.app.emit(EVENT_APP_CACHE_PROGRESS, progress.clone());
         }
     }
 }
 
-async fn update_progress(
-    scan_id: &str,
-    app_handle: &AppHandle,
-    current_path: &str,
-    scanned_files: u64,
-    scanned_size: u64,
-    start_instant: &Instant,
-    current_app_index: u64,
-    total_apps: u64,
-) {
-    let mut progress_map = SCAN_PROGRESS.write().await;
-    if let Some(progress) = progress_map.get_mut(scan_id) {
-        progress.current_path = current_path.to_string();
-        progress.scanned_files = scanned_files;
-        progress.scanned_size = scanned_size;
-
-        let elapsed = start_instant.elapsed().as_secs_f64();
-        progress.speed = if elapsed > 0.0 {
-            scanned_size as f64 / elapsed
-        } else {
-            0.0
-        };
-        progress.status = AppCacheScanStatus::Scanning;
-
-        let app_progress = if total_apps > 0 {
-            (current_app_index as f32 / total_apps as f32) * 100.0
-        } else {
-            0.0
-        };
-        progress.percent = app_progress.min(99.0);
-
-        let _ = app_handle.emit(EVENT_APP_CACHE_PROGRESS, progress.clone());
-    }
-}
-
-fn create_app_cache_file(
-    path: &Path,
-    category: CleanCategory,
-    app: AppType,
-    chat_object: &str,
-) -> Option<AppCacheFile> {
-    let metadata = fs::metadata(path).ok()?;
-
-    let size = metadata.len();
-    let name = path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("unknown")
-        .to_string();
-
-    let modified_at = metadata
-        .modified()
-        .ok()
-        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
-        .map(|d| d.as_millis() as i64)
-        .unwrap_or(0);
-
-    let created_at = metadata
-        .created()
-        .ok()
-        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
-        .map(|d| d.as_millis() as i64)
-        .unwrap_or(modified_at);
-
-    Some(AppCacheFile {
-        id: Uuid::new_v4().to_string(),
-        path: path.to_string_lossy().to_string(),
-        name,
-        size,
-        category,
-        app,
-        chat_object: chat_object.to_string(),
-        created_at,
-        modified_at,
-        selected: false,
-        is_encrypted: false,
-        original_format: None,
-    })
-}
 
 pub async fn get_app_cache_progress(scan_id: &str) -> Option<AppCacheScanProgress> {
     let progress_map = SCAN_PROGRESS.read().await;
@@ -0,0 +1,794 @@
+//! 开发项目构建产物扫描
+//!
+//! 通过清单文件（Cargo.toml、package.json、build.gradle、pyproject.toml、CMakeLists.txt）
+//! 识别项目根目录，统计各构建产物目录的大小，并根据项目最近一次改动时间评估闲置程度。
+//! 只有能由构建工具重新生成的目录才会标记为可清理。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+use walkdir::WalkDir;
+
+use crate::models::ScanStatus;
+use crate::modules::scanner_framework::{
+    ControlAction, ScanContext, ScanManager, ScanProgress as ScanProgressTrait,
+};
+use crate::utils::path::SystemPaths;
+use crate::utils::path_match::PathPrefix;
+
+pub const EVENT_BUILD_ARTIFACT_PROGRESS: &str = "build-artifact:progress";
+pub const EVENT_BUILD_ARTIFACT_COMPLETE: &str = "build-artifact:complete";
+
+/// 项目改动时间在该天数内视为活跃项目
+const ACTIVE_DAYS: u64 = 7;
+/// 超过该天数未改动视为完全闲置
+const STALE_DAYS: u64 = 365;
+/// 计算项目最近改动时间时最多检查的文件数
+const ACTIVITY_SAMPLE_LIMIT: usize = 5000;
+
+/// 查找项目时不进入的目录
+const SKIP_DIRS: &[&str] = &[
+    ".git",
+    ".hg",
+    ".svn",
+    "node_modules",
+    "target",
+    ".gradle",
+    "__pycache__",
+    ".venv",
+    "venv",
+    ".tox",
+    ".mypy_cache",
+    ".pytest_cache",
+    ".next",
+    ".nuxt",
+];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum ProjectKind {
+    Cargo,
+    Node,
+    Gradle,
+    Python,
+    CMake,
+}
+
+impl ProjectKind {
+    pub fn display_name(&self) -> &str {
+        match self {
+            Self::Cargo => "Rust (Cargo)",
+            Self::Node => "Node.js",
+            Self::Gradle => "Gradle",
+            Self::Python => "Python",
+            Self::CMake => "CMake",
+        }
+    }
+
+    pub fn manifests(&self) -> &'static [&'static str] {
+        match self {
+            Self::Cargo => &["Cargo.toml"],
+            Self::Node => &["package.json"],
+            Self::Gradle => &[
+                "build.gradle",
+                "build.gradle.kts",
+                "settings.gradle",
+                "settings.gradle.kts",
+            ],
+            Self::Python => &[
+                "pyproject.toml",
+                "setup.py",
+                "setup.cfg",
+                "requirements.txt",
+                "Pipfile",
+            ],
+            Self::CMake => &["CMakeLists.txt"],
+        }
+    }
+
+    pub fn all() -> [ProjectKind; 5] {
+        [
+            Self::Cargo,
+            Self::Node,
+            Self::Gradle,
+            Self::Python,
+            Self::CMake,
+        ]
+    }
+
+    /// 识别目录下存在的项目类型
+    pub fn detect(dir: &Path) -> Vec<ProjectKind> {
+        Self::all()
+            .into_iter()
+            .filter(|kind| kind.manifests().iter().any(|m| dir.join(m).is_file()))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildArtifact {
+    pub path: String,
+    pub kind: ProjectKind,
+    pub name: String,
+    pub size: u64,
+    pub file_count: u64,
+    pub regenerable: bool,
+    /// 可清理时为重新生成的方式，不可清理时为原因
+    pub note: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevProject {
+    pub root: String,
+    pub name: String,
+    pub kinds: Vec<ProjectKind>,
+    pub last_activity: i64,
+    pub stale_days: u64,
+    /// 0-100，越高表示项目闲置越久
+    pub staleness_score: u8,
+    pub artifacts: Vec<BuildArtifact>,
+    pub artifact_size: u64,
+    pub cleanable_size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildArtifactScanResult {
+    pub scan_id: String,
+    pub projects: Vec<DevProject>,
+    pub project_count: u64,
+    pub artifact_size: u64,
+    pub cleanable_size: u64,
+    pub duration_ms: u64,
+}
+
+impl BuildArtifactScanResult {
+    /// 可清理的构建产物路径，`min_stale_days` 用于只选择闲置一段时间的项目
+    pub fn cleanable_paths(&self, min_stale_days: u64) -> Vec<PathBuf> {
+        self.projects
+            .iter()
+            .filter(|p| p.stale_days >= min_stale_days)
+            .flat_map(|p| p.artifacts.iter())
+            .filter(|a| a.regenerable)
+            .map(|a| PathBuf::from(&a.path))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildArtifactScanOptions {
+    #[serde(default)]
+    pub scan_paths: Vec<String>,
+    #[serde(default)]
+    pub exclude_paths: Vec<String>,
+    #[serde(default = "default_max_depth")]
+    pub max_depth: usize,
+}
+
+fn default_max_depth() -> usize {
+    8
+}
+
+impl Default for BuildArtifactScanOptions {
+    fn default() -> Self {
+        Self {
+            scan_paths: vec![],
+            exclude_paths: vec![],
+            max_depth: default_max_depth(),
+        }
+    }
+}
+
+/// 根据闲置天数计算闲置分数
+pub fn staleness_score(stale_days: u64) -> u8 {
+    if stale_days <= ACTIVE_DAYS {
+        return 0;
+    }
+    if stale_days >= STALE_DAYS {
+        return 100;
+    }
+    ((stale_days - ACTIVE_DAYS) * 100 / (STALE_DAYS - ACTIVE_DAYS)) as u8
+}
+
+pub struct BuildArtifactDetector {
+    options: BuildArtifactScanOptions,
+    exclude_paths: Vec<PathPrefix>,
+}
+
+impl BuildArtifactDetector {
+    pub fn new() -> Self {
+        Self::with_options(BuildArtifactScanOptions::default())
+    }
+
+    pub fn with_options(options: BuildArtifactScanOptions) -> Self {
+        Self {
+            exclude_paths: options.exclude_paths.iter().map(PathPrefix::new).collect(),
+            options,
+        }
+    }
+
+    pub fn detect(&self) -> Vec<DevProject> {
+        let cancelled = AtomicBool::new(false);
+        self.detect_with_progress(&cancelled, |_, _| {})
+    }
+
+    /// 扫描项目，`on_project` 在每发现一个项目后调用
+    pub fn detect_with_progress<F>(
+        &self,
+        cancelled: &AtomicBool,
+        mut on_project: F,
+    ) -> Vec<DevProject>
+    where
+        F: FnMut(&Path, &DevProject),
+    {
+        let roots: Vec<PathBuf> = if self.options.scan_paths.is_empty() {
+            SystemPaths::home_dir().into_iter().collect()
+        } else {
+            self.options.scan_paths.iter().map(PathBuf::from).collect()
+        };
+
+        let now = now_secs();
+        let (project_roots, pycache_dirs) = self.find_projects(&roots, cancelled);
+        let mut pycache_by_project = assign_pycache_dirs(&project_roots, pycache_dirs);
+
+        let mut projects = Vec::new();
+        for (root, kinds) in project_roots {
+            if cancelled.load(Ordering::Relaxed) {
+                break;
+            }
+
+            let pycache = pycache_by_project.remove(&root).unwrap_or_default();
+            if let Some(project) = inspect_project(&root, &kinds, pycache, now) {
+                on_project(&root, &project);
+                projects.push(project);
+            }
+        }
+
+        projects.sort_by_key(|p| std::cmp::Reverse(p.cleanable_size));
+        projects
+    }
+
+    fn find_projects(
+        &self,
+        roots: &[PathBuf],
+        cancelled: &AtomicBool,
+    ) -> (Vec<(PathBuf, Vec<ProjectKind>)>, Vec<PathBuf>) {
+        let mut projects = Vec::new();
+        let mut pycache_dirs = Vec::new();
+
+        for root in roots {
+            let walker = WalkDir::new(root)
+                .follow_links(false)
+                .max_depth(self.options.max_depth)
+                .into_iter()
+                .filter_entry(|e| e.file_type().is_dir() && !self.is_excluded(e.path()));
+
+            for entry in walker.filter_map(|e| e.ok()) {
+                if cancelled.load(Ordering::Relaxed) {
+                    return (projects, pycache_dirs);
+                }
+
+                let name = entry.file_name().to_string_lossy();
+                if name == "__pycache__" {
+                    pycache_dirs.push(entry.path().to_path_buf());
+                    continue;
+                }
+                if entry.depth() > 0 && SKIP_DIRS.contains(&name.as_ref()) {
+                    continue;
+                }
+
+                let kinds = ProjectKind::detect(entry.path());
+                if !kinds.is_empty() {
+                    projects.push((entry.path().to_path_buf(), kinds));
+                }
+            }
+        }
+
+        (projects, pycache_dirs)
+    }
+
+    fn is_excluded(&self, path: &Path) -> bool {
+        self.exclude_paths.iter().any(|e| e.contains(path))
+            || path
+                .parent()
+                .and_then(|p| p.file_name())
+                .map(|n| SKIP_DIRS.contains(&n.to_string_lossy().as_ref()))
+                .unwrap_or(false)
+    }
+}
+
+impl Default for BuildArtifactDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 将 `__pycache__` 目录归到包含它的最深一层 Python 项目
+fn assign_pycache_dirs(
+    projects: &[(PathBuf, Vec<ProjectKind>)],
+    pycache_dirs: Vec<PathBuf>,
+) -> HashMap<PathBuf, Vec<PathBuf>> {
+    let mut by_project: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+
+    for dir in pycache_dirs {
+        let owner = projects
+            .iter()
+            .filter(|(root, kinds)| kinds.contains(&ProjectKind::Python) && dir.starts_with(root))
+            .max_by_key(|(root, _)| root.components().count());
+        if let Some((root, _)) = owner {
+            by_project.entry(root.clone()).or_default().push(dir);
+        }
+    }
+
+    by_project
+}
+
+/// 检查单个项目的构建产物与闲置时间，没有任何构建产物时返回 None
+pub fn inspect_project(
+    root: &Path,
+    kinds: &[ProjectKind],
+    pycache_dirs: Vec<PathBuf>,
+    now: i64,
+) -> Option<DevProject> {
+    let mut artifacts = Vec::new();
+
+    for kind in kinds {
+        for (dir, regenerable, note) in artifact_dirs(root, *kind) {
+            artifacts.push(measure_artifact(&dir, *kind, regenerable, note));
+        }
+    }
+    for dir in pycache_dirs {
+        artifacts.push(measure_artifact(
+            &dir,
+            ProjectKind::Python,
+            true,
+            "运行 Python 时自动生成".to_string(),
+        ));
+    }
+
+    // 同一目录可能被多种项目类型识别（如 Gradle 与 CMake 的 build），只保留一条
+    let mut seen = std::collections::HashSet::new();
+    artifacts.retain(|a| seen.insert(a.path.clone()));
+
+    if artifacts.is_empty() {
+        return None;
+    }
+
+    let artifact_paths: Vec<PathBuf> = artifacts.iter().map(|a| PathBuf::from(&a.path)).collect();
+    let last_activity = last_activity(root, &artifact_paths);
+    let stale_days = if last_activity > 0 && last_activity < now {
+        ((now - last_activity) / 86400) as u64
+    } else {
+        0
+    };
+
+    Some(DevProject {
+        root: root.to_string_lossy().to_string(),
+        name: root
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        kinds: kinds.to_vec(),
+        last_activity,
+        stale_days,
+        staleness_score: staleness_score(stale_days),
+        artifact_size: artifacts.iter().map(|a| a.size).sum(),
+        cleanable_size: artifacts
+            .iter()
+            .filter(|a| a.regenerable)
+            .map(|a| a.size)
+            .sum(),
+        artifacts,
+    })
+}
+
+/// 目录是否是其上级项目中可以重新生成的构建产物目录
+pub fn is_regenerable_artifact_dir(dir: &Path) -> bool {
+    let Some(root) = dir.parent() else {
+        return false;
+    };
+    ProjectKind::detect(root).into_iter().any(|kind| {
+        artifact_dirs(root, kind)
+            .iter()
+            .any(|(artifact, regenerable, _)| *regenerable && artifact == dir)
+    })
+}
+
+/// 列出某类项目根目录下存在的构建产物目录及其能否重新生成
+fn artifact_dirs(root: &Path, kind: ProjectKind) -> Vec<(PathBuf, bool, String)> {
+    let mut dirs = Vec::new();
+    let existing = |name: &str| {
+        let dir = root.join(name);
+        if dir.is_dir() && !is_symlink(&dir) {
+            Some(dir)
+        } else {
+            None
+        }
+    };
+
+    match kind {
+        ProjectKind::Cargo => {
+            if let Some(dir) = existing("target") {
+                let is_cargo_target = dir.join("CACHEDIR.TAG").is_file()
+                    || dir.join(".rustc_info.json").is_file()
+                    || dir.join("debug").is_dir()
+                    || dir.join("release").is_dir();
+                if is_cargo_target {
+                    dirs.push((dir, true, "cargo build".to_string()));
+                } else {
+                    dirs.push((dir, false, "目录内容不像 Cargo 的构建输出".to_string()));
+                }
+            }
+        }
+        ProjectKind::Node => {
+            if let Some(dir) = existing("node_modules") {
+                dirs.push((dir, true, node_install_command(root)));
+            }
+            for name in [".next", ".nuxt", ".parcel-cache", ".turbo"] {
+                if let Some(dir) = existing(name) {
+                    dirs.push((dir, true, "重新构建时自动生成".to_string()));
+                }
+            }
+            let has_build_script = package_json_has_script(root, "build");
+            for name in ["dist", "build"] {
+                if let Some(dir) = existing(name) {
+                    if has_build_script {
+                        dirs.push((dir, true, "npm run build".to_string()));
+                    } else {
+                        dirs.push((dir, false, "package.json 中没有 build 脚本".to_string()));
+                    }
+                }
+            }
+        }
+        ProjectKind::Gradle => {
+            if let Some(dir) = existing(".gradle") {
+                dirs.push((dir, true, "Gradle 运行时自动生成".to_string()));
+            }
+            // 只有 settings.gradle 的根项目自身不构建，build 目录可能是别的内容
+            if let Some(dir) = existing("build") {
+                let has_build_script = ["build.gradle", "build.gradle.kts"]
+                    .iter()
+                    .any(|name| root.join(name).is_file());
+                if has_build_script {
+                    dirs.push((dir, true, "gradle build".to_string()));
+                } else {
+                    dirs.push((dir, false, "没有 build.gradle 构建脚本".to_string()));
+                }
+            }
+        }
+        ProjectKind::Python => {
+            for name in [".pytest_cache", ".mypy_cache", ".ruff_cache", ".tox"] {
+                if let Some(dir) = existing(name) {
+                    dirs.push((dir, true, "运行对应工具时自动生成".to_string()));
+                }
+            }
+            for name in [".venv", "venv"] {
+                if let Some(dir) = existing(name) {
+                    if !dir.join("pyvenv.cfg").is_file() {
+                        continue;
+                    }
+                    if has_python_dependency_spec(root) {
+                        dirs.push((dir, true, "重新创建虚拟环境并安装依赖".to_string()));
+                    } else {
+                        dirs.push((dir, false, "没有依赖声明文件，无法重建虚拟环境".to_string()));
+                    }
+                }
+            }
+            if pyproject_has_build_system(root) {
+                for name in ["dist", "build"] {
+                    if let Some(dir) = existing(name) {
+                        dirs.push((dir, true, "python -m build".to_string()));
+                    }
+                }
+            }
+        }
+        ProjectKind::CMake => {
+            if let Ok(entries) = fs::read_dir(root) {
+                for entry in entries.flatten() {
+                    let dir = entry.path();
+                    if dir.is_dir() && !is_symlink(&dir) && dir.join("CMakeCache.txt").is_file() {
+                        dirs.push((dir, true, "cmake 重新配置并构建".to_string()));
+                    }
+                }
+            }
+        }
+    }
+
+    dirs
+}
+
+fn node_install_command(root: &Path) -> String {
+    if root.join("pnpm-lock.yaml").is_file() {
+        "pnpm install".to_string()
+    } else if root.join("yarn.lock").is_file() {
+        "yarn install".to_string()
+    } else if root.join("package-lock.json").is_file() {
+        "npm ci".to_string()
+    } else {
+        "npm install".to_string()
+    }
+}
+
+fn package_json_has_script(root: &Path, script: &str) -> bool {
+    fs::read_to_string(root.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|json| json.get("scripts").and_then(|s| s.get(script)).cloned())
+        .is_some()
+}
+
+fn has_python_dependency_spec(root: &Path) -> bool {
+    [
+        "pyproject.toml",
+        "requirements.txt",
+        "Pipfile",
+        "setup.py",
+        "setup.cfg",
+        "poetry.lock",
+    ]
+    .iter()
+    .any(|f| root.join(f).is_file())
+}
+
+fn pyproject_has_build_system(root: &Path) -> bool {
+    fs::read_to_string(root.join("pyproject.toml"))
+        .map(|content| content.contains("[build-system]"))
+        .unwrap_or(false)
+}
+
+fn is_symlink(path: &Path) -> bool {
+    fs::symlink_metadata(path)
+        .map(|m| m.file_type().is_symlink())
+        .unwrap_or(false)
+}
+
+fn measure_artifact(
+    dir: &Path,
+    kind: ProjectKind,
+    regenerable: bool,
+    note: String,
+) -> BuildArtifact {
+    let mut size = 0u64;
+    let mut file_count = 0u64;
+
+    for entry in WalkDir::new(dir)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+    {
+        if entry.file_type().is_file() {
+            if let Ok(metadata) = entry.metadata() {
+                size += metadata.len();
+                file_count += 1;
+            }
+        }
+    }
+
+    BuildArtifact {
+        path: dir.to_string_lossy().to_string(),
+        kind,
+        name: dir
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        size,
+        file_count,
+        regenerable,
+        note,
+    }
+}
+
+/// 项目最近一次改动时间：源码文件与 `.git` 元数据中最新的修改时间
+fn last_activity(root: &Path, artifact_paths: &[PathBuf]) -> i64 {
+    let mut latest = 0i64;
+
+    for marker in [".git/index", ".git/HEAD", ".git/FETCH_HEAD"] {
+        latest = latest.max(modified_secs(&root.join(marker)));
+    }
+
+    let walker = WalkDir::new(root)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|e| {
+            if e.depth() == 0 {
+                return true;
+            }
+            let name = e.file_name().to_string_lossy();
+            !(e.file_type().is_dir()
+                && (SKIP_DIRS.contains(&name.as_ref())
+                    || artifact_paths.iter().any(|a| a == e.path())))
+        });
+
+    for entry in walker
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .take(ACTIVITY_SAMPLE_LIMIT)
+    {
+        latest = latest.max(modified_secs(entry.path()));
+    }
+
+    latest
+}
+
+fn modified_secs(path: &Path) -> i64 {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+fn now_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildArtifactScanProgress {
+    pub scan_id: String,
+    pub current_path: String,
+    pub projects_found: u64,
+    pub artifact_size: u64,
+    pub cleanable_size: u64,
+    pub status: ScanStatus,
+}
+
+impl BuildArtifactScanProgress {
+    pub fn new(scan_id: &str) -> Self {
+        Self {
+            scan_id: scan_id.to_string(),
+            current_path: String::new(),
+            projects_found: 0,
+            artifact_size: 0,
+            cleanable_size: 0,
+            status: ScanStatus::Scanning,
+        }
+    }
+}
+
+impl ScanProgressTrait for BuildArtifactScanProgress {
+    fn set_status(&mut self, status: ScanStatus) {
+        self.status = status;
+    }
+
+    fn event_name() -> &'static str {
+        EVENT_BUILD_ARTIFACT_PROGRESS
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref SCAN_MANAGER: ScanManager<BuildArtifactScanProgress, BuildArtifactScanResult> = ScanManager::new();
+}
+
+pub async fn start_scan(
+    app: AppHandle,
+    options: BuildArtifactScanOptions,
+) -> Result<String, String> {
+    let scan_id = crate::models::generate_scan_id();
+    let progress = BuildArtifactScanProgress::new(&scan_id);
+    SCAN_MANAGER
+        .start_scan_with_id(app, scan_id, progress, move |mut ctx| async move {
+            perform_scan(&mut ctx, options).await
+        })
+        .await
+}
+
+pub async fn pause_scan(scan_id: &str) -> Result<(), String> {
+    SCAN_MANAGER.pause_scan(scan_id).await
+}
+
+pub async fn resume_scan(scan_id: &str) -> Result<(), String> {
+    SCAN_MANAGER.resume_scan(scan_id).await
+}
+
+pub async fn cancel_scan(scan_id: &str) -> Result<(), String> {
+    SCAN_MANAGER.cancel_scan(scan_id).await
+}
+
+pub async fn get_progress(scan_id: &str) -> Option<BuildArtifactScanProgress> {
+    SCAN_MANAGER.get_progress(scan_id).await
+}
+
+pub async fn get_result(scan_id: &str) -> Option<BuildArtifactScanResult> {
+    SCAN_MANAGER.get_result(scan_id).await
+}
+
+pub async fn clear_scan(scan_id: &str) -> Result<(), String> {
+    SCAN_MANAGER.clear_scan(scan_id).await
+}
+
+async fn perform_scan(
+    ctx: &mut ScanContext<BuildArtifactScanProgress>,
+    options: BuildArtifactScanOptions,
+) -> Result<BuildArtifactScanResult, String> {
+    let start_instant = Instant::now();
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let progress_store = SCAN_MANAGER.get_progress_store();
+
+    let scan_id = ctx.scan_id.clone();
+    let app = ctx.app.clone();
+    let cancelled_clone = cancelled.clone();
+    let progress_store_clone = progress_store.clone();
+
+    let handle = tokio::task::spawn_blocking(move || {
+        let detector = BuildArtifactDetector::with_options(options);
+        let mut projects_found = 0u64;
+        let mut artifact_size = 0u64;
+        let mut cleanable_size = 0u64;
+
+        detector.detect_with_progress(&cancelled_clone, |root, project| {
+            projects_found += 1;
+            artifact_size += project.artifact_size;
+            cleanable_size += project.cleanable_size;
+
+            let progress = BuildArtifactScanProgress {
+                scan_id: scan_id.clone(),
+                current_path: root.display().to_string(),
+                projects_found,
+                artifact_size,
+                cleanable_size,
+                status: ScanStatus::Scanning,
+            };
+            let _ = app.emit(EVENT_BUILD_ARTIFACT_PROGRESS, &progress);
+
+            if let Ok(mut store) = progress_store_clone.try_write() {
+                if let Some(p) = store.get_mut(&scan_id) {
+                    p.current_path = progress.current_path;
+                    p.projects_found = projects_found;
+                    p.artifact_size = artifact_size;
+                    p.cleanable_size = cleanable_size;
+                }
+            }
+        })
+    });
+
+    loop {
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+        if ctx.check_control(&progress_store).await == ControlAction::Cancel {
+            cancelled.store(true, Ordering::Relaxed);
+            handle.await.ok();
+            return Err("Scan cancelled".to_string());
+        }
+
+        if handle.is_finished() {
+            break;
+        }
+    }
+
+    let projects = handle.await.map_err(|e| e.to_string())?;
+
+    let result = BuildArtifactScanResult {
+        scan_id: ctx.scan_id.clone(),
+        project_count: projects.len() as u64,
+        artifact_size: projects.iter().map(|p| p.artifact_size).sum(),
+        cleanable_size: projects.iter().map(|p| p.cleanable_size).sum(),
+        projects,
+        duration_ms: start_instant.elapsed().as_millis() as u64,
+    };
+
+    {
+        let mut store = progress_store.write().await;
+        if let Some(progress) = store.get_mut(&ctx.scan_id) {
+            progress.status = ScanStatus::Completed;
+            let _ = ctx
+                .app
+                .emit(EVENT_BUILD_ARTIFACT_PROGRESS, progress.clone());
+        }
+    }
+    let _ = ctx.app.emit(EVENT_BUILD_ARTIFACT_COMPLETE, &result);
+
+    Ok(result)
+}
@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 use walkdir::WalkDir;
 
 use crate::utils::file_type::get_file_type;
+use crate::utils::path_match::SegmentPattern;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileTypeStats {
@@ -121,6 +122,12 @@ impl CancellableFileClassifier {
         let mut total_folders: u64 = 0;
         let mut largest_files: Vec<FileBriefInfo> = Vec::new();
         let mut file_count = 0;
+        let exclude_patterns: Vec<SegmentPattern> = self
+            .options
+            .exclude_paths
+            .iter()
+            .map(|exclude| SegmentPattern::new(exclude))
+            .collect();
 
         let walker = WalkDir::new(&root_path)
             .follow_links(false)
@@ -138,11 +145,8 @@ impl CancellableFileClassifier {
                     }
                 }
                 
-                let path_str = e.path().to_string_lossy().to_lowercase();
-                for exclude in &self.options.exclude_paths {
-                    if path_str.contains(&exclude.to_lowercase()) {
-                        return false;
-                    }
+                if exclude_patterns.iter().any(|pattern| pattern.matches(e.path())) {
+                    return false;
                 }
                 
                 true
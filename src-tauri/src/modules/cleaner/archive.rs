@@ -0,0 +1,568 @@
+//! 归档代替删除
+//!
+//! 将选中的文件打包为 tar.zst 或 zip，并在归档中嵌入清单，记录每个文件的原路径、
+//! 修改时间与 SHA-256。写入完成后重新读取整个归档逐一校验，校验通过后只删除
+//! 清单中大小与修改时间未变的原文件。恢复时按清单把选中的条目写回原位置，
+//! 并还原修改时间。
+//!
+//! 只归档普通文件：目录按其中的文件归档，空目录不保留；包含符号链接的路径
+//! 拒绝归档，避免删除后无法还原。
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+use sha2::{Digest, Sha256};
+use walkdir::WalkDir;
+
+use super::safe_remove::{remove_empty_dir, remove_file_if};
+use super::safety::SafetyChecker;
+use crate::models::{
+    ArchiveFormat, ArchiveManifest, ArchiveManifestEntry, CleanError, DiskTidyError, RestoreResult,
+};
+
+/// 清单在归档中的条目名
+pub const MANIFEST_NAME: &str = "disktidy-manifest.json";
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// 列出路径下需要归档的文件，路径本身是文件时返回其自身
+pub fn collect_files(path: &Path) -> io::Result<Vec<PathBuf>> {
+    let metadata = fs::symlink_metadata(path)?;
+    if metadata.file_type().is_symlink() {
+        return Err(symlink_error(path));
+    }
+    if metadata.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut files = Vec::new();
+    for entry in WalkDir::new(path).follow_links(false).sort_by_file_name() {
+        let entry = entry.map_err(io::Error::from)?;
+        let file_type = entry.file_type();
+        if file_type.is_symlink() {
+            return Err(symlink_error(entry.path()));
+        }
+        if file_type.is_file() {
+            files.push(entry.into_path());
+        }
+    }
+    Ok(files)
+}
+
+fn symlink_error(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("符号链接无法归档: {}", path.display()),
+    )
+}
+
+/// 由绝对路径生成归档中的条目名，如 `C/Users/a.txt`、`home/user/a.txt`
+pub fn entry_name(path: &Path) -> String {
+    let mut parts = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Prefix(prefix) => {
+                let prefix = prefix.as_os_str().to_string_lossy();
+                parts.push(
+                    prefix
+                        .trim_start_matches(r"\\?\")
+                        .trim_end_matches(':')
+                        .replace(['\\', ':'], "_"),
+                );
+            }
+            Component::Normal(name) => parts.push(name.to_string_lossy().to_string()),
+            Component::RootDir | Component::CurDir | Component::ParentDir => {}
+        }
+    }
+    parts.join("/")
+}
+
+/// 将文件打包到 `destination` 目录下的新归档中，返回归档路径与清单
+///
+/// 先写入 `.partial` 临时文件，同步后再重命名，失败时删除临时文件。
+pub fn create_archive(
+    destination: &Path,
+    format: ArchiveFormat,
+    compression_level: i32,
+    files: &[PathBuf],
+) -> io::Result<(PathBuf, ArchiveManifest)> {
+    fs::create_dir_all(destination)?;
+    let archive_path = unique_archive_path(destination, format);
+    let partial_path = PathBuf::from(format!("{}.partial", archive_path.display()));
+
+    let written = File::create(&partial_path).and_then(|file| match format {
+        ArchiveFormat::TarZst => write_tar_zst(file, compression_level, files),
+        ArchiveFormat::Zip => write_zip(file, compression_level, files),
+    });
+    let manifest = match written.and_then(|manifest| {
+        fs::rename(&partial_path, &archive_path)?;
+        Ok(manifest)
+    }) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            let _ = fs::remove_file(&partial_path);
+            return Err(e);
+        }
+    };
+
+    Ok((archive_path, manifest))
+}
+
+fn unique_archive_path(destination: &Path, format: ArchiveFormat) -> PathBuf {
+    let stem = format!(
+        "disktidy-archive-{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    );
+    let mut path = destination.join(format!("{}{}", stem, format.extension()));
+    let mut index = 1;
+    while path.exists() {
+        path = destination.join(format!("{}-{}{}", stem, index, format.extension()));
+        index += 1;
+    }
+    path
+}
+
+fn new_manifest(format: ArchiveFormat) -> ArchiveManifest {
+    ArchiveManifest {
+        version: MANIFEST_VERSION,
+        created_at: chrono::Utc::now().timestamp(),
+        hostname: hostname::get()
+            .map(|h| h.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        format,
+        entries: Vec::new(),
+    }
+}
+
+/// 打开待归档的文件，返回其清单条目（哈希待写入后填充）
+fn open_source(path: &Path) -> io::Result<(File, ArchiveManifestEntry)> {
+    let file = File::open(path)?;
+    let metadata = file.metadata()?;
+    let (modified_time, modified_nanos) = modified_parts(&metadata);
+
+    Ok((
+        file,
+        ArchiveManifestEntry {
+            original_path: path.to_string_lossy().to_string(),
+            archive_path: entry_name(path),
+            size: metadata.len(),
+            modified_time,
+            modified_nanos,
+            sha256: String::new(),
+        },
+    ))
+}
+
+/// 修改时间的秒数与纳秒部分，读取失败时为 0
+fn modified_parts(metadata: &fs::Metadata) -> (i64, u32) {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| (d.as_secs() as i64, d.subsec_nanos()))
+        .unwrap_or((0, 0))
+}
+
+/// 写入归档时同时计算哈希，并检查读取的长度与归档前的大小一致
+fn finish_entry(
+    mut entry: ArchiveManifestEntry,
+    reader: HashingReader<io::Take<File>>,
+) -> io::Result<ArchiveManifestEntry> {
+    let (read, sha256) = reader.finish();
+    if read != entry.size {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("文件在归档过程中被修改: {}", entry.original_path),
+        ));
+    }
+    entry.sha256 = sha256;
+    Ok(entry)
+}
+
+fn write_tar_zst(file: File, level: i32, files: &[PathBuf]) -> io::Result<ArchiveManifest> {
+    let mut manifest = new_manifest(ArchiveFormat::TarZst);
+    let encoder = zstd::stream::write::Encoder::new(file, level.clamp(1, 22))?;
+    let mut builder = tar::Builder::new(encoder);
+
+    for path in files {
+        let (source, entry) = open_source(path)?;
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Regular);
+        header.set_size(entry.size);
+        header.set_mode(0o644);
+        header.set_mtime(entry.modified_time.max(0) as u64);
+
+        let mut reader = HashingReader::new(source.take(entry.size));
+        builder.append_data(&mut header, &entry.archive_path, &mut reader)?;
+        manifest.entries.push(finish_entry(entry, reader)?);
+    }
+
+    let manifest_json = serde_json::to_vec_pretty(&manifest)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_entry_type(tar::EntryType::Regular);
+    header.set_size(manifest_json.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(manifest.created_at.max(0) as u64);
+    builder.append_data(&mut header, MANIFEST_NAME, manifest_json.as_slice())?;
+
+    let file = builder.into_inner()?.finish()?;
+    file.sync_all()?;
+    Ok(manifest)
+}
+
+fn write_zip(file: File, level: i32, files: &[PathBuf]) -> io::Result<ArchiveManifest> {
+    use zip::write::SimpleFileOptions;
+    use zip::CompressionMethod;
+
+    let mut manifest = new_manifest(ArchiveFormat::Zip);
+    let mut writer = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .compression_level(Some(level.clamp(0, 9) as i64));
+
+    for path in files {
+        let (source, entry) = open_source(path)?;
+        let entry_options = options
+            .large_file(entry.size >= u32::MAX as u64)
+            .last_modified_time(zip_time(entry.modified_time));
+        writer.start_file(entry.archive_path.as_str(), entry_options)?;
+
+        let mut reader = HashingReader::new(source.take(entry.size));
+        io::copy(&mut reader, &mut writer)?;
+        manifest.entries.push(finish_entry(entry, reader)?);
+    }
+
+    writer.start_file(MANIFEST_NAME, options)?;
+    serde_json::to_writer_pretty(&mut writer, &manifest)?;
+
+    let file = writer.finish()?;
+    file.sync_all()?;
+    Ok(manifest)
+}
+
+/// zip 只能记录 1980 年之后、精度为两秒的本地时间，精确的时间以清单为准
+fn zip_time(timestamp: i64) -> zip::DateTime {
+    use chrono::{Datelike, Timelike};
+
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|t| t.with_timezone(&chrono::Local).naive_local())
+        .and_then(|t| {
+            zip::DateTime::from_date_and_time(
+                t.year().clamp(1980, 2107) as u16,
+                t.month() as u8,
+                t.day() as u8,
+                t.hour() as u8,
+                t.minute() as u8,
+                t.second() as u8,
+            )
+            .ok()
+        })
+        .unwrap_or_default()
+}
+
+/// 依次读取归档中的每个条目
+fn visit_entries(
+    archive_path: &Path,
+    visitor: &mut dyn FnMut(&str, &mut dyn Read) -> io::Result<()>,
+) -> io::Result<()> {
+    let format = ArchiveFormat::from_path(archive_path).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("不支持的归档格式: {}", archive_path.display()),
+        )
+    })?;
+    let file = File::open(archive_path)?;
+
+    match format {
+        ArchiveFormat::TarZst => {
+            let mut archive = tar::Archive::new(zstd::stream::read::Decoder::new(file)?);
+            for entry in archive.entries()? {
+                let mut entry = entry?;
+                let name = entry.path()?.to_string_lossy().to_string();
+                visitor(&name, &mut entry)?;
+            }
+        }
+        ArchiveFormat::Zip => {
+            let mut archive = zip::ZipArchive::new(file)?;
+            for index in 0..archive.len() {
+                let mut entry = archive.by_index(index)?;
+                let name = entry.name().to_string();
+                visitor(&name, &mut entry)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 读取归档中的清单
+pub fn read_manifest(archive_path: &Path) -> io::Result<ArchiveManifest> {
+    let mut manifest = None;
+    visit_entries(archive_path, &mut |name, reader| {
+        if name == MANIFEST_NAME {
+            manifest = Some(serde_json::from_reader(reader)?);
+        }
+        Ok(())
+    })?;
+
+    manifest.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("归档中没有清单: {}", archive_path.display()),
+        )
+    })
+}
+
+/// 重新读取归档，检查每个文件的大小与哈希都与清单一致
+pub fn verify_archive(archive_path: &Path, manifest: &ArchiveManifest) -> io::Result<()> {
+    let mut expected: HashMap<&str, &ArchiveManifestEntry> = manifest
+        .entries
+        .iter()
+        .map(|e| (e.archive_path.as_str(), e))
+        .collect();
+    let mut embedded = None;
+
+    visit_entries(archive_path, &mut |name, reader| {
+        if name == MANIFEST_NAME {
+            embedded = Some(serde_json::from_reader::<_, ArchiveManifest>(reader)?);
+            return Ok(());
+        }
+        let entry = expected.remove(name).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("归档中有清单之外的条目: {}", name),
+            )
+        })?;
+        let (size, sha256) = hash_reader(reader)?;
+        if size != entry.size || sha256 != entry.sha256 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("归档校验失败: {}", entry.original_path),
+            ));
+        }
+        Ok(())
+    })?;
+
+    if let Some(missing) = expected.values().next() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("归档中缺少文件: {}", missing.original_path),
+        ));
+    }
+    match embedded {
+        Some(embedded) if embedded.entries == manifest.entries => Ok(()),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "归档中的清单与写入时不一致",
+        )),
+    }
+}
+
+/// 删除已归档的原文件，返回未能删除的文件及原因
+///
+/// 只删除清单中的文件。删除前确认大小与修改时间仍与清单一致，归档之后被
+/// 修改的文件保留；归档之后新增到目录中的文件不在清单中，不会被删除。
+pub fn remove_archived_files(manifest: &ArchiveManifest) -> Vec<(PathBuf, io::Error)> {
+    let mut failures = Vec::new();
+    for entry in &manifest.entries {
+        let path = PathBuf::from(&entry.original_path);
+        let unchanged = |metadata: &fs::Metadata| {
+            metadata.len() == entry.size
+                && modified_parts(metadata) == (entry.modified_time, entry.modified_nanos)
+        };
+        match remove_file_if(&path, unchanged) {
+            Ok(true) => {}
+            Ok(false) => failures.push((path, io::Error::other("文件在归档后被修改，未删除"))),
+            Err(e) => failures.push((path, e)),
+        }
+    }
+    failures
+}
+
+/// 自下而上删除目录中的空目录，包括目录本身，非空的目录保留
+pub fn prune_empty_dirs(root: &Path) {
+    for entry in WalkDir::new(root)
+        .follow_links(false)
+        .contents_first(true)
+        .into_iter()
+        .filter_map(Result::ok)
+    {
+        if entry.file_type().is_dir() {
+            let _ = remove_empty_dir(entry.path());
+        }
+    }
+}
+
+/// 将归档中选中的文件恢复到原位置
+///
+/// `selection` 为原路径或其上级目录，为空时恢复全部文件。目标已存在且
+/// `overwrite` 为假时跳过。清单中的路径来自归档文件本身，包含 `..` 或未通过
+/// `checker` 检查（受保护目录、敏感文件等）的条目拒绝恢复。每个文件先写入
+/// 同目录下的临时文件，校验哈希并还原修改时间后再重命名到原位置。
+pub fn restore_archive(
+    archive_path: &Path,
+    selection: Option<&[String]>,
+    overwrite: bool,
+    checker: &SafetyChecker,
+) -> io::Result<RestoreResult> {
+    let manifest = read_manifest(archive_path)?;
+    let mut wanted: HashMap<String, ArchiveManifestEntry> = manifest
+        .entries
+        .into_iter()
+        .filter(|e| match selection {
+            Some(selected) => selected
+                .iter()
+                .any(|s| Path::new(&e.original_path).starts_with(s)),
+            None => true,
+        })
+        .map(|e| (e.archive_path.clone(), e))
+        .collect();
+
+    let mut result = RestoreResult {
+        restored_files: 0,
+        restored_size: 0,
+        skipped_files: 0,
+        errors: Vec::new(),
+    };
+
+    visit_entries(archive_path, &mut |name, reader| {
+        let Some(entry) = wanted.remove(name) else {
+            return Ok(());
+        };
+        let target = PathBuf::from(&entry.original_path);
+        if let Err(e) = check_restore_target(&target, checker) {
+            result.errors.push(restore_error(&entry.original_path, e));
+            return Ok(());
+        }
+        if fs::symlink_metadata(&target).is_ok() && !overwrite {
+            result.skipped_files += 1;
+            return Ok(());
+        }
+        match restore_entry(&entry, &target, reader) {
+            Ok(()) => {
+                result.restored_files += 1;
+                result.restored_size += entry.size;
+            }
+            Err(e) => result.errors.push(restore_error(&entry.original_path, e)),
+        }
+        Ok(())
+    })?;
+
+    for entry in wanted.into_values() {
+        result.errors.push(restore_error(
+            &entry.original_path,
+            io::Error::new(io::ErrorKind::NotFound, "归档中缺少该文件"),
+        ));
+    }
+
+    Ok(result)
+}
+
+/// 检查清单中的原路径能否写入
+fn check_restore_target(target: &Path, checker: &SafetyChecker) -> io::Result<()> {
+    let normalized = target.is_absolute()
+        && target
+            .components()
+            .all(|c| !matches!(c, Component::ParentDir | Component::CurDir));
+    if !normalized {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("清单中的路径无效: {}", target.display()),
+        ));
+    }
+    if !checker.is_safe_to_delete(target) {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("不能恢复到受保护的位置: {}", target.display()),
+        ));
+    }
+    Ok(())
+}
+
+fn restore_entry(
+    entry: &ArchiveManifestEntry,
+    target: &Path,
+    reader: &mut dyn Read,
+) -> io::Result<()> {
+    let parent = target.parent().unwrap_or(target);
+    fs::create_dir_all(parent)?;
+
+    let file_name = target
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let temp_path = parent.join(format!(".{}.disktidy-restore", file_name));
+
+    let restored = (|| {
+        let mut file = File::create(&temp_path)?;
+        let mut hashing = HashingReader::new(reader);
+        io::copy(&mut hashing, &mut file)?;
+        let (size, sha256) = hashing.finish();
+        if size != entry.size || sha256 != entry.sha256 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "恢复的内容与清单中的哈希不一致",
+            ));
+        }
+        if entry.modified_time >= 0 {
+            file.set_modified(
+                UNIX_EPOCH + Duration::new(entry.modified_time as u64, entry.modified_nanos),
+            )?;
+        }
+        file.sync_all()?;
+        fs::rename(&temp_path, target)
+    })();
+
+    if restored.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    restored
+}
+
+fn restore_error(path: &str, error: io::Error) -> CleanError {
+    let error = DiskTidyError::IoError(error);
+    CleanError {
+        path: path.to_string(),
+        error_code: error.error_code().to_string(),
+        error_message: error.to_string(),
+        processes: Vec::new(),
+    }
+}
+
+fn hash_reader(reader: &mut dyn Read) -> io::Result<(u64, String)> {
+    let mut hashing = HashingReader::new(reader);
+    io::copy(&mut hashing, &mut io::sink())?;
+    Ok(hashing.finish())
+}
+
+/// 读取的同时计算 SHA-256
+struct HashingReader<R> {
+    inner: R,
+    hasher: Sha256,
+    read: u64,
+}
+
+impl<R: Read> HashingReader<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+            read: 0,
+        }
+    }
+
+    fn finish(self) -> (u64, String) {
+        (self.read, format!("{:x}", self.hasher.finalize()))
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.read += n as u64;
+        Ok(n)
+    }
+}
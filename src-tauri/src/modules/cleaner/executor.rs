@@ -1,16 +1,26 @@
+use futures::stream::{self, StreamExt};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::fs;
-use tokio::io::AsyncWriteExt;
 use tokio::sync::RwLock;
 
 use crate::models::{
     CleanError, CleanOptions, CleanProgress, CleanResult, DiskTidyError, GarbageCategory,
-    GarbageFile, DuplicateGroup,
+    GarbageFile, DuplicateGroup, EntrySize, FileHolder, ArchiveOptions, ArchiveResult,
 };
+use super::archive::{
+    collect_files, create_archive, prune_empty_dirs, remove_archived_files, verify_archive,
+};
+use super::in_use::{describe_holders, InUseDetector, OpenFileIndex, ProcessTable};
+use super::io_priority::BackgroundPool;
+use super::throttle::{device_id, DeviceLimiter, IoThrottle};
 use super::safety::SafetyChecker;
 use super::recycle_bin::RecycleBin;
+use super::safe_remove::{is_symlink_error, remove_nofollow, resolve_delete_target};
+use super::secure_erase::{storage_warnings, SecureEraser};
+use super::size::{bytes_per_second, measure_entry};
 
 pub const SECURE_OVERWRITE_PATTERNS: [u8; 3] = [0x00, 0xFF, 0xAA];
 pub const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
@@ -18,6 +28,9 @@ pub const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
 pub struct CleanerExecutor {
     options: CleanOptions,
     safety_checker: SafetyChecker,
+    in_use: Arc<InUseDetector>,
+    /// 启用后台优先级时执行阻塞文件操作的专用线程
+    background: Option<BackgroundPool>,
     cancelled: Arc<RwLock<bool>>,
 }
 
@@ -29,12 +42,38 @@ pub struct CleanContext {
     pub skipped_files: u64,
     pub total_size: u64,
     pub cleaned_size: u64,
+    pub freed_size: u64,
+    pub trashed_size: u64,
     pub errors: Vec<CleanError>,
     pub start_time: Instant,
 }
 
 pub type ProgressCallback = Arc<dyn Fn(CleanProgress) + Send + Sync>;
 
+/// 一次清理过程中各批次共享的状态
+struct CleanBatch<'a> {
+    ctx: &'a mut CleanContext,
+    /// 错误及其在输入中的位置，结束后按输入顺序排列
+    errors: Vec<(usize, CleanError)>,
+    progress_callback: Option<ProgressCallback>,
+    throttle: IoThrottle,
+    devices: DeviceLimiter,
+}
+
+/// 路径被清理的方式，决定其空间是否真正释放
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Disposal {
+    Removed,
+    Trashed,
+}
+
+enum ItemOutcome {
+    /// 删除前统计的大小与清理结果
+    Done(EntrySize, Result<Disposal, DiskTidyError>),
+    InUse(Vec<FileHolder>),
+    Cancelled,
+}
+
 impl CleanerExecutor {
     pub fn new() -> Self {
         Self::with_options(CleanOptions::default())
@@ -43,15 +82,37 @@ impl CleanerExecutor {
     pub fn with_options(options: CleanOptions) -> Self {
         Self {
             safety_checker: SafetyChecker::new(),
+            in_use: Arc::new(InUseDetector::new()),
+            background: Self::background_pool(&options),
             options,
             cancelled: Arc::new(RwLock::new(false)),
         }
     }
 
     pub fn set_options(&mut self, options: CleanOptions) {
+        if options.io != self.options.io {
+            self.background = Self::background_pool(&options);
+        }
         self.options = options;
     }
 
+    fn background_pool(options: &CleanOptions) -> Option<BackgroundPool> {
+        options
+            .io
+            .background_priority
+            .then(|| BackgroundPool::new(options.io.max_concurrency))
+    }
+
+    /// 共享的取消标志，供外部在清理进行中取消
+    pub fn cancel_flag(&self) -> Arc<RwLock<bool>> {
+        self.cancelled.clone()
+    }
+
+    /// 替换占用检测所用的进程信息来源
+    pub fn set_process_table(&mut self, table: Box<dyn ProcessTable>) {
+        self.in_use = Arc::new(InUseDetector::with_table(table));
+    }
+
     pub async fn cancel(&self) {
         let mut cancelled = self.cancelled.write().await;
         *cancelled = true;
@@ -68,6 +129,11 @@ impl CleanerExecutor {
         self.clean_with_progress(files, None).await
     }
 
+    /// 并发清理多个路径
+    ///
+    /// 同时处理的路径数受 `io.max_concurrency` 与 `io.per_device_concurrency`
+    /// 限制，并按 `io` 中的速率限制等待。每完成一个路径回调一次进度；取消后不再
+    /// 开始新的路径，已开始的路径会执行完毕，未开始的计入跳过数。
     pub async fn clean_with_progress(
         &self,
         files: Vec<PathBuf>,
@@ -82,45 +148,47 @@ impl CleanerExecutor {
             skipped_files: 0,
             total_size: 0,
             cleaned_size: 0,
+            freed_size: 0,
+            trashed_size: 0,
             errors: Vec::new(),
             start_time: Instant::now(),
         };
+        let mut batch = CleanBatch {
+            ctx: &mut ctx,
+            errors: Vec::new(),
+            progress_callback,
+            throttle: IoThrottle::from_options(&self.options.io),
+            devices: DeviceLimiter::new(self.options.io.per_device_concurrency),
+        };
 
-        for path in &files {
-            if self.is_cancelled().await {
-                break;
-            }
+        let secure_delete_warnings = if self.uses_secure_delete() {
+            let paths = files.clone();
+            self.run_blocking(move || storage_warnings(&paths))
+                .await
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
 
-            if let Some(ref callback) = progress_callback {
-                callback(CleanProgress {
-                    total_files: ctx.total_files,
-                    cleaned_files: ctx.cleaned_files,
-                    current_file: path.to_string_lossy().to_string(),
-                    percent: if ctx.total_files > 0 {
-                        ctx.cleaned_files as f32 / ctx.total_files as f32 * 100.0
-                    } else {
-                        0.0
-                    },
-                    speed: self.calculate_speed(&ctx),
-                });
-            }
+        // 被占用的文件推迟到最后处理，届时重新检测一次，仍被占用则跳过
+        let items: Vec<(usize, PathBuf)> = files.into_iter().enumerate().collect();
+        let index = self.snapshot_in_use().await;
+        let deferred = self.run_batch(&mut batch, items, &index, false).await;
 
-            match self.clean_single(path).await {
-                Ok(size) => {
-                    ctx.cleaned_files += 1;
-                    ctx.cleaned_size += size;
-                }
-                Err(e) => {
-                    ctx.failed_files += 1;
-                    ctx.errors.push(CleanError {
-                        path: path.to_string_lossy().to_string(),
-                        error_code: e.error_code().to_string(),
-                        error_message: e.to_string(),
-                    });
-                }
+        if !deferred.is_empty() {
+            if self.is_cancelled().await {
+                batch.ctx.skipped_files += deferred.len() as u64;
+            } else {
+                let index = self.snapshot_in_use().await;
+                self.run_batch(&mut batch, deferred, &index, true).await;
             }
         }
 
+        let mut errors = batch.errors;
+        errors.sort_by_key(|(index, _)| *index);
+        ctx.errors = errors.into_iter().map(|(_, error)| error).collect();
+        let elapsed = ctx.start_time.elapsed();
+
         Ok(CleanResult {
             scan_id: clean_id,
             total_files: ctx.total_files,
@@ -129,63 +197,239 @@ impl CleanerExecutor {
             skipped_files: ctx.skipped_files,
             total_size: ctx.total_size,
             cleaned_size: ctx.cleaned_size,
+            freed_size: ctx.freed_size,
+            trashed_size: ctx.trashed_size,
+            bytes_per_sec: bytes_per_second(ctx.cleaned_size, elapsed),
+            secure_delete_warnings,
             errors: ctx.errors,
-            duration_ms: ctx.start_time.elapsed().as_millis() as u64,
+            duration_ms: elapsed.as_millis() as u64,
         })
     }
 
-    fn calculate_speed(&self, ctx: &CleanContext) -> u64 {
-        let elapsed = ctx.start_time.elapsed().as_secs();
-        if elapsed > 0 {
-            ctx.cleaned_size / elapsed
+    /// 并发处理一批路径，返回被占用而推迟的路径
+    ///
+    /// `final_pass` 为真时不再推迟，被占用的路径直接记为跳过。
+    async fn run_batch(
+        &self,
+        batch: &mut CleanBatch<'_>,
+        items: Vec<(usize, PathBuf)>,
+        index: &OpenFileIndex,
+        final_pass: bool,
+    ) -> Vec<(usize, PathBuf)> {
+        let throttle = &batch.throttle;
+        let devices = &batch.devices;
+        let secure_passes = if self.uses_secure_delete() {
+            SecureEraser::new(&self.options.secure, self.options.secure_pass_count)
+                .passes()
+                .len() as u64
         } else {
-            0
+            1
+        };
+
+        let mut outcomes = stream::iter(items)
+            .map(|(position, path)| async move {
+                if self.is_cancelled().await {
+                    return (position, path, ItemOutcome::Cancelled);
+                }
+
+                let holders = index.holders_of(&path);
+                if !holders.is_empty() {
+                    return (position, path, ItemOutcome::InUse(holders));
+                }
+
+                let metadata = std::fs::symlink_metadata(&path).ok();
+                let _permit = devices.acquire(device_id(&path, metadata.as_ref())).await;
+                let size = self.measure(&path).await;
+                if !throttle.is_unlimited() {
+                    throttle.acquire(size.apparent * secure_passes).await;
+                }
+
+                let result = self.clean_measured(&path).await;
+                (position, path, ItemOutcome::Done(size, result))
+            })
+            .buffer_unordered(self.options.io.max_concurrency.max(1));
+
+        let mut deferred = Vec::new();
+        while let Some((position, path, outcome)) = outcomes.next().await {
+            match outcome {
+                ItemOutcome::Cancelled => {
+                    batch.ctx.skipped_files += 1;
+                    continue;
+                }
+                ItemOutcome::InUse(_) if !final_pass => {
+                    deferred.push((position, path));
+                    continue;
+                }
+                ItemOutcome::InUse(holders) => {
+                    let error = Self::in_use_error(&path, &holders);
+                    batch.ctx.skipped_files += 1;
+                    batch.errors.push((
+                        position,
+                        CleanError {
+                            path: path.to_string_lossy().to_string(),
+                            error_code: error.error_code().to_string(),
+                            error_message: error.to_string(),
+                            processes: holders,
+                        },
+                    ));
+                }
+                ItemOutcome::Done(size, Ok(disposal)) => {
+                    batch.ctx.total_size += size.apparent;
+                    batch.ctx.cleaned_files += 1;
+                    batch.ctx.cleaned_size += size.apparent;
+                    match disposal {
+                        Disposal::Removed => batch.ctx.freed_size += size.reclaimable,
+                        Disposal::Trashed => batch.ctx.trashed_size += size.allocated,
+                    }
+                }
+                ItemOutcome::Done(size, Err(e)) => {
+                    batch.ctx.total_size += size.apparent;
+                    batch.ctx.failed_files += 1;
+                    batch.errors.push((
+                        position,
+                        CleanError {
+                            path: path.to_string_lossy().to_string(),
+                            error_code: e.error_code().to_string(),
+                            error_message: e.to_string(),
+                            processes: Vec::new(),
+                        },
+                    ));
+                }
+            }
+
+            if let Some(ref callback) = batch.progress_callback {
+                let ctx = &*batch.ctx;
+                let processed = ctx.cleaned_files + ctx.failed_files + ctx.skipped_files;
+                callback(CleanProgress {
+                    total_files: ctx.total_files,
+                    cleaned_files: ctx.cleaned_files,
+                    current_file: path.to_string_lossy().to_string(),
+                    percent: if ctx.total_files > 0 {
+                        processed as f32 / ctx.total_files as f32 * 100.0
+                    } else {
+                        0.0
+                    },
+                    speed: bytes_per_second(ctx.cleaned_size, ctx.start_time.elapsed()),
+                });
+            }
         }
+
+        deferred
+    }
+
+    pub async fn snapshot_in_use(&self) -> OpenFileIndex {
+        let detector = self.in_use.clone();
+        tokio::task::spawn_blocking(move || detector.snapshot())
+            .await
+            .unwrap_or_default()
+    }
+
+    fn in_use_error(path: &Path, holders: &[FileHolder]) -> DiskTidyError {
+        DiskTidyError::CleanFileInUse(format!(
+            "{}（{}）",
+            path.to_string_lossy(),
+            describe_holders(holders)
+        ))
+    }
+
+    /// 查询当前占用该路径的进程
+    pub async fn in_use_holders(&self, path: &Path) -> Vec<FileHolder> {
+        self.snapshot_in_use().await.holders_of(path)
+    }
+
+    /// 统计路径的大小，路径不存在或无法读取时为 0
+    async fn measure(&self, path: &Path) -> EntrySize {
+        let measure_path = path.to_path_buf();
+        self.run_blocking(move || measure_entry(&measure_path))
+            .await
+            .ok()
+            .and_then(|r| r.ok())
+            .unwrap_or_default()
     }
 
+    /// 清理单个路径，返回清理的表观字节数（目录为其全部内容）
+    ///
+    /// 被其他进程占用时返回 `CleanFileInUse`。
     pub async fn clean_single(&self, path: &Path) -> Result<u64, DiskTidyError> {
-        if !path.exists() {
+        let holders = self.in_use_holders(path).await;
+        if !holders.is_empty() {
+            return Err(Self::in_use_error(path, &holders));
+        }
+        let size = self.measure(path).await;
+        self.clean_measured(path).await?;
+        Ok(size.apparent)
+    }
+
+    async fn clean_measured(&self, path: &Path) -> Result<Disposal, DiskTidyError> {
+        // 不跟随符号链接：悬空的链接本身也可以被清理
+        if fs::symlink_metadata(path).await.is_err() {
             return Err(DiskTidyError::FileNotFound {
                 path: path.to_string_lossy().to_string(),
             });
         }
 
-        let safety_result = self.safety_checker.check(path);
+        // 只规范化一次，安全检查与删除针对的是同一个路径
+        let target = resolve_delete_target(path).map_err(|e| self.handle_delete_error(path, e))?;
+        let safety_result = self.safety_checker.check(&target);
         if !safety_result.safe_to_delete {
             return Err(DiskTidyError::ProtectedPath {
                 path: path.to_string_lossy().to_string(),
             });
         }
 
-        let metadata = fs::metadata(path).await.map_err(DiskTidyError::IoError)?;
-        let file_size = metadata.len();
-
+        // 回收站与原文件位于同一卷（Windows 每个卷有各自的回收站，其他平台
+        // 通过重命名移入），移入后空间并未释放
         if self.options.move_to_recycle_bin {
             self.move_to_recycle_bin(path).await?;
-        } else if self.options.secure_delete {
-            self.secure_delete(path, self.options.secure_pass_count).await?;
+            return Ok(Disposal::Trashed);
+        }
+
+        if self.options.secure_delete {
+            self.secure_delete_resolved(&target, self.options.secure_pass_count)
+                .await?;
         } else {
-            self.permanent_delete(path).await?;
+            self.remove_resolved(&target).await?;
         }
 
-        Ok(file_size)
+        Ok(Disposal::Removed)
     }
 
     pub async fn permanent_delete(&self, path: &Path) -> Result<(), DiskTidyError> {
-        if path.is_dir() {
-            fs::remove_dir_all(path)
-                .await
-                .map_err(|e| self.handle_delete_error(path, e))?;
-        } else {
-            fs::remove_file(path)
-                .await
-                .map_err(|e| self.handle_delete_error(path, e))?;
+        let target = resolve_delete_target(path).map_err(|e| self.handle_delete_error(path, e))?;
+        self.remove_resolved(&target).await
+    }
+
+    /// 基于目录文件描述符删除，不跟随符号链接
+    ///
+    /// `target` 必须已经规范化：删除时从根目录逐级打开上级目录，检查之后
+    /// 被替换成符号链接的目录会导致删除失败，而不是删除链接指向的内容。
+    async fn remove_resolved(&self, target: &Path) -> Result<(), DiskTidyError> {
+        if !self.safety_checker.is_safe_to_delete(target) {
+            return Err(DiskTidyError::ProtectedPath {
+                path: target.to_string_lossy().to_string(),
+            });
         }
 
+        let remove_target = target.to_path_buf();
+        self.run_blocking(move || remove_nofollow(&remove_target))
+            .await?
+            .map_err(|e| self.handle_delete_error(target, e))?;
+
         Ok(())
     }
 
     fn handle_delete_error(&self, path: &Path, error: std::io::Error) -> DiskTidyError {
+        if is_symlink_error(&error) {
+            return DiskTidyError::SymlinkRefused {
+                path: path.to_string_lossy().to_string(),
+            };
+        }
+        if is_busy_error(&error) {
+            return DiskTidyError::FileInUse {
+                path: path.to_string_lossy().to_string(),
+            };
+        }
+
         match error.kind() {
             std::io::ErrorKind::PermissionDenied => DiskTidyError::PermissionDenied {
                 path: path.to_string_lossy().to_string(),
@@ -202,55 +446,45 @@ impl CleanerExecutor {
         path: &Path,
         passes: u8,
     ) -> Result<(), DiskTidyError> {
-        if !path.is_file() {
-            return self.permanent_delete(path).await;
-        }
-
-        let metadata = fs::metadata(path).await.map_err(DiskTidyError::IoError)?;
-        let file_size = metadata.len();
-
-        for pass in 0..passes {
-            self.overwrite_file(path, file_size, pass).await?;
-        }
-
-        self.permanent_delete(path).await?;
-
-        Ok(())
+        let target = resolve_delete_target(path).map_err(|e| self.handle_delete_error(path, e))?;
+        self.secure_delete_resolved(&target, passes).await
     }
 
-    async fn overwrite_file(
+    /// 按 `secure` 中的方案覆写并清除名称后删除，目录递归处理
+    async fn secure_delete_resolved(
         &self,
-        path: &Path,
-        size: u64,
-        pass: u8,
+        target: &Path,
+        passes: u8,
     ) -> Result<(), DiskTidyError> {
-        let mut file = fs::OpenOptions::new()
-            .write(true)
-            .open(path)
-            .await
-            .map_err(DiskTidyError::IoError)?;
+        if !self.safety_checker.is_safe_to_delete(target) {
+            return Err(DiskTidyError::ProtectedPath {
+                path: target.to_string_lossy().to_string(),
+            });
+        }
 
-        let pattern = SECURE_OVERWRITE_PATTERNS[pass as usize % SECURE_OVERWRITE_PATTERNS.len()];
-        let mut buffer = vec![pattern; DEFAULT_CHUNK_SIZE];
-        let mut remaining = size;
+        let eraser = SecureEraser::new(&self.options.secure, passes);
+        let erase_target = target.to_path_buf();
+        self.run_blocking(move || eraser.erase(&erase_target))
+            .await?
+            .map_err(|e| self.handle_delete_error(target, e))
+    }
 
-        while remaining > 0 {
-            let write_size = std::cmp::min(remaining, DEFAULT_CHUNK_SIZE as u64) as usize;
-            
-            if write_size < DEFAULT_CHUNK_SIZE {
-                buffer.truncate(write_size);
-            }
+    fn uses_secure_delete(&self) -> bool {
+        self.options.secure_delete && !self.options.move_to_recycle_bin
+    }
 
-            file.write_all(&buffer[..write_size])
+    /// 在阻塞线程中执行文件操作，启用后台优先级时使用专用的低优先级线程
+    async fn run_blocking<F, R>(&self, job: F) -> Result<R, DiskTidyError>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        match &self.background {
+            Some(pool) => pool.run(job).await,
+            None => tokio::task::spawn_blocking(job)
                 .await
-                .map_err(DiskTidyError::IoError)?;
-
-            remaining -= write_size as u64;
+                .map_err(|e| DiskTidyError::Unknown(e.to_string())),
         }
-
-        file.flush().await.map_err(DiskTidyError::IoError)?;
-
-        Ok(())
     }
 
     #[cfg(windows)]
@@ -277,6 +511,172 @@ impl CleanerExecutor {
         Ok(())
     }
 
+    /// 将路径打包归档，校验归档后删除原路径
+    ///
+    /// 受保护、被占用、包含符号链接或包含归档目标目录的路径不归档，记录为错误。
+    /// 选中的路径互相包含时每个文件只归档一次。归档校验失败时删除归档并返回
+    /// 错误，原路径保持不变；归档后取消时保留原路径。删除时只删除清单中未被
+    /// 修改的文件，再删除因此变空的目录，归档后新增或修改的文件保留。
+    pub async fn archive(
+        &self,
+        files: Vec<PathBuf>,
+        options: &ArchiveOptions,
+    ) -> Result<ArchiveResult, DiskTidyError> {
+        let start_time = Instant::now();
+        if options.destination.is_empty() {
+            return Err(DiskTidyError::InvalidParameter {
+                message: "未指定归档位置".to_string(),
+            });
+        }
+        let destination = std::path::absolute(&options.destination)?;
+
+        let mut errors = Vec::new();
+        let mut accepted = Vec::new();
+        let mut sources = Vec::new();
+        let mut seen = HashSet::new();
+        for path in files {
+            match self.archive_source(&path, &destination).await {
+                Ok((target, files)) => {
+                    accepted.push((path, target));
+                    sources.extend(files.into_iter().filter(|f| seen.insert(f.clone())));
+                }
+                Err((e, processes)) => errors.push(CleanError {
+                    path: path.to_string_lossy().to_string(),
+                    error_code: e.error_code().to_string(),
+                    error_message: e.to_string(),
+                    processes,
+                }),
+            }
+        }
+
+        let mut result = ArchiveResult {
+            archive_path: String::new(),
+            format: options.format,
+            archived_files: 0,
+            archived_size: 0,
+            archive_size: 0,
+            removed_paths: 0,
+            errors: Vec::new(),
+            duration_ms: 0,
+        };
+        if sources.is_empty() {
+            result.errors = errors;
+            result.duration_ms = start_time.elapsed().as_millis() as u64;
+            return Ok(result);
+        }
+
+        let format = options.format;
+        let level = options.compression_level;
+        let (archive_path, manifest) = self
+            .run_blocking(move || {
+                let (archive_path, manifest) =
+                    create_archive(&destination, format, level, &sources)?;
+                if let Err(e) = verify_archive(&archive_path, &manifest) {
+                    let _ = std::fs::remove_file(&archive_path);
+                    return Err(e);
+                }
+                Ok((archive_path, manifest))
+            })
+            .await??;
+
+        result.archive_path = archive_path.to_string_lossy().to_string();
+        result.archived_files = manifest.entries.len() as u64;
+        result.archived_size = manifest.entries.iter().map(|e| e.size).sum();
+        result.archive_size = fs::metadata(&archive_path).await.map(|m| m.len()).unwrap_or(0);
+
+        if options.remove_originals && !self.is_cancelled().await {
+            let targets: Vec<PathBuf> = accepted.iter().map(|(_, t)| t.clone()).collect();
+            let failures = self
+                .run_blocking(move || {
+                    let failures = remove_archived_files(&manifest);
+                    for target in &targets {
+                        prune_empty_dirs(target);
+                    }
+                    failures
+                })
+                .await?;
+
+            for (path, target) in &accepted {
+                // 有文件未能删除时错误记录在文件上
+                if failures.iter().any(|(file, _)| file.starts_with(target)) {
+                    continue;
+                }
+                if fs::symlink_metadata(target).await.is_err() {
+                    result.removed_paths += 1;
+                } else {
+                    let e = DiskTidyError::IoError(std::io::Error::other(
+                        "目录中有归档后新增的文件，已保留",
+                    ));
+                    errors.push(CleanError {
+                        path: path.to_string_lossy().to_string(),
+                        error_code: e.error_code().to_string(),
+                        error_message: e.to_string(),
+                        processes: Vec::new(),
+                    });
+                }
+            }
+            for (file, error) in failures {
+                let e = self.handle_delete_error(&file, error);
+                errors.push(CleanError {
+                    path: file.to_string_lossy().to_string(),
+                    error_code: e.error_code().to_string(),
+                    error_message: e.to_string(),
+                    processes: Vec::new(),
+                });
+            }
+        }
+
+        result.errors = errors;
+        result.duration_ms = start_time.elapsed().as_millis() as u64;
+        Ok(result)
+    }
+
+    /// 检查路径能否归档，返回规范化后的路径与其中的文件
+    async fn archive_source(
+        &self,
+        path: &Path,
+        destination: &Path,
+    ) -> Result<(PathBuf, Vec<PathBuf>), (DiskTidyError, Vec<FileHolder>)> {
+        if fs::symlink_metadata(path).await.is_err() {
+            return Err((
+                DiskTidyError::FileNotFound {
+                    path: path.to_string_lossy().to_string(),
+                },
+                Vec::new(),
+            ));
+        }
+        let target = resolve_delete_target(path)
+            .map_err(|e| (self.handle_delete_error(path, e), Vec::new()))?;
+        if !self.safety_checker.check(&target).safe_to_delete {
+            return Err((
+                DiskTidyError::ProtectedPath {
+                    path: path.to_string_lossy().to_string(),
+                },
+                Vec::new(),
+            ));
+        }
+        if destination.starts_with(&target) {
+            return Err((
+                DiskTidyError::InvalidParameter {
+                    message: format!("归档位置位于要归档的目录中: {}", path.display()),
+                },
+                Vec::new(),
+            ));
+        }
+        let holders = self.in_use_holders(path).await;
+        if !holders.is_empty() {
+            return Err((Self::in_use_error(path, &holders), holders));
+        }
+
+        let collect_path = target.clone();
+        let files = self
+            .run_blocking(move || collect_files(&collect_path))
+            .await
+            .map_err(|e| (e, Vec::new()))?
+            .map_err(|e| (DiskTidyError::IoError(e), Vec::new()))?;
+        Ok((target, files))
+    }
+
     pub async fn clean_by_category(
         &self,
         category: GarbageCategory,
@@ -317,6 +717,24 @@ impl CleanerExecutor {
     }
 }
 
+/// 文件或目录正忙：Linux 上的挂载点、正在执行的程序，Windows 上的共享冲突
+fn is_busy_error(error: &std::io::Error) -> bool {
+    #[cfg(unix)]
+    {
+        matches!(error.raw_os_error(), Some(libc::EBUSY) | Some(libc::ETXTBSY))
+    }
+    #[cfg(windows)]
+    {
+        // ERROR_SHARING_VIOLATION、ERROR_LOCK_VIOLATION
+        matches!(error.raw_os_error(), Some(32) | Some(33))
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = error;
+        false
+    }
+}
+
 impl Default for CleanerExecutor {
     fn default() -> Self {
         Self::new()
@@ -0,0 +1,243 @@
+//! 检测被运行中进程占用的文件
+//!
+//! Linux 上通过 `/proc/<pid>/fd` 中的符号链接找到进程打开的文件，通过
+//! `/proc/<pid>/maps` 找到映射到内存中的文件（如正在使用的动态库）。
+//! 进程信息的来源抽象为 [`ProcessTable`]，测试中可以替换为固定的数据。
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::safe_remove::resolve_delete_target;
+use crate::models::{FileHoldKind, FileHolder};
+
+/// 一个进程持有的文件
+#[derive(Debug, Clone, Default)]
+pub struct ProcessFiles {
+    pub pid: u32,
+    pub name: String,
+    pub open_files: Vec<PathBuf>,
+    pub mapped_files: Vec<PathBuf>,
+}
+
+/// 进程及其持有文件的来源
+pub trait ProcessTable: Send + Sync {
+    fn processes(&self) -> io::Result<Vec<ProcessFiles>>;
+}
+
+/// 读取 procfs 的进程信息
+///
+/// 没有权限读取的进程（其他用户的进程）会被跳过；系统没有 procfs 时
+/// 返回空列表，即不做占用检测。
+pub struct ProcFs {
+    root: PathBuf,
+}
+
+impl ProcFs {
+    pub fn new() -> Self {
+        Self::with_root("/proc")
+    }
+
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn read_process(&self, pid: u32) -> Option<ProcessFiles> {
+        let dir = self.root.join(pid.to_string());
+        let name = fs::read_to_string(dir.join("comm"))
+            .map(|comm| comm.trim_end().to_string())
+            .unwrap_or_default();
+
+        let open_files = fs::read_dir(dir.join("fd"))
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter_map(|entry| fs::read_link(entry.path()).ok())
+                    .filter_map(file_target)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mapped_files = fs::read_to_string(dir.join("maps"))
+            .map(|maps| parse_maps(&maps))
+            .unwrap_or_default();
+
+        Some(ProcessFiles {
+            pid,
+            name,
+            open_files,
+            mapped_files,
+        })
+    }
+}
+
+impl Default for ProcFs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProcessTable for ProcFs {
+    fn processes(&self) -> io::Result<Vec<ProcessFiles>> {
+        let entries = match fs::read_dir(&self.root) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        Ok(entries
+            .flatten()
+            .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+            .filter_map(|pid| self.read_process(pid))
+            .collect())
+    }
+}
+
+/// fd 链接的目标：只保留文件系统中的路径，忽略 `socket:[…]`、`pipe:[…]`
+/// 等匿名对象；已被删除的文件带有 ` (deleted)` 后缀，同样忽略
+fn file_target(target: PathBuf) -> Option<PathBuf> {
+    let text = target.to_str()?;
+    if !text.starts_with('/') || text.ends_with(" (deleted)") {
+        return None;
+    }
+    Some(target)
+}
+
+/// 解析 maps 文件，返回映射的文件路径（去重）
+///
+/// 每行格式为 `地址 权限 偏移 设备 inode 路径`，路径中可能包含空格。
+pub fn parse_maps(content: &str) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for line in content.lines() {
+        let mut rest = line;
+        for _ in 0..5 {
+            rest = rest.trim_start();
+            rest = match rest.find(' ') {
+                Some(index) => &rest[index..],
+                None => "",
+            };
+        }
+        let path = rest.trim();
+        if let Some(path) = file_target(PathBuf::from(path)) {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    paths
+}
+
+/// 某一时刻所有进程持有文件的索引
+#[derive(Debug, Clone, Default)]
+pub struct OpenFileIndex {
+    holders: BTreeMap<PathBuf, Vec<FileHolder>>,
+}
+
+impl OpenFileIndex {
+    pub fn from_processes(processes: Vec<ProcessFiles>) -> Self {
+        let mut holders: BTreeMap<PathBuf, Vec<FileHolder>> = BTreeMap::new();
+
+        for process in processes {
+            let files = process
+                .open_files
+                .into_iter()
+                .map(|p| (p, FileHoldKind::Open))
+                .chain(
+                    process
+                        .mapped_files
+                        .into_iter()
+                        .map(|p| (p, FileHoldKind::Mapped)),
+                );
+
+            for (path, kind) in files {
+                let entry = holders.entry(path.clone()).or_default();
+                // 同一进程多次打开同一文件只记录一次
+                if !entry.iter().any(|h| h.pid == process.pid && h.kind == kind) {
+                    entry.push(FileHolder {
+                        pid: process.pid,
+                        process_name: process.name.clone(),
+                        path: path.to_string_lossy().to_string(),
+                        kind,
+                    });
+                }
+            }
+        }
+
+        Self { holders }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.holders.is_empty()
+    }
+
+    /// 占用该路径的进程；路径为目录时包括占用目录中任意文件的进程
+    ///
+    /// `path` 应为规范化后的绝对路径，与 procfs 中记录的形式一致。
+    pub fn holders(&self, path: &Path) -> Vec<FileHolder> {
+        self.holders
+            .range(path.to_path_buf()..)
+            .take_while(|(held, _)| held.starts_with(path))
+            .flat_map(|(_, holders)| holders.iter().cloned())
+            .collect()
+    }
+
+    /// 同 [`holders`](Self::holders)，先将路径规范化，无法规范化时按原样比较
+    pub fn holders_of(&self, path: &Path) -> Vec<FileHolder> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        match resolve_delete_target(path) {
+            Ok(target) => self.holders(&target),
+            Err(_) => self.holders(path),
+        }
+    }
+}
+
+/// 按需生成占用索引
+pub struct InUseDetector {
+    table: Box<dyn ProcessTable>,
+}
+
+impl InUseDetector {
+    pub fn new() -> Self {
+        Self::with_table(Box::new(ProcFs::new()))
+    }
+
+    pub fn with_table(table: Box<dyn ProcessTable>) -> Self {
+        Self { table }
+    }
+
+    /// 读取失败时返回空索引，占用检测不应阻止清理
+    pub fn snapshot(&self) -> OpenFileIndex {
+        match self.table.processes() {
+            Ok(processes) => OpenFileIndex::from_processes(processes),
+            Err(e) => {
+                log::warn!("Failed to read process table: {}", e);
+                OpenFileIndex::default()
+            }
+        }
+    }
+
+    pub fn holders(&self, path: &Path) -> Vec<FileHolder> {
+        self.snapshot().holders_of(path)
+    }
+}
+
+impl Default for InUseDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 拼接占用进程的说明，用于错误信息
+pub fn describe_holders(holders: &[FileHolder]) -> String {
+    let mut described: Vec<String> = Vec::new();
+    for holder in holders {
+        let text = holder.describe();
+        if !described.contains(&text) {
+            described.push(text);
+        }
+    }
+    described.join("、")
+}
@@ -0,0 +1,112 @@
+//! 以后台优先级执行阻塞的文件操作
+//!
+//! Linux 上 I/O 优先级与 nice 值都是按线程设置的，而普通用户调低 nice 值后
+//! 无法再调回，因此不能在 tokio 的共享阻塞线程池上修改。这里为清理任务
+//! 启动专用的工作线程，线程启动时切换到 idle I/O 调度类并调高 nice 值，
+//! 执行器释放时线程随之退出。
+
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::models::DiskTidyError;
+
+/// 后台线程使用的 nice 值
+pub const BACKGROUND_NICE: i32 = 19;
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// 将当前线程切换到后台优先级，返回是否全部设置成功
+///
+/// 其他平台上不做任何处理。
+pub fn lower_current_thread_priority() -> bool {
+    imp::lower_current_thread_priority()
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+    const IOPRIO_CLASS_IDLE: libc::c_int = 3;
+    const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+
+    pub fn lower_current_thread_priority() -> bool {
+        // who 为 0 时作用于调用线程
+        let ioprio = unsafe {
+            libc::syscall(
+                libc::SYS_ioprio_set,
+                IOPRIO_WHO_PROCESS,
+                0,
+                IOPRIO_CLASS_IDLE << IOPRIO_CLASS_SHIFT,
+            )
+        } == 0;
+        let nice = unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, super::BACKGROUND_NICE) } == 0;
+        ioprio && nice
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    pub fn lower_current_thread_priority() -> bool {
+        false
+    }
+}
+
+/// 以后台优先级运行的固定大小线程池
+///
+/// 池被释放时通道关闭，工作线程执行完已提交的任务后退出。
+pub struct BackgroundPool {
+    sender: mpsc::Sender<Job>,
+}
+
+impl BackgroundPool {
+    pub fn new(threads: usize) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        for index in 0..threads.max(1) {
+            let receiver = receiver.clone();
+            let spawned = thread::Builder::new()
+                .name(format!("disktidy-clean-{}", index))
+                .spawn(move || {
+                    if !lower_current_thread_priority() {
+                        log::debug!("Background I/O priority not fully applied");
+                    }
+                    loop {
+                        let job = match receiver.lock() {
+                            Ok(receiver) => receiver.recv(),
+                            Err(_) => break,
+                        };
+                        match job {
+                            Ok(job) => job(),
+                            Err(_) => break,
+                        }
+                    }
+                });
+            if let Err(e) = spawned {
+                log::warn!("Failed to spawn background clean thread: {}", e);
+            }
+        }
+
+        Self { sender }
+    }
+
+    /// 在后台线程中执行 `job` 并等待结果
+    pub async fn run<F, R>(&self, job: F) -> Result<R, DiskTidyError>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (result_tx, result_rx) = tokio::sync::oneshot::channel();
+        let job: Job = Box::new(move || {
+            let _ = result_tx.send(job());
+        });
+
+        if self.sender.send(job).is_err() {
+            return Err(DiskTidyError::Unknown("后台清理线程已退出".to_string()));
+        }
+
+        result_rx
+            .await
+            .map_err(|_| DiskTidyError::Unknown("后台清理任务异常退出".to_string()))
+    }
+}
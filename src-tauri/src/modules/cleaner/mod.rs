@@ -2,6 +2,13 @@ pub mod executor;
 pub mod safety;
 pub mod recycle_bin;
 pub mod report;
+pub mod safe_remove;
+pub mod in_use;
+pub mod throttle;
+pub mod io_priority;
+pub mod size;
+pub mod secure_erase;
+pub mod archive;
 
 pub use executor::*;
 pub use safety::*;
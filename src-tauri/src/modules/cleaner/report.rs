@@ -14,6 +14,12 @@ pub struct CleanReportData {
     pub skipped_files: u64,
     pub total_size: u64,
     pub cleaned_size: u64,
+    /// 实际释放的磁盘空间
+    #[serde(default)]
+    pub freed_size: u64,
+    /// 移入回收站、尚未释放的空间
+    #[serde(default)]
+    pub trashed_size: u64,
     pub errors: Vec<CleanError>,
     pub summary: CleanSummary,
 }
@@ -77,6 +83,8 @@ impl CleanReportGenerator {
             skipped_files: result.skipped_files,
             total_size: result.total_size,
             cleaned_size: result.cleaned_size,
+            freed_size: result.freed_size,
+            trashed_size: result.trashed_size,
             errors: result.errors.clone(),
             summary,
         }
@@ -89,10 +97,13 @@ impl CleanReportGenerator {
             0.0
         };
 
-        let average_speed = if result.duration_ms > 0 {
-            (result.cleaned_size * 1000) / result.duration_ms as u64
+        // 旧版本的结果没有记录吞吐量，按毫秒耗时折算
+        let average_speed = if result.bytes_per_sec > 0 {
+            result.bytes_per_sec
         } else {
-            0
+            (result.cleaned_size * 1000)
+                .checked_div(result.duration_ms)
+                .unwrap_or(0)
         };
 
         let error_categories = self.categorize_errors(&result.errors);
@@ -261,6 +272,18 @@ impl CleanReportGenerator {
                 <td>已清理大小</td>
                 <td>{}</td>
             </tr>
+            <tr>
+                <td>实际释放</td>
+                <td>{}</td>
+            </tr>
+            <tr>
+                <td>移入回收站</td>
+                <td>{}</td>
+            </tr>
+            <tr>
+                <td>平均速度</td>
+                <td>{}/s</td>
+            </tr>
             <tr>
                 <td>耗时</td>
                 <td>{} ms</td>
@@ -277,7 +300,7 @@ impl CleanReportGenerator {
 </html>"#,
             report.clean_id,
             report.cleaned_files,
-            format_size(report.cleaned_size),
+            format_size(report.freed_size),
             report.summary.success_rate,
             report.total_files,
             report.cleaned_files,
@@ -285,6 +308,9 @@ impl CleanReportGenerator {
             report.skipped_files,
             format_size(report.total_size),
             format_size(report.cleaned_size),
+            format_size(report.freed_size),
+            format_size(report.trashed_size),
+            format_size(report.summary.average_speed),
             report.duration_ms,
             self.generate_error_section(report),
             chrono::DateTime::from_timestamp(report.end_time as i64, 0)
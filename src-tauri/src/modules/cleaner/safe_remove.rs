@@ -0,0 +1,464 @@
+//! 不跟随符号链接的删除
+//!
+//! 扫描阶段检查过的路径到真正删除时可能已被替换：某一级目录换成指向系统目录的
+//! 符号链接后，按路径字符串删除会把删除操作重定向到扫描范围之外。这里的删除
+//! 全程基于目录文件描述符（`openat`/`unlinkat` + `O_NOFOLLOW`）进行：
+//!
+//! - 目标路径先规范化，`..` 与上级目录中的符号链接都会被解析，再交给安全检查；
+//! - 删除时从根目录逐级打开规范化后的上级目录，任何一级变成符号链接都会失败；
+//! - 递归删除目录时不进入符号链接，只删除链接本身，也不跨越挂载点。
+
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// 将待删除路径规范化：解析上级目录中的 `..` 与符号链接，保留最后一级名称
+///
+/// 最后一级如果是符号链接，删除的是链接本身，因此不对其解析。上级目录中
+/// 只跟随属于 root 或当前用户的符号链接，其他用户放置的链接返回 `ELOOP`。
+pub fn resolve_delete_target(path: &Path) -> io::Result<PathBuf> {
+    let name = match path.components().next_back() {
+        Some(Component::Normal(name)) => name,
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("无法删除的路径: {}", path.display()),
+            ))
+        }
+    };
+
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => {
+            check_symlink_owners(parent)?;
+            parent.canonicalize()?
+        }
+        _ => std::env::current_dir()?,
+    };
+
+    Ok(parent.join(name))
+}
+
+#[cfg(unix)]
+fn check_symlink_owners(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::MetadataExt;
+
+    let euid = unsafe { libc::geteuid() };
+    for ancestor in path.ancestors() {
+        if let Ok(metadata) = std::fs::symlink_metadata(ancestor) {
+            if metadata.file_type().is_symlink() && metadata.uid() != 0 && metadata.uid() != euid {
+                return Err(io::Error::from_raw_os_error(libc::ELOOP));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_symlink_owners(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/// 判断错误是否由路径中的符号链接引起
+pub fn is_symlink_error(error: &io::Error) -> bool {
+    #[cfg(unix)]
+    {
+        error.raw_os_error() == Some(libc::ELOOP)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = error;
+        false
+    }
+}
+
+/// 删除文件、符号链接或整个目录，不跟随任何符号链接
+///
+/// `path` 应为 [`resolve_delete_target`] 返回的规范化路径，上级目录中出现
+/// 符号链接时返回 `ELOOP` 错误。
+pub fn remove_nofollow(path: &Path) -> io::Result<()> {
+    imp::remove(path)
+}
+
+/// 删除普通文件，删除前用 `unchanged` 检查打开的文件，不满足时保留并返回 `false`
+///
+/// 检查与删除针对的是同一个目录项：删除前确认名称仍指向检查过的文件。
+pub fn remove_file_if(
+    path: &Path,
+    unchanged: impl FnOnce(&std::fs::Metadata) -> bool,
+) -> io::Result<bool> {
+    imp::remove_file_if(path, unchanged)
+}
+
+/// 删除空目录，不跟随符号链接，目录非空时返回错误
+pub fn remove_empty_dir(path: &Path) -> io::Result<()> {
+    imp::remove_empty_dir(path)
+}
+
+/// 打开普通文件，不跟随任何一级符号链接
+///
+/// 与删除一样从根目录逐级打开上级目录，最后一级不是普通文件时返回错误。
+pub fn open_file_nofollow(path: &Path, write: bool) -> io::Result<std::fs::File> {
+    imp::open_file(path, write)
+}
+
+/// 在同一目录中重命名，目标已存在时返回 `AlreadyExists` 而不是覆盖
+pub fn rename_noreplace(path: &Path, new_name: &std::ffi::OsStr) -> io::Result<PathBuf> {
+    imp::rename_noreplace(path, new_name)?;
+    Ok(path.with_file_name(new_name))
+}
+
+#[cfg(unix)]
+mod imp {
+    use std::ffi::{CStr, CString, OsStr};
+    use std::fs::File;
+    use std::io;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::{Component, Path};
+
+    pub fn remove(path: &Path) -> io::Result<()> {
+        let (dir, name) = open_entry_parent(path)?;
+        remove_entry(&dir, &name)
+    }
+
+    pub fn remove_file_if(
+        path: &Path,
+        unchanged: impl FnOnce(&std::fs::Metadata) -> bool,
+    ) -> io::Result<bool> {
+        use std::os::unix::fs::MetadataExt;
+
+        let (dir, name) = open_entry_parent(path)?;
+        let metadata = entry_metadata(&dir, &name, path)?;
+        if !unchanged(&metadata) {
+            return Ok(false);
+        }
+
+        let stat = stat_at(dir.as_raw_fd(), &name)?;
+        if stat.st_dev as u64 != metadata.dev() || stat.st_ino as u64 != metadata.ino() {
+            return Err(io::Error::other(format!(
+                "文件在删除过程中被替换: {}",
+                path.display()
+            )));
+        }
+        unlink_at(dir.as_raw_fd(), &name, 0)?;
+        Ok(true)
+    }
+
+    pub fn remove_empty_dir(path: &Path) -> io::Result<()> {
+        let (dir, name) = open_entry_parent(path)?;
+        unlink_at(dir.as_raw_fd(), &name, libc::AT_REMOVEDIR)
+    }
+
+    pub fn open_file(path: &Path, write: bool) -> io::Result<File> {
+        let (dir, name) = open_entry_parent(path)?;
+        open_file_at(&dir, &name, if write { libc::O_WRONLY } else { libc::O_RDONLY })
+    }
+
+    pub fn rename_noreplace(path: &Path, new_name: &OsStr) -> io::Result<()> {
+        let (dir, name) = open_entry_parent(path)?;
+        let new_name = c_name(new_name)?;
+
+        #[cfg(target_os = "linux")]
+        {
+            let result = unsafe {
+                libc::renameat2(
+                    dir.as_raw_fd(),
+                    name.as_ptr(),
+                    dir.as_raw_fd(),
+                    new_name.as_ptr(),
+                    libc::RENAME_NOREPLACE,
+                )
+            };
+            if result == 0 {
+                return Ok(());
+            }
+            let error = io::Error::last_os_error();
+            // 文件系统不支持 RENAME_NOREPLACE 时退回到先检查再重命名
+            if !matches!(
+                error.raw_os_error(),
+                Some(libc::EINVAL) | Some(libc::ENOSYS)
+            ) {
+                return Err(error);
+            }
+        }
+
+        if stat_at(dir.as_raw_fd(), &new_name).is_ok() {
+            return Err(io::Error::from(io::ErrorKind::AlreadyExists));
+        }
+        let result = unsafe {
+            libc::renameat(
+                dir.as_raw_fd(),
+                name.as_ptr(),
+                dir.as_raw_fd(),
+                new_name.as_ptr(),
+            )
+        };
+        if result != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// 打开路径的上级目录，返回目录与最后一级名称
+    fn open_entry_parent(path: &Path) -> io::Result<(OwnedFd, CString)> {
+        match (path.parent(), path.file_name()) {
+            (Some(parent), Some(name)) => Ok((open_parent(parent)?, c_name(name)?)),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("无效的路径: {}", path.display()),
+            )),
+        }
+    }
+
+    /// 以 `O_NOFOLLOW` 打开目录中的普通文件
+    fn open_file_at(dir: &OwnedFd, name: &CStr, access: libc::c_int) -> io::Result<File> {
+        let fd = unsafe {
+            libc::openat(
+                dir.as_raw_fd(),
+                name.as_ptr(),
+                access | libc::O_NOFOLLOW | libc::O_NONBLOCK | libc::O_CLOEXEC,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let file = File::from(unsafe { OwnedFd::from_raw_fd(fd) });
+        if !file.metadata()?.is_file() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("不是普通文件: {}", name.to_string_lossy()),
+            ));
+        }
+        Ok(file)
+    }
+
+    /// 读取目录中普通文件的元数据，不需要读权限，不跟随符号链接
+    ///
+    /// Linux 上以 `O_PATH` 打开目录项；其他系统按路径读取，调用方随后用
+    /// [`stat_at`] 确认它与目录文件描述符中的目录项是同一个文件。
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn entry_metadata(dir: &OwnedFd, name: &CStr, _path: &Path) -> io::Result<std::fs::Metadata> {
+        open_file_at(dir, name, libc::O_PATH)?.metadata()
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    fn entry_metadata(_dir: &OwnedFd, name: &CStr, path: &Path) -> io::Result<std::fs::Metadata> {
+        let metadata = std::fs::symlink_metadata(path)?;
+        if !metadata.is_file() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("不是普通文件: {}", name.to_string_lossy()),
+            ));
+        }
+        Ok(metadata)
+    }
+
+    /// 从根目录开始逐级打开目录，任何一级是符号链接都会失败
+    fn open_parent(path: &Path) -> io::Result<OwnedFd> {
+        if !path.is_absolute() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("需要绝对路径: {}", path.display()),
+            ));
+        }
+
+        let root = c_name(OsStr::new("/"))?;
+        let mut dir = open_dir_at(libc::AT_FDCWD, &root)?;
+        for component in path.components() {
+            match component {
+                Component::RootDir => {}
+                Component::Normal(name) => {
+                    let name = c_name(name)?;
+                    dir = match open_dir_at(dir.as_raw_fd(), &name) {
+                        Ok(child) => child,
+                        // 部分系统对 O_DIRECTORY 打开符号链接返回 ENOTDIR，统一为 ELOOP
+                        Err(e) if e.raw_os_error() == Some(libc::ENOTDIR) => {
+                            let stat = stat_at(dir.as_raw_fd(), &name)?;
+                            if stat.st_mode & libc::S_IFMT == libc::S_IFLNK {
+                                return Err(io::Error::from_raw_os_error(libc::ELOOP));
+                            }
+                            return Err(e);
+                        }
+                        Err(e) => return Err(e),
+                    };
+                }
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("路径未规范化: {}", path.display()),
+                    ))
+                }
+            }
+        }
+        Ok(dir)
+    }
+
+    fn remove_entry(dir: &OwnedFd, name: &CStr) -> io::Result<()> {
+        let stat = stat_at(dir.as_raw_fd(), name)?;
+        if stat.st_mode & libc::S_IFMT != libc::S_IFDIR {
+            return unlink_at(dir.as_raw_fd(), name, 0);
+        }
+
+        let child = match open_dir_at(dir.as_raw_fd(), name) {
+            Ok(child) => child,
+            // 检查之后目录被替换成了符号链接或文件，只删除该条目本身
+            Err(e) if matches!(e.raw_os_error(), Some(libc::ELOOP) | Some(libc::ENOTDIR)) => {
+                return unlink_at(dir.as_raw_fd(), name, 0);
+            }
+            Err(e) => return Err(e),
+        };
+
+        let opened = fstat(child.as_raw_fd())?;
+        if opened.st_dev != stat.st_dev || opened.st_ino != stat.st_ino {
+            return Err(io::Error::other(format!(
+                "目录在删除过程中被替换: {}",
+                name.to_string_lossy()
+            )));
+        }
+        if opened.st_dev != fstat(dir.as_raw_fd())?.st_dev {
+            return Err(io::Error::other(format!(
+                "拒绝跨越挂载点删除: {}",
+                name.to_string_lossy()
+            )));
+        }
+
+        for entry in read_dir(&child)? {
+            remove_entry(&child, &entry)?;
+        }
+        drop(child);
+
+        unlink_at(dir.as_raw_fd(), name, libc::AT_REMOVEDIR)
+    }
+
+    fn c_name(name: &OsStr) -> io::Result<CString> {
+        CString::new(name.as_bytes())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "路径中包含空字符"))
+    }
+
+    fn open_dir_at(dirfd: RawFd, name: &CStr) -> io::Result<OwnedFd> {
+        let fd = unsafe {
+            libc::openat(
+                dirfd,
+                name.as_ptr(),
+                libc::O_RDONLY | libc::O_DIRECTORY | libc::O_NOFOLLOW | libc::O_CLOEXEC,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+    }
+
+    fn stat_at(dirfd: RawFd, name: &CStr) -> io::Result<libc::stat> {
+        let mut stat = std::mem::MaybeUninit::<libc::stat>::uninit();
+        let ret = unsafe {
+            libc::fstatat(
+                dirfd,
+                name.as_ptr(),
+                stat.as_mut_ptr(),
+                libc::AT_SYMLINK_NOFOLLOW,
+            )
+        };
+        if ret != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(unsafe { stat.assume_init() })
+    }
+
+    fn fstat(fd: RawFd) -> io::Result<libc::stat> {
+        let mut stat = std::mem::MaybeUninit::<libc::stat>::uninit();
+        if unsafe { libc::fstat(fd, stat.as_mut_ptr()) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(unsafe { stat.assume_init() })
+    }
+
+    fn unlink_at(dirfd: RawFd, name: &CStr, flags: libc::c_int) -> io::Result<()> {
+        if unsafe { libc::unlinkat(dirfd, name.as_ptr(), flags) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// 读取目录项名称，先全部读出再删除，避免边遍历边修改目录
+    fn read_dir(dir: &OwnedFd) -> io::Result<Vec<CString>> {
+        let fd = unsafe { libc::fcntl(dir.as_raw_fd(), libc::F_DUPFD_CLOEXEC, 0) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let stream = unsafe { libc::fdopendir(fd) };
+        if stream.is_null() {
+            let error = io::Error::last_os_error();
+            unsafe { libc::close(fd) };
+            return Err(error);
+        }
+
+        let mut names = Vec::new();
+        loop {
+            let entry = unsafe { libc::readdir(stream) };
+            if entry.is_null() {
+                break;
+            }
+            let name = unsafe { CStr::from_ptr((*entry).d_name.as_ptr()) };
+            if name.to_bytes() != b"." && name.to_bytes() != b".." {
+                names.push(name.to_owned());
+            }
+        }
+        unsafe { libc::closedir(stream) };
+
+        Ok(names)
+    }
+}
+
+#[cfg(not(unix))]
+mod imp {
+    use std::ffi::OsStr;
+    use std::fs::{self, File, OpenOptions};
+    use std::io;
+    use std::path::Path;
+
+    /// 标准库的 `remove_dir_all` 在 Windows 上不会跟随符号链接与目录联接
+    pub fn remove(path: &Path) -> io::Result<()> {
+        let file_type = fs::symlink_metadata(path)?.file_type();
+        if file_type.is_symlink() {
+            fs::remove_file(path).or_else(|_| fs::remove_dir(path))
+        } else if file_type.is_dir() {
+            fs::remove_dir_all(path)
+        } else {
+            fs::remove_file(path)
+        }
+    }
+
+    pub fn remove_file_if(
+        path: &Path,
+        unchanged: impl FnOnce(&fs::Metadata) -> bool,
+    ) -> io::Result<bool> {
+        let metadata = open_file(path, false)?.metadata()?;
+        if !unchanged(&metadata) {
+            return Ok(false);
+        }
+        fs::remove_file(path)?;
+        Ok(true)
+    }
+
+    pub fn remove_empty_dir(path: &Path) -> io::Result<()> {
+        fs::remove_dir(path)
+    }
+
+    pub fn open_file(path: &Path, write: bool) -> io::Result<File> {
+        if !fs::symlink_metadata(path)?.is_file() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("不是普通文件: {}", path.display()),
+            ));
+        }
+        OpenOptions::new().read(!write).write(write).open(path)
+    }
+
+    pub fn rename_noreplace(path: &Path, new_name: &OsStr) -> io::Result<()> {
+        let target = path.with_file_name(new_name);
+        if fs::symlink_metadata(&target).is_ok() {
+            return Err(io::Error::from(io::ErrorKind::AlreadyExists));
+        }
+        fs::rename(path, target)
+    }
+}
@@ -1,6 +1,11 @@
 use std::path::{Path, PathBuf};
-use crate::models::RiskLevel;
+use crate::models::{GitPathStatus, RiskAssessment, RiskFactor, RiskLevel};
+use crate::modules::build_artifact::is_regenerable_artifact_dir;
+use crate::modules::git_repo::GitRepoCache;
+use crate::modules::risk_score::{RiskInput, RiskModel};
+use super::safe_remove::resolve_delete_target;
 use crate::utils::path::SystemPaths;
+use crate::utils::path_match::PathPrefix;
 
 pub const DEFAULT_PROTECTED_EXTENSIONS: &[&str] = &[
     "sys", "dll", "exe", "bat", "cmd", "reg", "ini", "drv"
@@ -15,67 +20,163 @@ pub struct SafetyCheckResult {
     pub safe_to_delete: bool,
     pub risk_level: RiskLevel,
     pub reason: Option<String>,
+    pub git_status: Option<GitPathStatus>,
+    pub risk_score: u8,
+    pub risk_factors: Vec<RiskFactor>,
+}
+
+impl SafetyCheckResult {
+    /// 由安全规则直接判定的结果
+    fn rule(
+        safe_to_delete: bool,
+        risk_level: RiskLevel,
+        reason: &str,
+        git_status: Option<GitPathStatus>,
+    ) -> Self {
+        Self::from_assessment(
+            safe_to_delete,
+            RiskAssessment::fixed(risk_level, reason),
+            Some(reason.to_string()),
+            git_status,
+        )
+    }
+
+    fn from_assessment(
+        safe_to_delete: bool,
+        assessment: RiskAssessment,
+        reason: Option<String>,
+        git_status: Option<GitPathStatus>,
+    ) -> Self {
+        Self {
+            safe_to_delete,
+            risk_level: assessment.risk_level,
+            reason,
+            git_status,
+            risk_score: assessment.score,
+            risk_factors: assessment.factors,
+        }
+    }
 }
 
 pub struct SafetyChecker {
     protected_paths: Vec<PathBuf>,
+    protected_prefixes: Vec<PathPrefix>,
     protected_extensions: Vec<String>,
     sensitive_patterns: Vec<String>,
+    git_repos: GitRepoCache,
+    risk_model: RiskModel,
 }
 
 impl SafetyChecker {
     pub fn new() -> Self {
+        let protected_paths = Self::get_default_protected_paths();
         Self {
-            protected_paths: Self::get_default_protected_paths(),
+            protected_prefixes: protected_paths.iter().map(PathPrefix::new).collect(),
+            protected_paths,
             protected_extensions: Self::get_default_protected_extensions(),
             sensitive_patterns: Self::get_default_sensitive_patterns(),
+            git_repos: GitRepoCache::new(),
+            risk_model: RiskModel::from_saved_settings(),
         }
     }
 
     pub fn check(&self, path: &Path) -> SafetyCheckResult {
         if self.is_protected_path(path) {
-            return SafetyCheckResult {
-                safe_to_delete: false,
-                risk_level: RiskLevel::Critical,
-                reason: Some("系统受保护路径".to_string()),
-            };
+            return SafetyCheckResult::rule(false, RiskLevel::Critical, "系统受保护路径", None);
+        }
+
+        let git_status = self.git_repos.status(path);
+        match git_status {
+            Some(GitPathStatus::GitDir) => {
+                return SafetyCheckResult::rule(
+                    false,
+                    RiskLevel::Critical,
+                    "Git 仓库数据",
+                    git_status,
+                );
+            }
+            Some(GitPathStatus::Tracked) => {
+                return SafetyCheckResult::rule(
+                    false,
+                    RiskLevel::High,
+                    "Git 仓库中已跟踪的文件",
+                    git_status,
+                );
+            }
+            _ => {}
         }
 
         if self.is_protected_extension(path) {
-            return SafetyCheckResult {
-                safe_to_delete: false,
-                risk_level: RiskLevel::High,
-                reason: Some("受保护的文件类型".to_string()),
-            };
+            return SafetyCheckResult::rule(false, RiskLevel::High, "受保护的文件类型", git_status);
         }
 
         if self.is_sensitive_file(path) {
-            return SafetyCheckResult {
-                safe_to_delete: false,
-                risk_level: RiskLevel::High,
-                reason: Some("敏感文件".to_string()),
-            };
+            return SafetyCheckResult::rule(false, RiskLevel::High, "敏感文件", git_status);
         }
 
-        let risk_level = self.assess_risk_level(path);
-
-        SafetyCheckResult {
-            safe_to_delete: risk_level != RiskLevel::Critical,
-            risk_level,
-            reason: None,
+        // 被忽略的文件不一定能重新生成（如本地配置、数据文件），只有同时位于
+        // 已知构建产物目录中才直接判定为低风险，其余的忽略状态作为评分信号
+        if git_status == Some(GitPathStatus::Ignored) && self.is_in_build_output(path) {
+            return SafetyCheckResult::rule(
+                true,
+                RiskLevel::Low,
+                "被 .gitignore 忽略的构建产物，可重新生成",
+                git_status,
+            );
         }
+
+        let assessment = self.assess_risk(path, git_status);
+
+        SafetyCheckResult::from_assessment(
+            assessment.risk_level != RiskLevel::Critical,
+            assessment,
+            None,
+            git_status,
+        )
     }
 
     pub fn is_safe_to_delete(&self, path: &Path) -> bool {
-        !self.is_protected_path(path) && !self.is_protected_extension(path) && !self.is_sensitive_file(path)
+        !self.is_protected_path(path)
+            && !self.is_git_protected(path)
+            && !self.is_protected_extension(path)
+            && !self.is_sensitive_file(path)
+    }
+
+    fn is_git_protected(&self, path: &Path) -> bool {
+        matches!(
+            self.git_repos.status(path),
+            Some(GitPathStatus::Tracked) | Some(GitPathStatus::GitDir)
+        )
     }
 
+    /// 路径位于所在仓库中某个可重新生成的构建产物目录之下
+    fn is_in_build_output(&self, path: &Path) -> bool {
+        let Some(repo) = self.git_repos.repository_for(path) else {
+            return false;
+        };
+        path.ancestors()
+            .take_while(|dir| *dir != repo.root && dir.starts_with(&repo.root))
+            .any(is_regenerable_artifact_dir)
+    }
+
+    /// 同时检查原始路径与规范化后的路径，`..` 或上级目录中的符号链接
+    /// 不能把删除目标引向受保护目录
     fn is_protected_path(&self, path: &Path) -> bool {
-        let path_str = path.to_string_lossy().to_lowercase();
-        self.protected_paths.iter().any(|p| {
-            let p_str = p.to_string_lossy().to_lowercase();
-            path_str.starts_with(&p_str)
-        })
+        if self.matches_protected_root(path) {
+            return true;
+        }
+
+        match resolve_delete_target(path) {
+            Ok(resolved) => resolved != path && self.matches_protected_root(&resolved),
+            Err(_) => false,
+        }
+    }
+
+    /// 位于受保护目录之下，或删除后会连带删除受保护目录
+    fn matches_protected_root(&self, path: &Path) -> bool {
+        self.protected_prefixes
+            .iter()
+            .any(|p| p.contains(path) || p.is_within(path))
     }
 
     fn is_protected_extension(&self, path: &Path) -> bool {
@@ -96,22 +197,15 @@ impl SafetyChecker {
         self.sensitive_patterns.iter().any(|p| file_name.contains(&p.to_lowercase()))
     }
 
-    fn assess_risk_level(&self, path: &Path) -> RiskLevel {
-        let path_str = path.to_string_lossy().to_lowercase();
-
-        if path_str.contains("desktop") || path_str.contains("documents") {
-            return RiskLevel::Medium;
-        }
-
-        if path_str.contains("downloads") {
-            return RiskLevel::Low;
-        }
-
-        if path_str.contains("temp") || path_str.contains("cache") {
-            return RiskLevel::Low;
-        }
+    fn assess_risk(&self, path: &Path, git_status: Option<GitPathStatus>) -> RiskAssessment {
+        let mut input = RiskInput::from_path(path);
+        input.git_status = git_status;
+        self.risk_model.assess(&input)
+    }
 
-        RiskLevel::Medium
+    /// 替换风险模型，用于应用用户配置的权重与白名单
+    pub fn set_risk_model(&mut self, model: RiskModel) {
+        self.risk_model = model;
     }
 
     fn get_default_protected_paths() -> Vec<PathBuf> {
@@ -128,12 +222,14 @@ impl SafetyChecker {
 
     pub fn add_protected_path(&mut self, path: PathBuf) {
         if !self.protected_paths.contains(&path) {
+            self.protected_prefixes.push(PathPrefix::new(&path));
             self.protected_paths.push(path);
         }
     }
 
     pub fn remove_protected_path(&mut self, path: &Path) {
         self.protected_paths.retain(|p| p != path);
+        self.protected_prefixes.retain(|p| p.path() != path);
     }
 
     pub fn add_protected_extension(&mut self, ext: String) {
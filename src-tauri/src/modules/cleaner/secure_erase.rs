@@ -0,0 +1,478 @@
+//! 安全删除：覆写文件内容、清除文件名后再删除
+//!
+//! 每遍覆写后调用 fsync 确保数据落盘，可选地读回校验。覆写完成后将文件截断
+//! 为 0，再多次重命名为随机名称，使目录项中不再保留原文件名，最后以不跟随
+//! 符号链接的方式删除。目录会被递归处理，不进入符号链接，也不跨越挂载点。
+//!
+//! 覆写只能改写文件系统分配给文件的数据块。固态硬盘、写时复制文件系统与
+//! 日志文件系统上原数据可能仍保留在别处，[`storage_warnings`] 用于提示用户。
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
+use walkdir::WalkDir;
+
+use super::executor::{DEFAULT_CHUNK_SIZE, SECURE_OVERWRITE_PATTERNS};
+use super::safe_remove::{open_file_nofollow, remove_nofollow, rename_noreplace};
+use super::throttle::device_id;
+use crate::models::{
+    OverwritePattern, SecureDeleteOptions, SecureDeleteScheme, SecureDeleteWarning,
+    SecureDeleteWarningKind,
+};
+
+/// DoD 5220.22-M 三遍覆写
+pub const DOD_5220_22_M: [OverwritePattern; 3] = [
+    OverwritePattern::Byte(0x00),
+    OverwritePattern::Byte(0xFF),
+    OverwritePattern::Random,
+];
+
+const NAME_CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// 方案对应的各遍覆写数据
+///
+/// 自定义方案未指定数据时按 `pass_count` 循环使用默认的三种字节。
+pub fn overwrite_passes(options: &SecureDeleteOptions, pass_count: u8) -> Vec<OverwritePattern> {
+    match options.scheme {
+        SecureDeleteScheme::Random => vec![OverwritePattern::Random],
+        SecureDeleteScheme::Dod5220 => DOD_5220_22_M.to_vec(),
+        SecureDeleteScheme::Custom if !options.patterns.is_empty() => options.patterns.clone(),
+        SecureDeleteScheme::Custom => (0..pass_count.max(1) as usize)
+            .map(|pass| {
+                OverwritePattern::Byte(
+                    SECURE_OVERWRITE_PATTERNS[pass % SECURE_OVERWRITE_PATTERNS.len()],
+                )
+            })
+            .collect(),
+    }
+}
+
+/// 按选定方案执行安全删除，所有操作都是阻塞的
+pub struct SecureEraser {
+    passes: Vec<OverwritePattern>,
+    verify: bool,
+    rename_count: u8,
+    wipe_slack: bool,
+}
+
+impl SecureEraser {
+    pub fn new(options: &SecureDeleteOptions, pass_count: u8) -> Self {
+        Self {
+            passes: overwrite_passes(options, pass_count),
+            verify: options.verify,
+            rename_count: options.rename_count,
+            wipe_slack: options.wipe_slack,
+        }
+    }
+
+    pub fn passes(&self) -> &[OverwritePattern] {
+        &self.passes
+    }
+
+    /// 安全删除文件、符号链接或目录
+    ///
+    /// `target` 应为规范化后的路径。符号链接只删除链接本身；有其他硬链接的
+    /// 文件不覆写（覆写会改变其他链接看到的内容），只清除名称后删除。
+    pub fn erase(&self, target: &Path) -> io::Result<()> {
+        let metadata = std::fs::symlink_metadata(target)?;
+        if !metadata.is_dir() {
+            return self.erase_entry(target, &metadata);
+        }
+
+        // 先处理目录中的内容，最后处理目录本身
+        for entry in WalkDir::new(target)
+            .follow_links(false)
+            .same_file_system(true)
+            .contents_first(true)
+        {
+            let entry = entry.map_err(io::Error::from)?;
+            let metadata = entry.metadata().map_err(io::Error::from)?;
+            self.erase_entry(entry.path(), &metadata)?;
+        }
+        Ok(())
+    }
+
+    fn erase_entry(&self, path: &Path, metadata: &std::fs::Metadata) -> io::Result<()> {
+        if metadata.is_file() {
+            if has_other_links(metadata) {
+                log::warn!(
+                    "File has other hard links, removing without overwrite: {}",
+                    path.display()
+                );
+            } else {
+                self.overwrite(path, metadata.len())?;
+            }
+        }
+
+        let scrubbed = self.scrub_name(path)?;
+        remove_nofollow(&scrubbed)
+    }
+
+    /// 按各遍数据覆写文件，每遍同步到磁盘，最后截断为 0
+    ///
+    /// 文件与删除一样经由目录文件描述符逐级打开，遍历之后上级目录被替换成
+    /// 符号链接时打开失败，不会覆写目标之外的文件。启用 `wipe_slack` 时覆写到
+    /// 最后一个块的末尾。
+    pub fn overwrite(&self, path: &Path, size: u64) -> io::Result<()> {
+        let mut file = open_file_nofollow(path, true)?;
+        let size = if self.wipe_slack {
+            round_up_to_block(size, &file.metadata()?)
+        } else {
+            size
+        };
+
+        for pattern in &self.passes {
+            let seed: [u8; 32] = rand::random();
+            file.rewind()?;
+            write_pattern(&mut file, *pattern, seed, size)?;
+            file.sync_all()?;
+
+            if self.verify {
+                drop_cached_pages(&file);
+                let mut reader = open_file_nofollow(path, false)?;
+                verify_pattern(&mut reader, *pattern, seed, size)?;
+            }
+        }
+
+        // 各遍覆写已经到达 `size`，启用 `wipe_slack` 时还包括最后一个块的剩余部分
+        truncate_file(&mut file, 0, false)
+    }
+
+    /// 多次重命名为与原名等长的随机名称，返回最终的路径
+    pub fn scrub_name(&self, path: &Path) -> io::Result<PathBuf> {
+        let (parent, name) = match (path.parent(), path.file_name()) {
+            (Some(parent), Some(name)) => (parent, name),
+            _ => return Ok(path.to_path_buf()),
+        };
+
+        let length = name.len().clamp(1, 255);
+        let mut current = path.to_path_buf();
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..self.rename_count {
+            let random_name: String = (0..length)
+                .map(|_| NAME_CHARS[rng.gen_range(0..NAME_CHARS.len())] as char)
+                .collect();
+            match rename_noreplace(&current, random_name.as_ref()) {
+                Ok(next) => current = next,
+                // 随机名称恰好已存在，跳过这一次
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        if current != path {
+            sync_dir(parent);
+        }
+        Ok(current)
+    }
+}
+
+/// 缩短文件，`wipe_slack` 为真时先清除被截掉的部分
+///
+/// 截断只修改文件长度，被截掉的数据仍留在磁盘块中，截断后新末尾所在块的
+/// 剩余部分（松弛空间）也还保留着旧数据。这里先用零覆写从 `new_len` 到原末尾
+/// 所在块结束的区域并同步，再截断。DiskTidy 缩短文件时都经过这里。
+pub fn shrink_file(path: &Path, new_len: u64, wipe_slack: bool) -> io::Result<()> {
+    let mut file = open_file_nofollow(path, true)?;
+    truncate_file(&mut file, new_len, wipe_slack)
+}
+
+fn truncate_file(file: &mut File, new_len: u64, wipe_slack: bool) -> io::Result<()> {
+    let metadata = file.metadata()?;
+    if new_len >= metadata.len() {
+        return Ok(());
+    }
+
+    if wipe_slack {
+        let end = round_up_to_block(metadata.len(), &metadata);
+        file.seek(io::SeekFrom::Start(new_len))?;
+        write_pattern(file, OverwritePattern::Byte(0), [0; 32], end - new_len)?;
+        file.sync_all()?;
+    }
+
+    file.set_len(new_len)?;
+    file.sync_all()
+}
+
+/// 向上取整到文件系统块大小
+fn round_up_to_block(size: u64, metadata: &std::fs::Metadata) -> u64 {
+    #[cfg(unix)]
+    let block = {
+        use std::os::unix::fs::MetadataExt;
+        metadata.blksize().max(512)
+    };
+    #[cfg(not(unix))]
+    let block = {
+        let _ = metadata;
+        4096
+    };
+    size.div_ceil(block) * block
+}
+
+/// 覆写数据流：随机数据由种子生成，校验时可以重新生成相同的数据
+fn fill_chunk(buffer: &mut [u8], pattern: OverwritePattern, rng: &mut StdRng) {
+    match pattern {
+        OverwritePattern::Byte(byte) => buffer.fill(byte),
+        OverwritePattern::Random => rng.fill_bytes(buffer),
+    }
+}
+
+fn write_pattern(
+    file: &mut File,
+    pattern: OverwritePattern,
+    seed: [u8; 32],
+    size: u64,
+) -> io::Result<()> {
+    let mut rng = StdRng::from_seed(seed);
+    let mut buffer = vec![0u8; DEFAULT_CHUNK_SIZE];
+    let mut remaining = size;
+
+    while remaining > 0 {
+        let chunk = remaining.min(DEFAULT_CHUNK_SIZE as u64) as usize;
+        fill_chunk(&mut buffer[..chunk], pattern, &mut rng);
+        file.write_all(&buffer[..chunk])?;
+        remaining -= chunk as u64;
+    }
+    file.flush()
+}
+
+fn verify_pattern(
+    file: &mut File,
+    pattern: OverwritePattern,
+    seed: [u8; 32],
+    size: u64,
+) -> io::Result<()> {
+    let mut rng = StdRng::from_seed(seed);
+    let mut expected = vec![0u8; DEFAULT_CHUNK_SIZE];
+    let mut actual = vec![0u8; DEFAULT_CHUNK_SIZE];
+    let mut remaining = size;
+
+    while remaining > 0 {
+        let chunk = remaining.min(DEFAULT_CHUNK_SIZE as u64) as usize;
+        fill_chunk(&mut expected[..chunk], pattern, &mut rng);
+        file.read_exact(&mut actual[..chunk])?;
+        if expected[..chunk] != actual[..chunk] {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "覆写校验失败，偏移 {} 处的数据与写入的不一致",
+                    size - remaining
+                ),
+            ));
+        }
+        remaining -= chunk as u64;
+    }
+    Ok(())
+}
+
+/// 丢弃页缓存，使校验读到的是磁盘上的数据而不是缓存
+#[cfg(target_os = "linux")]
+fn drop_cached_pages(file: &File) {
+    use std::os::fd::AsRawFd;
+
+    unsafe {
+        libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_DONTNEED);
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn drop_cached_pages(_file: &File) {}
+
+#[cfg(unix)]
+fn has_other_links(metadata: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    metadata.nlink() > 1
+}
+
+#[cfg(not(unix))]
+fn has_other_links(_metadata: &std::fs::Metadata) -> bool {
+    false
+}
+
+/// 同步目录，使重命名后的目录项落盘
+fn sync_dir(dir: &Path) {
+    #[cfg(unix)]
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+    #[cfg(not(unix))]
+    let _ = dir;
+}
+
+/// 检查各目标所在存储上覆写是否可靠，每个设备只提示一次
+pub fn storage_warnings(paths: &[PathBuf]) -> Vec<SecureDeleteWarning> {
+    let mut seen = HashSet::new();
+    let mut warnings = Vec::new();
+
+    for path in paths {
+        let existing = existing_ancestor(path);
+        let metadata = std::fs::metadata(existing).ok();
+        if !seen.insert(device_id(existing, metadata.as_ref())) {
+            continue;
+        }
+
+        let storage = probe_storage(existing);
+        for kind in storage.kinds {
+            warnings.push(SecureDeleteWarning {
+                path: path.to_string_lossy().to_string(),
+                kind,
+                file_system: storage.file_system.clone(),
+                message: warning_message(kind, &storage.file_system),
+            });
+        }
+    }
+
+    warnings
+}
+
+/// 最近的存在的上级路径，目标可能已被删除或是悬空的符号链接
+fn existing_ancestor(path: &Path) -> &Path {
+    path.ancestors()
+        .find(|p| std::fs::metadata(p).is_ok())
+        .unwrap_or(path)
+}
+
+fn warning_message(kind: SecureDeleteWarningKind, file_system: &str) -> String {
+    let file_system = if file_system.is_empty() {
+        "该文件系统"
+    } else {
+        file_system
+    };
+    match kind {
+        SecureDeleteWarningKind::SolidState => {
+            "目标位于固态硬盘，磨损均衡会把写入重定向到新的闪存块，覆写无法保证清除原数据，\
+             建议使用全盘加密或硬盘自带的安全擦除"
+                .to_string()
+        }
+        SecureDeleteWarningKind::CopyOnWrite => format!(
+            "{} 为写时复制文件系统，覆写会写入新的数据块，原数据可能保留在快照或空闲空间中",
+            file_system
+        ),
+        SecureDeleteWarningKind::Journaling => format!(
+            "{} 会在日志中记录变更，文件名等元数据可能残留在日志中",
+            file_system
+        ),
+    }
+}
+
+/// 探测到的存储特性
+#[derive(Debug, Default)]
+struct StorageProbe {
+    file_system: String,
+    kinds: Vec<SecureDeleteWarningKind>,
+}
+
+#[cfg(target_os = "linux")]
+fn probe_storage(path: &Path) -> StorageProbe {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::fs::MetadataExt;
+
+    let mut probe = StorageProbe::default();
+
+    if let Ok(c_path) = CString::new(path.as_os_str().as_bytes()) {
+        let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+        if unsafe { libc::statfs(c_path.as_ptr(), &mut stat) } == 0 {
+            if let Some((name, kind)) = classify_file_system(stat.f_type as u32) {
+                probe.file_system = name.to_string();
+                probe.kinds.extend(kind);
+            }
+        }
+    }
+
+    if let Ok(metadata) = std::fs::metadata(path) {
+        if is_solid_state(metadata.dev()) {
+            probe.kinds.insert(0, SecureDeleteWarningKind::SolidState);
+        }
+    }
+
+    probe
+}
+
+/// 按 statfs 的 f_type 识别文件系统
+#[cfg(target_os = "linux")]
+fn classify_file_system(magic: u32) -> Option<(&'static str, Option<SecureDeleteWarningKind>)> {
+    use SecureDeleteWarningKind::{CopyOnWrite, Journaling};
+
+    Some(match magic {
+        0x9123_683E => ("btrfs", Some(CopyOnWrite)),
+        0x2FC1_2FC1 => ("zfs", Some(CopyOnWrite)),
+        0xCA45_1A4E => ("bcachefs", Some(CopyOnWrite)),
+        0xF2F5_2010 => ("f2fs", Some(CopyOnWrite)),
+        0x3434 => ("nilfs2", Some(CopyOnWrite)),
+        0xEF53 => ("ext4", Some(Journaling)),
+        0x5846_5342 => ("xfs", Some(Journaling)),
+        0x3153_464A => ("jfs", Some(Journaling)),
+        0x5265_4973 => ("reiserfs", Some(Journaling)),
+        0x5346_544E => ("ntfs", Some(Journaling)),
+        0x0102_1994 => ("tmpfs", None),
+        _ => return None,
+    })
+}
+
+/// 通过 sysfs 中块设备的 rotational 属性判断是否为固态硬盘
+#[cfg(target_os = "linux")]
+fn is_solid_state(dev: u64) -> bool {
+    let major = ((dev >> 8) & 0xfff) | ((dev >> 32) & !0xfff);
+    let minor = (dev & 0xff) | ((dev >> 12) & !0xff);
+    let device = PathBuf::from(format!("/sys/dev/block/{}:{}", major, minor));
+
+    // 分区没有 queue 目录，使用所属磁盘的属性
+    [
+        device.join("queue/rotational"),
+        device.join("../queue/rotational"),
+    ]
+    .iter()
+    .find_map(|p| std::fs::read_to_string(p).ok())
+    .map(|value| value.trim() == "0")
+    .unwrap_or(false)
+}
+
+#[cfg(windows)]
+fn probe_storage(path: &Path) -> StorageProbe {
+    use std::path::Component;
+    use windows::Win32::Storage::FileSystem::GetVolumeInformationW;
+
+    let mut probe = StorageProbe::default();
+    let root = match path.components().next() {
+        Some(Component::Prefix(prefix)) => format!("{}\\", prefix.as_os_str().to_string_lossy()),
+        _ => return probe,
+    };
+    let wide_root: Vec<u16> = root.encode_utf16().chain(std::iter::once(0)).collect();
+    let mut file_system = vec![0u16; 32];
+
+    let result = unsafe {
+        GetVolumeInformationW(
+            windows::core::PCWSTR(wide_root.as_ptr()),
+            None,
+            None,
+            None,
+            None,
+            Some(file_system.as_mut_slice()),
+        )
+    };
+    if result.is_err() {
+        return probe;
+    }
+
+    probe.file_system = String::from_utf16_lossy(&file_system)
+        .trim_end_matches('\0')
+        .to_string();
+    // 固态硬盘需要通过设备 IOCTL 查询，这里只按文件系统提示
+    match probe.file_system.to_ascii_uppercase().as_str() {
+        "REFS" => probe.kinds.push(SecureDeleteWarningKind::CopyOnWrite),
+        "NTFS" => probe.kinds.push(SecureDeleteWarningKind::Journaling),
+        _ => {}
+    }
+    probe
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn probe_storage(_path: &Path) -> StorageProbe {
+    StorageProbe::default()
+}
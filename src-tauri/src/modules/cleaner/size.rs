@@ -0,0 +1,130 @@
+//! 删除前统计路径占用的空间
+//!
+//! 目录按内容递归统计，不跟随符号链接。区分两种大小：
+//! - 表观大小：文件内容的字节数，与文件管理器显示的大小一致
+//! - 分配大小：实际占用的磁盘块，稀疏文件可能小于表观大小，小文件通常大于
+//!
+//! 同一文件的多个硬链接只统计一次；还有硬链接位于统计范围之外的文件，删除后
+//! 空间不会释放，不计入可释放大小。
+
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use walkdir::WalkDir;
+
+use crate::models::EntrySize;
+
+/// 统计路径（目录时递归）占用的空间
+///
+/// 路径本身无法读取时返回错误；目录中无法读取的子项被忽略。
+pub fn measure_entry(path: &Path) -> io::Result<EntrySize> {
+    let root = std::fs::symlink_metadata(path)?;
+    let mut size = EntrySize::default();
+    let mut links = LinkTracker::default();
+
+    if !root.is_dir() {
+        links.add(&mut size, &root);
+        links.finish(&mut size);
+        return Ok(size);
+    }
+
+    for entry in WalkDir::new(path)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+    {
+        if let Ok(metadata) = entry.metadata() {
+            links.add(&mut size, &metadata);
+        }
+    }
+    links.finish(&mut size);
+
+    Ok(size)
+}
+
+/// 按耗时计算每秒处理的字节数，不足一秒时按实际时长折算
+pub fn bytes_per_second(bytes: u64, elapsed: Duration) -> u64 {
+    let secs = elapsed.as_secs_f64();
+    if secs > 0.0 {
+        (bytes as f64 / secs) as u64
+    } else {
+        0
+    }
+}
+
+/// 记录有多个硬链接的文件，统计结束后判断其空间能否释放
+#[derive(Default)]
+struct LinkTracker {
+    /// (设备, inode) -> (链接总数, 范围内出现次数, 分配大小)
+    shared: HashMap<(u64, u64), (u64, u64, u64)>,
+}
+
+impl LinkTracker {
+    fn add(&mut self, size: &mut EntrySize, metadata: &std::fs::Metadata) {
+        let allocated = allocated_size(metadata);
+
+        if metadata.is_dir() {
+            // 目录本身的块随目录删除一同释放，不计入表观大小
+            size.allocated += allocated;
+            size.reclaimable += allocated;
+            return;
+        }
+
+        if let Some((key, nlink)) = link_identity(metadata) {
+            let seen = self.shared.entry(key).or_insert((nlink, 0, allocated));
+            seen.1 += 1;
+            if seen.1 > 1 {
+                return;
+            }
+        } else {
+            size.reclaimable += allocated;
+        }
+
+        size.file_count += 1;
+        size.apparent += metadata.len();
+        size.allocated += allocated;
+    }
+
+    fn finish(self, size: &mut EntrySize) {
+        size.reclaimable += self
+            .shared
+            .values()
+            .filter(|(nlink, seen, _)| seen >= nlink)
+            .map(|(_, _, allocated)| allocated)
+            .sum::<u64>();
+    }
+}
+
+#[cfg(unix)]
+fn allocated_size(metadata: &std::fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+
+    // st_blocks 固定以 512 字节为单位
+    metadata.blocks() * 512
+}
+
+#[cfg(not(unix))]
+fn allocated_size(metadata: &std::fs::Metadata) -> u64 {
+    // 没有可移植的接口获取簇大小，按 4 KiB 对齐估算
+    const CLUSTER: u64 = 4096;
+    if metadata.is_dir() {
+        0
+    } else {
+        metadata.len().div_ceil(CLUSTER) * CLUSTER
+    }
+}
+
+/// 有多个硬链接的文件返回 (设备, inode) 与链接数
+#[cfg(unix)]
+fn link_identity(metadata: &std::fs::Metadata) -> Option<((u64, u64), u64)> {
+    use std::os::unix::fs::MetadataExt;
+
+    (metadata.nlink() > 1).then(|| ((metadata.dev(), metadata.ino()), metadata.nlink()))
+}
+
+#[cfg(not(unix))]
+fn link_identity(_metadata: &std::fs::Metadata) -> Option<((u64, u64), u64)> {
+    None
+}
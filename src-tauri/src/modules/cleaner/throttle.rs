@@ -0,0 +1,147 @@
+//! 清理时的限速与按设备限制并发
+//!
+//! 速率限制使用令牌桶：每秒补充 `rate` 个令牌，最多积累一秒的量。单次请求
+//! 超过桶容量（如一个很大的文件）时令牌会变为负数，调用方按欠下的量等待，
+//! 因此长时间平均速率仍不会超过限制。
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::models::CleanIoOptions;
+
+struct TokenBucket {
+    rate: f64,
+    available: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u64) -> Self {
+        Self {
+            rate: rate as f64,
+            available: rate as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// 取走 `amount` 个令牌，返回需要等待的时间
+    fn reserve(&mut self, amount: f64, now: Instant) -> Duration {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.available = (self.available + elapsed * self.rate).min(self.rate);
+        self.last_refill = now;
+        self.available -= amount;
+
+        if self.available >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.available / self.rate)
+        }
+    }
+}
+
+/// 按字节数与操作数限速，两项限制都为 0 时不做任何等待
+pub struct IoThrottle {
+    bytes: Option<Mutex<TokenBucket>>,
+    ops: Option<Mutex<TokenBucket>>,
+}
+
+impl IoThrottle {
+    pub fn new(max_bytes_per_sec: u64, max_ops_per_sec: u64) -> Self {
+        Self {
+            bytes: (max_bytes_per_sec > 0).then(|| Mutex::new(TokenBucket::new(max_bytes_per_sec))),
+            ops: (max_ops_per_sec > 0).then(|| Mutex::new(TokenBucket::new(max_ops_per_sec))),
+        }
+    }
+
+    pub fn from_options(options: &CleanIoOptions) -> Self {
+        Self::new(options.max_bytes_per_sec, options.max_ops_per_sec)
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.bytes.is_none() && self.ops.is_none()
+    }
+
+    /// 计算一次操作需要等待的时间，取两项限制中较长的一项
+    pub fn reserve(&self, bytes: u64) -> Duration {
+        let now = Instant::now();
+        let reserve = |bucket: &Option<Mutex<TokenBucket>>, amount: f64| {
+            bucket
+                .as_ref()
+                .and_then(|b| b.lock().ok().map(|mut b| b.reserve(amount, now)))
+                .unwrap_or_default()
+        };
+        reserve(&self.bytes, bytes as f64).max(reserve(&self.ops, 1.0))
+    }
+
+    pub async fn acquire(&self, bytes: u64) {
+        let wait = self.reserve(bytes);
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// 为每个设备维护一个信号量，限制同一设备上同时进行的操作数
+pub struct DeviceLimiter {
+    per_device: usize,
+    devices: Mutex<HashMap<u64, Arc<Semaphore>>>,
+}
+
+impl DeviceLimiter {
+    pub fn new(per_device: usize) -> Self {
+        Self {
+            per_device: per_device.max(1),
+            devices: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn acquire(&self, device: u64) -> Option<OwnedSemaphorePermit> {
+        let semaphore = {
+            let mut devices = self.devices.lock().ok()?;
+            devices
+                .entry(device)
+                .or_insert_with(|| Arc::new(Semaphore::new(self.per_device)))
+                .clone()
+        };
+        semaphore.acquire_owned().await.ok()
+    }
+}
+
+/// 路径所在设备的标识，`metadata` 为路径本身（不跟随符号链接）的元数据
+///
+/// 无法获取时所有路径视为同一设备。
+pub fn device_id(path: &Path, metadata: Option<&std::fs::Metadata>) -> u64 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        let _ = path;
+        metadata.map(|m| m.dev()).unwrap_or(0)
+    }
+
+    #[cfg(windows)]
+    {
+        use std::hash::{Hash, Hasher};
+        use std::path::Component;
+
+        // 按盘符或 UNC 共享区分设备
+        let _ = metadata;
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        if let Some(Component::Prefix(prefix)) = path.components().next() {
+            prefix.as_os_str().to_ascii_lowercase().hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = (path, metadata);
+        0
+    }
+}
@@ -0,0 +1,580 @@
+//! 目标空间清理规划
+//!
+//! 用户给出需要释放的空间大小，规划器汇总各分析器的结果，
+//! 按风险从低到高挑选清理项，直到达到目标或没有可接受的候选项为止。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::models::{
+    CleanupCandidate, CleanupSource, DuplicateAnalysisResult, GarbageAnalysisResult,
+    GarbageCategory, GitPathStatus, LargeFile, LargeFileAnalysisResult, MergedCandidate,
+    RiskLevel,
+};
+use crate::modules::app_cache::{AppCacheScanResult, CleanCategory};
+use crate::modules::file_analyzer::JunkScanResult;
+use crate::modules::cleaner::in_use::OpenFileIndex;
+use crate::modules::risk_score::{RiskModel, RiskModelConfig};
+use crate::modules::smart_clean::build_smart_clean_plan_with;
+use crate::modules::software_residue::{ResidueScanResult, ResidueType};
+use crate::utils::file_category::CleanSafety;
+use crate::utils::format::format_bytes;
+use crate::utils::path_match::{CaseSensitivity, NormalizedPath};
+
+/// 规划器的输入：各分析器最近一次的结果，未运行的分析器留空即可
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CleanupPlanInput {
+    #[serde(default)]
+    pub garbage: Option<GarbageAnalysisResult>,
+    #[serde(default)]
+    pub junk: Vec<JunkScanResult>,
+    #[serde(default)]
+    pub app_cache: Option<AppCacheScanResult>,
+    #[serde(default)]
+    pub duplicates: Option<DuplicateAnalysisResult>,
+    #[serde(default)]
+    pub large_files: Option<LargeFileAnalysisResult>,
+    #[serde(default)]
+    pub residue: Vec<ResidueScanResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupPlanOptions {
+    pub target_bytes: u64,
+    #[serde(default = "default_max_risk")]
+    pub max_risk: RiskLevel,
+    #[serde(default)]
+    pub risk_model: RiskModelConfig,
+}
+
+fn default_max_risk() -> RiskLevel {
+    RiskLevel::Medium
+}
+
+impl Default for CleanupPlanOptions {
+    fn default() -> Self {
+        Self {
+            target_bytes: 0,
+            max_risk: default_max_risk(),
+            risk_model: RiskModelConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedCleanupItem {
+    pub candidate: CleanupCandidate,
+    pub explanation: String,
+    pub cumulative_size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskTierSummary {
+    pub risk_level: RiskLevel,
+    pub candidate_count: u64,
+    pub total_size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupPlan {
+    pub plan_id: String,
+    pub target_bytes: u64,
+    pub planned_bytes: u64,
+    pub target_reached: bool,
+    pub shortfall_bytes: u64,
+    pub max_risk: RiskLevel,
+    pub items: Vec<PlannedCleanupItem>,
+    pub available_by_risk: Vec<RiskTierSummary>,
+    pub message: String,
+}
+
+impl CleanupPlan {
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.items
+            .iter()
+            .map(|item| PathBuf::from(&item.candidate.path))
+            .collect()
+    }
+}
+
+pub struct CleanupPlanner {
+    options: CleanupPlanOptions,
+    open_files: OpenFileIndex,
+}
+
+impl CleanupPlanner {
+    pub fn new(target_bytes: u64) -> Self {
+        Self::with_options(CleanupPlanOptions {
+            target_bytes,
+            ..Default::default()
+        })
+    }
+
+    pub fn with_options(options: CleanupPlanOptions) -> Self {
+        Self {
+            options,
+            open_files: OpenFileIndex::default(),
+        }
+    }
+
+    /// 设置评分时使用的文件占用索引
+    pub fn set_open_files(&mut self, open_files: OpenFileIndex) {
+        self.open_files = open_files;
+    }
+
+    /// 与智能清理使用同一套拆分与合并规则：先按风险上限拆出保留项，再只在
+    /// 待清理的候选项之间合并嵌套路径
+    pub fn plan(&self, input: &CleanupPlanInput) -> CleanupPlan {
+        let mut model =
+            RiskModel::with_config(self.options.risk_model.clone().with_saved_whitelist());
+        model.set_open_files(self.open_files.clone());
+        let plan_id = uuid::Uuid::new_v4().to_string();
+        let split =
+            build_smart_clean_plan_with(&plan_id, input, self.options.max_risk.clone(), &model);
+        self.plan_split(plan_id, split.candidates, split.held_back)
+    }
+
+    /// 为已合并的候选项生成计划，超出风险上限的候选项只计入统计
+    pub fn plan_candidates(&self, candidates: Vec<CleanupCandidate>) -> CleanupPlan {
+        let (selected, held_back) = candidates
+            .into_iter()
+            .filter(|c| c.size > 0)
+            .partition(|c| c.risk_level <= self.options.max_risk);
+        self.plan_split(uuid::Uuid::new_v4().to_string(), selected, held_back)
+    }
+
+    fn plan_split(
+        &self,
+        plan_id: String,
+        mut candidates: Vec<CleanupCandidate>,
+        held_back: Vec<CleanupCandidate>,
+    ) -> CleanupPlan {
+        let now = now_secs();
+        let target = self.options.target_bytes;
+        let max_risk = self.options.max_risk.clone();
+
+        candidates.sort_by(|a, b| {
+            a.risk_level
+                .cmp(&b.risk_level)
+                .then(a.risk_score.cmp(&b.risk_score))
+                .then(b.regenerable.cmp(&a.regenerable))
+                .then(b.age_days(now).cmp(&a.age_days(now)))
+                .then(b.size.cmp(&a.size))
+        });
+
+        let available_by_risk = summarize_by_risk(candidates.iter().chain(&held_back));
+
+        let mut items = Vec::new();
+        let mut planned_bytes = 0u64;
+
+        for candidate in candidates {
+            if planned_bytes >= target {
+                break;
+            }
+
+            planned_bytes += candidate.size;
+            items.push(PlannedCleanupItem {
+                explanation: explain(&candidate, now),
+                cumulative_size: planned_bytes,
+                candidate,
+            });
+        }
+
+        let target_reached = planned_bytes >= target;
+        let shortfall_bytes = target.saturating_sub(planned_bytes);
+        let message = if target_reached {
+            format!(
+                "已规划 {} 个清理项，可释放 {}（目标 {}）",
+                items.len(),
+                format_bytes(planned_bytes),
+                format_bytes(target)
+            )
+        } else {
+            let riskier: u64 = held_back.iter().map(|c| c.size).sum();
+            let mut message = format!(
+                "在{}及以下只能释放 {}，距目标还差 {}",
+                max_risk.display_name(),
+                format_bytes(planned_bytes),
+                format_bytes(shortfall_bytes)
+            );
+            if riskier > 0 {
+                message.push_str(&format!(
+                    "；另有 {} 属于更高风险项目，未纳入计划",
+                    format_bytes(riskier)
+                ));
+            } else {
+                message.push_str("；现有分析结果不足以达到目标");
+            }
+            message
+        };
+
+        CleanupPlan {
+            plan_id,
+            target_bytes: target,
+            planned_bytes,
+            target_reached,
+            shortfall_bytes,
+            max_risk,
+            items,
+            available_by_risk,
+            message,
+        }
+    }
+}
+
+/// 将各分析器的结果逐条转换为候选项，不做去重
+pub fn normalize_findings(input: &CleanupPlanInput) -> Vec<CleanupCandidate> {
+    normalize_findings_with(input, &RiskModel::new())
+}
+
+/// 同 [`normalize_findings`]，并用给定的风险模型为每个候选项评分
+///
+/// 分析器给出的风险等级作为一项信号参与评分，候选项最终的风险等级取自评分结果。
+pub fn normalize_findings_with(input: &CleanupPlanInput, model: &RiskModel) -> Vec<CleanupCandidate> {
+    let mut candidates = Vec::new();
+
+    if let Some(garbage) = &input.garbage {
+        for stats in garbage.categories.values() {
+            for file in stats.files.iter().filter(|f| f.safe_to_delete) {
+                candidates.push(CleanupCandidate {
+                    path: file.path.clone(),
+                    size: file.size,
+                    source: CleanupSource::Garbage,
+                    risk_level: file.risk_level.clone(),
+                    regenerable: matches!(
+                        file.category,
+                        GarbageCategory::SystemTemp
+                            | GarbageCategory::BrowserCache
+                            | GarbageCategory::AppCache
+                    ),
+                    modified_time: file.modified_time,
+                    reason: file.category.display_name().to_string(),
+                    also_reported_by: Vec::new(),
+                    risk_score: 0,
+                    risk_factors: Vec::new(),
+                });
+            }
+        }
+    }
+
+    for result in &input.junk {
+        for item in result.items.iter().filter(|i| i.safe_to_delete) {
+            candidates.push(CleanupCandidate {
+                path: item.path.clone(),
+                size: item.size,
+                source: CleanupSource::Junk,
+                risk_level: RiskLevel::from_str(&item.risk_level).unwrap_or(RiskLevel::Medium),
+                regenerable: false,
+                modified_time: item.modified_time,
+                reason: item.description.clone(),
+                also_reported_by: Vec::new(),
+                risk_score: 0,
+                risk_factors: Vec::new(),
+            });
+        }
+    }
+
+    if let Some(app_cache) = &input.app_cache {
+        for file in &app_cache.files {
+            let risk_level = file
+                .risk_level
+                .clone()
+                .unwrap_or_else(|| app_cache_clean_safety(&file.category).to_risk_level());
+            candidates.push(CleanupCandidate {
+                path: file.path.clone(),
+                size: file.size,
+                source: CleanupSource::AppCache,
+                risk_level,
+                regenerable: is_regenerable_app_cache(&file.category),
+                modified_time: file.modified_secs(),
+                reason: format!("{} {}", file.app, file.category.display_name()),
+                also_reported_by: Vec::new(),
+                risk_score: 0,
+                risk_factors: Vec::new(),
+            });
+        }
+    }
+
+    if let Some(duplicates) = &input.duplicates {
+        for group in &duplicates.groups {
+            let original = match group.files.iter().find(|f| f.is_original) {
+                Some(original) => original,
+                None => continue,
+            };
+            for file in group.files.iter().filter(|f| !f.is_original) {
+                candidates.push(CleanupCandidate {
+                    path: file.path.clone(),
+                    size: group.size,
+                    source: CleanupSource::Duplicate,
+                    risk_level: RiskLevel::Low,
+                    regenerable: true,
+                    modified_time: file.modified_time,
+                    reason: format!("与 {} 内容相同，保留原件", original.path),
+                    also_reported_by: Vec::new(),
+                    risk_score: 0,
+                    risk_factors: Vec::new(),
+                });
+            }
+        }
+    }
+
+    if let Some(large_files) = &input.large_files {
+        let now = now_secs();
+        for file in &large_files.files {
+            // 已被 Git 跟踪的文件不作为清理候选
+            let (risk_level, regenerable, reason) = match file.git_status {
+                Some(GitPathStatus::Tracked) | Some(GitPathStatus::GitDir) => continue,
+                Some(GitPathStatus::Ignored) => (
+                    RiskLevel::Medium,
+                    true,
+                    format!("被 .gitignore 忽略的大文件（{}）", file.file_type),
+                ),
+                _ => (
+                    large_file_risk(file, now),
+                    false,
+                    format!("大文件（{}）", file.file_type),
+                ),
+            };
+            candidates.push(CleanupCandidate {
+                path: file.path.clone(),
+                size: file.size,
+                source: CleanupSource::LargeFile,
+                risk_level,
+                regenerable,
+                modified_time: file.modified_time,
+                reason,
+                also_reported_by: Vec::new(),
+                risk_score: 0,
+                risk_factors: Vec::new(),
+            });
+        }
+    }
+
+    for result in &input.residue {
+        if result.residue_type == ResidueType::RegistryKey {
+            continue;
+        }
+        for item in result.items.iter().filter(|i| i.safe_to_delete) {
+            candidates.push(CleanupCandidate {
+                path: item.path.clone(),
+                size: item.size,
+                source: CleanupSource::Residue,
+                risk_level: RiskLevel::from_str(&item.risk_level).unwrap_or(RiskLevel::Medium),
+                regenerable: item.residue_type == ResidueType::CacheFile,
+                modified_time: item.last_modified,
+                reason: format!("{}：{}", item.app_name, item.description),
+                also_reported_by: Vec::new(),
+                risk_score: 0,
+                risk_factors: Vec::new(),
+            });
+        }
+    }
+
+    for candidate in &mut candidates {
+        model.score_candidate(candidate);
+    }
+
+    candidates
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CandidateMergeResult {
+    pub candidates: Vec<CleanupCandidate>,
+    pub merged: Vec<MergedCandidate>,
+}
+
+/// 合并重叠的候选项
+///
+/// 同一路径只保留风险最高的一条，其余来源记录在 `also_reported_by` 中；
+/// 位于其他候选目录之内的路径会随目录一起删除，因此从列表中移出并记录到 `merged`。
+pub fn merge_candidates(candidates: Vec<CleanupCandidate>) -> CandidateMergeResult {
+    let by_key = dedup_by_key(candidates);
+
+    let paths_by_key: HashMap<String, String> = by_key
+        .iter()
+        .map(|(key, candidate)| (key.clone(), candidate.path.clone()))
+        .collect();
+    let mut result = CandidateMergeResult::default();
+
+    for (key, candidate) in by_key {
+        match outermost_ancestor(&key, &paths_by_key) {
+            Some(ancestor) => result.merged.push(MergedCandidate {
+                path: candidate.path,
+                size: candidate.size,
+                source: candidate.source,
+                merged_into: ancestor.to_string(),
+            }),
+            None => result.candidates.push(candidate),
+        }
+    }
+
+    result
+}
+
+/// 同一路径只保留风险最高的一条，其余来源记录在 `also_reported_by` 中，
+/// 分析器意见不一致时按最谨慎的结论处理
+pub fn dedup_candidates(candidates: Vec<CleanupCandidate>) -> Vec<CleanupCandidate> {
+    dedup_by_key(candidates).into_values().collect()
+}
+
+fn dedup_by_key(candidates: Vec<CleanupCandidate>) -> HashMap<String, CleanupCandidate> {
+    let mut by_key: HashMap<String, CleanupCandidate> = HashMap::new();
+
+    for candidate in candidates {
+        let key = path_key(&candidate.path);
+        let merged = match by_key.remove(&key) {
+            Some(existing) => {
+                let (mut kept, dropped) = if candidate.risk_level > existing.risk_level {
+                    (candidate, existing)
+                } else {
+                    (existing, candidate)
+                };
+                for source in std::iter::once(dropped.source).chain(dropped.also_reported_by) {
+                    if source != kept.source && !kept.also_reported_by.contains(&source) {
+                        kept.also_reported_by.push(source);
+                    }
+                }
+                kept
+            }
+            None => candidate,
+        };
+        by_key.insert(key, merged);
+    }
+    by_key
+}
+
+/// 用于比较的路径形式：按 [`NormalizedPath`] 规范化后以 `/` 连接各组件，
+/// 大小写规则取平台默认值
+pub fn path_key(path: &str) -> String {
+    let normalized = NormalizedPath::parse(path, CaseSensitivity::platform_default());
+    match normalized.components().split_first() {
+        Some((root, rest)) if root == "/" => format!("/{}", rest.join("/")),
+        _ => normalized.components().join("/"),
+    }
+}
+
+/// 路径的各级上级目录（不含自身），从根目录开始
+pub fn ancestor_keys(key: &str) -> impl Iterator<Item = &str> {
+    key.match_indices('/')
+        .map(move |(i, _)| if i == 0 { "/" } else { &key[..i] })
+        .filter(move |prefix| *prefix != key)
+}
+
+fn outermost_ancestor<'a>(key: &str, paths_by_key: &'a HashMap<String, String>) -> Option<&'a str> {
+    ancestor_keys(key).find_map(|prefix| paths_by_key.get(prefix).map(|p| p.as_str()))
+}
+
+fn app_cache_clean_safety(category: &CleanCategory) -> CleanSafety {
+    match category {
+        CleanCategory::CacheData
+        | CleanCategory::ThumbCache
+        | CleanCategory::TempFiles
+        | CleanCategory::LogFiles
+        | CleanCategory::ShaderCache => CleanSafety::Safe,
+        CleanCategory::EmojiCache | CleanCategory::InstallPackages => CleanSafety::LowRisk,
+        CleanCategory::ChatImages | CleanCategory::VideoFiles | CleanCategory::VoiceFiles => {
+            CleanSafety::MediumRisk
+        }
+        CleanCategory::DocumentFiles => CleanSafety::HighRisk,
+    }
+}
+
+fn is_regenerable_app_cache(category: &CleanCategory) -> bool {
+    matches!(
+        category,
+        CleanCategory::CacheData
+            | CleanCategory::ThumbCache
+            | CleanCategory::TempFiles
+            | CleanCategory::EmojiCache
+            | CleanCategory::ShaderCache
+    )
+}
+
+fn summarize_by_risk<'a>(
+    candidates: impl Iterator<Item = &'a CleanupCandidate>,
+) -> Vec<RiskTierSummary> {
+    let mut tiers: Vec<RiskTierSummary> = Vec::new();
+
+    for candidate in candidates {
+        match tiers.iter_mut().find(|t| t.risk_level == candidate.risk_level) {
+            Some(tier) => {
+                tier.candidate_count += 1;
+                tier.total_size += candidate.size;
+            }
+            None => tiers.push(RiskTierSummary {
+                risk_level: candidate.risk_level.clone(),
+                candidate_count: 1,
+                total_size: candidate.size,
+            }),
+        }
+    }
+
+    tiers.sort_by(|a, b| a.risk_level.cmp(&b.risk_level));
+    tiers
+}
+
+fn explain(candidate: &CleanupCandidate, now: i64) -> String {
+    let mut parts = vec![candidate.risk_level.display_name().to_string()];
+
+    if candidate.risk_factors.is_empty() {
+        if candidate.regenerable {
+            parts.push("可由程序重新生成".to_string());
+        }
+
+        let age_days = candidate.age_days(now);
+        if age_days > 0 {
+            parts.push(format!("已 {} 天未修改", age_days));
+        }
+    } else {
+        // 已评分的候选项列出影响最大的几项因素
+        parts.extend(
+            candidate
+                .risk_factors
+                .iter()
+                .take(3)
+                .map(|f| f.description.clone()),
+        );
+    }
+
+    parts.push(format!("释放 {}", format_bytes(candidate.size)));
+
+    format!(
+        "{}（{}）：{}",
+        candidate.reason,
+        candidate.source.display_name(),
+        parts.join("，")
+    )
+}
+
+/// 长期未使用时可直接删除的大文件扩展名：压缩包、磁盘镜像、安装包、备份与临时文件
+const DISPOSABLE_LARGE_FILE_EXTENSIONS: &[&str] = &[
+    "zip", "rar", "7z", "tar", "gz", "bz2", "xz", "tgz", "tbz", "cab", "iso", "img", "vhd",
+    "vhdx", "vmdk", "qcow2", "exe", "msi", "dmg", "pkg", "deb", "rpm", "apk", "bak", "old",
+    "backup", "orig", "tmp", "temp",
+];
+
+/// 按大文件最近的使用时间与类型给出分析器等级，长期未使用的安装包、压缩包、
+/// 磁盘镜像等风险较低，最近仍在使用的文件风险较高
+fn large_file_risk(file: &LargeFile, now: i64) -> RiskLevel {
+    let last_used = file.accessed_time.max(file.modified_time);
+    if last_used <= 0 {
+        return RiskLevel::High;
+    }
+    let idle_days = (now - last_used).max(0) / 86400;
+    let extension = file.extension.trim_start_matches('.').to_lowercase();
+    let disposable = DISPOSABLE_LARGE_FILE_EXTENSIONS.contains(&extension.as_str());
+    match idle_days {
+        d if d >= 365 && disposable => RiskLevel::Low,
+        d if d >= 90 => RiskLevel::Medium,
+        _ => RiskLevel::High,
+    }
+}
+
+fn now_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64
+}
@@ -107,6 +107,7 @@ async fn perform_scan(
         include_hidden: options.include_hidden,
         include_system: options.include_system,
         exclude_paths: options.exclude_paths.clone(),
+        respect_ignore_files: true,
     };
     let filter = StandardFileFilter::new(&filter_options);
     let walker = FileWalker::new(&filter);
@@ -256,6 +257,7 @@ async fn perform_scan(
     result.total_size = categorized_size;
     result.duration = (result.end_time - result.start_time) as u64;
     result.status = ScanStatus::Completed;
+    result.ignored_paths = filter.ignored_paths();
 
     // 转换为最终分类
     let final_categories: Vec<FileCategory> = full_categories
@@ -356,6 +358,8 @@ pub async fn delete_scanned_files(scan_id: &str, move_to_trash: bool) -> Result<
         move_to_recycle_bin: move_to_trash,
         secure_delete: false,
         secure_pass_count: 3,
+        secure: Default::default(),
+        io: Default::default(),
     };
     
     let executor = crate::modules::cleaner::CleanerExecutor::with_options(options);
@@ -378,6 +382,8 @@ pub async fn delete_selected_files(scan_id: &str, file_paths: Vec<String>, move_
         move_to_recycle_bin: move_to_trash,
         secure_delete: false,
         secure_pass_count: 3,
+        secure: Default::default(),
+        io: Default::default(),
     };
     
     let executor = crate::modules::cleaner::CleanerExecutor::with_options(options);
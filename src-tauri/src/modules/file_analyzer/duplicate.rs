@@ -1,12 +1,17 @@
 use crate::models::cleaner::{
     DuplicateAnalysisResult, DuplicateDetectorOptions, DuplicateFile, DuplicateGroup,
 };
+use crate::modules::risk_score::{RiskInput, RiskModel};
+use crate::utils::gitignore::DisktidyIgnore;
 use crate::utils::hash::{HashCalculator, HashResult};
 use crate::utils::path::{PathUtils, SystemPaths};
+use crate::utils::path_match::PathPrefix;
 use crate::utils::file_type::get_file_type;
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use walkdir::WalkDir;
 
@@ -36,7 +41,10 @@ pub type DuplicateProgressCallback = Box<dyn Fn(DuplicateDetectionProgress) + Se
 pub struct DuplicateDetector {
     options: DuplicateDetectorOptions,
     hash_calculator: HashCalculator,
-    protected_paths: Vec<PathBuf>,
+    protected_paths: Vec<PathPrefix>,
+    ignore_files: DisktidyIgnore,
+    risk_model: RiskModel,
+    cancelled: Option<Arc<AtomicBool>>,
 }
 
 impl DuplicateDetector {
@@ -47,7 +55,13 @@ impl DuplicateDetector {
     pub fn with_options(options: DuplicateDetectorOptions) -> Self {
         Self {
             hash_calculator: HashCalculator::with_cache(options.use_cache),
-            protected_paths: SystemPaths::get_protected_paths(),
+            protected_paths: SystemPaths::get_protected_paths()
+                .iter()
+                .map(PathPrefix::new)
+                .collect(),
+            ignore_files: DisktidyIgnore::new(),
+            risk_model: RiskModel::from_saved_settings(),
+            cancelled: None,
             options,
         }
     }
@@ -86,6 +100,7 @@ impl DuplicateDetector {
             wasted_space,
             groups: duplicate_groups,
             duration_ms: end_time - start_time,
+            ignored_paths: self.ignore_files.take_excluded_paths(),
         }
     }
 
@@ -119,6 +134,7 @@ impl DuplicateDetector {
             wasted_space,
             groups: duplicate_groups,
             duration_ms: end_time - start_time,
+            ignored_paths: self.ignore_files.take_excluded_paths(),
         }
     }
 
@@ -210,10 +226,14 @@ impl DuplicateDetector {
             let walker = WalkDir::new(scan_path)
                 .follow_links(false)
                 .into_iter()
+                .filter_entry(|e| !self.ignore_files.is_excluded_entry(e))
                 .filter_map(|e| e.ok())
                 .filter(|e| e.file_type().is_file());
 
             for entry in walker {
+                if self.is_cancelled() {
+                    return files;
+                }
                 let path = entry.path().to_path_buf();
 
                 if self.should_skip(&path) {
@@ -228,10 +248,8 @@ impl DuplicateDetector {
     }
 
     fn should_skip(&self, path: &Path) -> bool {
-        for protected in &self.protected_paths {
-            if path.starts_with(protected) {
-                return true;
-            }
+        if self.protected_paths.iter().any(|p| p.contains(path)) {
+            return true;
         }
 
         if let Ok(metadata) = fs::metadata(path) {
@@ -287,6 +305,9 @@ impl DuplicateDetector {
         let mut processed = 0u64;
 
         for (size, files) in size_groups {
+            if self.is_cancelled() {
+                break;
+            }
             let partial_hashes = self.calculate_partial_hashes(files);
 
             let mut partial_groups: HashMap<String, Vec<&PathBuf>> = HashMap::new();
@@ -484,32 +505,34 @@ impl DuplicateDetector {
             .collect()
     }
 
+    /// 删除分数越高越适合删除，由风险模型的分数换算；原件的分数始终为负
     fn calculate_delete_score(&self, file: &DuplicateFile) -> i32 {
-        let mut score = 0;
-
-        let path_lower = file.path.to_lowercase();
-
-        if path_lower.contains("\\temp\\") {
-            score += 50;
+        let mut input = RiskInput::from_path(Path::new(&file.path));
+        if file.modified_time > 0 {
+            input.modified_time = file.modified_time;
         }
+        let mut score = 100 - self.risk_model.assess(&input).score as i32;
 
-        if path_lower.contains("\\downloads\\") {
-            score += 30;
+        if file.is_original {
+            score -= 101;
         }
 
-        if path_lower.contains("\\desktop\\") {
-            score -= 20;
-        }
+        score
+    }
 
-        if path_lower.contains("\\documents\\") {
-            score -= 30;
-        }
+    pub fn set_risk_model(&mut self, model: RiskModel) {
+        self.risk_model = model;
+    }
 
-        if file.is_original {
-            score -= 100;
-        }
+    /// 设置取消标志，标志置位后尽快结束扫描，返回已找到的结果
+    pub fn set_cancel_flag(&mut self, flag: Arc<AtomicBool>) {
+        self.cancelled = Some(flag);
+    }
 
-        score
+    fn is_cancelled(&self) -> bool {
+        self.cancelled
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::Relaxed))
     }
 
     pub fn suggest_original<'a>(&self, group: &'a DuplicateGroup) -> Option<&'a DuplicateFile> {
@@ -1,10 +1,15 @@
 use crate::models::cleaner::{
     CategoryStats, GarbageAnalysisResult, GarbageCategory, GarbageFile, RiskLevel,
 };
+use crate::models::IgnoredPath;
+use crate::utils::gitignore::DisktidyIgnore;
 use crate::utils::path::{PathUtils, SystemPaths};
+use crate::utils::path_match::{PathPrefix, SegmentPattern};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 const TEMP_FILE_EXTENSIONS: &[&str] = &["tmp", "temp", "bak", "old", "swp"];
@@ -129,24 +134,55 @@ impl BrowserInfo {
 
 pub struct GarbageDetector {
     options: GarbageDetectorOptions,
-    protected_paths: Vec<PathBuf>,
+    protected_paths: Vec<PathPrefix>,
+    protected_patterns: Vec<SegmentPattern>,
+    ignore_files: DisktidyIgnore,
+    cancelled: Option<Arc<AtomicBool>>,
 }
 
 impl GarbageDetector {
     pub fn new() -> Self {
-        Self {
-            options: GarbageDetectorOptions::default(),
-            protected_paths: SystemPaths::get_protected_paths(),
-        }
+        Self::with_options(GarbageDetectorOptions::default())
     }
 
     pub fn with_options(options: GarbageDetectorOptions) -> Self {
         Self {
             options,
-            protected_paths: SystemPaths::get_protected_paths(),
+            protected_paths: SystemPaths::get_protected_paths()
+                .iter()
+                .map(PathPrefix::new)
+                .collect(),
+            protected_patterns: [
+                "windows\\system32",
+                "windows\\syswow64",
+                "program files",
+                "program files (x86)",
+                "programdata",
+            ]
+            .iter()
+            .map(|pattern| SegmentPattern::new(pattern))
+            .collect(),
+            ignore_files: DisktidyIgnore::new(),
+            cancelled: None,
         }
     }
 
+    /// 设置取消标志，标志置位后尽快结束扫描，返回已找到的结果
+    pub fn set_cancel_flag(&mut self, flag: Arc<AtomicBool>) {
+        self.cancelled = Some(flag);
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::Relaxed))
+    }
+
+    /// 取出上次检测中被 `.disktidyignore` 排除的路径
+    pub fn take_ignored_paths(&self) -> Vec<IgnoredPath> {
+        self.ignore_files.take_excluded_paths()
+    }
+
     pub fn detect_all(&self) -> GarbageAnalysisResult {
         let start_time = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -215,6 +251,7 @@ impl GarbageDetector {
             categories,
             high_risk_count,
             duration_ms: end_time - start_time,
+            ignored_paths: self.ignore_files.take_excluded_paths(),
         }
     }
 
@@ -320,8 +357,15 @@ impl GarbageDetector {
         };
 
         for entry in read_dir.flatten() {
+            if self.is_cancelled() {
+                return;
+            }
             let path = entry.path();
 
+            if self.ignore_files.is_excluded(&path, path.is_dir()) {
+                continue;
+            }
+
             if path.is_dir() {
                 self.scan_directory(&path, category.clone(), files);
             } else {
@@ -343,8 +387,15 @@ impl GarbageDetector {
         };
 
         for entry in read_dir.flatten() {
+            if self.is_cancelled() {
+                return;
+            }
             let path = entry.path();
 
+            if self.ignore_files.is_excluded(&path, path.is_dir()) {
+                continue;
+            }
+
             if path.is_dir() {
                 let dir_name = path
                     .file_name()
@@ -448,29 +499,13 @@ impl GarbageDetector {
     }
 
     fn is_protected_path(&self, path: &Path) -> bool {
-        let path_lower = path.to_string_lossy().to_lowercase();
-        
-        for protected in &self.protected_paths {
-            if PathUtils::is_subpath(protected, path) || PathUtils::equals_ignore_case(path, protected) {
-                return true;
-            }
-        }
-
-        let protected_patterns = [
-            "windows\\system32",
-            "windows\\syswow64",
-            "program files",
-            "program files (x86)",
-            "programdata",
-        ];
-
-        for pattern in &protected_patterns {
-            if path_lower.contains(pattern) {
-                return true;
-            }
+        if self.protected_paths.iter().any(|p| p.contains(path)) {
+            return true;
         }
 
-        false
+        self.protected_patterns
+            .iter()
+            .any(|pattern| pattern.matches(path))
     }
 
     fn get_recycle_bin_paths(&self) -> Vec<PathBuf> {
@@ -1,14 +1,19 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use walkdir::WalkDir;
 
 #[cfg(windows)]
 use std::os::windows::fs::MetadataExt;
 
+use crate::models::IgnoredPath;
 use crate::modules::cleaner::safety::SafetyChecker;
+use crate::utils::gitignore::DisktidyIgnore;
 use crate::utils::path::{PathUtils, SystemPaths};
+use crate::utils::path_match::PathPrefix;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
@@ -79,6 +84,8 @@ pub struct JunkScanResult {
     pub items: Vec<JunkFile>,
     pub total_size: u64,
     pub count: u64,
+    #[serde(default)]
+    pub ignored_paths: Vec<IgnoredPath>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -123,7 +130,10 @@ impl Default for JunkScanOptions {
 pub struct JunkFileDetector {
     options: JunkScanOptions,
     safety_checker: SafetyChecker,
-    protected_paths: Vec<PathBuf>,
+    protected_paths: Vec<PathPrefix>,
+    exclude_paths: Vec<PathPrefix>,
+    ignore_files: DisktidyIgnore,
+    cancelled: Option<Arc<AtomicBool>>,
 }
 
 impl JunkFileDetector {
@@ -133,12 +143,29 @@ impl JunkFileDetector {
 
     pub fn with_options(options: JunkScanOptions) -> Self {
         Self {
-            protected_paths: SystemPaths::get_protected_paths(),
+            protected_paths: SystemPaths::get_protected_paths()
+                .iter()
+                .map(PathPrefix::new)
+                .collect(),
+            exclude_paths: options.exclude_paths.iter().map(PathPrefix::new).collect(),
             safety_checker: SafetyChecker::new(),
+            ignore_files: DisktidyIgnore::new(),
+            cancelled: None,
             options,
         }
     }
 
+    /// 设置取消标志，标志置位后尽快结束扫描，返回已找到的结果
+    pub fn set_cancel_flag(&mut self, flag: Arc<AtomicBool>) {
+        self.cancelled = Some(flag);
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::Relaxed))
+    }
+
     pub fn detect_all(&self) -> Vec<JunkScanResult> {
         let mut results = Vec::new();
 
@@ -205,10 +232,14 @@ impl JunkFileDetector {
             let walker = WalkDir::new(scan_path)
                 .follow_links(false)
                 .into_iter()
+                .filter_entry(|e| !self.ignore_files.is_excluded_entry(e))
                 .filter_map(|e| e.ok())
                 .filter(|e| e.file_type().is_dir());
 
             for entry in walker {
+                if self.is_cancelled() {
+                    break;
+                }
                 let path = entry.path();
 
                 if self.should_skip(path) {
@@ -228,6 +259,7 @@ impl JunkFileDetector {
             }
         }
 
+        let ignored_paths = self.ignore_files.take_excluded_paths();
         if items.is_empty() {
             return None;
         }
@@ -237,6 +269,7 @@ impl JunkFileDetector {
             total_size: 0,
             count: items.len() as u64,
             items,
+            ignored_paths,
         })
     }
 
@@ -252,10 +285,14 @@ impl JunkFileDetector {
             let walker = WalkDir::new(scan_path)
                 .follow_links(false)
                 .into_iter()
+                .filter_entry(|e| !self.ignore_files.is_excluded_entry(e))
                 .filter_map(|e| e.ok())
                 .filter(|e| e.file_type().is_file());
 
             for entry in walker {
+                if self.is_cancelled() {
+                    break;
+                }
                 let path = entry.path();
 
                 if self.should_skip(path) {
@@ -276,6 +313,7 @@ impl JunkFileDetector {
             }
         }
 
+        let ignored_paths = self.ignore_files.take_excluded_paths();
         if items.is_empty() {
             return None;
         }
@@ -286,6 +324,7 @@ impl JunkFileDetector {
             total_size,
             count: items.len() as u64,
             items,
+            ignored_paths,
         })
     }
 
@@ -306,10 +345,14 @@ impl JunkFileDetector {
             let walker = WalkDir::new(scan_path)
                 .follow_links(false)
                 .into_iter()
+                .filter_entry(|e| !self.ignore_files.is_excluded_entry(e))
                 .filter_map(|e| e.ok())
                 .filter(|e| e.file_type().is_file());
 
             for entry in walker {
+                if self.is_cancelled() {
+                    break;
+                }
                 let path = entry.path();
 
                 if self.should_skip(path) {
@@ -341,6 +384,7 @@ impl JunkFileDetector {
             }
         }
 
+        let ignored_paths = self.ignore_files.take_excluded_paths();
         if items.is_empty() {
             return None;
         }
@@ -351,6 +395,7 @@ impl JunkFileDetector {
             total_size,
             count: items.len() as u64,
             items,
+            ignored_paths,
         })
     }
 
@@ -371,10 +416,14 @@ impl JunkFileDetector {
             let walker = WalkDir::new(scan_path)
                 .follow_links(false)
                 .into_iter()
+                .filter_entry(|e| !self.ignore_files.is_excluded_entry(e))
                 .filter_map(|e| e.ok())
                 .filter(|e| e.file_type().is_file());
 
             for entry in walker {
+                if self.is_cancelled() {
+                    break;
+                }
                 let path = entry.path();
 
                 if self.should_skip(path) {
@@ -409,6 +458,7 @@ impl JunkFileDetector {
             }
         }
 
+        let ignored_paths = self.ignore_files.take_excluded_paths();
         if items.is_empty() {
             return None;
         }
@@ -419,6 +469,7 @@ impl JunkFileDetector {
             total_size,
             count: items.len() as u64,
             items,
+            ignored_paths,
         })
     }
 
@@ -434,10 +485,14 @@ impl JunkFileDetector {
             let walker = WalkDir::new(download_path)
                 .follow_links(false)
                 .into_iter()
+                .filter_entry(|e| !self.ignore_files.is_excluded_entry(e))
                 .filter_map(|e| e.ok())
                 .filter(|e| e.file_type().is_file());
 
             for entry in walker {
+                if self.is_cancelled() {
+                    break;
+                }
                 let path = entry.path();
 
                 if self.should_skip(path) {
@@ -460,6 +515,7 @@ impl JunkFileDetector {
             }
         }
 
+        let ignored_paths = self.ignore_files.take_excluded_paths();
         if items.is_empty() {
             return None;
         }
@@ -470,6 +526,7 @@ impl JunkFileDetector {
             total_size,
             count: items.len() as u64,
             items,
+            ignored_paths,
         })
     }
 
@@ -485,10 +542,14 @@ impl JunkFileDetector {
             let walker = WalkDir::new(scan_path)
                 .follow_links(false)
                 .into_iter()
+                .filter_entry(|e| !self.ignore_files.is_excluded_entry(e))
                 .filter_map(|e| e.ok())
                 .filter(|e| e.file_type().is_file());
 
             for entry in walker {
+                if self.is_cancelled() {
+                    break;
+                }
                 let path = entry.path();
 
                 if self.should_skip(path) {
@@ -514,6 +575,7 @@ impl JunkFileDetector {
             }
         }
 
+        let ignored_paths = self.ignore_files.take_excluded_paths();
         if items.is_empty() {
             return None;
         }
@@ -524,6 +586,7 @@ impl JunkFileDetector {
             total_size,
             count: items.len() as u64,
             items,
+            ignored_paths,
         })
     }
 
@@ -557,18 +620,12 @@ impl JunkFileDetector {
     }
 
     fn should_skip(&self, path: &Path) -> bool {
-        let path_str = path.to_string_lossy();
-
-        for exclude in &self.options.exclude_paths {
-            if path_str.to_lowercase().starts_with(&exclude.to_lowercase()) {
-                return true;
-            }
+        if self.exclude_paths.iter().any(|p| p.contains(path)) {
+            return true;
         }
 
-        for protected in &self.protected_paths {
-            if path.starts_with(protected) {
-                return true;
-            }
+        if self.protected_paths.iter().any(|p| p.contains(path)) {
+            return true;
         }
 
         if !self.options.include_hidden {
@@ -37,7 +37,7 @@ use crate::modules::scanner_framework::{
     StandardFileFilter,
 };
 
-use crate::models::{ScanStatus, EVENT_JUNK_FILE_COMPLETE, EVENT_JUNK_FILE_PROGRESS};
+use crate::models::{IgnoredPath, ScanStatus, EVENT_JUNK_FILE_COMPLETE, EVENT_JUNK_FILE_PROGRESS};
 use crate::modules::cleaner::safety::SafetyChecker;
 use crate::modules::file_analyzer::{JunkFile, JunkFileType, JunkScanOptions, JunkScanResult};
 use crate::utils::path::{PathUtils, SystemPaths};
@@ -97,6 +97,17 @@ lazy_static::lazy_static! {
     static ref SCAN_MANAGER: ScanManager<JunkFileScanProgress, Vec<JunkScanResult>> = ScanManager::new();
     static ref JUNK_FILES_STORE: Arc<RwLock<HashMap<String, HashMap<JunkFileType, Vec<JunkFile>>>>> =
         Arc::new(RwLock::new(HashMap::new()));
+    static ref JUNK_IGNORED_STORE: Arc<RwLock<HashMap<String, Vec<IgnoredPath>>>> =
+        Arc::new(RwLock::new(HashMap::new()));
+}
+
+/// 获取扫描中被 `.disktidyignore` 排除的路径
+pub async fn get_junk_ignored_paths(scan_id: &str) -> Vec<IgnoredPath> {
+    JUNK_IGNORED_STORE
+        .read()
+        .ok()
+        .and_then(|store| store.get(scan_id).cloned())
+        .unwrap_or_default()
 }
 
 pub async fn get_junk_category_files(
@@ -266,6 +277,7 @@ async fn perform_scan(
         include_hidden: options.include_hidden,
         include_system: options.include_system,
         exclude_paths: options.exclude_paths.clone(),
+        respect_ignore_files: true,
     };
     let filter = StandardFileFilter::new(&filter_options);
     let safety_checker = SafetyChecker::new();
@@ -491,6 +503,9 @@ async fn perform_scan(
         let mut store = JUNK_FILES_STORE.write().unwrap();
         store.insert(ctx.scan_id.clone(), results_by_type.clone());
     }
+    if let Ok(mut store) = JUNK_IGNORED_STORE.write() {
+        store.insert(ctx.scan_id.clone(), filter.ignored_paths());
+    }
 
     // 构建返回结果 - 限制每个类型返回的文件数量以提高性能
     const MAX_ITEMS_PER_TYPE: usize = 100;
@@ -505,6 +520,7 @@ async fn perform_scan(
                 total_size,
                 count: items.len() as u64,
                 items: display_items,
+                ignored_paths: Vec::new(),
             }
         })
         .collect();
@@ -822,5 +838,8 @@ pub async fn cancel_junk_file_scan(scan_id: &str) -> Result<(), String> {
 }
 
 pub async fn clear_junk_file_scan_result(scan_id: &str) -> Result<(), String> {
+    if let Ok(mut store) = JUNK_IGNORED_STORE.write() {
+        store.remove(scan_id);
+    }
     SCAN_MANAGER.clear_scan(scan_id).await
 }
@@ -1,11 +1,16 @@
 use crate::models::cleaner::{
     LargeFile, LargeFileAnalysisResult, LargeFileAnalyzerOptions, LargeFileDetails,
 };
+use crate::modules::git_repo::GitRepoCache;
+use crate::utils::gitignore::DisktidyIgnore;
 use crate::utils::path::{PathUtils, SystemPaths};
+use crate::utils::path_match::PathPrefix;
 use crate::utils::file_type::get_file_type;
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use walkdir::WalkDir;
 
@@ -39,7 +44,11 @@ pub struct LargeFileStats {
 
 pub struct LargeFileAnalyzer {
     options: LargeFileAnalyzerOptions,
-    protected_paths: Vec<PathBuf>,
+    protected_paths: Vec<PathPrefix>,
+    exclude_paths: Vec<PathPrefix>,
+    git_repos: GitRepoCache,
+    ignore_files: DisktidyIgnore,
+    cancelled: Option<Arc<AtomicBool>>,
 }
 
 impl LargeFileAnalyzer {
@@ -49,8 +58,15 @@ impl LargeFileAnalyzer {
 
     pub fn with_options(options: LargeFileAnalyzerOptions) -> Self {
         Self {
+            protected_paths: SystemPaths::get_protected_paths()
+                .iter()
+                .map(PathPrefix::new)
+                .collect(),
+            exclude_paths: options.exclude_paths.iter().map(PathPrefix::new).collect(),
             options,
-            protected_paths: SystemPaths::get_protected_paths(),
+            git_repos: GitRepoCache::new(),
+            ignore_files: DisktidyIgnore::new(),
+            cancelled: None,
         }
     }
 
@@ -58,7 +74,19 @@ impl LargeFileAnalyzer {
         self.options.threshold = size;
     }
 
+    /// 设置取消标志，标志置位后尽快结束扫描，返回已找到的结果
+    pub fn set_cancel_flag(&mut self, flag: Arc<AtomicBool>) {
+        self.cancelled = Some(flag);
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::Relaxed))
+    }
+
     pub fn add_exclude_path(&mut self, path: PathBuf) {
+        self.exclude_paths.push(PathPrefix::new(&path));
         self.options.exclude_paths.push(path.to_string_lossy().to_string());
     }
 
@@ -84,6 +112,7 @@ impl LargeFileAnalyzer {
             files: large_files,
             threshold: self.options.threshold,
             duration_ms: end_time - start_time,
+            ignored_paths: self.ignore_files.take_excluded_paths(),
         }
     }
 
@@ -113,6 +142,7 @@ impl LargeFileAnalyzer {
             files: large_files,
             threshold: self.options.threshold,
             duration_ms: end_time - start_time,
+            ignored_paths: self.ignore_files.take_excluded_paths(),
         }
     }
 
@@ -132,10 +162,14 @@ impl LargeFileAnalyzer {
             let walker = WalkDir::new(scan_path)
                 .follow_links(false)
                 .into_iter()
+                .filter_entry(|e| !self.ignore_files.is_excluded_entry(e))
                 .filter_map(|e| e.ok())
                 .filter(|e| e.file_type().is_file());
 
             for entry in walker {
+                if self.is_cancelled() {
+                    break;
+                }
                 let path = entry.path();
 
                 if self.should_skip(path) {
@@ -168,18 +202,12 @@ impl LargeFileAnalyzer {
     }
 
     fn should_skip(&self, path: &Path) -> bool {
-        let path_str = path.to_string_lossy();
-
-        for exclude in &self.options.exclude_paths {
-            if path_str.to_lowercase().starts_with(&exclude.to_lowercase()) {
-                return true;
-            }
+        if self.exclude_paths.iter().any(|p| p.contains(path)) {
+            return true;
         }
 
-        for protected in &self.protected_paths {
-            if path.starts_with(protected) {
-                return true;
-            }
+        if self.protected_paths.iter().any(|p| p.contains(path)) {
+            return true;
         }
 
         if !self.options.include_hidden {
@@ -232,6 +260,7 @@ impl LargeFileAnalyzer {
                 .unwrap_or(0),
             file_type,
             extension,
+            git_status: self.git_repos.status(path),
         })
     }
 
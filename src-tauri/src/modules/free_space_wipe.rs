@@ -0,0 +1,591 @@
+//! 空闲空间擦除
+//!
+//! 普通删除只移除目录项，数据块中的内容在被覆盖前仍可恢复。这里在所选卷上
+//! 创建临时文件写满空闲空间（随机数据或固定字节），逐个 fsync 后全部删除，
+//! 使之前删除的文件所占的块被覆盖。
+//!
+//! - 始终保留安全余量，不会把文件系统写满，写入过程中定期重新检查空闲空间，
+//!   其他程序占用了空间时提前停止
+//! - 支持暂停与取消，无论成功、取消还是出错，临时文件都会被删除
+//! - 进度通过扫描框架发送
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+use tauri::{AppHandle, Emitter};
+
+use crate::models::{OverwritePattern, ScanStatus};
+use crate::modules::scanner_framework::{
+    ControlAction, ScanContext, ScanManager, ScanProgress as ScanProgressTrait,
+};
+
+pub const EVENT_FREE_SPACE_WIPE_PROGRESS: &str = "free-space-wipe:progress";
+pub const EVENT_FREE_SPACE_WIPE_COMPLETE: &str = "free-space-wipe:complete";
+
+/// 每次写入的大小
+const WRITE_CHUNK_SIZE: usize = 1024 * 1024;
+/// 每写入多少块重新检查一次空闲空间
+const SPACE_CHECK_INTERVAL: u64 = 64;
+/// 临时文件所在目录名的前缀
+pub const WIPE_DIR_PREFIX: &str = ".disktidy-wipe-";
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FreeSpaceWipeConfig {
+    /// 要擦除的卷上的目录，通常为挂载点，临时文件创建在其中
+    pub path: String,
+    pub pattern: OverwritePattern,
+    /// 擦除时至少保留的空闲空间
+    pub min_free_bytes: u64,
+    /// 擦除时至少保留的空闲空间占卷容量的百分比，与 `min_free_bytes` 取较大者
+    pub min_free_percent: f64,
+    /// 单个临时文件的最大大小，避免超过文件系统的单文件大小限制
+    pub max_file_size: u64,
+}
+
+impl Default for FreeSpaceWipeConfig {
+    fn default() -> Self {
+        Self {
+            path: String::new(),
+            pattern: OverwritePattern::Random,
+            min_free_bytes: 512 * 1024 * 1024,
+            min_free_percent: 2.0,
+            max_file_size: 1024 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FreeSpaceWipeProgress {
+    pub scan_id: String,
+    pub current_path: String,
+    pub written_bytes: u64,
+    pub target_bytes: u64,
+    pub files_created: u64,
+    pub percent: f32,
+    pub speed: f64,
+    pub status: ScanStatus,
+}
+
+impl FreeSpaceWipeProgress {
+    pub fn new(scan_id: &str) -> Self {
+        Self {
+            scan_id: scan_id.to_string(),
+            current_path: String::new(),
+            written_bytes: 0,
+            target_bytes: 0,
+            files_created: 0,
+            percent: 0.0,
+            speed: 0.0,
+            status: ScanStatus::Scanning,
+        }
+    }
+}
+
+impl ScanProgressTrait for FreeSpaceWipeProgress {
+    fn set_status(&mut self, status: ScanStatus) {
+        self.status = status;
+    }
+
+    fn event_name() -> &'static str {
+        EVENT_FREE_SPACE_WIPE_PROGRESS
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FreeSpaceWipeResult {
+    pub scan_id: String,
+    pub path: String,
+    pub written_bytes: u64,
+    pub files_created: u64,
+    pub available_before: u64,
+    pub available_after: u64,
+    pub safety_margin: u64,
+    pub cancelled: bool,
+    pub duration_ms: u64,
+}
+
+/// 卷的容量与可用空间
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VolumeSpace {
+    pub total: u64,
+    pub available: u64,
+}
+
+/// 查询卷空间的来源，测试中可以替换为固定的数据
+pub trait SpaceProbe: Send + Sync {
+    fn space(&self, path: &Path) -> io::Result<VolumeSpace>;
+}
+
+/// 通过系统调用查询卷空间
+pub struct SystemSpace;
+
+impl SpaceProbe for SystemSpace {
+    #[cfg(unix)]
+    fn space(&self, path: &Path) -> io::Result<VolumeSpace> {
+        use std::ffi::CString;
+        use std::os::unix::ffi::OsStrExt;
+
+        let c_path = CString::new(path.as_os_str().as_bytes())?;
+        let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+        if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let fragment = stat.f_frsize as u64;
+        Ok(VolumeSpace {
+            total: stat.f_blocks as u64 * fragment,
+            available: stat.f_bavail as u64 * fragment,
+        })
+    }
+
+    #[cfg(windows)]
+    fn space(&self, path: &Path) -> io::Result<VolumeSpace> {
+        use windows::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+        let wide_path: Vec<u16> = path
+            .to_string_lossy()
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .collect();
+        let mut available: u64 = 0;
+        let mut total: u64 = 0;
+        unsafe {
+            GetDiskFreeSpaceExW(
+                windows::core::PCWSTR(wide_path.as_ptr()),
+                Some(&mut available),
+                Some(&mut total),
+                None,
+            )
+        }
+        .map_err(|e| io::Error::other(e.to_string()))?;
+        Ok(VolumeSpace { total, available })
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    fn space(&self, _path: &Path) -> io::Result<VolumeSpace> {
+        Err(io::Error::from(io::ErrorKind::Unsupported))
+    }
+}
+
+/// 擦除过程中的统计
+#[derive(Debug, Clone, Default)]
+pub struct WipeStats {
+    pub current_path: PathBuf,
+    pub written_bytes: u64,
+    pub target_bytes: u64,
+    pub files_created: u64,
+}
+
+/// 一次擦除的结果
+#[derive(Debug, Clone, Default)]
+pub struct WipeSummary {
+    pub available_before: u64,
+    pub available_after: u64,
+    pub safety_margin: u64,
+    pub written_bytes: u64,
+    pub files_created: u64,
+    pub cancelled: bool,
+}
+
+/// 单个临时文件写入结束的原因
+enum FillEnd {
+    FileFull,
+    TargetReached,
+    /// 空闲空间已降到安全余量以下
+    LowSpace,
+    Cancelled,
+}
+
+pub struct FreeSpaceWiper {
+    config: FreeSpaceWipeConfig,
+    probe: Box<dyn SpaceProbe>,
+}
+
+impl FreeSpaceWiper {
+    pub fn new(config: FreeSpaceWipeConfig) -> Self {
+        Self::with_probe(config, Box::new(SystemSpace))
+    }
+
+    pub fn with_probe(config: FreeSpaceWipeConfig, probe: Box<dyn SpaceProbe>) -> Self {
+        Self { config, probe }
+    }
+
+    /// 需要保留的空闲空间
+    pub fn safety_margin(&self, space: VolumeSpace) -> u64 {
+        let percent = (space.total as f64 * self.config.min_free_percent.max(0.0) / 100.0) as u64;
+        self.config.min_free_bytes.max(percent)
+    }
+
+    /// 执行擦除，阻塞直到完成、取消或出错
+    ///
+    /// `paused` 为真时暂停写入；`on_progress` 在每写入一块后调用。
+    pub fn run(
+        &self,
+        cancelled: &AtomicBool,
+        paused: &AtomicBool,
+        on_progress: &mut dyn FnMut(&WipeStats),
+    ) -> io::Result<WipeSummary> {
+        let root = PathBuf::from(&self.config.path);
+        if !root.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("目录不存在: {}", root.display()),
+            ));
+        }
+
+        let before = self.probe.space(&root)?;
+        let margin = self.safety_margin(before);
+        let mut stats = WipeStats {
+            target_bytes: before.available.saturating_sub(margin),
+            ..Default::default()
+        };
+
+        let work_dir = root.join(format!("{}{}", WIPE_DIR_PREFIX, uuid::Uuid::new_v4()));
+        fs::create_dir(&work_dir)?;
+
+        let filled = self.fill(
+            &work_dir,
+            margin,
+            &mut stats,
+            cancelled,
+            paused,
+            on_progress,
+        );
+        // 无论结果如何都删除临时文件，释放占用的空间
+        let removed = fs::remove_dir_all(&work_dir);
+        sync_dir(&root);
+
+        let was_cancelled = filled?;
+        removed?;
+
+        let after = self.probe.space(&root).unwrap_or(before);
+        Ok(WipeSummary {
+            available_before: before.available,
+            available_after: after.available,
+            safety_margin: margin,
+            written_bytes: stats.written_bytes,
+            files_created: stats.files_created,
+            cancelled: was_cancelled,
+        })
+    }
+
+    /// 依次创建临时文件直到达到目标，返回是否被取消
+    fn fill(
+        &self,
+        work_dir: &Path,
+        margin: u64,
+        stats: &mut WipeStats,
+        cancelled: &AtomicBool,
+        paused: &AtomicBool,
+        on_progress: &mut dyn FnMut(&WipeStats),
+    ) -> io::Result<bool> {
+        let mut writer = ChunkWriter::new(self.config.pattern);
+        let mut chunks: u64 = 0;
+
+        while stats.written_bytes < stats.target_bytes {
+            let path = work_dir.join(format!("wipe-{:05}.tmp", stats.files_created));
+            let mut file = File::create(&path)?;
+            stats.files_created += 1;
+            stats.current_path = path;
+
+            let end = self.fill_file(
+                &mut file,
+                &mut writer,
+                margin,
+                &mut chunks,
+                stats,
+                cancelled,
+                paused,
+                on_progress,
+            )?;
+            file.sync_all()?;
+
+            match end {
+                FillEnd::FileFull => continue,
+                FillEnd::TargetReached => break,
+                FillEnd::LowSpace => {
+                    log::info!("Free space reached safety margin, stopping wipe");
+                    break;
+                }
+                FillEnd::Cancelled => return Ok(true),
+            }
+        }
+
+        Ok(false)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn fill_file(
+        &self,
+        file: &mut File,
+        writer: &mut ChunkWriter,
+        margin: u64,
+        chunks: &mut u64,
+        stats: &mut WipeStats,
+        cancelled: &AtomicBool,
+        paused: &AtomicBool,
+        on_progress: &mut dyn FnMut(&WipeStats),
+    ) -> io::Result<FillEnd> {
+        let root = Path::new(&self.config.path);
+        let mut in_file: u64 = 0;
+
+        loop {
+            if stats.written_bytes >= stats.target_bytes {
+                return Ok(FillEnd::TargetReached);
+            }
+            if in_file >= self.config.max_file_size.max(1) {
+                return Ok(FillEnd::FileFull);
+            }
+            while paused.load(Ordering::Relaxed) && !cancelled.load(Ordering::Relaxed) {
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            if cancelled.load(Ordering::Relaxed) {
+                return Ok(FillEnd::Cancelled);
+            }
+
+            if *chunks > 0 && chunks.is_multiple_of(SPACE_CHECK_INTERVAL) {
+                let space = self.probe.space(root)?;
+                if space.available <= margin.saturating_add(WRITE_CHUNK_SIZE as u64) {
+                    return Ok(FillEnd::LowSpace);
+                }
+            }
+
+            let size = (WRITE_CHUNK_SIZE as u64)
+                .min(stats.target_bytes - stats.written_bytes)
+                .min(self.config.max_file_size.max(1) - in_file) as usize;
+            match file.write_all(writer.next(size)) {
+                Ok(()) => {}
+                // 余量计算之外的空间已被占用，到此为止
+                Err(e) if is_no_space(&e) => return Ok(FillEnd::LowSpace),
+                Err(e) => return Err(e),
+            }
+
+            *chunks += 1;
+            in_file += size as u64;
+            stats.written_bytes += size as u64;
+            on_progress(stats);
+        }
+    }
+}
+
+/// 生成写入的数据，固定字节只填充一次
+struct ChunkWriter {
+    pattern: OverwritePattern,
+    buffer: Vec<u8>,
+    rng: StdRng,
+}
+
+impl ChunkWriter {
+    fn new(pattern: OverwritePattern) -> Self {
+        let fill = match pattern {
+            OverwritePattern::Byte(byte) => byte,
+            OverwritePattern::Random => 0,
+        };
+        Self {
+            pattern,
+            buffer: vec![fill; WRITE_CHUNK_SIZE],
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    fn next(&mut self, size: usize) -> &[u8] {
+        if self.pattern == OverwritePattern::Random {
+            self.rng.fill_bytes(&mut self.buffer[..size]);
+        }
+        &self.buffer[..size]
+    }
+}
+
+fn is_no_space(error: &io::Error) -> bool {
+    #[cfg(unix)]
+    {
+        error.raw_os_error() == Some(libc::ENOSPC)
+    }
+    #[cfg(windows)]
+    {
+        // ERROR_HANDLE_DISK_FULL、ERROR_DISK_FULL
+        matches!(error.raw_os_error(), Some(39) | Some(112))
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = error;
+        false
+    }
+}
+
+fn sync_dir(dir: &Path) {
+    #[cfg(unix)]
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+    #[cfg(not(unix))]
+    let _ = dir;
+}
+
+lazy_static::lazy_static! {
+    static ref WIPE_MANAGER: ScanManager<FreeSpaceWipeProgress, FreeSpaceWipeResult> = ScanManager::new();
+}
+
+pub async fn start_wipe(app: AppHandle, config: FreeSpaceWipeConfig) -> Result<String, String> {
+    let scan_id = crate::models::generate_scan_id();
+    let progress = FreeSpaceWipeProgress::new(&scan_id);
+    WIPE_MANAGER
+        .start_scan_with_id(app, scan_id, progress, move |mut ctx| async move {
+            perform_wipe(&mut ctx, config).await
+        })
+        .await
+}
+
+pub async fn pause_wipe(scan_id: &str) -> Result<(), String> {
+    WIPE_MANAGER.pause_scan(scan_id).await
+}
+
+pub async fn resume_wipe(scan_id: &str) -> Result<(), String> {
+    WIPE_MANAGER.resume_scan(scan_id).await
+}
+
+pub async fn cancel_wipe(scan_id: &str) -> Result<(), String> {
+    WIPE_MANAGER.cancel_scan(scan_id).await
+}
+
+pub async fn get_progress(scan_id: &str) -> Option<FreeSpaceWipeProgress> {
+    WIPE_MANAGER.get_progress(scan_id).await
+}
+
+pub async fn get_result(scan_id: &str) -> Option<FreeSpaceWipeResult> {
+    WIPE_MANAGER.get_result(scan_id).await
+}
+
+pub async fn clear_wipe(scan_id: &str) -> Result<(), String> {
+    WIPE_MANAGER.clear_scan(scan_id).await
+}
+
+async fn perform_wipe(
+    ctx: &mut ScanContext<FreeSpaceWipeProgress>,
+    config: FreeSpaceWipeConfig,
+) -> Result<FreeSpaceWipeResult, String> {
+    let start_instant = Instant::now();
+    let is_paused = Arc::new(AtomicBool::new(false));
+    let is_cancelled = Arc::new(AtomicBool::new(false));
+
+    let scan_id = ctx.scan_id.clone();
+    let path = config.path.clone();
+    let progress_store = WIPE_MANAGER.get_progress_store();
+
+    let handle = {
+        let is_paused = is_paused.clone();
+        let is_cancelled = is_cancelled.clone();
+        let progress_store = progress_store.clone();
+        let scan_id = scan_id.clone();
+        let app = ctx.app.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let wiper = FreeSpaceWiper::new(config);
+            let mut last_update = Instant::now();
+            let update_interval = Duration::from_millis(200);
+
+            let mut on_progress = |stats: &WipeStats| {
+                let now = Instant::now();
+                if now.duration_since(last_update) < update_interval {
+                    return;
+                }
+                last_update = now;
+
+                let elapsed = start_instant.elapsed().as_secs_f64();
+                let progress = FreeSpaceWipeProgress {
+                    scan_id: scan_id.clone(),
+                    current_path: stats.current_path.display().to_string(),
+                    written_bytes: stats.written_bytes,
+                    target_bytes: stats.target_bytes,
+                    files_created: stats.files_created,
+                    percent: if stats.target_bytes > 0 {
+                        (stats.written_bytes as f64 / stats.target_bytes as f64 * 100.0) as f32
+                    } else {
+                        0.0
+                    },
+                    speed: if elapsed > 0.0 {
+                        stats.written_bytes as f64 / elapsed
+                    } else {
+                        0.0
+                    },
+                    status: ScanStatus::Scanning,
+                };
+                let _ = app.emit(EVENT_FREE_SPACE_WIPE_PROGRESS, &progress);
+
+                if let Ok(mut store) = progress_store.try_write() {
+                    if let Some(p) = store.get_mut(&scan_id) {
+                        let status = p.status.clone();
+                        *p = FreeSpaceWipeProgress { status, ..progress };
+                    }
+                }
+            };
+
+            wiper.run(&is_cancelled, &is_paused, &mut on_progress)
+        })
+    };
+
+    loop {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        if ctx.check_control(&progress_store).await == ControlAction::Cancel {
+            // 等待擦除线程删除临时文件后再返回
+            is_cancelled.store(true, Ordering::Relaxed);
+        }
+
+        let paused = {
+            let store = progress_store.read().await;
+            store
+                .get(&scan_id)
+                .map(|p| p.status == ScanStatus::Paused)
+                .unwrap_or(false)
+        };
+        is_paused.store(paused, Ordering::Relaxed);
+
+        if handle.is_finished() {
+            break;
+        }
+    }
+
+    let summary = handle
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("Free space wipe failed: {}", e))?;
+
+    let result = FreeSpaceWipeResult {
+        scan_id: scan_id.clone(),
+        path,
+        written_bytes: summary.written_bytes,
+        files_created: summary.files_created,
+        available_before: summary.available_before,
+        available_after: summary.available_after,
+        safety_margin: summary.safety_margin,
+        cancelled: summary.cancelled,
+        duration_ms: start_instant.elapsed().as_millis() as u64,
+    };
+
+    {
+        let mut store = progress_store.write().await;
+        if let Some(p) = store.get_mut(&scan_id) {
+            p.written_bytes = result.written_bytes;
+            p.files_created = result.files_created;
+            p.status = if result.cancelled {
+                ScanStatus::Cancelled
+            } else {
+                p.percent = 100.0;
+                ScanStatus::Completed
+            };
+        }
+    }
+
+    let _ = ctx.app.emit(EVENT_FREE_SPACE_WIPE_COMPLETE, &result);
+
+    Ok(result)
+}
@@ -0,0 +1,701 @@
+//! Git 仓库感知
+//!
+//! 纯 Rust 读取 `.git/index` 与各级 `.gitignore`，判断文件是否被跟踪或忽略：
+//! 已跟踪的源文件受保护，被忽略的构建产物视为可重新生成。
+//! 同时统计每个仓库的对象库大小、松散对象数量以及未跟踪的大文件。
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use walkdir::WalkDir;
+
+use crate::models::GitPathStatus;
+use crate::utils::format_bytes;
+use crate::utils::gitignore::{IgnoreFile, IgnoreTree};
+
+/// 查找仓库时不进入的目录
+const REPO_SEARCH_SKIP_DIRS: &[&str] = &["node_modules", ".gradle", "__pycache__"];
+
+/// 松散对象超过该数量时建议执行 `git gc`
+const LOOSE_OBJECT_GC_THRESHOLD: u64 = 256;
+
+/// 报告中列出的被忽略路径数量上限，总大小不受影响
+const MAX_LISTED_IGNORED_PATHS: usize = 500;
+
+/// 解析 `.git/index`，返回所有已跟踪文件的相对路径（以 `/` 分隔）
+///
+/// 支持索引格式 v2、v3 与 v4（路径前缀压缩）。条目之后的扩展中，签名以小写
+/// 字母开头的必须理解才能得到完整的条目列表，例如拆分索引的 `link` 与稀疏索引的
+/// `sdir`，遇到这类扩展时返回 None。
+pub fn parse_git_index(data: &[u8]) -> Option<Vec<String>> {
+    if data.len() < 12 || &data[0..4] != b"DIRC" {
+        return None;
+    }
+
+    let version = read_u32(data, 4)?;
+    if !(2..=4).contains(&version) {
+        return None;
+    }
+    let count = read_u32(data, 8)? as usize;
+
+    let mut paths = Vec::with_capacity(count);
+    let mut offset = 12;
+    let mut previous = Vec::new();
+
+    for _ in 0..count {
+        let entry_start = offset;
+        // 40 字节 stat 信息 + 20 字节对象 ID + 2 字节标志
+        let flags = read_u16(data, entry_start + 60)?;
+        offset = entry_start + 62;
+        if version >= 3 && flags & 0x4000 != 0 {
+            offset += 2;
+        }
+
+        let name = if version == 4 {
+            let (strip, consumed) = read_offset_varint(data, offset)?;
+            offset += consumed;
+            let end = offset + data.get(offset..)?.iter().position(|b| *b == 0)?;
+            let keep = previous.len().checked_sub(strip as usize)?;
+            let mut name = previous[..keep].to_vec();
+            name.extend_from_slice(&data[offset..end]);
+            offset = end + 1;
+            name
+        } else {
+            let end = offset + data.get(offset..)?.iter().position(|b| *b == 0)?;
+            let name = data[offset..end].to_vec();
+            // 条目以 1 到 8 个 NUL 补齐到 8 字节边界
+            let entry_len = end - entry_start;
+            offset = entry_start + (entry_len / 8 + 1) * 8;
+            name
+        };
+
+        paths.push(String::from_utf8_lossy(&name).to_string());
+        previous = name;
+    }
+
+    // 扩展位于条目与末尾 20 字节校验和之间：4 字节签名 + 4 字节长度 + 数据
+    let end = data.len().checked_sub(20)?;
+    while offset + 8 <= end {
+        if data[offset].is_ascii_lowercase() {
+            return None;
+        }
+        offset += 8 + read_u32(data, offset + 4)? as usize;
+    }
+
+    Some(paths)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+/// Git 的偏移量变长整数编码（每个延续字节隐含加一）
+fn read_offset_varint(data: &[u8], offset: usize) -> Option<(u64, usize)> {
+    let mut byte = *data.get(offset)?;
+    let mut value = (byte & 0x7f) as u64;
+    let mut consumed = 1;
+    while byte & 0x80 != 0 {
+        byte = *data.get(offset + consumed)?;
+        value = ((value + 1) << 7) | (byte & 0x7f) as u64;
+        consumed += 1;
+    }
+    Some((value, consumed))
+}
+
+/// 一个 Git 工作区
+pub struct GitRepository {
+    pub root: PathBuf,
+    pub git_dir: PathBuf,
+    tracked: BTreeSet<String>,
+    /// 索引存在但无法完整解析时为 false，此时无法区分跟踪状态，所有路径按已跟踪保护
+    index_supported: bool,
+    ignore: IgnoreTree,
+}
+
+impl GitRepository {
+    /// 从给定路径向上查找所在的仓库
+    pub fn discover(path: &Path) -> Option<Self> {
+        path.ancestors()
+            .find(|dir| dir.join(".git").exists())
+            .and_then(Self::open)
+    }
+
+    /// 打开以 `root` 为工作区根目录的仓库
+    pub fn open(root: &Path) -> Option<Self> {
+        let git_dir = resolve_git_dir(root)?;
+
+        // 尚未提交过的仓库没有索引文件，这时确实没有已跟踪的文件
+        let (tracked, index_supported) = match fs::read(git_dir.join("index")) {
+            Ok(data) => match parse_git_index(&data) {
+                Some(paths) => (paths.iter().map(|p| normalize_key(p)).collect(), true),
+                None => {
+                    log::warn!(
+                        "Unsupported git index, treating all paths as tracked: {}",
+                        root.display()
+                    );
+                    (BTreeSet::new(), false)
+                }
+            },
+            Err(_) => (BTreeSet::new(), true),
+        };
+
+        let mut ignore = IgnoreTree::new(root, ".gitignore");
+        if let Some(global) = global_excludes_file() {
+            if let Some(file) = IgnoreFile::load(root, &global) {
+                ignore = ignore.with_extra(file);
+            }
+        }
+        if let Some(file) = IgnoreFile::load(root, &git_dir.join("info").join("exclude")) {
+            ignore = ignore.with_extra(file);
+        }
+
+        Some(Self {
+            root: root.to_path_buf(),
+            git_dir,
+            tracked,
+            index_supported,
+            ignore,
+        })
+    }
+
+    pub fn tracked_count(&self) -> usize {
+        self.tracked.len()
+    }
+
+    /// 索引能否完整解析，不能时所有路径都按已跟踪处理
+    pub fn index_supported(&self) -> bool {
+        self.index_supported
+    }
+
+    fn has_tracked(&self) -> bool {
+        !self.index_supported || !self.tracked.is_empty()
+    }
+
+    pub fn ignore_tree(&self) -> &IgnoreTree {
+        &self.ignore
+    }
+
+    /// 文件是否被跟踪；目录只要包含已跟踪文件即视为已跟踪
+    pub fn is_tracked(&self, path: &Path, is_dir: bool) -> bool {
+        match self.relative_key(path) {
+            Some(key) if key.is_empty() => self.has_tracked(),
+            Some(key) => self.is_tracked_key(&key, is_dir),
+            None => false,
+        }
+    }
+
+    fn is_tracked_key(&self, key: &str, is_dir: bool) -> bool {
+        if !self.index_supported || self.tracked.contains(key) {
+            return true;
+        }
+        if !is_dir {
+            return false;
+        }
+        let prefix = format!("{}/", key);
+        self.tracked
+            .range(prefix.clone()..)
+            .next()
+            .map(|p| p.starts_with(&prefix))
+            .unwrap_or(false)
+    }
+
+    pub fn status(&self, path: &Path) -> GitPathStatus {
+        if path.starts_with(&self.git_dir) {
+            return GitPathStatus::GitDir;
+        }
+
+        let key = match self.relative_key(path) {
+            Some(k) => k,
+            None => return GitPathStatus::Untracked,
+        };
+        if key == ".git" || key.starts_with(".git/") {
+            return GitPathStatus::GitDir;
+        }
+        if key.is_empty() {
+            return if self.has_tracked() {
+                GitPathStatus::Tracked
+            } else {
+                GitPathStatus::Untracked
+            };
+        }
+
+        let is_dir = path.is_dir();
+        if self.is_tracked_key(&key, is_dir) {
+            GitPathStatus::Tracked
+        } else if self.ignore.is_ignored(path, is_dir) {
+            GitPathStatus::Ignored
+        } else {
+            GitPathStatus::Untracked
+        }
+    }
+
+    fn relative_key(&self, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+        Some(normalize_key(&relative.to_string_lossy()))
+    }
+}
+
+fn normalize_key(path: &str) -> String {
+    let key = path.replace('\\', "/").trim_matches('/').to_string();
+    if cfg!(windows) {
+        key.to_lowercase()
+    } else {
+        key
+    }
+}
+
+/// `.git` 可以是目录，也可以是指向实际仓库目录的 `gitdir:` 文件（工作树、子模块）
+fn resolve_git_dir(root: &Path) -> Option<PathBuf> {
+    let dot_git = root.join(".git");
+    if dot_git.is_dir() {
+        return Some(dot_git);
+    }
+
+    let content = fs::read_to_string(&dot_git).ok()?;
+    let target = content
+        .lines()
+        .find_map(|l| l.strip_prefix("gitdir:"))?
+        .trim();
+    let target = PathBuf::from(target);
+    let git_dir = if target.is_absolute() {
+        target
+    } else {
+        root.join(target)
+    };
+    git_dir.is_dir().then_some(git_dir)
+}
+
+/// 未配置 core.excludesFile 时 Git 使用的全局忽略文件
+fn global_excludes_file() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|h| h.join(".config")))
+        .map(|c| c.join("git").join("ignore"))
+        .filter(|p| p.is_file())
+}
+
+/// 按目录缓存仓库查找结果，供大量路径的逐个判断使用
+///
+/// 仓库按索引与查询路径各级 `.gitignore` 的修改时间缓存，提交、暂存或修改任意
+/// 一级忽略规则后重新读取，长时间运行的进程不会沿用过期的跟踪与忽略状态。
+/// 目录到仓库根目录的映射在 `dir_ttl` 后过期，之后新建或删除的仓库也能被发现。
+pub struct GitRepoCache {
+    dirs: Mutex<HashMap<PathBuf, CachedDir>>,
+    repos: Mutex<HashMap<PathBuf, CachedRepository>>,
+    dir_ttl: Duration,
+}
+
+/// 目录映射的默认有效期
+const DIR_CACHE_TTL: Duration = Duration::from_secs(30);
+
+struct CachedDir {
+    resolved_at: Instant,
+    root: Option<PathBuf>,
+}
+
+struct CachedRepository {
+    stamp: RepositoryStamp,
+    /// 已查询过的目录中 `.gitignore` 的修改时间
+    ignore_stamps: HashMap<PathBuf, Option<SystemTime>>,
+    repo: Option<Arc<GitRepository>>,
+}
+
+impl CachedRepository {
+    /// 记录尚未见过的目录，已记录的目录修改时间变化时返回 false
+    fn record_ignore_files(&mut self, files: &[(PathBuf, Option<SystemTime>)]) -> bool {
+        for (dir, modified) in files {
+            match self.ignore_stamps.get(dir) {
+                Some(recorded) if recorded != modified => return false,
+                Some(_) => {}
+                None => {
+                    self.ignore_stamps.insert(dir.clone(), *modified);
+                }
+            }
+        }
+        true
+    }
+}
+
+/// 索引与根目录 `.gitignore` 的修改时间
+type RepositoryStamp = (Option<SystemTime>, Option<SystemTime>);
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn repository_stamp(root: &Path) -> RepositoryStamp {
+    let index = resolve_git_dir(root).and_then(|git_dir| modified_time(&git_dir.join("index")));
+    (index, modified_time(&root.join(".gitignore")))
+}
+
+impl Default for GitRepoCache {
+    fn default() -> Self {
+        Self::with_dir_ttl(DIR_CACHE_TTL)
+    }
+}
+
+impl GitRepoCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_dir_ttl(dir_ttl: Duration) -> Self {
+        Self {
+            dirs: Mutex::new(HashMap::new()),
+            repos: Mutex::new(HashMap::new()),
+            dir_ttl,
+        }
+    }
+
+    pub fn repository_for(&self, path: &Path) -> Option<Arc<GitRepository>> {
+        let start = if path.is_dir() { path } else { path.parent()? };
+        let root = self.root_for(start)?;
+
+        let stamp = repository_stamp(&root);
+        // 影响该路径的是从所在目录到仓库根目录之间各级的 .gitignore
+        let ignore_files: Vec<(PathBuf, Option<SystemTime>)> = start
+            .ancestors()
+            .take_while(|dir| dir.starts_with(&root))
+            .map(|dir| (dir.to_path_buf(), modified_time(&dir.join(".gitignore"))))
+            .collect();
+
+        let mut repos = self.repos.lock().ok()?;
+        if let Some(cached) = repos.get_mut(&root) {
+            if cached.stamp == stamp && cached.record_ignore_files(&ignore_files) {
+                return cached.repo.clone();
+            }
+        }
+        let repo = GitRepository::open(&root).map(Arc::new);
+        let mut cached = CachedRepository {
+            stamp,
+            ignore_stamps: HashMap::new(),
+            repo: repo.clone(),
+        };
+        cached.record_ignore_files(&ignore_files);
+        repos.insert(root, cached);
+        repo
+    }
+
+    /// 向上查找包含 `.git` 的目录
+    fn root_for(&self, start: &Path) -> Option<PathBuf> {
+        let mut visited = Vec::new();
+        let mut found = None;
+        for dir in start.ancestors() {
+            if let Some(cached) = self.dirs.lock().ok()?.get(dir) {
+                // 过期或仓库已被删除时重新查找
+                let valid = cached.resolved_at.elapsed() < self.dir_ttl
+                    && cached.root.as_ref().is_none_or(|r| r.join(".git").exists());
+                if valid {
+                    found = cached.root.clone();
+                    break;
+                }
+            }
+            visited.push(dir.to_path_buf());
+            if dir.join(".git").exists() {
+                found = Some(dir.to_path_buf());
+                break;
+            }
+        }
+
+        let mut dirs = self.dirs.lock().ok()?;
+        let resolved_at = Instant::now();
+        for dir in visited {
+            dirs.insert(
+                dir,
+                CachedDir {
+                    resolved_at,
+                    root: found.clone(),
+                },
+            );
+        }
+        found
+    }
+
+    /// 不在任何仓库中时返回 None
+    pub fn status(&self, path: &Path) -> Option<GitPathStatus> {
+        self.repository_for(path).map(|repo| repo.status(path))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRepoScanOptions {
+    pub scan_paths: Vec<String>,
+    #[serde(default = "default_search_depth")]
+    pub max_depth: usize,
+    /// 未跟踪文件超过该大小时列入报告
+    #[serde(default = "default_large_file_threshold")]
+    pub large_file_threshold: u64,
+}
+
+fn default_search_depth() -> usize {
+    6
+}
+
+fn default_large_file_threshold() -> u64 {
+    50 * 1024 * 1024
+}
+
+impl Default for GitRepoScanOptions {
+    fn default() -> Self {
+        Self {
+            scan_paths: vec![],
+            max_depth: default_search_depth(),
+            large_file_threshold: default_large_file_threshold(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitUntrackedFile {
+    pub path: String,
+    pub size: u64,
+    pub modified_time: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitIgnoredPath {
+    pub path: String,
+    pub size: u64,
+    pub is_dir: bool,
+    /// 匹配的规则，格式为 `文件:行号 模式`
+    pub rule: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRepoReport {
+    pub root: String,
+    pub git_dir: String,
+    pub git_dir_size: u64,
+    pub pack_count: u64,
+    pub pack_size: u64,
+    pub loose_object_count: u64,
+    pub loose_object_size: u64,
+    pub tracked_files: u64,
+    pub untracked_size: u64,
+    pub large_untracked_files: Vec<GitUntrackedFile>,
+    pub ignored_size: u64,
+    pub ignored_paths: Vec<GitIgnoredPath>,
+    pub suggestions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRepoScanResult {
+    pub repositories: Vec<GitRepoReport>,
+    pub total_git_dir_size: u64,
+    pub total_ignored_size: u64,
+    pub duration_ms: u64,
+}
+
+/// 在扫描路径下查找所有 Git 工作区根目录
+pub fn find_git_repositories(scan_paths: &[PathBuf], max_depth: usize) -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+
+    for scan_path in scan_paths {
+        let walker = WalkDir::new(scan_path)
+            .follow_links(false)
+            .max_depth(max_depth)
+            .into_iter()
+            .filter_entry(|e| {
+                let name = e.file_name().to_string_lossy();
+                e.file_type().is_dir()
+                    && name != ".git"
+                    && !REPO_SEARCH_SKIP_DIRS.contains(&name.as_ref())
+            });
+
+        for entry in walker.filter_map(|e| e.ok()) {
+            if entry.path().join(".git").exists() && !roots.iter().any(|r| r == entry.path()) {
+                roots.push(entry.path().to_path_buf());
+            }
+        }
+    }
+
+    roots
+}
+
+pub fn scan_git_repositories(options: &GitRepoScanOptions) -> GitRepoScanResult {
+    let start_instant = Instant::now();
+    let scan_paths: Vec<PathBuf> = options.scan_paths.iter().map(PathBuf::from).collect();
+
+    let repositories: Vec<GitRepoReport> = find_git_repositories(&scan_paths, options.max_depth)
+        .iter()
+        .filter_map(|root| GitRepository::open(root))
+        .map(|repo| analyze_repository(&repo, options.large_file_threshold))
+        .collect();
+
+    GitRepoScanResult {
+        total_git_dir_size: repositories.iter().map(|r| r.git_dir_size).sum(),
+        total_ignored_size: repositories.iter().map(|r| r.ignored_size).sum(),
+        repositories,
+        duration_ms: start_instant.elapsed().as_millis() as u64,
+    }
+}
+
+pub fn analyze_repository(repo: &GitRepository, large_file_threshold: u64) -> GitRepoReport {
+    let objects = repo.git_dir.join("objects");
+
+    let mut loose_object_count = 0;
+    let mut loose_object_size = 0;
+    for dir in fs::read_dir(&objects).into_iter().flatten().flatten() {
+        let name = dir.file_name().to_string_lossy().to_string();
+        if name.len() != 2 || !name.chars().all(|c| c.is_ascii_hexdigit()) {
+            continue;
+        }
+        for object in fs::read_dir(dir.path()).into_iter().flatten().flatten() {
+            if let Ok(metadata) = object.metadata() {
+                loose_object_count += 1;
+                loose_object_size += metadata.len();
+            }
+        }
+    }
+
+    let mut pack_count = 0;
+    let mut pack_size = 0;
+    for pack in fs::read_dir(objects.join("pack"))
+        .into_iter()
+        .flatten()
+        .flatten()
+    {
+        let path = pack.path();
+        if path.extension().map(|e| e == "pack").unwrap_or(false) {
+            pack_count += 1;
+            pack_size += pack.metadata().map(|m| m.len()).unwrap_or(0);
+        }
+    }
+
+    let mut untracked_size = 0;
+    let mut large_untracked_files = Vec::new();
+    let mut ignored_size = 0;
+    let mut ignored_paths = Vec::new();
+
+    let mut walker = WalkDir::new(&repo.root).follow_links(false).into_iter();
+    while let Some(entry) = walker.next() {
+        let entry = match entry {
+            Ok(e) => e,
+            Err(_) => continue,
+        };
+        if entry.depth() == 0 {
+            continue;
+        }
+
+        let path = entry.path();
+        let is_dir = entry.file_type().is_dir();
+        if is_dir && (entry.file_name() == ".git" || path.join(".git").exists()) {
+            // 仓库数据和嵌套仓库单独统计
+            walker.skip_current_dir();
+            continue;
+        }
+
+        if let Some(matched) = repo.ignore.matched(path, is_dir).filter(|m| m.ignored) {
+            if !repo.is_tracked(path, is_dir) {
+                if is_dir {
+                    walker.skip_current_dir();
+                }
+                let size = if is_dir {
+                    dir_size(path)
+                } else {
+                    entry.metadata().map(|m| m.len()).unwrap_or(0)
+                };
+                ignored_size += size;
+                ignored_paths.push(GitIgnoredPath {
+                    path: path.to_string_lossy().to_string(),
+                    size,
+                    is_dir,
+                    rule: matched.describe(),
+                });
+                continue;
+            }
+        }
+
+        if is_dir || repo.is_tracked(path, false) {
+            continue;
+        }
+
+        let metadata = match entry.metadata() {
+            Ok(m) => m,
+            Err(_) => continue,
+        };
+        untracked_size += metadata.len();
+        if metadata.len() >= large_file_threshold {
+            large_untracked_files.push(GitUntrackedFile {
+                path: path.to_string_lossy().to_string(),
+                size: metadata.len(),
+                modified_time: metadata
+                    .modified()
+                    .ok()
+                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs() as i64)
+                    .unwrap_or(0),
+            });
+        }
+    }
+
+    large_untracked_files.sort_by_key(|f| std::cmp::Reverse(f.size));
+    ignored_paths.sort_by_key(|p| std::cmp::Reverse(p.size));
+    ignored_paths.truncate(MAX_LISTED_IGNORED_PATHS);
+
+    let mut suggestions = Vec::new();
+    if !repo.index_supported() {
+        suggestions
+            .push("索引使用了无法解析的扩展（如拆分索引），所有文件均按已跟踪处理".to_string());
+    }
+    if loose_object_count >= LOOSE_OBJECT_GC_THRESHOLD {
+        suggestions.push(format!(
+            "存在 {} 个松散对象（{}），运行 git gc 可将其打包压缩",
+            loose_object_count,
+            format_bytes(loose_object_size)
+        ));
+    }
+    if ignored_size > 0 {
+        suggestions.push(format!(
+            "被 .gitignore 忽略的生成文件共 {}，可重新构建生成",
+            format_bytes(ignored_size)
+        ));
+    }
+    if !large_untracked_files.is_empty() {
+        suggestions.push(format!(
+            "发现 {} 个未跟踪的大文件，请确认是否需要保留",
+            large_untracked_files.len()
+        ));
+    }
+
+    GitRepoReport {
+        root: repo.root.to_string_lossy().to_string(),
+        git_dir: repo.git_dir.to_string_lossy().to_string(),
+        git_dir_size: dir_size(&repo.git_dir),
+        pack_count,
+        pack_size,
+        loose_object_count,
+        loose_object_size,
+        tracked_files: repo.tracked_count() as u64,
+        untracked_size,
+        large_untracked_files,
+        ignored_size,
+        ignored_paths,
+        suggestions,
+    }
+}
+
+fn dir_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
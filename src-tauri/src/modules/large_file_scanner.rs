@@ -5,7 +5,7 @@
 //! - 实时进度：通过 Tauri 事件发送进度更新
 //! - 暂停支持：支持暂停/恢复/取消操作
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
@@ -14,6 +14,7 @@ use rayon::prelude::*;
 use tauri::{AppHandle, Emitter};
 
 use crate::models::{LargeFile, LargeFileAnalysisResult, ScanStatus};
+use crate::modules::git_repo::GitRepoCache;
 use crate::modules::scanner_framework::{
     ControlAction, FileWalker, FilterOptions, ScanContext, ScanManager,
     ScanProgress as ScanProgressTrait, StandardFileFilter,
@@ -163,6 +164,7 @@ async fn perform_scan(
         include_hidden: config.include_hidden,
         include_system: config.include_system,
         exclude_paths: config.exclude_paths.clone(),
+        respect_ignore_files: true,
     };
 
     let handle = tokio::task::spawn_blocking(move || {
@@ -252,6 +254,8 @@ async fn perform_scan(
                 }
             }
         });
+
+        filter.ignored_paths()
     });
 
     loop {
@@ -281,11 +285,16 @@ async fn perform_scan(
         }
     }
 
-    handle.await.ok();
+    let ignored_paths = handle.await.unwrap_or_default();
 
     let mut final_files = large_files.write().await;
     final_files.par_sort_by(|a, b| b.size.cmp(&a.size));
 
+    let git_repos = GitRepoCache::new();
+    for file in final_files.iter_mut() {
+        file.git_status = git_repos.status(Path::new(&file.path));
+    }
+
     let total_size: u64 = final_files.iter().map(|f| f.size).sum();
     let found_count = final_files.len() as u64;
     let duration_ms = start_instant.elapsed().as_millis() as u64;
@@ -297,6 +306,7 @@ async fn perform_scan(
         files: final_files.clone(),
         threshold: config.min_size_bytes,
         duration_ms,
+        ignored_paths,
     };
 
     let _ = ctx.app.emit(EVENT_LARGE_FILE_COMPLETE, &result);
@@ -341,5 +351,6 @@ fn create_large_file(path: &std::path::Path, metadata: &std::fs::Metadata) -> Op
         created_time,
         extension,
         file_type: String::new(),
+        git_status: None,
     })
 }
@@ -9,6 +9,13 @@ pub mod cancellable_file_classifier;
 pub mod driver_manager;
 pub mod software_residue;
 pub mod scanner_framework;
+pub mod cleanup_planner;
+pub mod smart_clean;
+pub mod build_artifact;
+pub mod package_cache;
+pub mod git_repo;
+pub mod risk_score;
+pub mod free_space_wipe;
 
 pub use system_info::*;
 pub use disk_scan::*;
@@ -18,13 +25,17 @@ pub use settings::*;
 // large_file_scanner 的函数通过 large_file_scanner:: 前缀访问，避免命名冲突
 pub use cancellable_file_classifier::*;
 pub use driver_manager::*;
+pub use cleanup_planner::*;
+pub use smart_clean::*;
 
 pub use app_cache::AppCacheScanOptions;
 pub use app_cache::AppCacheScanProgress;
 pub use app_cache::AppCacheScanResult;
 pub use app_cache::AppCacheFile;
 pub use app_cache::CleanCategory;
-pub use app_cache::AppType;
+pub use app_cache::AppDefinition;
+pub use app_cache::AppDefinitionList;
+pub use app_cache::AppDefinitionRegistry;
 pub use app_cache::start_app_cache_scan;
 pub use app_cache::get_app_cache_progress;
 pub use app_cache::get_app_cache_result;
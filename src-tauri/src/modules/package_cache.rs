@@ -0,0 +1,1215 @@
+//! 包管理器缓存分析
+//!
+//! 按各工具的缓存布局（cargo、npm、pip、Maven、Gradle、Go、conda）统计每个包、每个版本占用的空间，
+//! 并与磁盘上项目的锁文件交叉比对，找出没有项目引用且不在最近 N 个版本之内的旧版本。
+//! Maven 与 conda 没有锁文件，分别以 `pom.xml` 与 `environment.yml` 中写明的版本作为引用。
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, UNIX_EPOCH};
+use walkdir::WalkDir;
+
+use crate::utils::path::SystemPaths;
+
+/// 查找锁文件时不进入的目录
+const LOCKFILE_SKIP_DIRS: &[&str] = &[
+    ".git",
+    "node_modules",
+    "target",
+    ".gradle",
+    "build",
+    "__pycache__",
+    ".venv",
+    "venv",
+];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum PackageManager {
+    Cargo,
+    Npm,
+    Pip,
+    Maven,
+    Gradle,
+    Go,
+    Conda,
+}
+
+impl PackageManager {
+    pub fn all() -> [PackageManager; 7] {
+        [
+            Self::Cargo,
+            Self::Npm,
+            Self::Pip,
+            Self::Maven,
+            Self::Gradle,
+            Self::Go,
+            Self::Conda,
+        ]
+    }
+
+    pub fn display_name(&self) -> &str {
+        match self {
+            Self::Cargo => "Cargo",
+            Self::Npm => "npm",
+            Self::Pip => "pip",
+            Self::Maven => "Maven",
+            Self::Gradle => "Gradle",
+            Self::Go => "Go 模块",
+            Self::Conda => "conda",
+        }
+    }
+
+    /// 工具自带的清理命令，作为手动清理的参考
+    pub fn clean_command(&self) -> &str {
+        match self {
+            Self::Cargo => "cargo cache --autoclean",
+            Self::Npm => "npm cache clean --force",
+            Self::Pip => "pip cache purge",
+            Self::Maven => "mvn dependency:purge-local-repository",
+            Self::Gradle => "删除 ~/.gradle/caches 后重新构建",
+            Self::Go => "go clean -modcache",
+            Self::Conda => "conda clean --all",
+        }
+    }
+
+    /// 默认的缓存根目录
+    pub fn default_cache_roots(&self) -> Vec<PathBuf> {
+        let home = SystemPaths::home_dir();
+        let env_path = |name: &str| std::env::var_os(name).map(PathBuf::from);
+
+        let roots: Vec<PathBuf> = match self {
+            Self::Cargo => env_path("CARGO_HOME")
+                .or_else(|| home.as_ref().map(|h| h.join(".cargo")))
+                .into_iter()
+                .collect(),
+            Self::Npm => {
+                let base = env_path("npm_config_cache").or_else(|| {
+                    if cfg!(windows) {
+                        dirs::data_local_dir().map(|d| d.join("npm-cache"))
+                    } else {
+                        home.as_ref().map(|h| h.join(".npm"))
+                    }
+                });
+                base.map(|b| b.join("_cacache")).into_iter().collect()
+            }
+            Self::Pip => env_path("PIP_CACHE_DIR")
+                .or_else(|| dirs::cache_dir().map(|d| d.join("pip")))
+                .into_iter()
+                .collect(),
+            Self::Maven => home
+                .as_ref()
+                .map(|h| h.join(".m2").join("repository"))
+                .into_iter()
+                .collect(),
+            Self::Gradle => env_path("GRADLE_USER_HOME")
+                .or_else(|| home.as_ref().map(|h| h.join(".gradle")))
+                .map(|g| g.join("caches"))
+                .into_iter()
+                .collect(),
+            Self::Go => env_path("GOMODCACHE")
+                .or_else(|| {
+                    std::env::var_os("GOPATH")
+                        .and_then(|p| std::env::split_paths(&p).next())
+                        .map(|p| p.join("pkg").join("mod"))
+                })
+                .or_else(|| home.as_ref().map(|h| h.join("go").join("pkg").join("mod")))
+                .into_iter()
+                .collect(),
+            Self::Conda => {
+                let mut roots: Vec<PathBuf> = std::env::var_os("CONDA_PKGS_DIRS")
+                    .map(|p| std::env::split_paths(&p).collect())
+                    .unwrap_or_default();
+                if let Some(home) = &home {
+                    for dist in [
+                        "miniconda3",
+                        "anaconda3",
+                        "miniforge3",
+                        "mambaforge",
+                        ".conda",
+                    ] {
+                        roots.push(home.join(dist).join("pkgs"));
+                    }
+                }
+                roots
+            }
+        };
+
+        roots.into_iter().filter(|r| r.is_dir()).collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageCacheRoot {
+    pub manager: PackageManager,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageCacheOptions {
+    /// 要分析的包管理器，留空表示全部
+    #[serde(default)]
+    pub managers: Vec<PackageManager>,
+    /// 用于查找锁文件的项目目录，留空时使用用户主目录
+    #[serde(default)]
+    pub project_paths: Vec<String>,
+    /// 每个包至少保留的最新版本数
+    #[serde(default = "default_keep_recent")]
+    pub keep_recent: usize,
+    /// 指定缓存目录，设置后替代该包管理器的默认目录
+    #[serde(default)]
+    pub cache_roots: Vec<PackageCacheRoot>,
+    #[serde(default = "default_lockfile_depth")]
+    pub lockfile_search_depth: usize,
+}
+
+fn default_keep_recent() -> usize {
+    2
+}
+
+fn default_lockfile_depth() -> usize {
+    6
+}
+
+impl Default for PackageCacheOptions {
+    fn default() -> Self {
+        Self {
+            managers: vec![],
+            project_paths: vec![],
+            keep_recent: default_keep_recent(),
+            cache_roots: vec![],
+            lockfile_search_depth: default_lockfile_depth(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageVersionEntry {
+    pub name: String,
+    pub version: String,
+    pub paths: Vec<String>,
+    pub size: u64,
+    pub last_modified: i64,
+    pub referenced: bool,
+    pub removable: bool,
+    pub reason: String,
+    /// cargo 的 git 依赖检出，版本为提交哈希的前缀
+    #[serde(default)]
+    pub git_checkout: bool,
+}
+
+/// 无法按包和版本拆分的缓存内容，例如索引和 HTTP 缓存
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpaqueCacheEntry {
+    pub path: String,
+    pub size: u64,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageManagerCache {
+    pub manager: PackageManager,
+    pub cache_roots: Vec<String>,
+    pub entries: Vec<PackageVersionEntry>,
+    pub other: Vec<OpaqueCacheEntry>,
+    pub package_count: u64,
+    pub total_size: u64,
+    pub removable_size: u64,
+    pub lockfiles_found: u64,
+    pub clean_command: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageCacheAnalysisResult {
+    pub managers: Vec<PackageManagerCache>,
+    pub total_size: u64,
+    pub removable_size: u64,
+    pub lockfiles_scanned: u64,
+    pub duration_ms: u64,
+}
+
+impl PackageCacheAnalysisResult {
+    pub fn removable_paths(&self) -> Vec<PathBuf> {
+        self.managers
+            .iter()
+            .flat_map(|m| m.entries.iter())
+            .filter(|e| e.removable)
+            .flat_map(|e| e.paths.iter().map(PathBuf::from))
+            .collect()
+    }
+}
+
+/// 锁文件中引用的包版本
+#[derive(Debug, Clone, Default)]
+pub struct ReferencedVersions {
+    versions: HashMap<PackageManager, HashSet<(String, String)>>,
+    /// Cargo.lock 中 git 依赖锁定的完整提交哈希
+    git_revs: HashSet<String>,
+    lockfiles: HashMap<PackageManager, u64>,
+}
+
+impl ReferencedVersions {
+    pub fn insert(&mut self, manager: PackageManager, name: &str, version: &str) {
+        self.versions
+            .entry(manager)
+            .or_default()
+            .insert((normalize_name(manager, name), version.to_string()));
+    }
+
+    pub fn contains(&self, manager: PackageManager, name: &str, version: &str) -> bool {
+        self.versions
+            .get(&manager)
+            .map(|set| set.contains(&(normalize_name(manager, name), version.to_string())))
+            .unwrap_or(false)
+    }
+
+    pub fn insert_git_rev(&mut self, rev: &str) {
+        self.git_revs.insert(rev.to_lowercase());
+    }
+
+    /// 检出目录以提交哈希的前缀命名
+    pub fn contains_git_rev(&self, short_rev: &str) -> bool {
+        let short_rev = short_rev.to_lowercase();
+        short_rev.len() >= 7 && self.git_revs.iter().any(|rev| rev.starts_with(&short_rev))
+    }
+
+    pub fn lockfile_count(&self, manager: PackageManager) -> u64 {
+        self.lockfiles.get(&manager).copied().unwrap_or(0)
+    }
+
+    fn add_lockfile(&mut self, manager: PackageManager) {
+        *self.lockfiles.entry(manager).or_default() += 1;
+    }
+}
+
+pub struct PackageCacheAnalyzer {
+    options: PackageCacheOptions,
+}
+
+impl PackageCacheAnalyzer {
+    pub fn new() -> Self {
+        Self::with_options(PackageCacheOptions::default())
+    }
+
+    pub fn with_options(options: PackageCacheOptions) -> Self {
+        Self { options }
+    }
+
+    pub fn analyze(&self) -> PackageCacheAnalysisResult {
+        let start_instant = Instant::now();
+
+        let project_paths: Vec<PathBuf> = if self.options.project_paths.is_empty() {
+            SystemPaths::home_dir().into_iter().collect()
+        } else {
+            self.options
+                .project_paths
+                .iter()
+                .map(PathBuf::from)
+                .collect()
+        };
+        let referenced = scan_lockfiles(&project_paths, self.options.lockfile_search_depth);
+
+        let managers: Vec<PackageManager> = if self.options.managers.is_empty() {
+            PackageManager::all().to_vec()
+        } else {
+            self.options.managers.clone()
+        };
+
+        let mut results = Vec::new();
+        for manager in managers {
+            let roots = self.cache_roots(manager);
+            if roots.is_empty() {
+                continue;
+            }
+            results.push(self.analyze_manager(manager, &roots, &referenced));
+        }
+
+        PackageCacheAnalysisResult {
+            total_size: results.iter().map(|m| m.total_size).sum(),
+            removable_size: results.iter().map(|m| m.removable_size).sum(),
+            lockfiles_scanned: referenced.lockfiles.values().sum(),
+            managers: results,
+            duration_ms: start_instant.elapsed().as_millis() as u64,
+        }
+    }
+
+    fn cache_roots(&self, manager: PackageManager) -> Vec<PathBuf> {
+        let configured: Vec<PathBuf> = self
+            .options
+            .cache_roots
+            .iter()
+            .filter(|r| r.manager == manager)
+            .map(|r| PathBuf::from(&r.path))
+            .collect();
+
+        if configured.is_empty() {
+            manager.default_cache_roots()
+        } else {
+            configured.into_iter().filter(|r| r.is_dir()).collect()
+        }
+    }
+
+    pub fn analyze_manager(
+        &self,
+        manager: PackageManager,
+        roots: &[PathBuf],
+        referenced: &ReferencedVersions,
+    ) -> PackageManagerCache {
+        let mut collector = CacheCollector::default();
+        for root in roots {
+            match manager {
+                PackageManager::Cargo => collect_cargo(root, &mut collector),
+                PackageManager::Npm => collect_npm(root, &mut collector),
+                PackageManager::Pip => collect_pip(root, &mut collector),
+                PackageManager::Maven => collect_maven(root, &mut collector),
+                PackageManager::Gradle => collect_gradle(root, &mut collector),
+                PackageManager::Go => collect_go(root, &mut collector),
+                PackageManager::Conda => collect_conda(root, &mut collector),
+            }
+        }
+
+        let lockfiles_found = referenced.lockfile_count(manager);
+        let mut entries = collector.into_entries();
+        mark_removable(manager, &mut entries, referenced, self.options.keep_recent);
+
+        let package_count = entries
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect::<HashSet<_>>()
+            .len() as u64;
+        let total_size: u64 = entries.iter().map(|e| e.size).sum();
+        let removable_size = entries.iter().filter(|e| e.removable).map(|e| e.size).sum();
+
+        let other = collect_other(manager, roots);
+        let other_size: u64 = other.iter().map(|o| o.size).sum();
+
+        PackageManagerCache {
+            manager,
+            cache_roots: roots
+                .iter()
+                .map(|r| r.to_string_lossy().to_string())
+                .collect(),
+            entries,
+            other,
+            package_count,
+            total_size: total_size + other_size,
+            removable_size,
+            lockfiles_found,
+            clean_command: manager.clean_command().to_string(),
+        }
+    }
+}
+
+impl Default for PackageCacheAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 按包名和版本聚合缓存路径
+#[derive(Default)]
+struct CacheCollector {
+    entries: HashMap<(String, String), PackageVersionEntry>,
+}
+
+impl CacheCollector {
+    fn add(&mut self, name: &str, version: &str, path: &Path, size: u64) -> &mut PackageVersionEntry {
+        let modified = modified_secs(path);
+        let entry = self
+            .entries
+            .entry((name.to_string(), version.to_string()))
+            .or_insert_with(|| PackageVersionEntry {
+                name: name.to_string(),
+                version: version.to_string(),
+                paths: Vec::new(),
+                size: 0,
+                last_modified: 0,
+                referenced: false,
+                removable: false,
+                reason: String::new(),
+                git_checkout: false,
+            });
+        entry.paths.push(path.to_string_lossy().to_string());
+        entry.size += size;
+        entry.last_modified = entry.last_modified.max(modified);
+        entry
+    }
+
+    fn add_path(&mut self, name: &str, version: &str, path: &Path) -> &mut PackageVersionEntry {
+        let size = path_size(path);
+        self.add(name, version, path, size)
+    }
+
+    fn into_entries(self) -> Vec<PackageVersionEntry> {
+        let mut entries: Vec<PackageVersionEntry> = self.entries.into_values().collect();
+        entries.sort_by(|a, b| {
+            a.name
+                .cmp(&b.name)
+                .then(compare_versions(&b.version, &a.version))
+        });
+        entries
+    }
+}
+
+/// 标记可删除的版本：未被锁文件引用，且不在每个包最新的 `keep_recent` 个版本之内
+pub fn mark_removable(
+    manager: PackageManager,
+    entries: &mut [PackageVersionEntry],
+    referenced: &ReferencedVersions,
+    keep_recent: usize,
+) {
+    let has_lockfiles = referenced.lockfile_count(manager) > 0;
+
+    let mut by_name: HashMap<(String, bool), Vec<usize>> = HashMap::new();
+    for (index, entry) in entries.iter().enumerate() {
+        by_name
+            .entry((normalize_name(manager, &entry.name), entry.git_checkout))
+            .or_default()
+            .push(index);
+    }
+
+    for ((_, git_checkout), indices) in by_name.iter_mut() {
+        // 提交哈希没有先后顺序，git 检出按修改时间排列
+        if *git_checkout {
+            indices.sort_by_key(|i| std::cmp::Reverse(entries[*i].last_modified));
+        } else {
+            indices.sort_by(|a, b| compare_versions(&entries[*b].version, &entries[*a].version));
+        }
+
+        for (rank, index) in indices.iter().enumerate() {
+            let entry = &mut entries[*index];
+            entry.referenced = if entry.git_checkout {
+                referenced.contains_git_rev(&entry.version)
+            } else {
+                referenced.contains(manager, &entry.name, &entry.version)
+            };
+
+            if entry.referenced {
+                entry.removable = false;
+                entry.reason = "被项目锁文件引用".to_string();
+            } else if rank < keep_recent {
+                entry.removable = false;
+                entry.reason = format!("属于最新的 {} 个版本", keep_recent);
+            } else {
+                entry.removable = true;
+                entry.reason = if has_lockfiles {
+                    "没有项目锁文件引用该版本".to_string()
+                } else {
+                    format!("未找到锁文件，仅保留最新的 {} 个版本", keep_recent)
+                };
+            }
+        }
+    }
+}
+
+fn collect_cargo(root: &Path, collector: &mut CacheCollector) {
+    let registry = root.join("registry");
+
+    for entry in read_dir_entries(&registry.join("cache")) {
+        for file in read_dir_entries(&entry) {
+            let file_name = file_name_of(&file);
+            if let Some(stem) = file_name.strip_suffix(".crate") {
+                if let Some((name, version)) = split_name_version(stem) {
+                    collector.add_path(name, version, &file);
+                }
+            }
+        }
+    }
+
+    for entry in read_dir_entries(&registry.join("src")) {
+        for dir in read_dir_entries(&entry) {
+            if let Some((name, version)) = split_name_version(&file_name_of(&dir)) {
+                collector.add_path(name, version, &dir);
+            }
+        }
+    }
+
+    for repo in read_dir_entries(&root.join("git").join("checkouts")) {
+        let repo_name = file_name_of(&repo);
+        let name = repo_name
+            .rsplit_once('-')
+            .map(|(n, _)| n.to_string())
+            .unwrap_or(repo_name);
+        for rev in read_dir_entries(&repo) {
+            collector.add_path(&name, &file_name_of(&rev), &rev).git_checkout = true;
+        }
+    }
+}
+
+fn collect_npm(root: &Path, collector: &mut CacheCollector) {
+    let content_root = root.join("content-v2");
+    let mut seen_content = HashSet::new();
+
+    for bucket in WalkDir::new(root.join("index-v5"))
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+    {
+        let content = match fs::read_to_string(bucket.path()) {
+            Ok(c) => c,
+            Err(_) => continue,
+        };
+
+        for line in content.lines() {
+            let json = match line.split_once('\t') {
+                Some((_, json)) => json,
+                None => continue,
+            };
+            let value: serde_json::Value = match serde_json::from_str(json) {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
+
+            let key = value.get("key").and_then(|k| k.as_str()).unwrap_or("");
+            let integrity = value
+                .get("integrity")
+                .and_then(|i| i.as_str())
+                .unwrap_or("");
+            let (name, version) = match parse_npm_tarball_key(key) {
+                Some(parsed) => parsed,
+                None => continue,
+            };
+            let content_path = match integrity_to_content_path(&content_root, integrity) {
+                Some(p) => p,
+                None => continue,
+            };
+            if !seen_content.insert(content_path.clone()) || !content_path.is_file() {
+                continue;
+            }
+
+            let size = value
+                .get("size")
+                .and_then(|s| s.as_u64())
+                .unwrap_or_else(|| path_size(&content_path));
+            collector.add(&name, &version, &content_path, size);
+        }
+    }
+}
+
+fn collect_pip(root: &Path, collector: &mut CacheCollector) {
+    for entry in WalkDir::new(root.join("wheels"))
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+    {
+        let file_name = file_name_of(entry.path());
+        if let Some(stem) = file_name.strip_suffix(".whl") {
+            let mut parts = stem.splitn(3, '-');
+            if let (Some(name), Some(version)) = (parts.next(), parts.next()) {
+                collector.add_path(name, version, entry.path());
+            }
+        }
+    }
+}
+
+fn collect_maven(root: &Path, collector: &mut CacheCollector) {
+    let walker = WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| e.file_type().is_dir());
+
+    for entry in walker.filter_map(|e| e.ok()) {
+        let version_dir = entry.path();
+        let version = file_name_of(version_dir);
+        let artifact_dir = match version_dir.parent() {
+            Some(p) => p,
+            None => continue,
+        };
+        let artifact = file_name_of(artifact_dir);
+        let pom = version_dir.join(format!("{}-{}.pom", artifact, version));
+        if !pom.is_file() {
+            continue;
+        }
+
+        let group = artifact_dir
+            .parent()
+            .and_then(|g| g.strip_prefix(root).ok())
+            .map(|g| {
+                g.components()
+                    .map(|c| c.as_os_str().to_string_lossy().to_string())
+                    .collect::<Vec<_>>()
+                    .join(".")
+            })
+            .unwrap_or_default();
+
+        collector.add_path(&format!("{}:{}", group, artifact), &version, version_dir);
+    }
+}
+
+fn collect_gradle(root: &Path, collector: &mut CacheCollector) {
+    let files_root = root.join("modules-2").join("files-2.1");
+
+    for group in read_dir_entries(&files_root) {
+        for artifact in read_dir_entries(&group) {
+            let name = format!("{}:{}", file_name_of(&group), file_name_of(&artifact));
+            for version in read_dir_entries(&artifact) {
+                collector.add_path(&name, &file_name_of(&version), &version);
+            }
+        }
+    }
+}
+
+fn collect_go(root: &Path, collector: &mut CacheCollector) {
+    let walker = WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| e.file_type().is_dir() && !(e.depth() == 1 && e.file_name() == "cache"));
+
+    let mut walker = walker;
+    while let Some(Ok(entry)) = walker.next() {
+        let dir_name = file_name_of(entry.path());
+        let (_, version) = match dir_name.split_once('@') {
+            Some(parts) => parts,
+            None => continue,
+        };
+        walker.skip_current_dir();
+
+        let relative = match entry.path().strip_prefix(root) {
+            Ok(r) => r.to_string_lossy().replace('\\', "/"),
+            Err(_) => continue,
+        };
+        let module = relative
+            .split_once('@')
+            .map(|(m, _)| m)
+            .unwrap_or(&relative);
+        collector.add_path(&unescape_go_module(module), version, entry.path());
+    }
+}
+
+fn collect_conda(root: &Path, collector: &mut CacheCollector) {
+    for entry in read_dir_entries(root) {
+        let file_name = file_name_of(&entry);
+        let base = file_name
+            .strip_suffix(".tar.bz2")
+            .or_else(|| file_name.strip_suffix(".conda"))
+            .unwrap_or(&file_name);
+        if entry.is_dir() && !entry.join("info").is_dir() {
+            continue;
+        }
+
+        let mut parts = base.rsplitn(3, '-');
+        let (_build, version, name) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(b), Some(v), Some(n)) => (b, v, n),
+            _ => continue,
+        };
+        collector.add_path(name, version, &entry);
+    }
+}
+
+/// 各工具缓存中无法按版本拆分但可由工具重新下载的部分
+fn collect_other(manager: PackageManager, roots: &[PathBuf]) -> Vec<OpaqueCacheEntry> {
+    let candidates: Vec<(PathBuf, &str)> = roots
+        .iter()
+        .flat_map(|root| match manager {
+            PackageManager::Cargo => vec![
+                (root.join("registry").join("index"), "注册表索引"),
+                (root.join("git").join("db"), "Git 依赖仓库"),
+            ],
+            PackageManager::Npm => vec![(root.join("index-v5"), "缓存索引")],
+            PackageManager::Pip => vec![
+                (root.join("http"), "HTTP 下载缓存"),
+                (root.join("http-v2"), "HTTP 下载缓存"),
+            ],
+            PackageManager::Gradle => read_dir_entries(root)
+                .into_iter()
+                .filter(|p| file_name_of(p) != "modules-2")
+                .map(|p| (p, "Gradle 构建缓存"))
+                .collect(),
+            PackageManager::Go => vec![(root.join("cache").join("download"), "模块下载缓存")],
+            PackageManager::Maven | PackageManager::Conda => vec![],
+        })
+        .collect();
+
+    candidates
+        .into_iter()
+        .filter(|(path, _)| path.exists())
+        .map(|(path, description)| OpaqueCacheEntry {
+            size: path_size(&path),
+            path: path.to_string_lossy().to_string(),
+            description: description.to_string(),
+        })
+        .collect()
+}
+
+/// 在项目目录中查找锁文件并提取其中引用的包版本
+pub fn scan_lockfiles(paths: &[PathBuf], max_depth: usize) -> ReferencedVersions {
+    let mut referenced = ReferencedVersions::default();
+
+    for root in paths {
+        let walker = WalkDir::new(root)
+            .follow_links(false)
+            .max_depth(max_depth)
+            .into_iter()
+            .filter_entry(|e| {
+                e.depth() == 0
+                    || !(e.file_type().is_dir()
+                        && LOCKFILE_SKIP_DIRS.contains(&e.file_name().to_string_lossy().as_ref()))
+            });
+
+        for entry in walker
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+        {
+            let file_name = entry.file_name().to_string_lossy();
+            let manager = match file_name.as_ref() {
+                "Cargo.lock" => PackageManager::Cargo,
+                "package-lock.json" => PackageManager::Npm,
+                "poetry.lock" | "requirements.txt" => PackageManager::Pip,
+                "gradle.lockfile" => PackageManager::Gradle,
+                "go.sum" => PackageManager::Go,
+                "pom.xml" => PackageManager::Maven,
+                "environment.yml" | "environment.yaml" => PackageManager::Conda,
+                _ => continue,
+            };
+
+            let content = match fs::read_to_string(entry.path()) {
+                Ok(c) => c,
+                Err(_) => continue,
+            };
+
+            referenced.add_lockfile(manager);
+            match file_name.as_ref() {
+                "Cargo.lock" | "poetry.lock" => parse_toml_lock(manager, &content, &mut referenced),
+                "package-lock.json" => parse_package_lock(&content, &mut referenced),
+                "requirements.txt" => parse_requirements(&content, &mut referenced),
+                "gradle.lockfile" => parse_gradle_lockfile(&content, &mut referenced),
+                "go.sum" => parse_go_sum(&content, &mut referenced),
+                "pom.xml" => parse_pom(&content, &mut referenced),
+                "environment.yml" | "environment.yaml" => {
+                    parse_conda_environment(&content, &mut referenced)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    referenced
+}
+
+/// Cargo.lock 与 poetry.lock 都是 `[[package]]` 下的 name/version 键值对
+///
+/// Cargo.lock 中 git 依赖的 source 形如 `git+<url>?branch=main#<完整提交哈希>`，
+/// 检出目录按提交哈希而不是包名命名，因此单独记录提交哈希。
+fn parse_toml_lock(manager: PackageManager, content: &str, referenced: &mut ReferencedVersions) {
+    let mut name: Option<String> = None;
+
+    for line in content.lines().map(str::trim) {
+        if line == "[[package]]" {
+            name = None;
+        } else if let Some(value) = toml_string_value(line, "name") {
+            name = Some(value);
+        } else if let Some(value) = toml_string_value(line, "version") {
+            if let Some(n) = name.take() {
+                referenced.insert(manager, &n, &value);
+            }
+        } else if let Some(value) = toml_string_value(line, "source") {
+            if manager != PackageManager::Cargo || !value.starts_with("git+") {
+                continue;
+            }
+            if let Some((_, rev)) = value.rsplit_once('#') {
+                referenced.insert_git_rev(rev);
+            }
+        }
+    }
+}
+
+fn toml_string_value(line: &str, key: &str) -> Option<String> {
+    let rest = line
+        .strip_prefix(key)?
+        .trim_start()
+        .strip_prefix('=')?
+        .trim();
+    Some(rest.trim_matches('"').to_string())
+}
+
+fn parse_package_lock(content: &str, referenced: &mut ReferencedVersions) {
+    let json: serde_json::Value = match serde_json::from_str(content) {
+        Ok(v) => v,
+        Err(_) => return,
+    };
+
+    if let Some(packages) = json.get("packages").and_then(|p| p.as_object()) {
+        for (path, info) in packages {
+            let name = match path.rsplit_once("node_modules/") {
+                Some((_, name)) => name,
+                None => continue,
+            };
+            if let Some(version) = info.get("version").and_then(|v| v.as_str()) {
+                referenced.insert(PackageManager::Npm, name, version);
+            }
+        }
+    }
+
+    // lockfileVersion 1 使用嵌套的 dependencies
+    fn walk_dependencies(deps: &serde_json::Value, referenced: &mut ReferencedVersions) {
+        if let Some(deps) = deps.as_object() {
+            for (name, info) in deps {
+                if let Some(version) = info.get("version").and_then(|v| v.as_str()) {
+                    referenced.insert(PackageManager::Npm, name, version);
+                }
+                if let Some(nested) = info.get("dependencies") {
+                    walk_dependencies(nested, referenced);
+                }
+            }
+        }
+    }
+    if let Some(deps) = json.get("dependencies") {
+        walk_dependencies(deps, referenced);
+    }
+}
+
+fn parse_requirements(content: &str, referenced: &mut ReferencedVersions) {
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if let Some((name, version)) = line.split_once("==") {
+            let name = name.split('[').next().unwrap_or(name).trim();
+            let version = version.split(';').next().unwrap_or(version).trim();
+            referenced.insert(PackageManager::Pip, name, version);
+        }
+    }
+}
+
+fn parse_gradle_lockfile(content: &str, referenced: &mut ReferencedVersions) {
+    for line in content.lines() {
+        let coordinate = line.split('=').next().unwrap_or("").trim();
+        let parts: Vec<&str> = coordinate.split(':').collect();
+        if parts.len() == 3 {
+            referenced.insert(
+                PackageManager::Gradle,
+                &format!("{}:{}", parts[0], parts[1]),
+                parts[2],
+            );
+        }
+    }
+}
+
+fn parse_go_sum(content: &str, referenced: &mut ReferencedVersions) {
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        if let (Some(module), Some(version)) = (fields.next(), fields.next()) {
+            let version = version.trim_end_matches("/go.mod");
+            referenced.insert(PackageManager::Go, module, version);
+        }
+    }
+}
+
+/// Maven 没有锁文件，使用 pom.xml 中写明版本的依赖，`${...}` 只解析同一文件
+/// `<properties>` 中定义的属性
+fn parse_pom(content: &str, referenced: &mut ReferencedVersions) {
+    let mut properties: HashMap<&str, &str> = HashMap::new();
+    for block in xml_blocks(content, "properties") {
+        let mut rest = block;
+        while let Some((name, value, next)) = next_xml_element(rest) {
+            properties.insert(name, value.trim());
+            rest = next;
+        }
+    }
+
+    for block in xml_blocks(content, "dependency") {
+        let (Some(group), Some(artifact), Some(version)) = (
+            xml_text(block, "groupId"),
+            xml_text(block, "artifactId"),
+            xml_text(block, "version"),
+        ) else {
+            continue;
+        };
+        let version = match version.strip_prefix("${").and_then(|v| v.strip_suffix('}')) {
+            Some(property) => match properties.get(property) {
+                Some(value) => value,
+                None => continue,
+            },
+            None => version,
+        };
+        referenced.insert(
+            PackageManager::Maven,
+            &format!("{}:{}", group, artifact),
+            version,
+        );
+    }
+}
+
+/// 所有 `<tag>...</tag>` 的内容
+fn xml_blocks<'a>(content: &'a str, tag: &str) -> impl Iterator<Item = &'a str> + 'a {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let mut rest = content;
+    std::iter::from_fn(move || {
+        let start = rest.find(&open)? + open.len();
+        let length = rest[start..].find(&close)?;
+        let block = &rest[start..start + length];
+        rest = &rest[start + length + close.len()..];
+        Some(block)
+    })
+}
+
+fn xml_text<'a>(block: &'a str, tag: &str) -> Option<&'a str> {
+    xml_blocks(block, tag)
+        .next()
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+}
+
+/// 读取下一个元素，返回元素名、内容与其后的剩余部分
+fn next_xml_element(content: &str) -> Option<(&str, &str, &str)> {
+    let mut rest = content;
+    loop {
+        let after = &rest[rest.find('<')? + 1..];
+        let end = after.find('>')?;
+        let name = &after[..end];
+        let body = &after[end + 1..];
+        if name.starts_with(['/', '!', '?']) || name.ends_with('/') {
+            rest = body;
+            continue;
+        }
+        let close = format!("</{}>", name);
+        let length = body.find(&close)?;
+        return Some((name, &body[..length], &body[length + close.len()..]));
+    }
+}
+
+/// conda 环境文件中固定了版本的依赖，形如 `- numpy=1.26.4=py311h...` 或
+/// `- numpy==1.26.4`，pip 子列表中的依赖不属于 conda
+fn parse_conda_environment(content: &str, referenced: &mut ReferencedVersions) {
+    let mut in_dependencies = false;
+    let mut pip_indent: Option<usize> = None;
+
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or("");
+        if line.trim().is_empty() {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        if indent == 0 {
+            in_dependencies = line.trim_end() == "dependencies:";
+            pip_indent = None;
+            continue;
+        }
+        if !in_dependencies {
+            continue;
+        }
+        if let Some(pip) = pip_indent {
+            if indent > pip {
+                continue;
+            }
+            pip_indent = None;
+        }
+
+        let Some(spec) = line.trim().strip_prefix('-').map(str::trim) else {
+            continue;
+        };
+        if spec == "pip:" {
+            pip_indent = Some(indent);
+            continue;
+        }
+        // 去掉频道前缀，如 `conda-forge::numpy=1.26.4`
+        let spec = spec.rsplit_once("::").map_or(spec, |(_, s)| s);
+        let (name, version) = match spec.split_once("==") {
+            Some((name, version)) => (name, version),
+            None => match spec.split_once('=') {
+                Some((name, rest)) => (name, rest.split('=').next().unwrap_or(rest)),
+                None => continue,
+            },
+        };
+        let version = version.trim();
+        if !version.is_empty() && !version.contains(['*', '<', '>', ',']) {
+            referenced.insert(PackageManager::Conda, name.trim(), version);
+        }
+    }
+}
+
+/// 从 `name-1.2.3` 形式中拆出包名和版本
+pub fn split_name_version(value: &str) -> Option<(&str, &str)> {
+    value.match_indices('-').find_map(|(i, _)| {
+        let version = &value[i + 1..];
+        let mut parts = version.split('.');
+        let is_version = parts.next().map(is_number).unwrap_or(false)
+            && parts
+                .next()
+                .map(|p| {
+                    p.chars()
+                        .next()
+                        .map(|c| c.is_ascii_digit())
+                        .unwrap_or(false)
+                })
+                .unwrap_or(false);
+        if is_version && i > 0 {
+            Some((&value[..i], version))
+        } else {
+            None
+        }
+    })
+}
+
+fn is_number(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(|c| c.is_ascii_digit())
+}
+
+/// 比较版本号，数字段按数值比较，其余按字符串比较
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let split = |v: &str| -> Vec<String> {
+        v.trim_start_matches('v')
+            .split(['.', '-', '+', '_'])
+            .map(str::to_string)
+            .collect()
+    };
+    let (pa, pb) = (split(a), split(b));
+
+    for (x, y) in pa.iter().zip(pb.iter()) {
+        let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(nx), Ok(ny)) => nx.cmp(&ny),
+            (Ok(_), Err(_)) => Ordering::Greater,
+            (Err(_), Ok(_)) => Ordering::Less,
+            _ => x.cmp(y),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    // 前缀相同时，多出的非数字段视为预发布版本（2.0.0-beta < 2.0.0）
+    let is_prerelease =
+        |extra: Option<&String>| extra.map(|e| e.parse::<u64>().is_err()).unwrap_or(false);
+    match pa.len().cmp(&pb.len()) {
+        Ordering::Greater if is_prerelease(pa.get(pb.len())) => Ordering::Less,
+        Ordering::Less if is_prerelease(pb.get(pa.len())) => Ordering::Greater,
+        ordering => ordering,
+    }
+}
+
+/// 包名比较时的规范形式（pip 不区分大小写且 `-`、`_`、`.` 等价）
+fn normalize_name(manager: PackageManager, name: &str) -> String {
+    match manager {
+        PackageManager::Pip => name.to_lowercase().replace(['_', '.'], "-"),
+        _ => name.to_string(),
+    }
+}
+
+/// 解析 npm 缓存键中的 tarball 地址，得到包名和版本
+fn parse_npm_tarball_key(key: &str) -> Option<(String, String)> {
+    let url = key
+        .rsplit_once("request-cache:")
+        .map(|(_, u)| u)
+        .unwrap_or(key);
+    let (package_path, tarball) = url.split_once("/-/")?;
+    let tarball = tarball.strip_suffix(".tgz")?;
+
+    let name = package_path
+        .rsplit_once("://")
+        .map(|(_, rest)| rest)
+        .and_then(|rest| rest.split_once('/'))
+        .map(|(_, name)| name)?
+        .replace("%2f", "/")
+        .replace("%2F", "/");
+    let short_name = name.rsplit('/').next()?;
+    let version = tarball.strip_prefix(short_name)?.strip_prefix('-')?;
+
+    Some((name.clone(), version.to_string()))
+}
+
+/// 将 `sha512-<base64>` 形式的完整性校验值转换为 cacache 的内容文件路径
+fn integrity_to_content_path(content_root: &Path, integrity: &str) -> Option<PathBuf> {
+    let first = integrity.split_whitespace().next()?;
+    let (algorithm, encoded) = first.split_once('-')?;
+    let digest = decode_base64(encoded)?;
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    if hex.len() < 5 {
+        return None;
+    }
+
+    Some(
+        content_root
+            .join(algorithm)
+            .join(&hex[0..2])
+            .join(&hex[2..4])
+            .join(&hex[4..]),
+    )
+}
+
+fn decode_base64(input: &str) -> Option<Vec<u8>> {
+    let value = |c: u8| -> Option<u32> {
+        match c {
+            b'A'..=b'Z' => Some((c - b'A') as u32),
+            b'a'..=b'z' => Some((c - b'a' + 26) as u32),
+            b'0'..=b'9' => Some((c - b'0' + 52) as u32),
+            b'+' | b'-' => Some(62),
+            b'/' | b'_' => Some(63),
+            _ => None,
+        }
+    };
+
+    let bytes: Vec<u8> = input.bytes().filter(|b| *b != b'=').collect();
+    let mut output = Vec::with_capacity(bytes.len() * 3 / 4);
+    for chunk in bytes.chunks(4) {
+        let mut buffer = 0u32;
+        for (i, byte) in chunk.iter().enumerate() {
+            buffer |= value(*byte)? << (18 - 6 * i);
+        }
+        output.push((buffer >> 16) as u8);
+        if chunk.len() > 2 {
+            output.push((buffer >> 8) as u8);
+        }
+        if chunk.len() > 3 {
+            output.push(buffer as u8);
+        }
+    }
+
+    Some(output)
+}
+
+/// Go 模块缓存用 `!x` 表示大写字母
+fn unescape_go_module(module: &str) -> String {
+    let mut result = String::with_capacity(module.len());
+    let mut upper_next = false;
+    for c in module.chars() {
+        if c == '!' {
+            upper_next = true;
+        } else if upper_next {
+            result.extend(c.to_uppercase());
+            upper_next = false;
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+fn read_dir_entries(dir: &Path) -> Vec<PathBuf> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    entries.sort();
+    entries
+}
+
+fn file_name_of(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn path_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+fn modified_secs(path: &Path) -> i64 {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
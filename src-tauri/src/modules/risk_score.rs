@@ -0,0 +1,474 @@
+//! 可解释的风险评分
+//!
+//! 每个信号给出 -1 到 1 之间的取值，乘以可配置的权重后累加到基础分上，
+//! 得到 0-100 的风险分数。参与计算的每个信号都会记录为一条因素，
+//! 界面据此说明某个文件为什么被判定为低风险或高风险。
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::models::{
+    CleanupCandidate, GitPathStatus, RiskAssessment, RiskFactor, RiskLevel, RiskSignal,
+    RiskThresholds, RiskWeights, WhitelistSettings,
+};
+use crate::modules::cleaner::in_use::OpenFileIndex;
+use crate::modules::git_repo::GitRepoCache;
+use crate::utils::file_type::get_file_type;
+use crate::utils::path_match::{PathPrefix, SegmentPattern};
+
+const DAY: i64 = 86400;
+
+/// 路径所在位置的类别
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum LocationClass {
+    Documents,
+    Desktop,
+    Downloads,
+    Temp,
+    Cache,
+    Other,
+}
+
+impl LocationClass {
+    pub fn display_name(&self) -> &str {
+        match self {
+            Self::Documents => "文档目录",
+            Self::Desktop => "桌面",
+            Self::Downloads => "下载目录",
+            Self::Temp => "临时目录",
+            Self::Cache => "缓存目录",
+            Self::Other => "其他位置",
+        }
+    }
+
+    /// 位置信号的取值：用户保存资料的位置提高风险，临时与缓存目录降低风险
+    fn value(&self) -> f32 {
+        match self {
+            Self::Documents => 1.0,
+            Self::Desktop => 0.7,
+            Self::Downloads => -0.3,
+            Self::Temp => -1.0,
+            Self::Cache => -0.8,
+            Self::Other => 0.0,
+        }
+    }
+}
+
+/// 评分所需的文件信息，未知的信号留空即不参与计算
+#[derive(Debug, Clone, Default)]
+pub struct RiskInput {
+    pub path: PathBuf,
+    pub is_dir: bool,
+    /// 修改时间（秒），0 表示未知
+    pub modified_time: i64,
+    /// 访问时间（秒），0 表示未知
+    pub accessed_time: i64,
+    pub regenerable: bool,
+    pub in_use: Option<bool>,
+    pub git_status: Option<GitPathStatus>,
+    /// 分析器对该文件所属类别给出的风险等级
+    pub category_risk: Option<RiskLevel>,
+}
+
+impl RiskInput {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            ..Default::default()
+        }
+    }
+
+    /// 从文件系统读取类型与时间信息，不跟随符号链接
+    pub fn from_path(path: &Path) -> Self {
+        let mut input = Self::new(path);
+        if let Ok(metadata) = fs::symlink_metadata(path) {
+            input.is_dir = metadata.is_dir();
+            input.modified_time = secs(metadata.modified().ok());
+            input.accessed_time = secs(metadata.accessed().ok());
+        }
+        input
+    }
+
+    /// 占用索引为空时视为未检测，不产生占用信号
+    pub fn from_candidate(candidate: &CleanupCandidate, open_files: &OpenFileIndex) -> Self {
+        let mut input = Self::from_path(Path::new(&candidate.path));
+        if candidate.modified_time > 0 {
+            input.modified_time = candidate.modified_time;
+        }
+        input.regenerable = candidate.regenerable;
+        if !open_files.is_empty() {
+            input.in_use = Some(!open_files.holders_of(&input.path).is_empty());
+        }
+        input.category_risk = Some(candidate.risk_level.clone());
+        input
+    }
+}
+
+fn secs(time: Option<SystemTime>) -> i64 {
+    time.and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// 风险模型的可配置部分
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RiskModelConfig {
+    pub weights: RiskWeights,
+    pub thresholds: RiskThresholds,
+    /// 白名单路径，位于其中或与其相邻的文件风险更高
+    pub whitelist_paths: Vec<String>,
+}
+
+impl RiskModelConfig {
+    /// 由保存的白名单设置生成，白名单关闭时不包含任何路径
+    pub fn from_whitelist(whitelist: &WhitelistSettings) -> Self {
+        let whitelist_paths = if whitelist.enabled {
+            whitelist
+                .paths
+                .iter()
+                .filter(|p| p.enabled)
+                .map(|p| p.path.clone())
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Self {
+            whitelist_paths,
+            ..Default::default()
+        }
+    }
+
+    /// 追加保存的设置中的白名单路径，用于请求中单独传入的配置
+    pub fn with_saved_whitelist(mut self) -> Self {
+        for path in saved_config().whitelist_paths {
+            if !self.whitelist_paths.contains(&path) {
+                self.whitelist_paths.push(path);
+            }
+        }
+        self
+    }
+}
+
+lazy_static::lazy_static! {
+    /// 按保存的设置生成的配置，启动时与设置变化时更新
+    static ref SAVED_CONFIG: RwLock<RiskModelConfig> = RwLock::new(RiskModelConfig::default());
+}
+
+/// 替换按保存的设置生成的配置，之后创建的检查器都使用新的配置
+pub fn install_saved_config(config: RiskModelConfig) {
+    *SAVED_CONFIG.write().unwrap() = config;
+}
+
+pub fn saved_config() -> RiskModelConfig {
+    SAVED_CONFIG.read().unwrap().clone()
+}
+
+pub struct RiskModel {
+    config: RiskModelConfig,
+    whitelist: Vec<PathPrefix>,
+    /// 白名单路径所在的目录（不含根目录）
+    whitelist_parents: Vec<PathPrefix>,
+    locations: Vec<(PathPrefix, LocationClass)>,
+    location_patterns: Vec<(SegmentPattern, LocationClass)>,
+    git_repos: GitRepoCache,
+    open_files: OpenFileIndex,
+}
+
+impl RiskModel {
+    pub fn new() -> Self {
+        Self::with_config(RiskModelConfig::default())
+    }
+
+    /// 使用保存的设置中的白名单与权重
+    pub fn from_saved_settings() -> Self {
+        Self::with_config(saved_config())
+    }
+
+    pub fn with_config(config: RiskModelConfig) -> Self {
+        let mut locations = vec![(PathPrefix::new(std::env::temp_dir()), LocationClass::Temp)];
+        for (dir, class) in [
+            (dirs::cache_dir(), LocationClass::Cache),
+            (dirs::download_dir(), LocationClass::Downloads),
+            (dirs::desktop_dir(), LocationClass::Desktop),
+            (dirs::document_dir(), LocationClass::Documents),
+        ] {
+            // 未配置 XDG 目录时部分目录会回退到主目录，不能把整个主目录归类
+            if let Some(dir) = dir.filter(|d| Some(d) != dirs::home_dir().as_ref()) {
+                locations.push((PathPrefix::new(dir), class));
+            }
+        }
+
+        let location_patterns = [
+            (r"\temp\", LocationClass::Temp),
+            (r"\tmp\", LocationClass::Temp),
+            (r"cache\", LocationClass::Cache),
+            (r"caches\", LocationClass::Cache),
+            (r"\downloads\", LocationClass::Downloads),
+            (r"\desktop\", LocationClass::Desktop),
+            (r"\documents\", LocationClass::Documents),
+        ]
+        .iter()
+        .map(|(pattern, class)| (SegmentPattern::new(pattern), *class))
+        .collect();
+
+        let whitelist: Vec<PathPrefix> =
+            config.whitelist_paths.iter().map(PathPrefix::new).collect();
+        let whitelist_parents = whitelist
+            .iter()
+            .filter_map(|w| {
+                w.path()
+                    .parent()
+                    .filter(|p| p.parent().is_some())
+                    .map(|parent| PathPrefix::with_case(parent, w.case()))
+            })
+            .collect();
+
+        Self {
+            whitelist,
+            whitelist_parents,
+            config,
+            locations,
+            location_patterns,
+            git_repos: GitRepoCache::new(),
+            open_files: OpenFileIndex::default(),
+        }
+    }
+
+    pub fn config(&self) -> &RiskModelConfig {
+        &self.config
+    }
+
+    /// 设置评分时使用的文件占用索引，未设置时不检查占用
+    pub fn set_open_files(&mut self, open_files: OpenFileIndex) {
+        self.open_files = open_files;
+    }
+
+    /// 临时与缓存目录优先于其所在的用户目录，例如文档目录下的缓存
+    pub fn classify_location(&self, path: &Path) -> LocationClass {
+        let matched = |class: LocationClass| {
+            self.locations
+                .iter()
+                .any(|(prefix, c)| *c == class && prefix.contains(path))
+                || self
+                    .location_patterns
+                    .iter()
+                    .any(|(pattern, c)| *c == class && pattern.matches(path))
+        };
+
+        [
+            LocationClass::Temp,
+            LocationClass::Cache,
+            LocationClass::Documents,
+            LocationClass::Desktop,
+            LocationClass::Downloads,
+        ]
+        .into_iter()
+        .find(|class| matched(*class))
+        .unwrap_or(LocationClass::Other)
+    }
+
+    /// 读取文件信息并查询 Git 状态后评分
+    pub fn assess_path(&self, path: &Path) -> RiskAssessment {
+        let mut input = RiskInput::from_path(path);
+        input.git_status = self.git_repos.status(path);
+        self.assess(&input)
+    }
+
+    /// 为候选项评分
+    ///
+    /// 分析器给出的类别等级作为下限，评分只能提高风险等级，不会把高风险或
+    /// 严重风险的类别降为可自动清理。
+    pub fn score_candidate(&self, candidate: &mut CleanupCandidate) {
+        let mut input = RiskInput::from_candidate(candidate, &self.open_files);
+        input.git_status = self.git_repos.status(&input.path);
+        let assessment = self.assess(&input);
+
+        candidate.risk_level = candidate.risk_level.clone().max(assessment.risk_level);
+        candidate.risk_score = assessment.score;
+        candidate.risk_factors = assessment.factors;
+    }
+
+    pub fn assess(&self, input: &RiskInput) -> RiskAssessment {
+        self.assess_at(input, now_secs())
+    }
+
+    pub fn assess_at(&self, input: &RiskInput, now: i64) -> RiskAssessment {
+        let mut factors = Vec::new();
+        let mut add = |signal: RiskSignal, value: f32, description: String| {
+            let contribution = (self.config.weights.weight(signal) as f32 * value).round() as i32;
+            if contribution != 0 {
+                factors.push(RiskFactor {
+                    signal,
+                    contribution,
+                    description,
+                });
+            }
+        };
+
+        let location = self.classify_location(&input.path);
+        if location != LocationClass::Other {
+            add(
+                RiskSignal::Location,
+                location.value(),
+                format!("位于{}", location.display_name()),
+            );
+        }
+
+        if let Some((value, description)) = age_signal(input.modified_time, now) {
+            add(RiskSignal::Age, value, description);
+        }
+        if let Some((value, description)) = recent_modification_signal(input.modified_time, now) {
+            add(RiskSignal::RecentModification, value, description);
+        }
+        if let Some((value, description)) = last_access_signal(input.accessed_time, now) {
+            add(RiskSignal::LastAccess, value, description);
+        }
+
+        if !input.is_dir {
+            if let Some((value, description)) = file_type_signal(&input.path) {
+                add(RiskSignal::FileType, value, description);
+            }
+        }
+
+        if input.regenerable {
+            add(
+                RiskSignal::Regenerable,
+                -1.0,
+                "可由程序重新生成".to_string(),
+            );
+        }
+
+        if input.in_use == Some(true) {
+            add(RiskSignal::InUse, 1.0, "正被其他进程使用".to_string());
+        }
+
+        if let Some((value, description)) = input.git_status.as_ref().and_then(git_signal) {
+            add(RiskSignal::Git, value, description.to_string());
+        }
+
+        if let Some((value, description)) = self.whitelist_signal(&input.path) {
+            add(RiskSignal::Whitelist, value, description.to_string());
+        }
+
+        if let Some(level) = &input.category_risk {
+            let value = match level {
+                RiskLevel::Low => -1.0,
+                RiskLevel::Medium => 0.0,
+                RiskLevel::High | RiskLevel::Critical => 1.0,
+            };
+            add(
+                RiskSignal::Category,
+                value,
+                format!("分析器判定为{}", level.display_name()),
+            );
+        }
+
+        factors.sort_by_key(|f| std::cmp::Reverse(f.contribution.abs()));
+
+        let total = self.config.weights.base + factors.iter().map(|f| f.contribution).sum::<i32>();
+        let score = total.clamp(0, 100) as u8;
+
+        RiskAssessment {
+            score,
+            risk_level: self.config.thresholds.level(score),
+            factors,
+        }
+    }
+
+    /// 位于白名单路径内或包含白名单路径时取最大值，与白名单路径同在一个目录下时取一半
+    fn whitelist_signal(&self, path: &Path) -> Option<(f32, &'static str)> {
+        if self.whitelist.iter().any(|w| w.contains(path)) {
+            return Some((1.0, "位于白名单路径内"));
+        }
+        if self.whitelist.iter().any(|w| w.is_within(path)) {
+            return Some((1.0, "包含白名单路径"));
+        }
+        let near = self.whitelist_parents.iter().any(|p| p.contains(path));
+        near.then_some((0.5, "与白名单路径相邻"))
+    }
+}
+
+impl Default for RiskModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn age_signal(modified_time: i64, now: i64) -> Option<(f32, String)> {
+    let days = age_days(modified_time, now)?;
+    if days >= 365 {
+        Some((-1.0, format!("{} 天未修改", days)))
+    } else if days >= 90 {
+        Some((-0.5, format!("{} 天未修改", days)))
+    } else {
+        None
+    }
+}
+
+fn recent_modification_signal(modified_time: i64, now: i64) -> Option<(f32, String)> {
+    let days = age_days(modified_time, now)?;
+    if days < 1 {
+        Some((1.0, "24 小时内修改过".to_string()))
+    } else if days < 7 {
+        Some((0.5, format!("{} 天前修改过", days)))
+    } else {
+        None
+    }
+}
+
+fn last_access_signal(accessed_time: i64, now: i64) -> Option<(f32, String)> {
+    let days = age_days(accessed_time, now)?;
+    if days >= 180 {
+        Some((-1.0, format!("{} 天未访问", days)))
+    } else if days >= 30 {
+        Some((-0.5, format!("{} 天未访问", days)))
+    } else if days < 1 {
+        Some((0.5, "24 小时内访问过".to_string()))
+    } else {
+        None
+    }
+}
+
+fn age_days(timestamp: i64, now: i64) -> Option<i64> {
+    if timestamp <= 0 {
+        return None;
+    }
+    Some((now - timestamp).max(0) / DAY)
+}
+
+fn file_type_signal(path: &Path) -> Option<(f32, String)> {
+    let extension = path.extension()?.to_string_lossy().to_lowercase();
+    if matches!(extension.as_str(), "log" | "dmp" | "etl") {
+        return Some((-0.75, "日志或转储文件".to_string()));
+    }
+
+    let file_type = get_file_type(&extension);
+    let value = match file_type.as_str() {
+        "文档" | "图片" | "视频" | "音频" => 1.0,
+        "代码文件" | "数据库" => 0.75,
+        "配置文件" => 0.5,
+        "备份文件" => 0.25,
+        "压缩包" | "磁盘镜像" | "可执行文件" => -0.25,
+        "临时文件" => -1.0,
+        _ => return None,
+    };
+    Some((value, format!("文件类型为{}", file_type)))
+}
+
+fn git_signal(status: &GitPathStatus) -> Option<(f32, &'static str)> {
+    match status {
+        GitPathStatus::Tracked => Some((1.0, "已被 Git 跟踪")),
+        GitPathStatus::GitDir => Some((1.0, "Git 仓库数据")),
+        GitPathStatus::Untracked => Some((0.3, "Git 仓库中未提交的文件")),
+        GitPathStatus::Ignored => Some((-0.5, "被 .gitignore 忽略")),
+    }
+}
+
+fn now_secs() -> i64 {
+    secs(Some(SystemTime::now()))
+}
@@ -17,8 +17,10 @@ use walkdir::{DirEntry, WalkDir};
 #[cfg(windows)]
 use std::os::windows::fs::MetadataExt;
 
-use crate::models::{generate_scan_id, ScanStatus};
+use crate::models::{generate_scan_id, IgnoredPath, ScanStatus};
+use crate::utils::gitignore::DisktidyIgnore;
 use crate::utils::path::SystemPaths;
+use crate::utils::path_match::PathPrefix;
 
 /// 扫描控制句柄，用于控制扫描过程
 #[derive(Debug)]
@@ -168,36 +170,50 @@ pub struct StandardFileFilter {
     pub include_system: bool,
     pub exclude_paths: Vec<String>,
     pub protected_paths: Vec<PathBuf>,
+    pub respect_ignore_files: bool,
+    ignore_files: DisktidyIgnore,
+    exclude_prefixes: Vec<PathPrefix>,
+    protected_prefixes: Vec<PathPrefix>,
 }
 
 impl StandardFileFilter {
     pub fn new(options: &FilterOptions) -> Self {
+        let protected_paths = SystemPaths::get_protected_paths();
         Self {
             include_hidden: options.include_hidden,
             include_system: options.include_system,
+            exclude_prefixes: options.exclude_paths.iter().map(PathPrefix::new).collect(),
+            protected_prefixes: protected_paths.iter().map(PathPrefix::new).collect(),
             exclude_paths: options.exclude_paths.clone(),
-            protected_paths: SystemPaths::get_protected_paths(),
+            protected_paths,
+            respect_ignore_files: options.respect_ignore_files,
+            ignore_files: DisktidyIgnore::new(),
         }
     }
+
+    /// 遍历过程中被 `.disktidyignore` 排除的路径
+    pub fn ignored_paths(&self) -> Vec<IgnoredPath> {
+        self.ignore_files.excluded_paths()
+    }
 }
 
 impl FileFilter for StandardFileFilter {
     fn should_include(&self, entry: &DirEntry) -> bool {
         let path = entry.path();
-        let path_str = path.to_string_lossy();
 
         // 检查排除路径
-        for exclude in &self.exclude_paths {
-            if path_str.to_lowercase().starts_with(&exclude.to_lowercase()) {
-                return false;
-            }
+        if self.exclude_prefixes.iter().any(|p| p.contains(path)) {
+            return false;
         }
 
         // 检查受保护路径
-        for protected in &self.protected_paths {
-            if path.starts_with(protected) {
-                return false;
-            }
+        if self.protected_prefixes.iter().any(|p| p.contains(path)) {
+            return false;
+        }
+
+        // 检查 .disktidyignore 规则
+        if self.respect_ignore_files && self.ignore_files.is_excluded_entry(entry) {
+            return false;
         }
 
         // 对于目录：只检查排除路径和受保护路径，不检查隐藏/系统属性
@@ -243,6 +259,7 @@ pub struct FilterOptions {
     pub include_hidden: bool,
     pub include_system: bool,
     pub exclude_paths: Vec<String>,
+    pub respect_ignore_files: bool,
 }
 
 impl Default for FilterOptions {
@@ -251,6 +268,7 @@ impl Default for FilterOptions {
             include_hidden: false,
             include_system: false,
             exclude_paths: vec![],
+            respect_ignore_files: true,
         }
     }
 }
@@ -3,13 +3,15 @@ use std::fs;
 use std::io::{Read, Write};
 use std::sync::Mutex;
 
-use crate::models::settings::{AppSettings, CleanRule};
+use crate::models::settings::{AppSettings, CleanRule, WhitelistSettings};
 use crate::models::DiskTidyError;
+use crate::modules::risk_score::RiskModelConfig;
 
 pub struct SettingsManager {
     settings: Mutex<AppSettings>,
     config_path: PathBuf,
     rules: Mutex<Vec<CleanRule>>,
+    whitelist: Mutex<WhitelistSettings>,
 }
 
 impl SettingsManager {
@@ -17,22 +19,26 @@ impl SettingsManager {
         let config_path = Self::get_config_path();
         let settings = Self::load_settings(&config_path).unwrap_or_default();
         let rules = Self::load_rules(&config_path).unwrap_or_default();
+        let whitelist = Self::load_whitelist(&config_path).unwrap_or_default();
 
         Self {
             settings: Mutex::new(settings),
             config_path,
             rules: Mutex::new(rules),
+            whitelist: Mutex::new(whitelist),
         }
     }
 
     pub fn with_path(config_path: PathBuf) -> Self {
         let settings = Self::load_settings(&config_path).unwrap_or_default();
         let rules = Self::load_rules(&config_path).unwrap_or_default();
+        let whitelist = Self::load_whitelist(&config_path).unwrap_or_default();
 
         Self {
             settings: Mutex::new(settings),
             config_path,
             rules: Mutex::new(rules),
+            whitelist: Mutex::new(whitelist),
         }
     }
 
@@ -84,6 +90,24 @@ impl SettingsManager {
         Ok(config.rules)
     }
 
+    fn load_whitelist(path: &PathBuf) -> Result<WhitelistSettings, DiskTidyError> {
+        if !path.exists() {
+            return Ok(WhitelistSettings::default());
+        }
+
+        let mut file = fs::File::open(path)
+            .map_err(|e| DiskTidyError::SettingsLoadFailed(e.to_string()))?;
+
+        let mut content = String::new();
+        file.read_to_string(&mut content)
+            .map_err(|e| DiskTidyError::SettingsLoadFailed(e.to_string()))?;
+
+        let config: ConfigFile = serde_json::from_str(&content)
+            .map_err(|e| DiskTidyError::ConfigError { message: e.to_string() })?;
+
+        Ok(config.whitelist)
+    }
+
     pub fn get_settings(&self) -> AppSettings {
         let settings = self.settings.lock().unwrap();
         settings.clone()
@@ -213,13 +237,33 @@ impl SettingsManager {
         Ok(updated)
     }
 
+    pub fn get_whitelist(&self) -> WhitelistSettings {
+        self.whitelist.lock().unwrap().clone()
+    }
+
+    pub fn update_whitelist(&self, whitelist: WhitelistSettings) -> Result<WhitelistSettings, DiskTidyError> {
+        {
+            let mut current = self.whitelist.lock().unwrap();
+            *current = whitelist.clone();
+        }
+        self.save_to_file()?;
+        Ok(whitelist)
+    }
+
+    /// 按保存的白名单生成风险模型配置
+    pub fn risk_model_config(&self) -> RiskModelConfig {
+        RiskModelConfig::from_whitelist(&self.whitelist.lock().unwrap())
+    }
+
     fn save_to_file(&self) -> Result<(), DiskTidyError> {
         let settings = self.settings.lock().unwrap().clone();
         let rules = self.rules.lock().unwrap().clone();
+        let whitelist = self.whitelist.lock().unwrap().clone();
 
         let config = ConfigFile {
             settings,
             rules,
+            whitelist,
         };
 
         let content = serde_json::to_string_pretty(&config)
@@ -237,10 +281,12 @@ impl SettingsManager {
     pub fn export_settings(&self) -> Result<String, DiskTidyError> {
         let settings = self.settings.lock().unwrap().clone();
         let rules = self.rules.lock().unwrap().clone();
+        let whitelist = self.whitelist.lock().unwrap().clone();
 
         let config = ConfigFile {
             settings,
             rules,
+            whitelist,
         };
 
         serde_json::to_string_pretty(&config)
@@ -261,6 +307,11 @@ impl SettingsManager {
             *rules = config.rules;
         }
 
+        {
+            let mut whitelist = self.whitelist.lock().unwrap();
+            *whitelist = config.whitelist;
+        }
+
         self.save_to_file()
     }
 }
@@ -287,4 +338,7 @@ pub struct SettingsUpdate {
 struct ConfigFile {
     settings: AppSettings,
     rules: Vec<CleanRule>,
+    /// 旧版本的配置文件没有白名单
+    #[serde(default)]
+    whitelist: WhitelistSettings,
 }
@@ -0,0 +1,573 @@
+//! 智能清理
+//!
+//! 在同一个扫描 ID 下并发运行选定的分析器，将结果归一化为 `CleanupCandidate`，
+//! 合并重复与嵌套的路径，生成一份可审阅、可直接交给 `CleanerExecutor` 执行的清理计划。
+
+use futures::future::{join_all, BoxFuture};
+use log::error;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::models::{
+    CleanupCandidate, CleanupSource, DuplicateAnalysisResult, DuplicateDetectorOptions,
+    GarbageAnalysisResult, LargeFileAnalysisResult, LargeFileAnalyzerOptions, MergedCandidate,
+    RiskLevel,
+};
+use crate::modules::app_cache::{
+    cancel_app_cache_scan, clear_app_cache_result, get_app_cache_progress, get_app_cache_result,
+    start_app_cache_scan, AppCacheScanOptions, AppCacheScanResult, AppCacheScanStatus,
+    AppDefinitionRegistry, CleanCategory,
+};
+use crate::modules::cleaner::CleanerExecutor;
+use crate::modules::cleanup_planner::{
+    ancestor_keys, dedup_candidates, merge_candidates, normalize_findings_with, path_key,
+    CleanupPlanInput,
+};
+use crate::modules::risk_score::{RiskModel, RiskModelConfig};
+use crate::modules::file_analyzer::{
+    DuplicateDetector, GarbageDetector, JunkFileDetector, JunkScanResult, LargeFileAnalyzer,
+};
+use crate::modules::software_residue::{ResidueScanResult, SoftwareResidueScanner};
+use crate::utils::path::SystemPaths;
+
+pub const EVENT_SMART_CLEAN_PROGRESS: &str = "smart_clean:progress";
+pub const EVENT_SMART_CLEAN_COMPLETE: &str = "smart_clean:complete";
+
+const APP_CACHE_POLL_INTERVAL: Duration = Duration::from_millis(200);
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmartCleanOptions {
+    pub analyzers: Vec<CleanupSource>,
+    /// 重复文件与大文件分析的扫描目录，留空时使用用户主目录
+    #[serde(default)]
+    pub scan_paths: Vec<String>,
+    /// 高于该风险等级的候选项不会进入待清理列表，只作为保留项展示
+    #[serde(default = "default_max_risk")]
+    pub max_risk: RiskLevel,
+    #[serde(default)]
+    pub app_cache: Option<AppCacheScanOptions>,
+    #[serde(default)]
+    pub large_file_threshold: Option<u64>,
+    #[serde(default)]
+    pub risk_model: RiskModelConfig,
+}
+
+fn default_max_risk() -> RiskLevel {
+    RiskLevel::Medium
+}
+
+impl Default for SmartCleanOptions {
+    fn default() -> Self {
+        Self {
+            analyzers: vec![
+                CleanupSource::Garbage,
+                CleanupSource::Junk,
+                CleanupSource::AppCache,
+                CleanupSource::Residue,
+            ],
+            scan_paths: vec![],
+            max_risk: default_max_risk(),
+            app_cache: None,
+            large_file_threshold: None,
+            risk_model: RiskModelConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SmartCleanStatus {
+    Scanning,
+    Completed,
+    Cancelled,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmartCleanProgress {
+    pub scan_id: String,
+    pub status: SmartCleanStatus,
+    pub running_analyzers: Vec<CleanupSource>,
+    pub completed_analyzers: Vec<CleanupSource>,
+    pub total_analyzers: u32,
+    pub percent: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmartCleanSourceStats {
+    pub source: CleanupSource,
+    pub count: u64,
+    pub total_size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmartCleanAnalyzerError {
+    pub source: CleanupSource,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmartCleanPlan {
+    pub scan_id: String,
+    /// 待清理的候选项，按风险从低到高、体积从大到小排列
+    pub candidates: Vec<CleanupCandidate>,
+    /// 超出风险上限、默认不清理的候选项
+    pub held_back: Vec<CleanupCandidate>,
+    /// 已被所在目录覆盖的路径
+    pub merged: Vec<MergedCandidate>,
+    pub total_size: u64,
+    pub total_count: u64,
+    pub max_risk: RiskLevel,
+    pub source_stats: Vec<SmartCleanSourceStats>,
+    pub analyzer_errors: Vec<SmartCleanAnalyzerError>,
+    pub duration_ms: u64,
+}
+
+impl SmartCleanPlan {
+    /// 计划中待清理的路径，`excluded` 为用户审阅时取消勾选的路径
+    ///
+    /// 取消勾选的路径已合并到某个待清理目录中时，不再删除整个目录，而是改为
+    /// 删除该目录下其余已合并的路径
+    pub fn paths(&self, excluded: &[String]) -> Vec<PathBuf> {
+        let excluded: HashSet<String> = excluded.iter().map(|p| path_key(p)).collect();
+        // 取消勾选的路径本身及其各级上级目录都不能整体删除
+        let blocked: HashSet<&str> = excluded
+            .iter()
+            .flat_map(|key| std::iter::once(key.as_str()).chain(ancestor_keys(key)))
+            .collect();
+        let is_excluded = |key: &str| {
+            excluded.contains(key) || ancestor_keys(key).any(|a| excluded.contains(a))
+        };
+
+        let mut paths = Vec::new();
+        for candidate in &self.candidates {
+            let key = path_key(&candidate.path);
+            if excluded.contains(&key) {
+                continue;
+            }
+            if !blocked.contains(key.as_str()) {
+                paths.push(PathBuf::from(&candidate.path));
+                continue;
+            }
+
+            let children: Vec<(String, &MergedCandidate)> = self
+                .merged
+                .iter()
+                .filter(|m| path_key(&m.merged_into) == key)
+                .map(|m| (path_key(&m.path), m))
+                .filter(|(child, _)| !blocked.contains(child.as_str()) && !is_excluded(child))
+                .collect();
+            let child_keys: HashSet<&str> = children.iter().map(|(k, _)| k.as_str()).collect();
+            // 已合并的路径之间也可能嵌套，只保留最外层的一个
+            paths.extend(
+                children
+                    .iter()
+                    .filter(|(child, _)| !ancestor_keys(child).any(|a| child_keys.contains(a)))
+                    .map(|(_, m)| PathBuf::from(&m.path)),
+            );
+        }
+        paths
+    }
+}
+
+/// 由分析结果生成清理计划
+pub fn build_smart_clean_plan(
+    scan_id: &str,
+    input: &CleanupPlanInput,
+    max_risk: RiskLevel,
+) -> SmartCleanPlan {
+    build_smart_clean_plan_with(scan_id, input, max_risk, &RiskModel::new())
+}
+
+/// 同 [`build_smart_clean_plan`]，使用给定的风险模型为候选项评分
+pub fn build_smart_clean_plan_with(
+    scan_id: &str,
+    input: &CleanupPlanInput,
+    max_risk: RiskLevel,
+    model: &RiskModel,
+) -> SmartCleanPlan {
+    // 先按风险拆分再合并嵌套路径：超出上限的目录不能把其中低风险的路径
+    // 带出待清理列表，待清理的路径只合并到同样待清理的目录中
+    let (selected, mut held_back): (Vec<_>, Vec<_>) =
+        dedup_candidates(normalize_findings_with(input, model))
+            .into_iter()
+            .filter(|c| c.size > 0)
+            .partition(|c| c.risk_level <= max_risk);
+
+    // 删除目录会连带删除其中的保留项，这样的目录也转为保留项
+    let held_ancestors: HashSet<String> = held_back
+        .iter()
+        .flat_map(|c| {
+            let key = path_key(&c.path);
+            ancestor_keys(&key).map(str::to_string).collect::<Vec<_>>()
+        })
+        .collect();
+    let (selected, containing_held): (Vec<_>, Vec<_>) = selected
+        .into_iter()
+        .partition(|c| !held_ancestors.contains(&path_key(&c.path)));
+    held_back.extend(containing_held);
+
+    let merge_result = merge_candidates(selected);
+    let mut candidates = merge_result.candidates;
+
+    let by_risk_then_size = |a: &CleanupCandidate, b: &CleanupCandidate| {
+        a.risk_level
+            .cmp(&b.risk_level)
+            .then(a.risk_score.cmp(&b.risk_score))
+            .then(b.size.cmp(&a.size))
+    };
+    candidates.sort_by(by_risk_then_size);
+    held_back.sort_by(by_risk_then_size);
+
+    let mut source_stats: Vec<SmartCleanSourceStats> = Vec::new();
+    for candidate in &candidates {
+        match source_stats.iter_mut().find(|s| s.source == candidate.source) {
+            Some(stats) => {
+                stats.count += 1;
+                stats.total_size += candidate.size;
+            }
+            None => source_stats.push(SmartCleanSourceStats {
+                source: candidate.source,
+                count: 1,
+                total_size: candidate.size,
+            }),
+        }
+    }
+
+    SmartCleanPlan {
+        scan_id: scan_id.to_string(),
+        total_size: candidates.iter().map(|c| c.size).sum(),
+        total_count: candidates.len() as u64,
+        candidates,
+        held_back,
+        merged: merge_result.merged,
+        max_risk,
+        source_stats,
+        analyzer_errors: Vec::new(),
+        duration_ms: 0,
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref SMART_CLEAN_PROGRESS: Arc<RwLock<HashMap<String, SmartCleanProgress>>> = Arc::new(RwLock::new(HashMap::new()));
+    static ref SMART_CLEAN_PLANS: Arc<RwLock<HashMap<String, SmartCleanPlan>>> = Arc::new(RwLock::new(HashMap::new()));
+    static ref SMART_CLEAN_CANCEL_FLAGS: Arc<RwLock<HashMap<String, Arc<AtomicBool>>>> = Arc::new(RwLock::new(HashMap::new()));
+}
+
+fn generate_scan_id() -> String {
+    format!("smart_clean_{}", Uuid::new_v4())
+}
+
+pub async fn start_smart_clean_scan(
+    app: AppHandle,
+    mut options: SmartCleanOptions,
+) -> Result<String, String> {
+    let mut analyzers: Vec<CleanupSource> = Vec::new();
+    for source in options.analyzers.drain(..) {
+        if !analyzers.contains(&source) {
+            analyzers.push(source);
+        }
+    }
+    options.analyzers = analyzers;
+
+    if options.analyzers.is_empty() {
+        return Err("请至少选择一个分析项".to_string());
+    }
+
+    let scan_id = generate_scan_id();
+    let cancelled = Arc::new(AtomicBool::new(false));
+
+    let progress = SmartCleanProgress {
+        scan_id: scan_id.clone(),
+        status: SmartCleanStatus::Scanning,
+        running_analyzers: options.analyzers.clone(),
+        completed_analyzers: Vec::new(),
+        total_analyzers: options.analyzers.len() as u32,
+        percent: 0.0,
+    };
+    let _ = app.emit(EVENT_SMART_CLEAN_PROGRESS, &progress);
+    SMART_CLEAN_PROGRESS
+        .write()
+        .await
+        .insert(scan_id.clone(), progress);
+    SMART_CLEAN_CANCEL_FLAGS
+        .write()
+        .await
+        .insert(scan_id.clone(), cancelled.clone());
+
+    let scan_id_clone = scan_id.clone();
+    tokio::spawn(async move {
+        perform_smart_clean_scan(&app, &scan_id_clone, options, cancelled).await;
+        SMART_CLEAN_CANCEL_FLAGS.write().await.remove(&scan_id_clone);
+    });
+
+    Ok(scan_id)
+}
+
+enum AnalyzerOutput {
+    Garbage(GarbageAnalysisResult),
+    Junk(Vec<JunkScanResult>),
+    AppCache(AppCacheScanResult),
+    Duplicate(DuplicateAnalysisResult),
+    LargeFile(LargeFileAnalysisResult),
+    Residue(Vec<ResidueScanResult>),
+}
+
+async fn perform_smart_clean_scan(
+    app: &AppHandle,
+    scan_id: &str,
+    options: SmartCleanOptions,
+    cancelled: Arc<AtomicBool>,
+) {
+    let start_instant = Instant::now();
+    let scan_paths: Vec<PathBuf> = if options.scan_paths.is_empty() {
+        SystemPaths::home_dir().into_iter().collect()
+    } else {
+        options.scan_paths.iter().map(PathBuf::from).collect()
+    };
+
+    let tasks = options.analyzers.iter().map(|source| {
+        let source = *source;
+        let task = run_analyzer(
+            app.clone(),
+            source,
+            &options,
+            scan_paths.clone(),
+            cancelled.clone(),
+        );
+        async move {
+            let output = task.await;
+            mark_analyzer_done(app, scan_id, source).await;
+            (source, output)
+        }
+    });
+    let outputs = join_all(tasks).await;
+
+    if cancelled.load(Ordering::SeqCst) {
+        update_status(app, scan_id, SmartCleanStatus::Cancelled).await;
+        return;
+    }
+
+    let mut input = CleanupPlanInput::default();
+    let mut analyzer_errors = Vec::new();
+    for (source, output) in outputs {
+        match output {
+            Ok(AnalyzerOutput::Garbage(result)) => input.garbage = Some(result),
+            Ok(AnalyzerOutput::Junk(results)) => input.junk = results,
+            Ok(AnalyzerOutput::AppCache(result)) => input.app_cache = Some(result),
+            Ok(AnalyzerOutput::Duplicate(result)) => input.duplicates = Some(result),
+            Ok(AnalyzerOutput::LargeFile(result)) => input.large_files = Some(result),
+            Ok(AnalyzerOutput::Residue(results)) => input.residue = results,
+            Err(message) => {
+                error!("Smart clean analyzer {:?} failed: {}", source, message);
+                analyzer_errors.push(SmartCleanAnalyzerError { source, message });
+            }
+        }
+    }
+
+    let mut model = RiskModel::with_config(options.risk_model.clone().with_saved_whitelist());
+    model.set_open_files(CleanerExecutor::new().snapshot_in_use().await);
+    let mut plan = build_smart_clean_plan_with(scan_id, &input, options.max_risk, &model);
+    plan.analyzer_errors = analyzer_errors;
+    plan.duration_ms = start_instant.elapsed().as_millis() as u64;
+
+    SMART_CLEAN_PLANS
+        .write()
+        .await
+        .insert(scan_id.to_string(), plan.clone());
+    update_status(app, scan_id, SmartCleanStatus::Completed).await;
+    let _ = app.emit(EVENT_SMART_CLEAN_COMPLETE, &plan);
+}
+
+fn run_analyzer(
+    app: AppHandle,
+    source: CleanupSource,
+    options: &SmartCleanOptions,
+    scan_paths: Vec<PathBuf>,
+    cancelled: Arc<AtomicBool>,
+) -> BoxFuture<'static, Result<AnalyzerOutput, String>> {
+    match source {
+        CleanupSource::Garbage => Box::pin(run_blocking(move || {
+            let mut detector = GarbageDetector::new();
+            detector.set_cancel_flag(cancelled);
+            AnalyzerOutput::Garbage(detector.detect_all())
+        })),
+        CleanupSource::Junk => Box::pin(run_blocking(move || {
+            let mut detector = JunkFileDetector::new();
+            detector.set_cancel_flag(cancelled);
+            AnalyzerOutput::Junk(detector.detect_all())
+        })),
+        CleanupSource::Duplicate => Box::pin(run_blocking(move || {
+            let mut detector = DuplicateDetector::with_options(DuplicateDetectorOptions::default());
+            detector.set_cancel_flag(cancelled);
+            AnalyzerOutput::Duplicate(detector.find_duplicates(&scan_paths))
+        })),
+        CleanupSource::LargeFile => {
+            let mut large_file_options = LargeFileAnalyzerOptions::default();
+            if let Some(threshold) = options.large_file_threshold {
+                large_file_options.threshold = threshold;
+            }
+            Box::pin(run_blocking(move || {
+                let mut analyzer = LargeFileAnalyzer::with_options(large_file_options);
+                analyzer.set_cancel_flag(cancelled);
+                AnalyzerOutput::LargeFile(analyzer.analyze(&scan_paths))
+            }))
+        }
+        CleanupSource::Residue => Box::pin(run_residue_scan(cancelled)),
+        CleanupSource::AppCache => {
+            let app_cache_options = options
+                .app_cache
+                .clone()
+                .unwrap_or_else(default_app_cache_options);
+            Box::pin(run_app_cache_scan(app, app_cache_options, cancelled))
+        }
+    }
+}
+
+async fn run_blocking<F>(f: F) -> Result<AnalyzerOutput, String>
+where
+    F: FnOnce() -> AnalyzerOutput + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 软件残留扫描有自己的取消标志，扫描期间轮询智能清理的取消标志并转发
+async fn run_residue_scan(cancelled: Arc<AtomicBool>) -> Result<AnalyzerOutput, String> {
+    let scanner = tokio::task::spawn_blocking(SoftwareResidueScanner::new)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let scan = scanner.start_scan();
+    tokio::pin!(scan);
+    loop {
+        tokio::select! {
+            result = &mut scan => return result.map(AnalyzerOutput::Residue),
+            _ = tokio::time::sleep(CANCEL_POLL_INTERVAL) => {
+                if cancelled.load(Ordering::SeqCst) {
+                    scanner.cancel_scan();
+                }
+            }
+        }
+    }
+}
+
+/// 应用缓存扫描自带后台任务和进度事件，这里启动后轮询其状态直到结束
+async fn run_app_cache_scan(
+    app: AppHandle,
+    options: AppCacheScanOptions,
+    cancelled: Arc<AtomicBool>,
+) -> Result<AnalyzerOutput, String> {
+    let app_cache_scan_id = start_app_cache_scan(app, options).await?;
+
+    loop {
+        tokio::time::sleep(APP_CACHE_POLL_INTERVAL).await;
+
+        if cancelled.load(Ordering::SeqCst) {
+            let _ = cancel_app_cache_scan(&app_cache_scan_id).await;
+            return Err("扫描已取消".to_string());
+        }
+
+        match get_app_cache_progress(&app_cache_scan_id).await.map(|p| p.status) {
+            Some(AppCacheScanStatus::Completed) => break,
+            Some(AppCacheScanStatus::Cancelled) => return Err("应用缓存扫描已取消".to_string()),
+            Some(AppCacheScanStatus::Error) | None => return Err("应用缓存扫描失败".to_string()),
+            _ => {}
+        }
+    }
+
+    let result = get_app_cache_result(&app_cache_scan_id)
+        .await
+        .ok_or_else(|| "未找到应用缓存扫描结果".to_string())?;
+    let _ = clear_app_cache_result(&app_cache_scan_id).await;
+
+    Ok(AnalyzerOutput::AppCache(result))
+}
+
+fn default_app_cache_options() -> AppCacheScanOptions {
+    AppCacheScanOptions {
+        apps: AppDefinitionRegistry::load().ids(),
+        categories: [
+            CleanCategory::ChatImages,
+            CleanCategory::VideoFiles,
+            CleanCategory::DocumentFiles,
+            CleanCategory::InstallPackages,
+            CleanCategory::CacheData,
+            CleanCategory::VoiceFiles,
+            CleanCategory::EmojiCache,
+            CleanCategory::TempFiles,
+            CleanCategory::ThumbCache,
+            CleanCategory::LogFiles,
+            CleanCategory::ShaderCache,
+        ]
+        .iter()
+        .map(|c| c.to_str().to_string())
+        .collect(),
+        incremental: false,
+        force_rescan: false,
+    }
+}
+
+async fn mark_analyzer_done(app: &AppHandle, scan_id: &str, source: CleanupSource) {
+    let mut progress_map = SMART_CLEAN_PROGRESS.write().await;
+    if let Some(progress) = progress_map.get_mut(scan_id) {
+        progress.running_analyzers.retain(|s| *s != source);
+        if !progress.completed_analyzers.contains(&source) {
+            progress.completed_analyzers.push(source);
+        }
+        progress.percent = if progress.total_analyzers > 0 {
+            progress.completed_analyzers.len() as f32 / progress.total_analyzers as f32 * 100.0
+        } else {
+            100.0
+        };
+        let _ = app.emit(EVENT_SMART_CLEAN_PROGRESS, progress.clone());
+    }
+}
+
+async fn update_status(app: &AppHandle, scan_id: &str, status: SmartCleanStatus) {
+    let mut progress_map = SMART_CLEAN_PROGRESS.write().await;
+    if let Some(progress) = progress_map.get_mut(scan_id) {
+        if status == SmartCleanStatus::Completed {
+            progress.percent = 100.0;
+        }
+        progress.status = status;
+        let _ = app.emit(EVENT_SMART_CLEAN_PROGRESS, progress.clone());
+    }
+}
+
+pub async fn get_smart_clean_progress(scan_id: &str) -> Option<SmartCleanProgress> {
+    SMART_CLEAN_PROGRESS.read().await.get(scan_id).cloned()
+}
+
+pub async fn get_smart_clean_plan(scan_id: &str) -> Option<SmartCleanPlan> {
+    SMART_CLEAN_PLANS.read().await.get(scan_id).cloned()
+}
+
+pub async fn cancel_smart_clean_scan(scan_id: &str) -> Result<(), String> {
+    let flags = SMART_CLEAN_CANCEL_FLAGS.read().await;
+    if let Some(flag) = flags.get(scan_id) {
+        flag.store(true, Ordering::SeqCst);
+        Ok(())
+    } else {
+        Err("Scan not found".to_string())
+    }
+}
+
+pub async fn clear_smart_clean_result(scan_id: &str) -> Result<(), String> {
+    SMART_CLEAN_PLANS.write().await.remove(scan_id);
+    SMART_CLEAN_PROGRESS.write().await.remove(scan_id);
+    Ok(())
+}
@@ -0,0 +1,454 @@
+//! 残留项的置信度与依据
+//!
+//! 各扫描阶段先按自己的规则给出基础置信度和依据，再由 [`ResidueScorer`] 补充
+//! 与名称和路径本身有关的依据并调整置信度：
+//!
+//! - 名称与已卸载软件相似时提高置信度
+//! - 名称与已安装软件相似、与其发布者同名（共用的厂商目录）时降低置信度
+//! - 已安装软件或正在运行的进程引用其中的路径时几乎排除
+//! - 长期未修改、内容为空时提高，最近修改过、包含可执行文件时降低
+//!
+//! 置信度不低于 [`HIGH_CONFIDENCE`] 且可安全删除的项默认选中。
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+use super::scanner::{InstalledSoftware, ResidueEvidence};
+use crate::utils::path_match::PathPrefix;
+
+/// 默认选中的最低置信度
+pub const HIGH_CONFIDENCE: f32 = 0.8;
+
+/// 视为同一软件的最低名称相似度
+pub(crate) const SIMILAR_NAME: f32 = 0.8;
+const STALE_DAYS: u64 = 365;
+const RECENT_DAYS: u64 = 30;
+
+/// 查找可执行文件与最近修改时间时的遍历范围
+const WALK_DEPTH: usize = 3;
+const WALK_LIMIT: usize = 2000;
+
+const EXECUTABLE_EXTENSIONS: &[&str] = &["exe", "dll", "msi", "sys", "com", "appimage", "so"];
+
+/// 比较名称时忽略的版本、架构与公司后缀
+const IGNORED_TOKENS: &[&str] = &[
+    "x64",
+    "x86",
+    "amd64",
+    "arm64",
+    "win32",
+    "win64",
+    "64bit",
+    "32bit",
+    "inc",
+    "llc",
+    "ltd",
+    "corp",
+    "corporation",
+    "co",
+    "gmbh",
+    "limited",
+    "software",
+    "technologies",
+    "the",
+];
+
+/// 已卸载但仍有记录的软件，如保留配置的软件包、安装目录已不存在的卸载信息
+#[derive(Debug, Clone)]
+pub struct UninstalledProduct {
+    pub name: String,
+    pub publisher: Option<String>,
+}
+
+/// 正在运行的进程及其可执行文件、工作目录
+#[derive(Debug, Clone)]
+pub struct ProcessRef {
+    pub pid: u32,
+    pub name: String,
+    pub paths: Vec<PathBuf>,
+}
+
+/// 置信度与完整的依据
+#[derive(Debug, Clone)]
+pub struct Assessment {
+    pub confidence: f32,
+    pub evidence: Vec<ResidueEvidence>,
+}
+
+impl Assessment {
+    pub fn is_high_confidence(&self) -> bool {
+        self.confidence >= HIGH_CONFIDENCE
+    }
+}
+
+pub struct ResidueScorer {
+    installed: Vec<InstalledSoftware>,
+    /// 与 `installed` 一一对应的安装位置与卸载程序
+    installed_refs: Vec<InstalledRefs>,
+    uninstalled: Vec<UninstalledProduct>,
+    processes: Vec<ProcessRef>,
+}
+
+/// 已安装软件中用于判断引用关系的路径，创建评分器时解析一次
+struct InstalledRefs {
+    location: Option<PathPrefix>,
+    uninstaller: Option<PathBuf>,
+}
+
+impl InstalledRefs {
+    fn new(software: &InstalledSoftware) -> Self {
+        Self {
+            location: software
+                .install_location
+                .as_deref()
+                .map(|l| l.trim().trim_matches('"'))
+                .filter(|l| !l.is_empty())
+                .map(PathPrefix::new),
+            uninstaller: software.uninstall_string.as_deref().and_then(command_path),
+        }
+    }
+
+    /// 安装位置或卸载程序位于路径之下，或路径位于安装位置之下
+    fn references(&self, prefix: &PathPrefix, path: &Path) -> bool {
+        if let Some(location) = &self.location {
+            if prefix.contains(location.path()) || location.contains(path) {
+                return true;
+            }
+        }
+        self.uninstaller
+            .as_ref()
+            .is_some_and(|program| prefix.contains(program))
+    }
+}
+
+impl ResidueScorer {
+    pub fn new(installed: Vec<InstalledSoftware>, uninstalled: Vec<UninstalledProduct>) -> Self {
+        Self {
+            installed_refs: installed.iter().map(InstalledRefs::new).collect(),
+            installed,
+            uninstalled,
+            processes: Vec::new(),
+        }
+    }
+
+    pub fn with_processes(mut self, processes: Vec<ProcessRef>) -> Self {
+        self.processes = processes;
+        self
+    }
+
+    /// 当前正在运行的进程
+    pub fn running_processes() -> Vec<ProcessRef> {
+        use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+        let mut system = System::new();
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing()
+                .with_exe(UpdateKind::OnlyIfNotSet)
+                .with_cwd(UpdateKind::OnlyIfNotSet),
+        );
+        system
+            .processes()
+            .iter()
+            .map(|(pid, process)| ProcessRef {
+                pid: pid.as_u32(),
+                name: process.name().to_string_lossy().to_string(),
+                paths: process
+                    .exe()
+                    .into_iter()
+                    .chain(process.cwd())
+                    .map(Path::to_path_buf)
+                    .collect(),
+            })
+            .filter(|p| !p.paths.is_empty())
+            .collect()
+    }
+
+    /// 文件系统中的残留
+    pub fn assess_path(
+        &self,
+        path: &Path,
+        name: &str,
+        base: f32,
+        evidence: Vec<ResidueEvidence>,
+    ) -> Assessment {
+        let mut assessment = self.assess_name(name, base, evidence);
+        let evidence = &mut assessment.evidence;
+        let mut confidence = assessment.confidence;
+
+        let contents = Contents::inspect(path);
+        if contents.is_empty && !evidence.contains(&ResidueEvidence::Empty) {
+            evidence.push(ResidueEvidence::Empty);
+        }
+        if evidence.contains(&ResidueEvidence::Empty) {
+            confidence += 0.1;
+        }
+
+        let days = contents.days_since_write();
+        if let Some(days) = days {
+            if !evidence
+                .iter()
+                .any(|e| matches!(e, ResidueEvidence::LastWrite { .. }))
+            {
+                evidence.push(ResidueEvidence::LastWrite { days });
+            }
+            if days >= STALE_DAYS {
+                confidence += 0.1;
+            } else if days < RECENT_DAYS {
+                confidence *= 0.6;
+            }
+        }
+
+        if contents.executables > 0 {
+            evidence.push(ResidueEvidence::ContainsExecutables {
+                count: contents.executables,
+            });
+            confidence *= 0.7;
+        }
+
+        let prefix = PathPrefix::new(path);
+        if let Some((product, _)) = self
+            .installed
+            .iter()
+            .zip(&self.installed_refs)
+            .find(|(_, refs)| refs.references(&prefix, path))
+        {
+            evidence.push(ResidueEvidence::ReferencedByInstalled {
+                product: product.name.clone(),
+            });
+            confidence = confidence.min(0.1);
+        }
+        if let Some(process) = self
+            .processes
+            .iter()
+            .find(|p| p.paths.iter().any(|p| prefix.contains(p)))
+        {
+            evidence.push(ResidueEvidence::ReferencedByProcess {
+                process: process.name.clone(),
+                pid: process.pid,
+            });
+            confidence = confidence.min(0.05);
+        }
+
+        assessment.confidence = confidence.clamp(0.0, 1.0);
+        assessment
+    }
+
+    /// 注册表中的残留，只比较名称
+    pub fn assess_registry(
+        &self,
+        name: &str,
+        base: f32,
+        evidence: Vec<ResidueEvidence>,
+    ) -> Assessment {
+        let mut assessment = self.assess_name(name, base, evidence);
+        if assessment.evidence.contains(&ResidueEvidence::Empty) {
+            assessment.confidence = (assessment.confidence + 0.1).min(1.0);
+        }
+        assessment
+    }
+
+    fn assess_name(&self, name: &str, base: f32, mut evidence: Vec<ResidueEvidence>) -> Assessment {
+        let mut confidence = base;
+
+        let matched_removed = evidence.iter().any(|e| {
+            matches!(
+                e,
+                ResidueEvidence::RemovedPackage { .. }
+                    | ResidueEvidence::OwnedByRemovedPackage { .. }
+                    | ResidueEvidence::SandboxAppRemoved { .. }
+            )
+        });
+        if !matched_removed {
+            let best = self
+                .uninstalled
+                .iter()
+                .map(|p| (p, name_similarity(name, &p.name)))
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            if let Some((product, similarity)) = best.filter(|(_, s)| *s >= SIMILAR_NAME) {
+                evidence.push(ResidueEvidence::UninstalledProduct {
+                    product: product.name.clone(),
+                    similarity,
+                });
+                confidence = confidence.max(0.55 + 0.35 * similarity);
+            }
+        }
+
+        let best = self
+            .installed
+            .iter()
+            .map(|s| (s, name_similarity(name, &s.name)))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((product, similarity)) = best.filter(|(_, s)| *s >= SIMILAR_NAME) {
+            evidence.push(ResidueEvidence::InstalledNameSimilarity {
+                product: product.name.clone(),
+                similarity,
+            });
+            confidence *= 0.3;
+        }
+
+        if let Some(publisher) = self
+            .installed
+            .iter()
+            .filter_map(|s| s.publisher.as_deref())
+            .find(|publisher| name_similarity(name, publisher) >= 0.9)
+        {
+            evidence.push(ResidueEvidence::PublisherMatch {
+                publisher: publisher.to_string(),
+            });
+            confidence *= 0.5;
+        }
+
+        Assessment {
+            confidence: confidence.clamp(0.0, 1.0),
+            evidence,
+        }
+    }
+}
+
+/// 从卸载命令中取出程序路径，如 `"C:\App\uninst.exe" /S` 或 `MsiExec.exe /X{...}`
+fn command_path(command: &str) -> Option<PathBuf> {
+    let command = command.trim();
+    let program = match command.strip_prefix('"') {
+        Some(rest) => rest.split('"').next()?,
+        None => {
+            let lower = command.to_lowercase();
+            let end = lower.find(".exe").map(|i| i + 4).unwrap_or(command.len());
+            &command[..end]
+        }
+    };
+    let path = PathBuf::from(program);
+    (path.components().count() > 1).then_some(path)
+}
+
+/// 名称相似度，0 到 1
+///
+/// 先去掉大小写、标点、版本号和公司后缀，一方包含另一方时按较短一方所占比例
+/// 计算，否则使用编辑距离。
+pub fn name_similarity(a: &str, b: &str) -> f32 {
+    let a = normalize_name(a);
+    let b = normalize_name(b);
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    if a == b {
+        return 1.0;
+    }
+
+    let (short, long) = if a.len() <= b.len() {
+        (&a, &b)
+    } else {
+        (&b, &a)
+    };
+    let short_len = short.chars().count();
+    if short_len >= 4 && long.contains(short.as_str()) {
+        return 0.8 + 0.2 * short_len as f32 / long.chars().count() as f32;
+    }
+
+    let distance = levenshtein(&a, &b);
+    let max_len = a.chars().count().max(b.chars().count());
+    1.0 - distance as f32 / max_len as f32
+}
+
+fn normalize_name(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .enumerate()
+        // 第一个词即使是数字也保留，如 360、7-Zip
+        .filter(|(i, token)| *i == 0 || !is_version(token))
+        .filter(|(_, token)| !IGNORED_TOKENS.contains(token))
+        .map(|(_, token)| token)
+        .collect()
+}
+
+/// 如 `23`、`v2`、`2023r1`
+fn is_version(token: &str) -> bool {
+    let digits = token.strip_prefix('v').unwrap_or(token);
+    digits.starts_with(|c: char| c.is_ascii_digit())
+        && digits.chars().filter(|c| c.is_ascii_digit()).count() * 2 > digits.len()
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// 路径的内容概况，目录只遍历有限的深度和数量
+struct Contents {
+    is_empty: bool,
+    executables: usize,
+    last_write: Option<SystemTime>,
+}
+
+impl Contents {
+    fn inspect(path: &Path) -> Self {
+        let mut contents = Self {
+            is_empty: false,
+            executables: 0,
+            last_write: None,
+        };
+
+        let mut entries = 0;
+        for entry in WalkDir::new(path)
+            .max_depth(WALK_DEPTH)
+            .follow_links(false)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .take(WALK_LIMIT)
+        {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if let Ok(modified) = metadata.modified() {
+                contents.last_write = contents.last_write.max(Some(modified));
+            }
+            if entry.depth() > 0 {
+                entries += 1;
+            }
+            if metadata.is_file() && is_executable(entry.path(), &metadata) {
+                contents.executables += 1;
+            }
+        }
+
+        contents.is_empty = path.is_dir() && entries == 0;
+        contents
+    }
+
+    fn days_since_write(&self) -> Option<u64> {
+        let elapsed = SystemTime::now().duration_since(self.last_write?).ok()?;
+        Some(elapsed.as_secs() / (24 * 60 * 60))
+    }
+}
+
+pub(crate) fn is_executable(path: &Path, metadata: &std::fs::Metadata) -> bool {
+    let by_extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| EXECUTABLE_EXTENSIONS.contains(&ext.as_str()));
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        by_extension || metadata.permissions().mode() & 0o111 != 0
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        by_extension
+    }
+}
@@ -0,0 +1,408 @@
+//! 已安装软件清单
+//!
+//! 为每个已安装的软件统计安装目录及其关联的缓存、配置、数据目录的占用，并根据
+//! 可执行文件的访问时间和状态文件的修改时间估算最近使用时间，按总占用从大到小
+//! 排列，便于决定卸载哪些软件。
+//!
+//! 关联目录按目录名与软件名、发布者的相似度匹配：与发布者同名的厂商目录只在其
+//! 下一级查找该发布者的软件，如 `Google\Chrome`；包含其他软件安装目录的目录不计入。
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use walkdir::WalkDir;
+
+use super::confidence::{is_executable, name_similarity, SIMILAR_NAME};
+use super::linux::LinuxInventory;
+use super::registry::{self, RegistryBackend};
+use super::scanner::InstalledSoftware;
+use crate::utils::path::SystemPaths;
+use crate::utils::path_match::PathPrefix;
+
+/// 视为同一发布者的最低名称相似度
+const SAME_PUBLISHER: f32 = 0.9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FootprintKind {
+    Install,
+    Cache,
+    Config,
+    Data,
+}
+
+impl FootprintKind {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            FootprintKind::Install => "安装目录",
+            FootprintKind::Cache => "缓存",
+            FootprintKind::Config => "配置",
+            FootprintKind::Data => "数据",
+        }
+    }
+}
+
+/// 查找关联目录的位置，其下一级目录按名称归属到软件
+#[derive(Debug, Clone)]
+pub struct FootprintLocation {
+    pub path: PathBuf,
+    pub kind: FootprintKind,
+}
+
+impl FootprintLocation {
+    pub fn new(path: impl Into<PathBuf>, kind: FootprintKind) -> Self {
+        Self {
+            path: path.into(),
+            kind,
+        }
+    }
+
+    /// 当前系统上应用存放缓存、配置和数据的位置
+    pub fn system() -> Vec<Self> {
+        let mut locations = Vec::new();
+
+        if cfg!(windows) {
+            if let Some(roaming) = SystemPaths::app_data_roaming() {
+                locations.push(Self::new(roaming, FootprintKind::Config));
+            }
+            if let Some(local) = SystemPaths::app_data_local() {
+                locations.push(Self::new(local, FootprintKind::Data));
+            }
+            if let Some(program_data) = std::env::var_os("ProgramData") {
+                locations.push(Self::new(program_data, FootprintKind::Data));
+            }
+        } else if let Some(home) = SystemPaths::home_dir() {
+            if cfg!(target_os = "macos") {
+                let library = home.join("Library");
+                locations.push(Self::new(library.join("Caches"), FootprintKind::Cache));
+                locations.push(Self::new(
+                    library.join("Preferences"),
+                    FootprintKind::Config,
+                ));
+                locations.push(Self::new(
+                    library.join("Application Support"),
+                    FootprintKind::Data,
+                ));
+            } else {
+                locations.push(Self::new(home.join(".cache"), FootprintKind::Cache));
+                locations.push(Self::new(home.join(".config"), FootprintKind::Config));
+                locations.push(Self::new(home.join(".local/share"), FootprintKind::Data));
+                locations.push(Self::new(home.join(".var/app"), FootprintKind::Data));
+                locations.push(Self::new(home.join("snap"), FootprintKind::Data));
+            }
+        }
+
+        locations.retain(|l| l.path.is_dir());
+        locations
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FootprintDir {
+    pub path: String,
+    pub kind: FootprintKind,
+    pub size: u64,
+}
+
+/// 最近使用时间的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LastUsedSource {
+    /// 可执行文件的访问时间，文件系统关闭了访问时间更新时可能不准确
+    ExecutableAccessed,
+    /// 缓存、配置或数据目录中文件的修改时间
+    StateFileModified,
+}
+
+impl LastUsedSource {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            LastUsedSource::ExecutableAccessed => "程序访问时间",
+            LastUsedSource::StateFileModified => "数据修改时间",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SoftwareFootprint {
+    pub name: String,
+    pub publisher: Option<String>,
+    pub version: Option<String>,
+    pub install_location: Option<String>,
+    pub directories: Vec<FootprintDir>,
+    pub install_size: u64,
+    /// 缓存、配置与数据目录的占用
+    pub data_size: u64,
+    pub total_size: u64,
+    /// 估算的最近使用时间，Unix 秒
+    pub last_used: Option<i64>,
+    pub last_used_source: Option<LastUsedSource>,
+}
+
+/// 注册表与 Linux 包管理器中登记的已安装软件
+pub fn collect_installed_software(
+    registry: Option<&dyn RegistryBackend>,
+    linux_inventory: &LinuxInventory,
+) -> Vec<InstalledSoftware> {
+    let mut installed = registry
+        .map(registry::read_installed_software)
+        .unwrap_or_default();
+    installed.extend(linux_inventory.installed_software());
+    installed
+}
+
+/// 当前系统的已安装软件清单，按总占用从大到小排列
+pub fn software_inventory() -> Vec<SoftwareFootprint> {
+    let registry = registry::system_registry();
+    let installed =
+        collect_installed_software(registry.as_deref(), &LinuxInventory::for_current_system());
+    build_inventory(&installed, &FootprintLocation::system())
+}
+
+/// 统计每个软件的占用与最近使用时间，按总占用从大到小排列
+///
+/// 同名同版本的软件（如同时登记在 32 位与 64 位视图中）只保留一个。
+pub fn build_inventory(
+    installed: &[InstalledSoftware],
+    locations: &[FootprintLocation],
+) -> Vec<SoftwareFootprint> {
+    let mut seen = HashSet::new();
+    let installed: Vec<&InstalledSoftware> = installed
+        .iter()
+        .filter(|s| !s.name.trim().is_empty())
+        .filter(|s| seen.insert((s.name.to_lowercase(), s.version.clone())))
+        .collect();
+
+    let install_dirs: Vec<Option<PathBuf>> = installed.iter().map(|s| install_dir(s)).collect();
+    let install_prefixes: Vec<(usize, PathPrefix)> = install_dirs
+        .iter()
+        .enumerate()
+        .filter_map(|(i, dir)| dir.as_ref().map(|dir| (i, PathPrefix::new(dir))))
+        .collect();
+
+    let mut associated: Vec<Vec<(PathBuf, FootprintKind)>> = vec![Vec::new(); installed.len()];
+    for location in locations {
+        for (index, path) in associate(&location.path, &installed) {
+            let shared = install_prefixes
+                .iter()
+                .any(|(i, prefix)| *i != index && prefix.is_within(&path));
+            if !shared {
+                associated[index].push((path, location.kind));
+            }
+        }
+    }
+
+    let mut footprints: Vec<SoftwareFootprint> = installed
+        .into_par_iter()
+        .zip(install_dirs)
+        .zip(associated)
+        .map(|((software, install_dir), associated)| {
+            measure_software(software, install_dir, associated)
+        })
+        .collect();
+
+    footprints.sort_by(|a, b| {
+        b.total_size
+            .cmp(&a.total_size)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    footprints
+}
+
+fn install_dir(software: &InstalledSoftware) -> Option<PathBuf> {
+    software
+        .install_location
+        .as_deref()
+        .map(|l| l.trim().trim_matches('"'))
+        .filter(|l| !l.is_empty())
+        .map(PathBuf::from)
+        .filter(|p| p.is_dir())
+}
+
+/// 位置下一级目录中属于各软件的目录
+fn associate(root: &Path, installed: &[&InstalledSoftware]) -> Vec<(usize, PathBuf)> {
+    let mut found = Vec::new();
+    let all: Vec<usize> = (0..installed.len()).collect();
+
+    for (name, path) in subdirectories(root) {
+        let vendor: Vec<usize> = all
+            .iter()
+            .copied()
+            .filter(|&i| {
+                installed[i]
+                    .publisher
+                    .as_deref()
+                    .is_some_and(|p| name_similarity(&name, p) >= SAME_PUBLISHER)
+            })
+            .collect();
+
+        if vendor.is_empty() {
+            if let Some(index) = best_match(&name, installed, &all) {
+                found.push((index, path));
+            }
+            continue;
+        }
+
+        let before = found.len();
+        for (child_name, child) in subdirectories(&path) {
+            if let Some(index) = best_match(&child_name, installed, &vendor) {
+                found.push((index, child));
+            }
+        }
+        // 发布者只有一个软件时，整个厂商目录都归属于它
+        if found.len() == before && vendor.len() == 1 {
+            found.push((vendor[0], path));
+        }
+    }
+
+    found
+}
+
+fn subdirectories(path: &Path) -> Vec<(String, PathBuf)> {
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+                .map(|e| (e.file_name().to_string_lossy().to_string(), e.path()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn best_match(name: &str, installed: &[&InstalledSoftware], candidates: &[usize]) -> Option<usize> {
+    candidates
+        .iter()
+        .map(|&i| (i, name_similarity(name, &installed[i].name)))
+        .filter(|(_, similarity)| *similarity >= SIMILAR_NAME)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+}
+
+fn measure_software(
+    software: &InstalledSoftware,
+    install_dir: Option<PathBuf>,
+    associated: Vec<(PathBuf, FootprintKind)>,
+) -> SoftwareFootprint {
+    // 已计入的目录之下的目录不重复统计，嵌套在其中的目录在统计外层时跳过
+    let mut kept: Vec<(PathBuf, FootprintKind, PathPrefix)> = Vec::new();
+    for (path, kind) in install_dir
+        .map(|dir| (dir, FootprintKind::Install))
+        .into_iter()
+        .chain(associated)
+    {
+        if kept.iter().any(|(_, _, prefix)| prefix.contains(&path)) {
+            continue;
+        }
+        let prefix = PathPrefix::new(&path);
+        kept.push((path, kind, prefix));
+    }
+
+    let mut usage = Usage::default();
+    let mut directories = Vec::new();
+    for (index, (path, kind, prefix)) in kept.iter().enumerate() {
+        let nested: Vec<&PathPrefix> = kept
+            .iter()
+            .enumerate()
+            .filter(|(i, (other, _, _))| *i != index && prefix.contains(other))
+            .map(|(_, (_, _, other))| other)
+            .collect();
+        let size = usage.add(path, *kind, &nested);
+        directories.push(FootprintDir {
+            path: path.to_string_lossy().to_string(),
+            kind: *kind,
+            size,
+        });
+    }
+
+    // 包管理器安装的软件分散在系统目录中，没有安装目录时使用登记的安装大小
+    let install_size = if directories.iter().any(|d| d.kind == FootprintKind::Install) {
+        directories
+            .iter()
+            .filter(|d| d.kind == FootprintKind::Install)
+            .map(|d| d.size)
+            .sum()
+    } else {
+        software.installed_size.unwrap_or(0)
+    };
+    let data_size: u64 = directories
+        .iter()
+        .filter(|d| d.kind != FootprintKind::Install)
+        .map(|d| d.size)
+        .sum();
+    let total_size = install_size + data_size;
+    let (last_used, last_used_source) = match usage.last_used() {
+        Some((time, source)) => (Some(unix_seconds(time)), Some(source)),
+        None => (None, None),
+    };
+
+    SoftwareFootprint {
+        name: software.name.clone(),
+        publisher: software.publisher.clone(),
+        version: software.version.clone(),
+        install_location: software.install_location.clone(),
+        directories,
+        install_size,
+        data_size,
+        total_size,
+        last_used,
+        last_used_source,
+    }
+}
+
+#[derive(Default)]
+struct Usage {
+    executable_accessed: Option<SystemTime>,
+    state_modified: Option<SystemTime>,
+}
+
+impl Usage {
+    /// 统计目录占用并记录时间，返回目录大小
+    fn add(&mut self, path: &Path, kind: FootprintKind, nested: &[&PathPrefix]) -> u64 {
+        let mut size = 0;
+        for entry in WalkDir::new(path)
+            .follow_links(false)
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !nested.iter().any(|n| n.contains(e.path())))
+            .filter_map(|e| e.ok())
+        {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if !metadata.is_file() {
+                continue;
+            }
+            size += metadata.len();
+
+            if is_executable(entry.path(), &metadata) {
+                if let Ok(accessed) = metadata.accessed() {
+                    self.executable_accessed = self.executable_accessed.max(Some(accessed));
+                }
+            } else if kind != FootprintKind::Install {
+                if let Ok(modified) = metadata.modified() {
+                    self.state_modified = self.state_modified.max(Some(modified));
+                }
+            }
+        }
+        size
+    }
+
+    fn last_used(&self) -> Option<(SystemTime, LastUsedSource)> {
+        let accessed = self
+            .executable_accessed
+            .map(|t| (t, LastUsedSource::ExecutableAccessed));
+        let modified = self
+            .state_modified
+            .map(|t| (t, LastUsedSource::StateFileModified));
+        accessed.into_iter().chain(modified).max_by_key(|(t, _)| *t)
+    }
+}
+
+fn unix_seconds(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
@@ -0,0 +1,666 @@
+//! Linux 已安装软件清单与残留检测
+//!
+//! 清单来自 dpkg 的 status 文件、rpm 数据库（sqlite 或 ndb）、pacman 的本地
+//! 数据库、flatpak 的安装目录和 snap 目录。之后把 `~/.config`、`~/.local/share`、
+//! `~/.cache`、`/etc` 等位置下的条目与清单比对，没有已安装软件对应的条目按
+//! 找到的证据给出置信度：
+//!
+//! - dpkg 记录为"已卸载、保留配置"的软件包登记的配置文件，或与其同名的目录
+//! - rpm、pacman 卸载时留下的 `.rpmsave`、`.pacsave`
+//! - flatpak、snap 的应用数据目录（以应用 id 命名）对应的应用已不存在
+//! - 名称与已知应用匹配但未安装，或长期未修改且不属于任何软件包
+//!
+//! 所有路径都相对于 [`LinuxRoots`]，测试时可以指向临时目录。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use super::confidence::UninstalledProduct;
+use super::rpmdb;
+use super::scanner::{InstalledSoftware, ResidueEvidence, ResidueType};
+
+/// 长期未修改的判定阈值
+const STALE_AFTER: Duration = Duration::from_secs(180 * 24 * 60 * 60);
+
+/// 软件包已卸载但保留了登记的配置文件
+const CONFIDENCE_OWNED_BY_REMOVED: f32 = 0.95;
+/// 应用数据目录对应的 flatpak、snap 应用已卸载
+const CONFIDENCE_SANDBOX_DATA: f32 = 0.9;
+/// 包管理器卸载时保留的配置副本
+const CONFIDENCE_SAVED_CONFIG: f32 = 0.85;
+/// 与已卸载、保留配置的软件包同名
+const CONFIDENCE_REMOVED_PACKAGE: f32 = 0.8;
+/// 不属于任何软件包且长期未修改
+const CONFIDENCE_STALE: f32 = 0.3;
+
+/// 桌面环境与 XDG 规范共用的目录，不属于某个软件包
+const SHARED_ENTRIES: &[&str] = &[
+    "applications",
+    "autostart",
+    "backgrounds",
+    "dconf",
+    "desktop-directories",
+    "environment.d",
+    "flatpak",
+    "fontconfig",
+    "fonts",
+    "gtk-2.0",
+    "gtk-3.0",
+    "gtk-4.0",
+    "gvfs-metadata",
+    "icons",
+    "keyrings",
+    "menus",
+    "mesa_shader_cache",
+    "mesa_shader_cache_db",
+    "mime",
+    "mimeapps.list",
+    "recently-used.xbel",
+    "session",
+    "sounds",
+    "systemd",
+    "themes",
+    "thumbnails",
+    "trash",
+    "user-dirs.dirs",
+    "user-dirs.locale",
+];
+
+/// 卸载时保留的配置副本的后缀
+const SAVED_CONFIG_SUFFIXES: &[(&str, PackageManager)] = &[
+    (".rpmsave", PackageManager::Rpm),
+    (".pacsave", PackageManager::Pacman),
+];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum PackageManager {
+    Dpkg,
+    Rpm,
+    Pacman,
+    Flatpak,
+    Snap,
+}
+
+impl PackageManager {
+    pub fn display_name(&self) -> &str {
+        match self {
+            Self::Dpkg => "dpkg",
+            Self::Rpm => "rpm",
+            Self::Pacman => "pacman",
+            Self::Flatpak => "Flatpak",
+            Self::Snap => "Snap",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PackageState {
+    Installed,
+    /// 已卸载，但配置文件仍保留（dpkg 的 `rc` 状态）
+    ConfigRemaining,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinuxPackage {
+    pub name: String,
+    pub version: Option<String>,
+    pub manager: PackageManager,
+    pub state: PackageState,
+    /// 软件包登记的配置文件（目前只有 dpkg 提供）
+    pub conffiles: Vec<String>,
+    pub install_location: Option<String>,
+    /// 包管理器登记的安装大小（字节）
+    pub installed_size: Option<u64>,
+}
+
+impl LinuxPackage {
+    fn new(name: &str, manager: PackageManager) -> Self {
+        Self {
+            name: name.to_string(),
+            version: None,
+            manager,
+            state: PackageState::Installed,
+            conffiles: Vec::new(),
+            install_location: None,
+            installed_size: None,
+        }
+    }
+
+    /// 用于与目录名比对的名称，flatpak 应用 id 额外使用最后一段
+    fn aliases(&self) -> Vec<String> {
+        let name = self.name.to_lowercase();
+        let mut aliases = vec![name.clone()];
+        if self.manager == PackageManager::Flatpak {
+            if let Some(last) = name.rsplit('.').next() {
+                if last != name {
+                    aliases.push(last.to_string());
+                }
+            }
+        }
+        aliases
+    }
+
+    fn matches(&self, entry: &str) -> bool {
+        self.aliases().iter().any(|alias| names_match(entry, alias))
+    }
+
+    /// 路径是登记的配置文件，或是包含登记的配置文件的目录
+    fn owns(&self, path: &str) -> bool {
+        self.conffiles.iter().any(|conffile| {
+            conffile == path
+                || conffile
+                    .strip_prefix(path)
+                    .is_some_and(|rest| rest.starts_with('/'))
+        })
+    }
+}
+
+/// 扫描使用的根目录
+#[derive(Debug, Clone)]
+pub struct LinuxRoots {
+    /// 系统根目录，通常为 `/`
+    pub root: PathBuf,
+    pub home: Option<PathBuf>,
+}
+
+impl LinuxRoots {
+    pub fn system() -> Self {
+        Self {
+            root: PathBuf::from("/"),
+            home: dirs::home_dir(),
+        }
+    }
+
+    /// 要检查的位置、残留类型，以及只按应用 id 精确匹配的包管理器
+    fn locations(&self) -> Vec<(PathBuf, ResidueType, Option<PackageManager>)> {
+        let mut locations = Vec::new();
+        if let Some(home) = &self.home {
+            locations.push((home.join(".config"), ResidueType::ConfigFile, None));
+            locations.push((home.join(".local/share"), ResidueType::LeftoverFolder, None));
+            locations.push((home.join(".cache"), ResidueType::CacheFile, None));
+            locations.push((
+                home.join(".var/app"),
+                ResidueType::LeftoverFolder,
+                Some(PackageManager::Flatpak),
+            ));
+            locations.push((
+                home.join("snap"),
+                ResidueType::LeftoverFolder,
+                Some(PackageManager::Snap),
+            ));
+        }
+        locations.push((self.root.join("etc"), ResidueType::ConfigFile, None));
+        locations
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LinuxInventory {
+    pub packages: Vec<LinuxPackage>,
+}
+
+impl LinuxInventory {
+    /// 在 Linux 上读取当前系统的清单，其他系统返回空清单
+    pub fn for_current_system() -> Self {
+        if cfg!(target_os = "linux") {
+            Self::load(&LinuxRoots::system())
+        } else {
+            Self::default()
+        }
+    }
+
+    pub fn load(roots: &LinuxRoots) -> Self {
+        let root = &roots.root;
+        let mut packages = Vec::new();
+
+        if let Ok(content) = fs::read_to_string(root.join("var/lib/dpkg/status")) {
+            packages.extend(parse_dpkg_status(&content));
+        }
+        packages.extend(load_rpm(&root.join("var/lib/rpm")));
+        packages.extend(load_pacman(&root.join("var/lib/pacman/local")));
+        packages.extend(load_flatpak(&root.join("var/lib/flatpak/app")));
+        if let Some(home) = &roots.home {
+            packages.extend(load_flatpak(&home.join(".local/share/flatpak/app")));
+        }
+        packages.extend(load_snap(root));
+
+        log::info!(
+            "Loaded {} packages from Linux package managers",
+            packages.len()
+        );
+        Self { packages }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.packages.is_empty()
+    }
+
+    fn installed(&self) -> impl Iterator<Item = &LinuxPackage> {
+        self.packages
+            .iter()
+            .filter(|p| p.state == PackageState::Installed)
+    }
+
+    fn removed(&self) -> impl Iterator<Item = &LinuxPackage> {
+        self.packages
+            .iter()
+            .filter(|p| p.state == PackageState::ConfigRemaining)
+    }
+
+    /// 已卸载但保留了配置的软件包
+    pub fn removed_products(&self) -> Vec<UninstalledProduct> {
+        self.removed()
+            .map(|p| UninstalledProduct {
+                name: p.name.clone(),
+                publisher: None,
+            })
+            .collect()
+    }
+
+    pub fn installed_software(&self) -> Vec<InstalledSoftware> {
+        self.installed()
+            .map(|p| InstalledSoftware {
+                name: p.name.clone(),
+                publisher: None,
+                install_location: p.install_location.clone(),
+                uninstall_string: None,
+                version: p.version.clone(),
+                install_date: None,
+                installed_size: p.installed_size,
+            })
+            .collect()
+    }
+}
+
+/// 解析 `/var/lib/dpkg/status`
+pub fn parse_dpkg_status(content: &str) -> Vec<LinuxPackage> {
+    let mut packages = Vec::new();
+
+    for stanza in content.split("\n\n") {
+        let mut fields: HashMap<&str, Vec<&str>> = HashMap::new();
+        let mut current: Option<&str> = None;
+        for line in stanza.lines() {
+            if line.starts_with([' ', '\t']) {
+                if let Some(key) = current {
+                    fields.entry(key).or_default().push(line.trim());
+                }
+            } else if let Some((key, value)) = line.split_once(':') {
+                current = Some(key);
+                let value = value.trim();
+                let values = fields.entry(key).or_default();
+                if !value.is_empty() {
+                    values.push(value);
+                }
+            }
+        }
+
+        let field = |key: &str| fields.get(key).and_then(|v| v.first()).copied();
+        let Some(name) = field("Package") else {
+            continue;
+        };
+        let status = field("Status").unwrap_or("");
+        let state = if status.ends_with(" installed") {
+            PackageState::Installed
+        } else if status.ends_with(" config-files") {
+            PackageState::ConfigRemaining
+        } else {
+            continue;
+        };
+
+        let mut package = LinuxPackage::new(name, PackageManager::Dpkg);
+        package.state = state;
+        package.version = field("Version").map(str::to_string);
+        // Installed-Size 的单位为 KiB
+        package.installed_size = field("Installed-Size")
+            .and_then(|size| size.parse::<u64>().ok())
+            .map(|kib| kib * 1024);
+        package.conffiles = fields
+            .get("Conffiles")
+            .map(|lines| {
+                lines
+                    .iter()
+                    .filter_map(|line| line.split_whitespace().next())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        packages.push(package);
+    }
+
+    packages
+}
+
+/// 解析 pacman 本地数据库中软件包目录下的 `desc`
+pub fn parse_pacman_desc(content: &str) -> Option<LinuxPackage> {
+    let mut name = None;
+    let mut version = None;
+    let mut size = None;
+    let mut lines = content.lines();
+    while let Some(line) = lines.next() {
+        match line.trim() {
+            "%NAME%" => name = lines.next().map(|l| l.trim().to_string()),
+            "%VERSION%" => version = lines.next().map(|l| l.trim().to_string()),
+            "%SIZE%" => size = lines.next().and_then(|l| l.trim().parse::<u64>().ok()),
+            _ => {}
+        }
+    }
+
+    let mut package = LinuxPackage::new(&name.filter(|n| !n.is_empty())?, PackageManager::Pacman);
+    package.version = version;
+    package.installed_size = size;
+    Some(package)
+}
+
+fn load_rpm(dir: &Path) -> Vec<LinuxPackage> {
+    let headers = if let Ok(data) = fs::read(dir.join("rpmdb.sqlite")) {
+        rpmdb::read_sqlite_packages(&data).unwrap_or_default()
+    } else if let Ok(data) = fs::read(dir.join("Packages.db")) {
+        rpmdb::read_ndb_packages(&data)
+    } else {
+        return Vec::new();
+    };
+
+    headers
+        .into_iter()
+        .map(|header| {
+            let mut package = LinuxPackage::new(&header.name, PackageManager::Rpm);
+            package.version = header.version;
+            package.installed_size = header.size;
+            package
+        })
+        .collect()
+}
+
+fn load_pacman(dir: &Path) -> Vec<LinuxPackage> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| fs::read_to_string(entry.path().join("desc")).ok())
+        .filter_map(|desc| parse_pacman_desc(&desc))
+        .collect()
+}
+
+/// flatpak 每个应用一个以应用 id 命名的目录，`current` 指向当前部署
+fn load_flatpak(dir: &Path) -> Vec<LinuxPackage> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| {
+            let id = entry.file_name().to_string_lossy().to_string();
+            let mut package = LinuxPackage::new(&id, PackageManager::Flatpak);
+            package.install_location = Some(entry.path().to_string_lossy().to_string());
+            package
+        })
+        .collect()
+}
+
+/// snap 安装在 `/snap/<name>`，软件包文件为 `/var/lib/snapd/snaps/<name>_<rev>.snap`
+fn load_snap(root: &Path) -> Vec<LinuxPackage> {
+    let mut names: Vec<(String, Option<PathBuf>)> = Vec::new();
+
+    if let Ok(entries) = fs::read_dir(root.join("snap")) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if entry.path().is_dir() && name != "bin" {
+                names.push((name, Some(entry.path())));
+            }
+        }
+    }
+    if let Ok(entries) = fs::read_dir(root.join("var/lib/snapd/snaps")) {
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let Some(stem) = file_name.strip_suffix(".snap") else {
+                continue;
+            };
+            let name = stem.rsplit_once('_').map_or(stem, |(name, _)| name);
+            if !names.iter().any(|(n, _)| n == name) {
+                names.push((name.to_string(), None));
+            }
+        }
+    }
+
+    names
+        .into_iter()
+        .map(|(name, location)| {
+            let mut package = LinuxPackage::new(&name, PackageManager::Snap);
+            package.install_location = location.map(|p| p.to_string_lossy().to_string());
+            package
+        })
+        .collect()
+}
+
+/// 一个疑似残留的条目
+#[derive(Debug, Clone)]
+pub struct LinuxResidueCandidate {
+    pub path: PathBuf,
+    pub residue_type: ResidueType,
+    pub app_name: String,
+    pub confidence: f32,
+    /// 按置信度从高到低
+    pub evidence: Vec<ResidueEvidence>,
+}
+
+impl LinuxResidueCandidate {
+    pub fn description(&self) -> String {
+        self.evidence
+            .first()
+            .map(|e| e.description())
+            .unwrap_or_else(|| self.residue_type.description().to_string())
+    }
+}
+
+/// 查找各位置下不属于已安装软件的条目，清单为空时不返回任何结果
+pub fn find_linux_residue(
+    inventory: &LinuxInventory,
+    roots: &LinuxRoots,
+) -> Vec<LinuxResidueCandidate> {
+    if inventory.is_empty() {
+        return Vec::new();
+    }
+
+    let etc = roots.root.join("etc");
+    let mut candidates = Vec::new();
+    for (dir, residue_type, sandbox) in roots.locations() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        let is_etc = dir == etc;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            let candidate = match sandbox {
+                Some(manager) => check_sandbox_entry(inventory, &path, &name, manager),
+                None if is_etc => check_etc_entry(inventory, &path, &name),
+                None => check_home_entry(inventory, &path, &name),
+            };
+            if let Some((app_name, confidence, evidence)) = candidate {
+                candidates.push(LinuxResidueCandidate {
+                    path,
+                    residue_type: residue_type.clone(),
+                    app_name,
+                    confidence,
+                    evidence,
+                });
+            }
+        }
+    }
+
+    candidates.extend(find_saved_configs(&etc));
+    candidates
+}
+
+type EntryVerdict = (String, f32, Vec<ResidueEvidence>);
+
+/// `~/.var/app`、`~/snap` 下的目录以应用 id 命名，只与同一包管理器的应用精确比对
+fn check_sandbox_entry(
+    inventory: &LinuxInventory,
+    path: &Path,
+    name: &str,
+    manager: PackageManager,
+) -> Option<EntryVerdict> {
+    if !path.is_dir() || (manager == PackageManager::Snap && name == "bin") {
+        return None;
+    }
+    let has_manager = inventory.installed().any(|p| p.manager == manager);
+    if !has_manager
+        || inventory
+            .installed()
+            .any(|p| p.manager == manager && p.name.eq_ignore_ascii_case(name))
+    {
+        return None;
+    }
+    Some((
+        name.to_string(),
+        CONFIDENCE_SANDBOX_DATA,
+        vec![ResidueEvidence::SandboxAppRemoved {
+            manager,
+            app_id: name.to_string(),
+        }],
+    ))
+}
+
+/// `/etc` 中只报告有软件包卸载记录的条目
+fn check_etc_entry(inventory: &LinuxInventory, path: &Path, name: &str) -> Option<EntryVerdict> {
+    let path_str = path.to_string_lossy().to_string();
+    let system_path = format!("/etc/{}", name);
+    let owned_by = |p: &LinuxPackage| p.owns(&system_path) || p.owns(&path_str);
+
+    if inventory.installed().any(owned_by) {
+        return None;
+    }
+    if let Some(package) = inventory.removed().find(|p| owned_by(p)) {
+        return Some((
+            package.name.clone(),
+            CONFIDENCE_OWNED_BY_REMOVED,
+            vec![ResidueEvidence::OwnedByRemovedPackage {
+                manager: package.manager,
+                package: package.name.clone(),
+            }],
+        ));
+    }
+
+    let normalized = normalize_entry(name);
+    if is_shared_entry(&normalized) || inventory.installed().any(|p| p.matches(&normalized)) {
+        return None;
+    }
+    let package = inventory.removed().find(|p| p.matches(&normalized))?;
+    Some((
+        package.name.clone(),
+        CONFIDENCE_REMOVED_PACKAGE,
+        vec![ResidueEvidence::RemovedPackage {
+            manager: package.manager,
+            package: package.name.clone(),
+        }],
+    ))
+}
+
+fn check_home_entry(inventory: &LinuxInventory, path: &Path, name: &str) -> Option<EntryVerdict> {
+    let normalized = normalize_entry(name);
+    if normalized.is_empty()
+        || is_shared_entry(&normalized)
+        || inventory.installed().any(|p| p.matches(&normalized))
+    {
+        return None;
+    }
+
+    let mut evidence = Vec::new();
+    let mut confidence = 0.0f32;
+    let mut app_name = name.to_string();
+
+    if let Some(package) = inventory.removed().find(|p| p.matches(&normalized)) {
+        evidence.push(ResidueEvidence::RemovedPackage {
+            manager: package.manager,
+            package: package.name.clone(),
+        });
+        confidence = confidence.max(CONFIDENCE_REMOVED_PACKAGE);
+        app_name = package.name.clone();
+    }
+    if evidence.is_empty() {
+        let idle = fs::symlink_metadata(path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| SystemTime::now().duration_since(t).ok())?;
+        if idle < STALE_AFTER {
+            return None;
+        }
+        evidence.push(ResidueEvidence::LastWrite {
+            days: idle.as_secs() / (24 * 60 * 60),
+        });
+        confidence = CONFIDENCE_STALE;
+    }
+    evidence.push(ResidueEvidence::NotInInventory);
+
+    Some((app_name, confidence, evidence))
+}
+
+/// 查找 `/etc` 及其下一级目录中的 `.rpmsave`、`.pacsave`
+fn find_saved_configs(etc: &Path) -> Vec<LinuxResidueCandidate> {
+    let mut candidates = Vec::new();
+    let mut dirs = vec![(etc.to_path_buf(), 0)];
+
+    while let Some((dir, depth)) = dirs.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+            if is_dir {
+                if depth == 0 {
+                    dirs.push((path, depth + 1));
+                }
+                continue;
+            }
+            let Some((stem, manager)) = SAVED_CONFIG_SUFFIXES
+                .iter()
+                .find_map(|(suffix, manager)| name.strip_suffix(suffix).map(|s| (s, *manager)))
+            else {
+                continue;
+            };
+            candidates.push(LinuxResidueCandidate {
+                path,
+                residue_type: ResidueType::ConfigFile,
+                app_name: stem.to_string(),
+                confidence: CONFIDENCE_SAVED_CONFIG,
+                evidence: vec![ResidueEvidence::SavedConfig { manager }],
+            });
+        }
+    }
+
+    candidates
+}
+
+/// 小写，去掉开头的 `.` 与常见的配置后缀
+fn normalize_entry(name: &str) -> String {
+    let name = name.trim_start_matches('.').to_lowercase();
+    [".conf", ".d", ".cfg"]
+        .iter()
+        .find_map(|suffix| name.strip_suffix(suffix))
+        .map(str::to_string)
+        .unwrap_or(name)
+}
+
+fn is_shared_entry(name: &str) -> bool {
+    SHARED_ENTRIES.contains(&name)
+}
+
+/// 目录名与软件包名相同，或其中一个是另一个加 `-` 后缀（如 `google-chrome` 与
+/// `google-chrome-stable`）
+fn names_match(entry: &str, package: &str) -> bool {
+    fn has_dash_prefix(long: &str, short: &str) -> bool {
+        long.strip_prefix(short)
+            .is_some_and(|rest| rest.starts_with('-'))
+    }
+    entry == package || has_dash_prefix(package, entry) || has_dash_prefix(entry, package)
+}
@@ -1,3 +1,8 @@
+pub mod confidence;
+pub mod inventory;
+pub mod linux;
+pub mod registry;
+pub mod rpmdb;
 pub mod scanner;
 
 pub use scanner::*;
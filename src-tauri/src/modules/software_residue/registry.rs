@@ -0,0 +1,1033 @@
+//! 注册表访问、`.reg` 导入导出与残留项判定
+//!
+//! 扫描与删除都通过 [`RegistryBackend`] 访问注册表：Windows 上由 winreg 实现，
+//! 其他系统和测试中使用从 `.reg` 文本加载的 [`MemoryRegistry`]。删除注册表项之前
+//! 用 [`RegBackup`] 把整棵子树导出为带时间戳的 `.reg` 文件，恢复时用
+//! [`import_reg`] 重新导入，格式与 regedit 导出的文件相同。
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, PoisonError, RwLock};
+
+use super::scanner::{InstalledSoftware, KNOWN_APP_PATTERNS};
+use crate::models::DiskTidyError;
+use crate::utils::app_path_resolver::get_default_config_dir;
+
+pub const REG_FILE_HEADER: &str = "Windows Registry Editor Version 5.00";
+const REG_FILE_HEADER_V4: &str = "REGEDIT4";
+pub const REGISTRY_BACKUP_DIR: &str = "registry-backups";
+
+pub const REG_NONE: u32 = 0;
+pub const REG_SZ: u32 = 1;
+pub const REG_EXPAND_SZ: u32 = 2;
+pub const REG_BINARY: u32 = 3;
+pub const REG_DWORD: u32 = 4;
+pub const REG_MULTI_SZ: u32 = 7;
+pub const REG_QWORD: u32 = 11;
+
+/// 根键的完整名称与缩写
+const ROOT_KEYS: &[(&str, &str)] = &[
+    ("HKEY_LOCAL_MACHINE", "HKLM"),
+    ("HKEY_CURRENT_USER", "HKCU"),
+    ("HKEY_CLASSES_ROOT", "HKCR"),
+    ("HKEY_USERS", "HKU"),
+    ("HKEY_CURRENT_CONFIG", "HKCC"),
+];
+
+/// 记录已安装软件的卸载信息的位置
+pub const UNINSTALL_KEYS: &[&str] = &[
+    "HKLM\\SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Uninstall",
+    "HKLM\\SOFTWARE\\WOW6432Node\\Microsoft\\Windows\\CurrentVersion\\Uninstall",
+    "HKCU\\SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Uninstall",
+];
+
+/// 查找残留项的位置，软件通常在其下以厂商或产品名建立子项
+pub const SOFTWARE_KEYS: &[&str] = &["HKLM\\SOFTWARE", "HKCU\\SOFTWARE"];
+
+/// `SOFTWARE` 下由系统维护的子项，不作为残留
+const SYSTEM_SUBKEYS: &[&str] = &[
+    "classes",
+    "clients",
+    "microsoft",
+    "policies",
+    "registeredapplications",
+    "windows",
+    "wow6432node",
+    "odbc",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistryValue {
+    String(String),
+    ExpandString(String),
+    MultiString(Vec<String>),
+    Dword(u32),
+    Qword(u64),
+    Binary(Vec<u8>),
+    /// 其他类型，保留类型编号与原始数据
+    Raw {
+        kind: u32,
+        data: Vec<u8>,
+    },
+}
+
+impl RegistryValue {
+    pub fn kind(&self) -> u32 {
+        match self {
+            Self::String(_) => REG_SZ,
+            Self::ExpandString(_) => REG_EXPAND_SZ,
+            Self::MultiString(_) => REG_MULTI_SZ,
+            Self::Dword(_) => REG_DWORD,
+            Self::Qword(_) => REG_QWORD,
+            Self::Binary(_) => REG_BINARY,
+            Self::Raw { kind, .. } => *kind,
+        }
+    }
+
+    /// 注册表中保存的原始数据，字符串为以 0 结尾的 UTF-16LE
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::String(s) | Self::ExpandString(s) => encode_utf16(&[s.as_str()]),
+            Self::MultiString(items) => {
+                let mut items: Vec<&str> = items.iter().map(String::as_str).collect();
+                items.push("");
+                encode_utf16(&items)
+            }
+            Self::Dword(v) => v.to_le_bytes().to_vec(),
+            Self::Qword(v) => v.to_le_bytes().to_vec(),
+            Self::Binary(data) | Self::Raw { data, .. } => data.clone(),
+        }
+    }
+
+    pub fn from_raw(kind: u32, data: &[u8]) -> Self {
+        match kind {
+            REG_SZ => Self::String(decode_utf16(data).join("")),
+            REG_EXPAND_SZ => Self::ExpandString(decode_utf16(data).join("")),
+            REG_MULTI_SZ => Self::MultiString(
+                decode_utf16(data)
+                    .into_iter()
+                    .filter(|s| !s.is_empty())
+                    .collect(),
+            ),
+            REG_DWORD if data.len() == 4 => {
+                Self::Dword(u32::from_le_bytes([data[0], data[1], data[2], data[3]]))
+            }
+            REG_QWORD if data.len() == 8 => {
+                let mut bytes = [0u8; 8];
+                bytes.copy_from_slice(data);
+                Self::Qword(u64::from_le_bytes(bytes))
+            }
+            REG_BINARY => Self::Binary(data.to_vec()),
+            _ => Self::Raw {
+                kind,
+                data: data.to_vec(),
+            },
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) | Self::ExpandString(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+/// 每个字符串以 0 结尾
+fn encode_utf16(items: &[&str]) -> Vec<u8> {
+    items
+        .iter()
+        .flat_map(|s| s.encode_utf16().chain(std::iter::once(0)))
+        .flat_map(u16::to_le_bytes)
+        .collect()
+}
+
+/// 按 0 分隔，忽略末尾多余的 0
+fn decode_utf16(data: &[u8]) -> Vec<String> {
+    let units: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+    let mut items: Vec<String> = units
+        .split(|&u| u == 0)
+        .map(String::from_utf16_lossy)
+        .collect();
+    while items.len() > 1 && items.last().is_some_and(|s| s.is_empty()) {
+        items.pop();
+    }
+    items
+}
+
+/// 注册表的读写接口，路径为 `HKLM\SOFTWARE\...` 或 `HKEY_LOCAL_MACHINE\SOFTWARE\...`
+pub trait RegistryBackend: Send + Sync {
+    fn key_exists(&self, path: &str) -> bool;
+
+    /// 直接子项的名称
+    fn subkeys(&self, path: &str) -> Result<Vec<String>, DiskTidyError>;
+
+    fn values(&self, path: &str) -> Result<Vec<(String, RegistryValue)>, DiskTidyError>;
+
+    /// 创建注册表项及其不存在的上级
+    fn create_key(&self, path: &str) -> Result<(), DiskTidyError>;
+
+    fn set_value(&self, path: &str, name: &str, value: &RegistryValue)
+        -> Result<(), DiskTidyError>;
+
+    fn delete_value(&self, path: &str, name: &str) -> Result<(), DiskTidyError>;
+
+    /// 删除注册表项及其所有子项
+    fn delete_key(&self, path: &str) -> Result<(), DiskTidyError>;
+
+    /// 读取一个值，名称不区分大小写
+    fn value(&self, path: &str, name: &str) -> Option<RegistryValue> {
+        self.values(path)
+            .ok()?
+            .into_iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v)
+    }
+}
+
+/// 当前系统的注册表，非 Windows 系统返回 `None`
+pub fn system_registry() -> Option<Arc<dyn RegistryBackend>> {
+    #[cfg(windows)]
+    {
+        Some(Arc::new(WindowsRegistry))
+    }
+
+    #[cfg(not(windows))]
+    None
+}
+
+/// 把根键缩写展开为完整名称，并去掉多余的 `\`；根键无效时返回 `None`
+pub fn normalize_key_path(path: &str) -> Option<String> {
+    let mut segments = path.split('\\').filter(|s| !s.is_empty());
+    let root = segments.next()?;
+    let root = ROOT_KEYS
+        .iter()
+        .find(|(full, short)| root.eq_ignore_ascii_case(full) || root.eq_ignore_ascii_case(short))
+        .map(|(full, _)| *full)?;
+    Some(
+        std::iter::once(root)
+            .chain(segments)
+            .collect::<Vec<_>>()
+            .join("\\"),
+    )
+}
+
+fn registry_error(path: &str, reason: impl Into<String>) -> DiskTidyError {
+    DiskTidyError::RegistryOperationFailed {
+        path: path.to_string(),
+        reason: reason.into(),
+    }
+}
+
+fn normalized(path: &str) -> Result<String, DiskTidyError> {
+    normalize_key_path(path).ok_or_else(|| registry_error(path, "无效的根键"))
+}
+
+#[derive(Debug, Clone, Default)]
+struct MemoryKey {
+    /// 保留创建时的大小写
+    path: String,
+    values: Vec<(String, RegistryValue)>,
+}
+
+/// 内存中的注册表，名称不区分大小写
+#[derive(Debug, Default)]
+pub struct MemoryRegistry {
+    keys: RwLock<BTreeMap<String, MemoryKey>>,
+}
+
+impl MemoryRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_reg(text: &str) -> Result<Self, DiskTidyError> {
+        let registry = Self::new();
+        import_reg(&registry, text)?;
+        Ok(registry)
+    }
+}
+
+impl RegistryBackend for MemoryRegistry {
+    fn key_exists(&self, path: &str) -> bool {
+        let Some(path) = normalize_key_path(path) else {
+            return false;
+        };
+        let keys = self.keys.read().unwrap_or_else(PoisonError::into_inner);
+        keys.contains_key(&path.to_lowercase())
+    }
+
+    fn subkeys(&self, path: &str) -> Result<Vec<String>, DiskTidyError> {
+        let key = normalized(path)?.to_lowercase();
+        let keys = self.keys.read().unwrap_or_else(PoisonError::into_inner);
+        if !keys.contains_key(&key) {
+            return Err(registry_error(path, "注册表项不存在"));
+        }
+        let prefix = format!("{}\\", key);
+        Ok(keys
+            .iter()
+            .filter(|(k, _)| {
+                k.strip_prefix(&prefix)
+                    .is_some_and(|rest| !rest.contains('\\'))
+            })
+            .filter_map(|(_, v)| v.path.rsplit('\\').next().map(str::to_string))
+            .collect())
+    }
+
+    fn values(&self, path: &str) -> Result<Vec<(String, RegistryValue)>, DiskTidyError> {
+        let key = normalized(path)?.to_lowercase();
+        let keys = self.keys.read().unwrap_or_else(PoisonError::into_inner);
+        keys.get(&key)
+            .map(|k| k.values.clone())
+            .ok_or_else(|| registry_error(path, "注册表项不存在"))
+    }
+
+    fn create_key(&self, path: &str) -> Result<(), DiskTidyError> {
+        let path = normalized(path)?;
+        let mut keys = self.keys.write().unwrap_or_else(PoisonError::into_inner);
+        let mut current = String::new();
+        for segment in path.split('\\') {
+            if !current.is_empty() {
+                current.push('\\');
+            }
+            current.push_str(segment);
+            keys.entry(current.to_lowercase())
+                .or_insert_with(|| MemoryKey {
+                    path: current.clone(),
+                    values: Vec::new(),
+                });
+        }
+        Ok(())
+    }
+
+    fn set_value(
+        &self,
+        path: &str,
+        name: &str,
+        value: &RegistryValue,
+    ) -> Result<(), DiskTidyError> {
+        self.create_key(path)?;
+        let key = normalized(path)?.to_lowercase();
+        let mut keys = self.keys.write().unwrap_or_else(PoisonError::into_inner);
+        let values = &mut keys.entry(key).or_default().values;
+        match values
+            .iter_mut()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        {
+            Some((_, existing)) => *existing = value.clone(),
+            None => values.push((name.to_string(), value.clone())),
+        }
+        Ok(())
+    }
+
+    fn delete_value(&self, path: &str, name: &str) -> Result<(), DiskTidyError> {
+        let key = normalized(path)?.to_lowercase();
+        let mut keys = self.keys.write().unwrap_or_else(PoisonError::into_inner);
+        let values = &mut keys
+            .get_mut(&key)
+            .ok_or_else(|| registry_error(path, "注册表项不存在"))?
+            .values;
+        let before = values.len();
+        values.retain(|(n, _)| !n.eq_ignore_ascii_case(name));
+        if values.len() == before {
+            return Err(registry_error(path, format!("值 {} 不存在", name)));
+        }
+        Ok(())
+    }
+
+    fn delete_key(&self, path: &str) -> Result<(), DiskTidyError> {
+        let key = normalized(path)?.to_lowercase();
+        if !key.contains('\\') {
+            return Err(registry_error(path, "不能删除根键"));
+        }
+        let mut keys = self.keys.write().unwrap_or_else(PoisonError::into_inner);
+        if keys.remove(&key).is_none() {
+            return Err(registry_error(path, "注册表项不存在"));
+        }
+        let prefix = format!("{}\\", key);
+        keys.retain(|k, _| !k.starts_with(&prefix));
+        Ok(())
+    }
+}
+
+/// 通过 winreg 访问本机注册表
+#[cfg(windows)]
+pub struct WindowsRegistry;
+
+#[cfg(windows)]
+impl WindowsRegistry {
+    /// 返回根键与子路径
+    fn split(path: &str) -> Result<(winreg::RegKey, String), DiskTidyError> {
+        use winreg::enums::*;
+
+        let path = normalized(path)?;
+        let (root, subpath) = path.split_once('\\').unwrap_or((path.as_str(), ""));
+        let hkey = match root {
+            "HKEY_LOCAL_MACHINE" => HKEY_LOCAL_MACHINE,
+            "HKEY_CURRENT_USER" => HKEY_CURRENT_USER,
+            "HKEY_CLASSES_ROOT" => HKEY_CLASSES_ROOT,
+            "HKEY_USERS" => HKEY_USERS,
+            _ => HKEY_CURRENT_CONFIG,
+        };
+        Ok((winreg::RegKey::predef(hkey), subpath.to_string()))
+    }
+
+    fn open(path: &str, flags: u32) -> Result<winreg::RegKey, DiskTidyError> {
+        let (root, subpath) = Self::split(path)?;
+        if subpath.is_empty() {
+            return Ok(root);
+        }
+        root.open_subkey_with_flags(&subpath, flags)
+            .map_err(|e| registry_error(path, e.to_string()))
+    }
+
+    fn reg_type(kind: u32) -> winreg::enums::RegType {
+        use winreg::enums::RegType;
+
+        match kind {
+            REG_SZ => RegType::REG_SZ,
+            REG_EXPAND_SZ => RegType::REG_EXPAND_SZ,
+            REG_BINARY => RegType::REG_BINARY,
+            REG_DWORD => RegType::REG_DWORD,
+            5 => RegType::REG_DWORD_BIG_ENDIAN,
+            6 => RegType::REG_LINK,
+            REG_MULTI_SZ => RegType::REG_MULTI_SZ,
+            8 => RegType::REG_RESOURCE_LIST,
+            9 => RegType::REG_FULL_RESOURCE_DESCRIPTOR,
+            10 => RegType::REG_RESOURCE_REQUIREMENTS_LIST,
+            REG_QWORD => RegType::REG_QWORD,
+            _ => RegType::REG_NONE,
+        }
+    }
+}
+
+#[cfg(windows)]
+impl RegistryBackend for WindowsRegistry {
+    fn key_exists(&self, path: &str) -> bool {
+        Self::open(path, winreg::enums::KEY_READ).is_ok()
+    }
+
+    fn subkeys(&self, path: &str) -> Result<Vec<String>, DiskTidyError> {
+        let key = Self::open(path, winreg::enums::KEY_READ)?;
+        Ok(key.enum_keys().filter_map(|k| k.ok()).collect())
+    }
+
+    fn values(&self, path: &str) -> Result<Vec<(String, RegistryValue)>, DiskTidyError> {
+        let key = Self::open(path, winreg::enums::KEY_READ)?;
+        Ok(key
+            .enum_values()
+            .filter_map(|v| v.ok())
+            .map(|(name, value)| {
+                let value = RegistryValue::from_raw(value.vtype as u32, &value.bytes);
+                (name, value)
+            })
+            .collect())
+    }
+
+    fn create_key(&self, path: &str) -> Result<(), DiskTidyError> {
+        let (root, subpath) = Self::split(path)?;
+        root.create_subkey(&subpath)
+            .map(|_| ())
+            .map_err(|e| registry_error(path, e.to_string()))
+    }
+
+    fn set_value(
+        &self,
+        path: &str,
+        name: &str,
+        value: &RegistryValue,
+    ) -> Result<(), DiskTidyError> {
+        let (root, subpath) = Self::split(path)?;
+        let (key, _) = root
+            .create_subkey(&subpath)
+            .map_err(|e| registry_error(path, e.to_string()))?;
+        let raw = winreg::RegValue {
+            bytes: value.to_bytes(),
+            vtype: Self::reg_type(value.kind()),
+        };
+        key.set_raw_value(name, &raw)
+            .map_err(|e| registry_error(path, e.to_string()))
+    }
+
+    fn delete_value(&self, path: &str, name: &str) -> Result<(), DiskTidyError> {
+        let key = Self::open(path, winreg::enums::KEY_SET_VALUE)?;
+        key.delete_value(name)
+            .map_err(|e| registry_error(path, e.to_string()))
+    }
+
+    fn delete_key(&self, path: &str) -> Result<(), DiskTidyError> {
+        let (root, subpath) = Self::split(path)?;
+        if subpath.is_empty() {
+            return Err(registry_error(path, "不能删除根键"));
+        }
+        root.delete_subkey_all(&subpath)
+            .map_err(|e| registry_error(path, e.to_string()))
+    }
+}
+
+/// `.reg` 文件中的一条操作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegOperation {
+    CreateKey(String),
+    /// `[-HKEY_...]`
+    DeleteKey(String),
+    SetValue {
+        key: String,
+        name: String,
+        value: RegistryValue,
+    },
+    /// `"name"=-`
+    DeleteValue {
+        key: String,
+        name: String,
+    },
+}
+
+/// 解析 `.reg` 文本，行尾的 `\` 表示下一行是续行
+pub fn parse_reg(text: &str) -> Result<Vec<RegOperation>, DiskTidyError> {
+    let invalid = |line: usize, reason: &str| DiskTidyError::RegFileInvalid {
+        line,
+        reason: reason.to_string(),
+    };
+
+    let mut lines = text.trim_start_matches('\u{feff}').lines().enumerate();
+    let mut operations = Vec::new();
+    let mut current_key: Option<String> = None;
+    let mut has_header = false;
+
+    while let Some((index, line)) = lines.next() {
+        let line_number = index + 1;
+        let mut line = line.trim().to_string();
+        while line.ends_with('\\') {
+            line.pop();
+            match lines.next() {
+                Some((_, next)) => line.push_str(next.trim()),
+                None => break,
+            }
+        }
+
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+        if !has_header {
+            if line != REG_FILE_HEADER && line != REG_FILE_HEADER_V4 {
+                return Err(invalid(line_number, "缺少文件头"));
+            }
+            has_header = true;
+            continue;
+        }
+
+        if let Some(inner) = line.strip_prefix('[') {
+            let inner = inner
+                .strip_suffix(']')
+                .ok_or_else(|| invalid(line_number, "注册表项缺少 ]"))?;
+            let (delete, path) = match inner.strip_prefix('-') {
+                Some(path) => (true, path),
+                None => (false, inner),
+            };
+            let path =
+                normalize_key_path(path).ok_or_else(|| invalid(line_number, "无效的根键"))?;
+            if delete {
+                operations.push(RegOperation::DeleteKey(path));
+                current_key = None;
+            } else {
+                operations.push(RegOperation::CreateKey(path.clone()));
+                current_key = Some(path);
+            }
+            continue;
+        }
+
+        let key = current_key
+            .clone()
+            .ok_or_else(|| invalid(line_number, "值不属于任何注册表项"))?;
+        let (name, rest) = if let Some(rest) = line.strip_prefix('@') {
+            (String::new(), rest)
+        } else {
+            parse_quoted(&line).ok_or_else(|| invalid(line_number, "值名称格式错误"))?
+        };
+        let data = rest
+            .trim_start()
+            .strip_prefix('=')
+            .ok_or_else(|| invalid(line_number, "缺少 ="))?
+            .trim();
+
+        if data == "-" {
+            operations.push(RegOperation::DeleteValue { key, name });
+            continue;
+        }
+        let value = parse_value(data).ok_or_else(|| invalid(line_number, "值格式错误"))?;
+        operations.push(RegOperation::SetValue { key, name, value });
+    }
+
+    if !has_header {
+        return Err(invalid(1, "缺少文件头"));
+    }
+    Ok(operations)
+}
+
+/// 解析以 `"` 开头的字符串，返回内容与其后的部分
+fn parse_quoted(text: &str) -> Option<(String, &str)> {
+    let body = text.strip_prefix('"')?;
+    let mut value = String::new();
+    let mut chars = body.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => value.push(chars.next()?.1),
+            '"' => return Some((value, &body[i + 1..])),
+            c => value.push(c),
+        }
+    }
+    None
+}
+
+fn parse_value(data: &str) -> Option<RegistryValue> {
+    if data.starts_with('"') {
+        let (value, rest) = parse_quoted(data)?;
+        return rest
+            .trim()
+            .is_empty()
+            .then_some(RegistryValue::String(value));
+    }
+    if let Some(hex) = data.strip_prefix("dword:") {
+        return u32::from_str_radix(hex.trim(), 16)
+            .ok()
+            .map(RegistryValue::Dword);
+    }
+    if let Some(bytes) = data.strip_prefix("hex:") {
+        return parse_hex_bytes(bytes).map(RegistryValue::Binary);
+    }
+    let rest = data.strip_prefix("hex(")?;
+    let (kind, bytes) = rest.split_once("):")?;
+    let kind = u32::from_str_radix(kind, 16).ok()?;
+    Some(RegistryValue::from_raw(kind, &parse_hex_bytes(bytes)?))
+}
+
+fn parse_hex_bytes(text: &str) -> Option<Vec<u8>> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| u8::from_str_radix(s, 16).ok())
+        .collect()
+}
+
+/// 导入结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RegImportSummary {
+    pub keys: usize,
+    pub values: usize,
+    pub deleted_keys: usize,
+}
+
+/// 导入 `.reg` 文本；先解析整个文件，格式错误时不做任何修改
+pub fn import_reg(
+    backend: &dyn RegistryBackend,
+    text: &str,
+) -> Result<RegImportSummary, DiskTidyError> {
+    apply_reg_operations(backend, parse_reg(text)?)
+}
+
+fn apply_reg_operations(
+    backend: &dyn RegistryBackend,
+    operations: Vec<RegOperation>,
+) -> Result<RegImportSummary, DiskTidyError> {
+    let mut summary = RegImportSummary::default();
+
+    for operation in operations {
+        match operation {
+            RegOperation::CreateKey(path) => {
+                backend.create_key(&path)?;
+                summary.keys += 1;
+            }
+            RegOperation::DeleteKey(path) => {
+                if backend.key_exists(&path) {
+                    backend.delete_key(&path)?;
+                    summary.deleted_keys += 1;
+                }
+            }
+            RegOperation::SetValue { key, name, value } => {
+                backend.set_value(&key, &name, &value)?;
+                summary.values += 1;
+            }
+            RegOperation::DeleteValue { key, name } => {
+                // 值本来就不存在时视为成功
+                let _ = backend.delete_value(&key, &name);
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+/// 把注册表项及其所有子项导出为 `.reg` 文本，不存在的项被忽略
+pub fn export_reg(
+    backend: &dyn RegistryBackend,
+    paths: &[String],
+) -> Result<String, DiskTidyError> {
+    let mut output = format!("{}\r\n\r\n", REG_FILE_HEADER);
+    for path in paths {
+        if backend.key_exists(path) {
+            export_key(backend, &normalized(path)?, &mut output)?;
+        }
+    }
+    Ok(output)
+}
+
+fn export_key(
+    backend: &dyn RegistryBackend,
+    path: &str,
+    output: &mut String,
+) -> Result<(), DiskTidyError> {
+    output.push_str(&format!("[{}]\r\n", path));
+    for (name, value) in backend.values(path)? {
+        output.push_str(&format_value(&name, &value));
+        output.push_str("\r\n");
+    }
+    output.push_str("\r\n");
+
+    for subkey in backend.subkeys(path)? {
+        export_key(backend, &format!("{}\\{}", path, subkey), output)?;
+    }
+    Ok(())
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// 按 regedit 的格式输出一个值，十六进制数据每行不超过 80 列；
+/// 含换行的字符串无法写成一行带引号的文本，改用 `hex(1):` 保存 UTF-16LE 数据
+fn format_value(name: &str, value: &RegistryValue) -> String {
+    let mut line = if name.is_empty() {
+        "@=".to_string()
+    } else {
+        format!("{}=", quote(name))
+    };
+
+    let bytes = match value {
+        RegistryValue::String(s) if !s.contains(['\r', '\n']) => return line + &quote(s),
+        RegistryValue::Dword(v) => return format!("{}dword:{:08x}", line, v),
+        RegistryValue::Binary(data) => {
+            line.push_str("hex:");
+            data.clone()
+        }
+        other => {
+            line.push_str(&format!("hex({:x}):", other.kind()));
+            other.to_bytes()
+        }
+    };
+
+    let mut column = line.len();
+    for (i, byte) in bytes.iter().enumerate() {
+        line.push_str(&format!("{:02x}", byte));
+        column += 2;
+        if i + 1 < bytes.len() {
+            line.push(',');
+            column += 1;
+            if column > 76 {
+                line.push_str("\\\r\n  ");
+                column = 2;
+            }
+        }
+    }
+    line
+}
+
+/// 读取 `.reg` 文件，支持 regedit 默认的 UTF-16LE 和 UTF-8
+pub fn read_reg_file(path: &Path) -> Result<String, DiskTidyError> {
+    let data = fs::read(path)?;
+    match data.strip_prefix(&[0xFF, 0xFE]) {
+        Some(utf16) => Ok(decode_utf16(utf16).join("")),
+        None => Ok(String::from_utf8_lossy(&data).to_string()),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegBackupFile {
+    pub path: String,
+    pub size: u64,
+    pub created_at: i64,
+}
+
+/// 删除前的注册表备份目录
+pub struct RegBackup {
+    dir: PathBuf,
+}
+
+impl RegBackup {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn default_dir() -> Option<PathBuf> {
+        get_default_config_dir().map(|d| d.join(REGISTRY_BACKUP_DIR))
+    }
+
+    /// 导出到带时间戳的 `.reg` 文件（UTF-16LE，与 regedit 相同），返回文件路径
+    pub fn write(
+        &self,
+        backend: &dyn RegistryBackend,
+        paths: &[String],
+    ) -> Result<PathBuf, DiskTidyError> {
+        let text = export_reg(backend, paths)?;
+        fs::create_dir_all(&self.dir)?;
+        let path = self.unique_path();
+
+        let mut data = vec![0xFF, 0xFE];
+        data.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+        fs::write(&path, data)?;
+        log::info!(
+            "Backed up {} registry keys to {}",
+            paths.len(),
+            path.display()
+        );
+        Ok(path)
+    }
+
+    /// 从本目录中的备份恢复
+    ///
+    /// 只接受本目录中的 `.reg` 文件。备份由 [`export_reg`] 生成，只包含创建项与
+    /// 设置值，含有删除操作的文件视为已被修改，不导入。
+    pub fn restore(
+        &self,
+        backend: &dyn RegistryBackend,
+        path: &Path,
+    ) -> Result<RegImportSummary, DiskTidyError> {
+        let not_backup = || DiskTidyError::InvalidParameter {
+            message: format!("不是 DiskTidy 创建的注册表备份: {}", path.display()),
+        };
+        let dir = fs::canonicalize(&self.dir).map_err(|_| not_backup())?;
+        let file = fs::canonicalize(path).map_err(|_| not_backup())?;
+        if file.parent() != Some(dir.as_path())
+            || file.extension().and_then(|ext| ext.to_str()) != Some("reg")
+        {
+            return Err(not_backup());
+        }
+
+        let operations = parse_reg(&read_reg_file(&file)?)?;
+        if operations.iter().any(|op| {
+            matches!(
+                op,
+                RegOperation::DeleteKey(_) | RegOperation::DeleteValue { .. }
+            )
+        }) {
+            return Err(DiskTidyError::InvalidParameter {
+                message: format!("备份文件中包含删除操作，可能已被修改: {}", path.display()),
+            });
+        }
+
+        let summary = apply_reg_operations(backend, operations)?;
+        log::info!(
+            "Restored {} registry keys and {} values from {}",
+            summary.keys,
+            summary.values,
+            file.display()
+        );
+        Ok(summary)
+    }
+
+    fn unique_path(&self) -> PathBuf {
+        let stem = format!(
+            "registry-backup-{}",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        );
+        let mut path = self.dir.join(format!("{}.reg", stem));
+        let mut index = 1;
+        while path.exists() {
+            path = self.dir.join(format!("{}-{}.reg", stem, index));
+            index += 1;
+        }
+        path
+    }
+
+    /// 已有的备份，最新的在前
+    pub fn list(&self) -> Vec<RegBackupFile> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut files: Vec<RegBackupFile> = entries
+            .flatten()
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "reg"))
+            .filter_map(|e| {
+                let metadata = e.metadata().ok()?;
+                let created_at = metadata
+                    .modified()
+                    .ok()?
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .ok()?
+                    .as_secs() as i64;
+                Some(RegBackupFile {
+                    path: e.path().to_string_lossy().to_string(),
+                    size: metadata.len(),
+                    created_at,
+                })
+            })
+            .collect();
+        files.sort_by_key(|f| std::cmp::Reverse((f.created_at, f.path.clone())));
+        files
+    }
+}
+
+/// 批量删除注册表项的结果
+#[derive(Debug, Clone, Default)]
+pub struct RegistryDeleteOutcome {
+    pub backup_path: Option<PathBuf>,
+    pub deleted: Vec<String>,
+    /// 路径与失败原因
+    pub failed: Vec<(String, String)>,
+}
+
+/// 先把要删除的注册表项整体备份，备份成功后再逐个删除
+pub fn delete_keys_with_backup(
+    backend: &dyn RegistryBackend,
+    backup: &RegBackup,
+    paths: &[String],
+) -> RegistryDeleteOutcome {
+    let mut outcome = RegistryDeleteOutcome::default();
+    let existing: Vec<String> = paths
+        .iter()
+        .filter(|path| {
+            let exists = backend.key_exists(path);
+            if !exists {
+                outcome
+                    .failed
+                    .push((path.to_string(), "注册表项不存在".to_string()));
+            }
+            exists
+        })
+        .cloned()
+        .collect();
+    if existing.is_empty() {
+        return outcome;
+    }
+
+    match backup.write(backend, &existing) {
+        Ok(path) => outcome.backup_path = Some(path),
+        Err(e) => {
+            let reason = format!("备份失败，未删除: {}", e);
+            outcome
+                .failed
+                .extend(existing.into_iter().map(|path| (path, reason.clone())));
+            return outcome;
+        }
+    }
+
+    for path in existing {
+        match backend.delete_key(&path) {
+            Ok(()) => outcome.deleted.push(path),
+            Err(e) => outcome.failed.push((path, e.to_string())),
+        }
+    }
+    outcome
+}
+
+/// 读取卸载信息中登记的已安装软件
+pub fn read_installed_software(backend: &dyn RegistryBackend) -> Vec<InstalledSoftware> {
+    let mut software = Vec::new();
+    for uninstall in UNINSTALL_KEYS {
+        let Ok(subkeys) = backend.subkeys(uninstall) else {
+            continue;
+        };
+        for subkey in subkeys {
+            let path = format!("{}\\{}", uninstall, subkey);
+            let string = |name: &str| {
+                backend
+                    .value(&path, name)
+                    .and_then(|v| v.as_str().map(str::to_string))
+                    .filter(|s| !s.is_empty())
+            };
+            let Some(name) = string("DisplayName") else {
+                continue;
+            };
+            software.push(InstalledSoftware {
+                name,
+                publisher: string("Publisher"),
+                install_location: string("InstallLocation"),
+                uninstall_string: string("UninstallString"),
+                version: string("DisplayVersion"),
+                install_date: string("InstallDate"),
+                installed_size: None,
+            });
+        }
+    }
+    software
+}
+
+/// 疑似残留的注册表项
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistryLeftover {
+    /// 如 `HKCU\SOFTWARE\Vendor`
+    pub path: String,
+    pub name: String,
+    pub app_name: String,
+    pub reason: LeftoverReason,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeftoverReason {
+    /// 名称与已知应用匹配，但没有对应的已安装软件
+    KnownApp,
+    /// 没有任何值和子项
+    EmptyKey,
+}
+
+/// 查找 `SOFTWARE` 下不属于任何已安装软件的子项
+///
+/// 子项名与已安装软件的名称、发布者互相包含时视为仍在使用。
+pub fn find_leftover_keys(
+    backend: &dyn RegistryBackend,
+    installed: &[InstalledSoftware],
+) -> Vec<RegistryLeftover> {
+    let owners: Vec<String> = installed
+        .iter()
+        .flat_map(|s| std::iter::once(&s.name).chain(s.publisher.as_ref()))
+        .map(|s| s.to_lowercase())
+        .filter(|s| !s.is_empty())
+        .collect();
+    let is_installed = |name: &str| {
+        owners
+            .iter()
+            .any(|owner| owner.contains(name) || name.contains(owner.as_str()))
+    };
+
+    let mut leftovers = Vec::new();
+    for root in SOFTWARE_KEYS {
+        let Ok(subkeys) = backend.subkeys(root) else {
+            continue;
+        };
+        for subkey in subkeys {
+            let lower = subkey.to_lowercase();
+            if SYSTEM_SUBKEYS.contains(&lower.as_str()) || is_installed(&lower) {
+                continue;
+            }
+
+            let path = format!("{}\\{}", root, subkey);
+            let known_app = KNOWN_APP_PATTERNS
+                .iter()
+                .find(|(pattern, _)| lower.contains(pattern.as_str()))
+                .map(|(_, app)| app.clone());
+            let leftover = match known_app {
+                Some(app_name) => Some((app_name, LeftoverReason::KnownApp)),
+                None => {
+                    let empty = backend.subkeys(&path).is_ok_and(|k| k.is_empty())
+                        && backend.values(&path).is_ok_and(|v| v.is_empty());
+                    empty.then(|| (subkey.clone(), LeftoverReason::EmptyKey))
+                }
+            };
+
+            if let Some((app_name, reason)) = leftover {
+                leftovers.push(RegistryLeftover {
+                    path,
+                    name: subkey,
+                    app_name,
+                    reason,
+                });
+            }
+        }
+    }
+    leftovers
+}
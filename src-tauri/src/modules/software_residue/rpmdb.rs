@@ -0,0 +1,352 @@
+//! 读取 rpm 数据库中的软件包名称
+//!
+//! 新版 rpm 把每个软件包的头部（header）作为 blob 存放在 `rpmdb.sqlite` 的
+//! `Packages` 表或 `Packages.db`（ndb 格式）中。这里只需要名称、版本与大小，因此
+//! 直接按文件格式读取，不依赖 sqlite 库：
+//! - sqlite：从 `sqlite_master` 找到表的根页，遍历表 B 树并拼接溢出页
+//! - ndb：按 16 字节对齐查找 `BlbS` 块头，块头后即为软件包头部
+//!
+//! 只读取主数据库文件，尚未合并的 WAL 中的改动会被忽略。
+
+use std::collections::HashSet;
+
+/// 从 rpm 软件包头部读取的信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpmHeader {
+    pub name: String,
+    pub version: Option<String>,
+    /// 已安装文件的总大小（字节）
+    pub size: Option<u64>,
+}
+
+const RPMTAG_NAME: u32 = 1000;
+const RPMTAG_VERSION: u32 = 1001;
+const RPMTAG_SIZE: u32 = 1009;
+/// 超过 4 GiB 的软件包用 64 位的大小代替 `RPMTAG_SIZE`
+const RPMTAG_LONGSIZE: u32 = 5009;
+const RPM_INT32_TYPE: u32 = 4;
+const RPM_INT64_TYPE: u32 = 5;
+const RPM_STRING_TYPE: u32 = 6;
+const RPM_I18NSTRING_TYPE: u32 = 9;
+
+/// 解析软件包头部（不含 8 字节的头部魔数）
+pub fn parse_header(blob: &[u8]) -> Option<RpmHeader> {
+    let index_len = read_be_u32(blob, 0)? as usize;
+    let data_len = read_be_u32(blob, 4)? as usize;
+    let store = index_len.checked_mul(16)?.checked_add(8)?;
+    if store.checked_add(data_len)? > blob.len() {
+        return None;
+    }
+    let data = &blob[store..store + data_len];
+
+    let mut name = None;
+    let mut version = None;
+    let mut size = None;
+    let mut long_size = None;
+    for i in 0..index_len {
+        let entry = 8 + i * 16;
+        let tag = read_be_u32(blob, entry)?;
+        let kind = read_be_u32(blob, entry + 4)?;
+        let offset = read_be_u32(blob, entry + 8)? as usize;
+        let string = || {
+            if kind != RPM_STRING_TYPE && kind != RPM_I18NSTRING_TYPE {
+                return None;
+            }
+            let bytes = data.get(offset..)?;
+            let end = bytes.iter().position(|&b| b == 0)?;
+            Some(String::from_utf8_lossy(&bytes[..end]).to_string())
+        };
+        match tag {
+            RPMTAG_NAME => name = string(),
+            RPMTAG_VERSION => version = string(),
+            RPMTAG_SIZE if kind == RPM_INT32_TYPE => {
+                size = read_be_u32(data, offset).map(u64::from)
+            }
+            RPMTAG_LONGSIZE if kind == RPM_INT64_TYPE => long_size = read_be_u64(data, offset),
+            _ => {}
+        }
+    }
+
+    Some(RpmHeader {
+        name: name.filter(|n| !n.is_empty())?,
+        version,
+        size: long_size.or(size),
+    })
+}
+
+/// 读取 `rpmdb.sqlite` 中 `Packages` 表的所有软件包
+pub fn read_sqlite_packages(data: &[u8]) -> Option<Vec<RpmHeader>> {
+    let db = SqliteFile::new(data)?;
+    let root = db.table_root("Packages")?;
+    let headers = db
+        .rows(root)
+        .into_iter()
+        .filter_map(|row| {
+            row.into_iter().find_map(|value| match value {
+                SqliteValue::Blob(blob) => Some(blob),
+                _ => None,
+            })
+        })
+        .filter_map(|blob| parse_header(&blob))
+        .collect();
+    Some(headers)
+}
+
+/// ndb 块头的魔数 `BlbS`
+const NDB_BLOB_MAGIC: &[u8] = b"BlbS";
+const NDB_BLOB_HEADER_LEN: usize = 16;
+const NDB_BLOCK_SIZE: usize = 16;
+
+/// 读取 `Packages.db`（ndb 格式）中的所有软件包
+pub fn read_ndb_packages(data: &[u8]) -> Vec<RpmHeader> {
+    let mut headers = Vec::new();
+    let mut offset = 0;
+    while offset + NDB_BLOB_HEADER_LEN <= data.len() {
+        if &data[offset..offset + 4] != NDB_BLOB_MAGIC {
+            offset += NDB_BLOCK_SIZE;
+            continue;
+        }
+        let package_index = read_le_u32(data, offset + 4).unwrap_or(0);
+        let blob_len = read_le_u32(data, offset + 12).unwrap_or(0) as usize;
+        let start = offset + NDB_BLOB_HEADER_LEN;
+        match data.get(start..start.saturating_add(blob_len)) {
+            Some(blob) if package_index != 0 => {
+                if let Some(header) = parse_header(blob) {
+                    headers.push(header);
+                }
+                offset = (start + blob_len).div_ceil(NDB_BLOCK_SIZE) * NDB_BLOCK_SIZE;
+            }
+            _ => offset += NDB_BLOCK_SIZE,
+        }
+    }
+    headers
+}
+
+fn read_be_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn read_be_u64(data: &[u8], offset: usize) -> Option<u64> {
+    let bytes = data.get(offset..offset + 8)?;
+    Some(u64::from_be_bytes(bytes.try_into().ok()?))
+}
+
+fn read_le_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum SqliteValue {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+const SQLITE_MAGIC: &[u8] = b"SQLite format 3\0";
+const SQLITE_HEADER_LEN: usize = 100;
+const TABLE_INTERIOR_PAGE: u8 = 0x05;
+const TABLE_LEAF_PAGE: u8 = 0x0D;
+
+/// 只读的 sqlite 文件，只支持遍历表 B 树
+struct SqliteFile<'a> {
+    data: &'a [u8],
+    page_size: usize,
+    usable_size: usize,
+}
+
+impl<'a> SqliteFile<'a> {
+    fn new(data: &'a [u8]) -> Option<Self> {
+        if data.len() < SQLITE_HEADER_LEN || !data.starts_with(SQLITE_MAGIC) {
+            return None;
+        }
+        let page_size = match u16::from_be_bytes([data[16], data[17]]) {
+            1 => 65536,
+            size => size as usize,
+        };
+        if page_size < 512 {
+            return None;
+        }
+        Some(Self {
+            data,
+            page_size,
+            usable_size: page_size - data[20] as usize,
+        })
+    }
+
+    fn page(&self, number: u32) -> Option<&'a [u8]> {
+        let start = (number as usize).checked_sub(1)? * self.page_size;
+        self.data.get(start..start + self.page_size)
+    }
+
+    /// 在 `sqlite_master` 中查找表的根页
+    fn table_root(&self, name: &str) -> Option<u32> {
+        self.rows(1).into_iter().find_map(|row| match row.as_slice() {
+            [SqliteValue::Text(kind), SqliteValue::Text(table), _, SqliteValue::Integer(root), ..]
+                if kind == "table" && table == name =>
+            {
+                u32::try_from(*root).ok()
+            }
+            _ => None,
+        })
+    }
+
+    /// 遍历表 B 树，返回所有行
+    fn rows(&self, root: u32) -> Vec<Vec<SqliteValue>> {
+        let mut rows = Vec::new();
+        let mut pending = vec![root];
+        let mut visited = HashSet::new();
+
+        while let Some(number) = pending.pop() {
+            if !visited.insert(number) {
+                continue;
+            }
+            let Some(page) = self.page(number) else {
+                continue;
+            };
+            let header = if number == 1 { SQLITE_HEADER_LEN } else { 0 };
+            let Some(&kind) = page.get(header) else {
+                continue;
+            };
+            let cell_count = read_be_u16(page, header + 3).unwrap_or(0) as usize;
+
+            match kind {
+                TABLE_INTERIOR_PAGE => {
+                    let pointers = header + 12;
+                    for i in 0..cell_count {
+                        let child = read_be_u16(page, pointers + i * 2)
+                            .and_then(|cell| read_be_u32(page, cell as usize));
+                        if let Some(child) = child {
+                            pending.push(child);
+                        }
+                    }
+                    if let Some(right) = read_be_u32(page, header + 8) {
+                        pending.push(right);
+                    }
+                }
+                TABLE_LEAF_PAGE => {
+                    let pointers = header + 8;
+                    for i in 0..cell_count {
+                        let row = read_be_u16(page, pointers + i * 2)
+                            .and_then(|cell| self.leaf_cell(page, cell as usize))
+                            .and_then(|payload| parse_record(&payload));
+                        if let Some(row) = row {
+                            rows.push(row);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        rows
+    }
+
+    /// 读取表叶子页单元格的完整负载，必要时拼接溢出页
+    fn leaf_cell(&self, page: &[u8], offset: usize) -> Option<Vec<u8>> {
+        let (payload_len, n) = read_varint(page.get(offset..)?)?;
+        let (_rowid, m) = read_varint(page.get(offset + n..)?)?;
+        let payload_len = usize::try_from(payload_len).ok()?;
+        let start = offset + n + m;
+
+        let usable = self.usable_size;
+        let max_local = usable - 35;
+        let local_len = if payload_len <= max_local {
+            payload_len
+        } else {
+            let min_local = (usable - 12) * 32 / 255 - 23;
+            let local = min_local + (payload_len - min_local) % (usable - 4);
+            if local <= max_local {
+                local
+            } else {
+                min_local
+            }
+        };
+
+        let mut payload = Vec::with_capacity(payload_len);
+        payload.extend_from_slice(page.get(start..start + local_len)?);
+        if local_len < payload_len {
+            let mut next = read_be_u32(page, start + local_len)?;
+            let mut visited = HashSet::new();
+            while payload.len() < payload_len {
+                if next == 0 || !visited.insert(next) {
+                    return None;
+                }
+                let overflow = self.page(next)?;
+                let take = (payload_len - payload.len()).min(usable - 4);
+                payload.extend_from_slice(overflow.get(4..4 + take)?);
+                next = read_be_u32(overflow, 0)?;
+            }
+        }
+        Some(payload)
+    }
+}
+
+fn read_be_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+/// 读取 sqlite 变长整数，返回值与占用的字节数
+fn read_varint(data: &[u8]) -> Option<(u64, usize)> {
+    let mut value: u64 = 0;
+    for (i, &byte) in data.iter().take(9).enumerate() {
+        if i == 8 {
+            return Some(((value << 8) | byte as u64, 9));
+        }
+        value = (value << 7) | (byte & 0x7F) as u64;
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+/// 解析记录格式：头部为各列的类型，之后依次为各列的值
+fn parse_record(payload: &[u8]) -> Option<Vec<SqliteValue>> {
+    let (header_len, mut pos) = read_varint(payload)?;
+    let header_len = usize::try_from(header_len).ok()?;
+    let mut types = Vec::new();
+    while pos < header_len {
+        let (serial_type, n) = read_varint(payload.get(pos..)?)?;
+        types.push(serial_type);
+        pos += n;
+    }
+
+    let mut body = header_len;
+    let mut values = Vec::with_capacity(types.len());
+    for serial_type in types {
+        let len = match serial_type {
+            0 | 8 | 9 => 0,
+            1..=4 => serial_type as usize,
+            5 => 6,
+            6 | 7 => 8,
+            n if n >= 12 => ((n - 12) / 2) as usize,
+            _ => return None,
+        };
+        let bytes = payload.get(body..body + len)?;
+        body += len;
+
+        let value = match serial_type {
+            0 => SqliteValue::Null,
+            8 => SqliteValue::Integer(0),
+            9 => SqliteValue::Integer(1),
+            1..=6 => {
+                // 大端补码，按符号位扩展
+                let mut value: i64 = if bytes[0] & 0x80 != 0 { -1 } else { 0 };
+                for &byte in bytes {
+                    value = (value << 8) | byte as i64;
+                }
+                SqliteValue::Integer(value)
+            }
+            7 => SqliteValue::Real(f64::from_be_bytes(bytes.try_into().ok()?)),
+            n if n % 2 == 0 => SqliteValue::Blob(bytes.to_vec()),
+            _ => SqliteValue::Text(String::from_utf8_lossy(bytes).to_string()),
+        };
+        values.push(value);
+    }
+    Some(values)
+}
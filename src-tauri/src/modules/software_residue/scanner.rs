@@ -11,11 +11,16 @@ use walkdir::WalkDir;
 
 use lazy_static::lazy_static;
 
+use super::confidence::{ResidueScorer, UninstalledProduct};
+use super::inventory;
+use super::linux::{self, LinuxInventory, LinuxResidueCandidate, LinuxRoots, PackageManager};
+use super::registry::{self, LeftoverReason, RegistryBackend};
 use crate::modules::cleaner::safety::SafetyChecker;
 use crate::utils::path::{PathUtils, SystemPaths};
+use crate::utils::path_match::{PathPrefix, SegmentPattern};
 
 lazy_static! {
-    static ref KNOWN_APP_PATTERNS: HashMap<String, String> = {
+    pub(crate) static ref KNOWN_APP_PATTERNS: HashMap<String, String> = {
         let mut patterns = HashMap::new();
         patterns.insert("wechat".to_string(), "微信".to_string());
         patterns.insert("tencent".to_string(), "腾讯".to_string());
@@ -56,8 +61,24 @@ lazy_static! {
         patterns.insert("wps".to_string(), "WPS".to_string());
         patterns
     };
+
+    /// 系统目录名，路径中任一组件以其开头或结尾时视为受保护
+    static ref SYSTEM_FOLDER_PATTERNS: Vec<SegmentPattern> = [
+        "windows", "system32", "syswow64", "winsxs", "microsoft", "windowsapps",
+        "program files", "program files (x86)", "programdata",
+    ]
+    .iter()
+    .map(|folder| SegmentPattern::new(folder))
+    .collect();
 }
 
+/// 名称与已知应用匹配时的基础置信度
+const KNOWN_APP_CONFIDENCE: f32 = 0.5;
+/// 空目录或空注册表项的基础置信度
+const EMPTY_FOLDER_CONFIDENCE: f32 = 0.5;
+/// 长期未修改的目录的基础置信度
+const STALE_FOLDER_CONFIDENCE: f32 = 0.3;
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum ResidueType {
@@ -99,6 +120,125 @@ pub struct ResidueItem {
     pub last_modified: i64,
     pub safe_to_delete: bool,
     pub risk_level: String,
+    /// 判定为残留的置信度，0 到 1
+    #[serde(default)]
+    pub confidence: f32,
+    #[serde(default)]
+    pub evidence: Vec<ResidueEvidence>,
+    /// 是否默认选中，只有高置信度且可安全删除的项会被选中
+    #[serde(default)]
+    pub selected: bool,
+}
+
+/// 判定为残留的依据
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ResidueEvidence {
+    /// 软件包已卸载，路径是其登记的配置文件
+    OwnedByRemovedPackage {
+        manager: PackageManager,
+        package: String,
+    },
+    /// 与已卸载、保留配置的软件包同名
+    RemovedPackage {
+        manager: PackageManager,
+        package: String,
+    },
+    /// 包管理器卸载时保留的配置副本
+    SavedConfig { manager: PackageManager },
+    /// flatpak、snap 应用数据目录对应的应用已卸载
+    SandboxAppRemoved {
+        manager: PackageManager,
+        app_id: String,
+    },
+    /// 与已卸载但仍留有卸载信息的软件名称相似
+    UninstalledProduct { product: String, similarity: f32 },
+    /// 名称与已知应用匹配
+    KnownApp { pattern: String, app: String },
+    /// 没有已安装的软件与之对应
+    NotInInventory,
+    /// 目录或注册表项为空
+    Empty,
+    /// 最后修改时间距今的天数
+    LastWrite { days: u64 },
+    /// 名称与已安装的软件相似
+    InstalledNameSimilarity { product: String, similarity: f32 },
+    /// 名称与已安装软件的发布者相同，可能是多个软件共用的厂商目录
+    PublisherMatch { publisher: String },
+    /// 已安装软件的安装位置或卸载程序位于其中
+    ReferencedByInstalled { product: String },
+    /// 正在运行的进程的可执行文件或工作目录位于其中
+    ReferencedByProcess { process: String, pid: u32 },
+    /// 其中包含可执行文件，可能是免安装软件
+    ContainsExecutables { count: usize },
+}
+
+impl ResidueEvidence {
+    pub fn description(&self) -> String {
+        match self {
+            Self::OwnedByRemovedPackage { manager, package } => format!(
+                "{} 软件包 {} 已卸载，此为其保留的配置",
+                manager.display_name(),
+                package
+            ),
+            Self::RemovedPackage { manager, package } => format!(
+                "与已卸载的 {} 软件包 {} 同名",
+                manager.display_name(),
+                package
+            ),
+            Self::SavedConfig { manager } => {
+                format!("{} 卸载或升级时保留的配置副本", manager.display_name())
+            }
+            Self::SandboxAppRemoved { manager, app_id } => format!(
+                "{} 应用 {} 已卸载，此为其数据目录",
+                manager.display_name(),
+                app_id
+            ),
+            Self::UninstalledProduct {
+                product,
+                similarity,
+            } => format!(
+                "与已卸载的 {} 匹配（相似度 {:.0}%）",
+                product,
+                similarity * 100.0
+            ),
+            Self::KnownApp { app, .. } => format!("名称与 {} 匹配，但未找到已安装的软件", app),
+            Self::NotInInventory => "不属于任何已安装的软件".to_string(),
+            Self::Empty => "内容为空".to_string(),
+            Self::LastWrite { days } => format!("最后修改于 {} 天前", days),
+            Self::InstalledNameSimilarity {
+                product,
+                similarity,
+            } => format!(
+                "与已安装的 {} 名称相似（相似度 {:.0}%）",
+                product,
+                similarity * 100.0
+            ),
+            Self::PublisherMatch { publisher } => {
+                format!("与已安装软件的发布者 {} 同名，可能为共用目录", publisher)
+            }
+            Self::ReferencedByInstalled { product } => {
+                format!("已安装的 {} 仍在使用此位置", product)
+            }
+            Self::ReferencedByProcess { process, pid } => {
+                format!("正在运行的进程 {}（PID {}）使用此位置", process, pid)
+            }
+            Self::ContainsExecutables { count } => format!("包含 {} 个可执行文件", count),
+        }
+    }
+
+    /// 是否支持判定为残留，否则为反对的依据
+    pub fn supports_removal(&self) -> bool {
+        match self {
+            Self::LastWrite { days } => *days >= 180,
+            Self::InstalledNameSimilarity { .. }
+            | Self::PublisherMatch { .. }
+            | Self::ReferencedByInstalled { .. }
+            | Self::ReferencedByProcess { .. }
+            | Self::ContainsExecutables { .. } => false,
+            _ => true,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -117,6 +257,8 @@ pub struct InstalledSoftware {
     pub uninstall_string: Option<String>,
     pub version: Option<String>,
     pub install_date: Option<String>,
+    /// 包管理器登记的安装大小（字节），没有安装目录可统计时使用
+    pub installed_size: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -155,8 +297,12 @@ impl Default for ResidueScanOptions {
 pub struct SoftwareResidueScanner {
     options: ResidueScanOptions,
     safety_checker: SafetyChecker,
+    protected_prefixes: Vec<PathPrefix>,
     installed_software: Vec<InstalledSoftware>,
     installed_names: HashSet<String>,
+    linux_inventory: LinuxInventory,
+    registry: Option<Arc<dyn RegistryBackend>>,
+    scorer: ResidueScorer,
     is_scanning: Arc<AtomicBool>,
     is_paused: Arc<AtomicBool>,
     is_cancelled: Arc<AtomicBool>,
@@ -173,7 +319,19 @@ impl SoftwareResidueScanner {
     }
 
     pub fn with_options(options: ResidueScanOptions) -> Self {
-        let installed_software = Self::get_installed_software();
+        Self::with_registry(options, registry::system_registry())
+    }
+
+    /// 使用指定的注册表，`None` 表示跳过注册表相关的检测
+    pub fn with_registry(
+        options: ResidueScanOptions,
+        registry: Option<Arc<dyn RegistryBackend>>,
+    ) -> Self {
+        let linux_inventory = LinuxInventory::for_current_system();
+        let installed_software =
+            inventory::collect_installed_software(registry.as_deref(), &linux_inventory);
+        let scorer = Self::build_scorer(&installed_software, &linux_inventory)
+            .with_processes(ResidueScorer::running_processes());
         let installed_names: HashSet<String> = installed_software
             .iter()
             .filter_map(|s| {
@@ -189,8 +347,15 @@ impl SoftwareResidueScanner {
         Self {
             options,
             safety_checker: SafetyChecker::new(),
+            protected_prefixes: SystemPaths::get_protected_paths()
+                .iter()
+                .map(PathPrefix::new)
+                .collect(),
             installed_software,
             installed_names,
+            linux_inventory,
+            registry,
+            scorer,
             is_scanning: Arc::new(AtomicBool::new(false)),
             is_paused: Arc::new(AtomicBool::new(false)),
             is_cancelled: Arc::new(AtomicBool::new(false)),
@@ -202,74 +367,6 @@ impl SoftwareResidueScanner {
         }
     }
 
-    #[cfg(windows)]
-    fn get_installed_software() -> Vec<InstalledSoftware> {
-        use winreg::enums::*;
-        use winreg::RegKey;
-
-        let mut software_list = Vec::new();
-        let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
-
-        let paths = vec![
-            "SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Uninstall",
-            "SOFTWARE\\WOW6432Node\\Microsoft\\Windows\\CurrentVersion\\Uninstall",
-        ];
-
-        for path in paths {
-            if let Ok(uninstall_key) = hklm.open_subkey(path) {
-                for subkey in uninstall_key.enum_keys().filter_map(|k| k.ok()) {
-                    if let Ok(app_key) = uninstall_key.open_subkey(&subkey) {
-                        let name: String = app_key
-                            .get_value("DisplayName")
-                            .unwrap_or_default();
-
-                        if !name.is_empty() {
-                            let software = InstalledSoftware {
-                                name,
-                                publisher: app_key.get_value("Publisher").ok(),
-                                install_location: app_key.get_value("InstallLocation").ok(),
-                                uninstall_string: app_key.get_value("UninstallString").ok(),
-                                version: app_key.get_value("DisplayVersion").ok(),
-                                install_date: app_key.get_value("InstallDate").ok(),
-                            };
-                            software_list.push(software);
-                        }
-                    }
-                }
-            }
-        }
-
-        let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-        if let Ok(uninstall_key) = hkcu.open_subkey("SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Uninstall") {
-            for subkey in uninstall_key.enum_keys().filter_map(|k| k.ok()) {
-                if let Ok(app_key) = uninstall_key.open_subkey(&subkey) {
-                    let name: String = app_key
-                        .get_value("DisplayName")
-                        .unwrap_or_default();
-
-                    if !name.is_empty() {
-                        let software = InstalledSoftware {
-                            name,
-                            publisher: app_key.get_value("Publisher").ok(),
-                            install_location: app_key.get_value("InstallLocation").ok(),
-                            uninstall_string: app_key.get_value("UninstallString").ok(),
-                            version: app_key.get_value("DisplayVersion").ok(),
-                            install_date: app_key.get_value("InstallDate").ok(),
-                        };
-                        software_list.push(software);
-                    }
-                }
-            }
-        }
-
-        software_list
-    }
-
-    #[cfg(not(windows))]
-    fn get_installed_software() -> Vec<InstalledSoftware> {
-        Vec::new()
-    }
-
     pub async fn start_scan(&self) -> Result<Vec<ResidueScanResult>, String> {
         if self.is_scanning.load(Ordering::SeqCst) {
             return Err("扫描已在进行中".to_string());
@@ -290,12 +387,23 @@ impl SoftwareResidueScanner {
 
         self.update_progress(0.0, "初始化扫描", "").await;
 
+        // Linux 上遗留目录、缓存与配置来自同一次查找，只查找一次
+        let linux_candidates = if cfg!(target_os = "linux")
+            && (self.options.include_leftover_folders
+                || self.options.include_cache_files
+                || self.options.include_config_files)
+        {
+            linux::find_linux_residue(&self.linux_inventory, &LinuxRoots::system())
+        } else {
+            Vec::new()
+        };
+
         if self.options.include_leftover_folders {
             if self.check_cancelled() {
                 return Ok(vec![]);
             }
             self.update_progress(10.0, "扫描遗留目录", "").await;
-            if let Some(result) = self.scan_leftover_folders().await {
+            if let Some(result) = self.scan_leftover_folders(&linux_candidates).await {
                 results.push(result);
             }
         }
@@ -315,7 +423,7 @@ impl SoftwareResidueScanner {
                 return Ok(vec![]);
             }
             self.update_progress(60.0, "扫描缓存文件", "").await;
-            if let Some(result) = self.scan_cache_files().await {
+            if let Some(result) = self.scan_cache_files(&linux_candidates).await {
                 results.push(result);
             }
         }
@@ -325,7 +433,7 @@ impl SoftwareResidueScanner {
                 return Ok(vec![]);
             }
             self.update_progress(80.0, "扫描配置文件", "").await;
-            if let Some(result) = self.scan_config_files().await {
+            if let Some(result) = self.scan_config_files(&linux_candidates).await {
                 results.push(result);
             }
         }
@@ -371,12 +479,42 @@ impl SoftwareResidueScanner {
         });
     }
 
-    async fn scan_leftover_folders(&self) -> Option<ResidueScanResult> {
+    /// 卸载信息中登记的安装目录已不存在的软件视为已卸载
+    fn build_scorer(
+        installed_software: &[InstalledSoftware],
+        linux_inventory: &LinuxInventory,
+    ) -> ResidueScorer {
+        let (uninstalled, installed): (Vec<_>, Vec<_>) =
+            installed_software.iter().cloned().partition(|s| {
+                s.install_location
+                    .as_deref()
+                    .map(str::trim)
+                    .is_some_and(|l| !l.is_empty() && !Path::new(l).exists())
+            });
+        let uninstalled = uninstalled
+            .into_iter()
+            .map(|s| UninstalledProduct {
+                name: s.name,
+                publisher: s.publisher,
+            })
+            .chain(linux_inventory.removed_products())
+            .collect();
+        ResidueScorer::new(installed, uninstalled)
+    }
+
+    async fn scan_leftover_folders(
+        &self,
+        linux_candidates: &[LinuxResidueCandidate],
+    ) -> Option<ResidueScanResult> {
+        if cfg!(target_os = "linux") {
+            return self
+                .scan_linux_residue(linux_candidates, ResidueType::LeftoverFolder)
+                .await;
+        }
+
         let mut items = Vec::new();
         let scan_paths = self.get_scan_paths();
 
-        let known_app_folders = self.get_known_app_folder_patterns();
-
         for scan_path in &scan_paths {
             if !scan_path.exists() {
                 continue;
@@ -407,13 +545,14 @@ impl SoftwareResidueScanner {
                     continue;
                 }
 
-                if self.is_potential_leftover_folder(path, &folder_name, &known_app_folders) {
+                if let Some(basis) = self.leftover_folder_evidence(path, &folder_name) {
                     if let Some(item) = self.create_residue_item(
                         path,
                         0,
                         ResidueType::LeftoverFolder,
                         &folder_name,
                         "可能为已卸载软件的残留目录",
+                        basis,
                     ).await {
                         self.found_count.fetch_add(1, Ordering::SeqCst);
                         items.push(item);
@@ -438,80 +577,71 @@ impl SoftwareResidueScanner {
     }
 
     async fn scan_registry_keys(&self) -> Option<ResidueScanResult> {
-        #[cfg(windows)]
-        {
-            let mut items = Vec::new();
-
-            use winreg::enums::*;
-            use winreg::RegKey;
-
-            let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
-            let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-
-            let software_paths = vec![
-                (hklm, "SOFTWARE", "HKLM"),
-                (hkcu, "SOFTWARE", "HKCU"),
-            ];
-
-            for (root_key, path, root_name) in software_paths {
-                if self.check_cancelled() {
-                    return None;
-                }
-
-                self.wait_if_paused().await;
-
-                if let Ok(software_key) = root_key.open_subkey(path) {
-                    for subkey in software_key.enum_keys().filter_map(|k| k.ok()) {
-                        if self.check_cancelled() {
-                            return None;
-                        }
-
-                        let subkey_lower = subkey.to_lowercase();
-
-                        if self.is_installed_software_name(&subkey) {
-                            continue;
-                        }
-
-                        if self.is_potential_leftover_registry_key(&subkey_lower) {
-                            let full_path = format!("{}\\{}\\{}", root_name, path, subkey);
-                            let item = ResidueItem {
-                                id: Uuid::new_v4().to_string(),
-                                name: subkey.clone(),
-                                path: full_path.clone(),
-                                size: 0,
-                                residue_type: ResidueType::RegistryKey,
-                                app_name: subkey,
-                                description: "可能为已卸载软件的注册表残留项".to_string(),
-                                last_modified: 0,
-                                safe_to_delete: false,
-                                risk_level: "High".to_string(),
-                            };
-                            self.found_count.fetch_add(1, Ordering::SeqCst);
-                            items.push(item);
-                        }
+        let registry = self.registry.as_deref()?;
 
-                        self.scanned_count.fetch_add(1, Ordering::SeqCst);
-                    }
-                }
-            }
+        if self.check_cancelled() {
+            return None;
+        }
 
-            if items.is_empty() {
-                return None;
-            }
+        self.wait_if_paused().await;
 
-            Some(ResidueScanResult {
+        let mut items = Vec::new();
+        for leftover in registry::find_leftover_keys(registry, &self.installed_software) {
+            let (description, base, evidence) = match leftover.reason {
+                LeftoverReason::KnownApp => (
+                    "可能为已卸载软件的注册表残留项",
+                    KNOWN_APP_CONFIDENCE,
+                    self.known_app_evidence(&leftover.name).unwrap_or_default(),
+                ),
+                LeftoverReason::EmptyKey => (
+                    "没有任何内容的注册表项",
+                    EMPTY_FOLDER_CONFIDENCE,
+                    vec![ResidueEvidence::Empty, ResidueEvidence::NotInInventory],
+                ),
+            };
+            let assessment = self.scorer.assess_registry(&leftover.name, base, evidence);
+            items.push(ResidueItem {
+                id: Uuid::new_v4().to_string(),
+                name: leftover.name,
+                path: leftover.path,
+                size: 0,
                 residue_type: ResidueType::RegistryKey,
-                items,
-                total_size: 0,
-                count: self.found_count.load(Ordering::SeqCst) as u64,
-            })
+                app_name: leftover.app_name,
+                description: description.to_string(),
+                last_modified: 0,
+                safe_to_delete: false,
+                risk_level: "High".to_string(),
+                confidence: assessment.confidence,
+                evidence: assessment.evidence,
+                selected: false,
+            });
+            self.found_count.fetch_add(1, Ordering::SeqCst);
+            self.scanned_count.fetch_add(1, Ordering::SeqCst);
         }
 
-        #[cfg(not(windows))]
-        None
+        if items.is_empty() {
+            return None;
+        }
+
+        let count = items.len() as u64;
+        Some(ResidueScanResult {
+            residue_type: ResidueType::RegistryKey,
+            items,
+            total_size: 0,
+            count,
+        })
     }
 
-    async fn scan_cache_files(&self) -> Option<ResidueScanResult> {
+    async fn scan_cache_files(
+        &self,
+        linux_candidates: &[LinuxResidueCandidate],
+    ) -> Option<ResidueScanResult> {
+        if cfg!(target_os = "linux") {
+            return self
+                .scan_linux_residue(linux_candidates, ResidueType::CacheFile)
+                .await;
+        }
+
         let mut items = Vec::new();
         let cache_paths = self.get_cache_scan_paths();
 
@@ -540,13 +670,14 @@ impl SoftwareResidueScanner {
                             continue;
                         }
 
-                        if self.is_potential_leftover_cache(&folder_name) {
+                        if let Some(evidence) = self.known_app_evidence(&folder_name) {
                             if let Some(item) = self.create_residue_item(
                                 &path,
                                 0,
                                 ResidueType::CacheFile,
                                 &folder_name,
                                 "可能为已卸载软件的缓存文件",
+                                (KNOWN_APP_CONFIDENCE, evidence),
                             ).await {
                                 self.found_count.fetch_add(1, Ordering::SeqCst);
                                 items.push(item);
@@ -572,7 +703,16 @@ impl SoftwareResidueScanner {
         })
     }
 
-    async fn scan_config_files(&self) -> Option<ResidueScanResult> {
+    async fn scan_config_files(
+        &self,
+        linux_candidates: &[LinuxResidueCandidate],
+    ) -> Option<ResidueScanResult> {
+        if cfg!(target_os = "linux") {
+            return self
+                .scan_linux_residue(linux_candidates, ResidueType::ConfigFile)
+                .await;
+        }
+
         let mut items = Vec::new();
         let config_paths = self.get_config_scan_paths();
 
@@ -601,13 +741,14 @@ impl SoftwareResidueScanner {
                             continue;
                         }
 
-                        if self.is_potential_leftover_config(&folder_name) {
+                        if let Some(evidence) = self.known_app_evidence(&folder_name) {
                             if let Some(item) = self.create_residue_item(
                                 &path,
                                 0,
                                 ResidueType::ConfigFile,
                                 &folder_name,
                                 "可能为已卸载软件的配置文件",
+                                (KNOWN_APP_CONFIDENCE, evidence),
                             ).await {
                                 self.found_count.fetch_add(1, Ordering::SeqCst);
                                 items.push(item);
@@ -678,10 +819,6 @@ impl SoftwareResidueScanner {
         paths
     }
 
-    fn get_known_app_folder_patterns(&self) -> &'static HashMap<String, String> {
-        &KNOWN_APP_PATTERNS
-    }
-
     fn is_installed_software_folder(&self, folder_name: &str) -> bool {
         let name_lower = folder_name.to_lowercase();
 
@@ -694,118 +831,61 @@ impl SoftwareResidueScanner {
         false
     }
 
-    fn is_installed_software_name(&self, name: &str) -> bool {
-        let name_lower = name.to_lowercase();
-
-        for installed_name in &self.installed_names {
-            if name_lower == *installed_name {
-                return true;
-            }
-        }
-
-        false
-    }
-
-    fn is_potential_leftover_folder(
+    /// 名称与已知应用匹配、目录为空或长期未修改时返回基础置信度与依据
+    fn leftover_folder_evidence(
         &self,
         path: &Path,
         folder_name: &str,
-        known_patterns: &'static HashMap<String, String>,
-    ) -> bool {
-        let name_lower = folder_name.to_lowercase();
-
-        for (pattern, _) in known_patterns {
-            if name_lower.contains(pattern.as_str()) {
-                if !self.is_installed_software_folder(folder_name) {
-                    return true;
-                }
-            }
+    ) -> Option<(f32, Vec<ResidueEvidence>)> {
+        if let Some(evidence) = self.known_app_evidence(folder_name) {
+            return Some((KNOWN_APP_CONFIDENCE, evidence));
         }
 
-        if let Ok(entries) = fs::read_dir(path) {
-            let entry_count = entries.count();
-            if entry_count == 0 {
-                return true;
-            }
+        if fs::read_dir(path).is_ok_and(|mut entries| entries.next().is_none()) {
+            return Some((
+                EMPTY_FOLDER_CONFIDENCE,
+                vec![ResidueEvidence::Empty, ResidueEvidence::NotInInventory],
+            ));
         }
 
-        if let Ok(metadata) = fs::metadata(path) {
-            if let Ok(modified) = metadata.modified() {
-                if let Ok(elapsed) = modified.elapsed() {
-                    if elapsed.as_secs() > 180 * 24 * 60 * 60 {
-                        return true;
-                    }
-                }
-            }
+        let elapsed = fs::metadata(path).ok()?.modified().ok()?.elapsed().ok()?;
+        if elapsed.as_secs() > 180 * 24 * 60 * 60 {
+            return Some((
+                STALE_FOLDER_CONFIDENCE,
+                vec![
+                    ResidueEvidence::LastWrite {
+                        days: elapsed.as_secs() / (24 * 60 * 60),
+                    },
+                    ResidueEvidence::NotInInventory,
+                ],
+            ));
         }
 
-        false
-    }
-
-    fn is_potential_leftover_registry_key(&self, key_name: &str) -> bool {
-        let known_patterns = self.get_known_app_folder_patterns();
-
-        for (pattern, _) in known_patterns {
-            if key_name.contains(pattern.as_str()) {
-                return true;
-            }
-        }
-
-        false
-    }
-
-    fn is_potential_leftover_cache(&self, folder_name: &str) -> bool {
-        let name_lower = folder_name.to_lowercase();
-        let known_patterns = self.get_known_app_folder_patterns();
-
-        for (pattern, _) in known_patterns {
-            if name_lower.contains(pattern.as_str()) {
-                if !self.is_installed_software_folder(folder_name) {
-                    return true;
-                }
-            }
-        }
-
-        false
+        None
     }
 
-    fn is_potential_leftover_config(&self, folder_name: &str) -> bool {
+    fn known_app_evidence(&self, folder_name: &str) -> Option<Vec<ResidueEvidence>> {
         let name_lower = folder_name.to_lowercase();
-        let known_patterns = self.get_known_app_folder_patterns();
-
-        for (pattern, _) in known_patterns {
-            if name_lower.contains(pattern.as_str()) {
-                if !self.is_installed_software_folder(folder_name) {
-                    return true;
-                }
-            }
-        }
-
-        false
+        let (pattern, app) = KNOWN_APP_PATTERNS
+            .iter()
+            .find(|(pattern, _)| name_lower.contains(pattern.as_str()))?;
+        Some(vec![
+            ResidueEvidence::KnownApp {
+                pattern: pattern.clone(),
+                app: app.clone(),
+            },
+            ResidueEvidence::NotInInventory,
+        ])
     }
 
     fn is_protected_path(&self, path: &Path) -> bool {
-        let protected = SystemPaths::get_protected_paths();
-        let path_str = path.to_string_lossy().to_lowercase();
-
-        for p in protected {
-            if path_str.starts_with(&p.to_string_lossy().to_lowercase()) {
-                return true;
-            }
+        if self.protected_prefixes.iter().any(|p| p.contains(path)) {
+            return true;
         }
 
-        let system_folders = [
-            "windows", "system32", "syswow64", "winsxs", "microsoft", "windowsapps",
-            "program files", "program files (x86)", "programdata",
-        ];
-
-        for folder in system_folders {
-            if path_str.contains(folder) {
-                return true;
-            }
-        }
-
-        false
+        SYSTEM_FOLDER_PATTERNS
+            .iter()
+            .any(|pattern| pattern.matches(path))
     }
 
     async fn create_residue_item(
@@ -815,9 +895,14 @@ impl SoftwareResidueScanner {
         residue_type: ResidueType,
         app_name: &str,
         description: &str,
+        (base_confidence, evidence): (f32, Vec<ResidueEvidence>),
     ) -> Option<ResidueItem> {
         let metadata = fs::metadata(path).ok()?;
         let safety_result = self.safety_checker.check(path);
+        let name = PathUtils::get_filename(path)?;
+        let assessment = self
+            .scorer
+            .assess_path(path, &name, base_confidence, evidence);
 
         let actual_size = if size == 0 && path.is_dir() {
             self.calculate_folder_size(path).await
@@ -827,7 +912,7 @@ impl SoftwareResidueScanner {
 
         Some(ResidueItem {
             id: Uuid::new_v4().to_string(),
-            name: PathUtils::get_filename(path)?,
+            name,
             path: path.to_string_lossy().to_string(),
             size: actual_size,
             residue_type,
@@ -839,8 +924,59 @@ impl SoftwareResidueScanner {
                 .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
                 .map(|d| d.as_secs() as i64)
                 .unwrap_or(0),
+            selected: safety_result.safe_to_delete && assessment.is_high_confidence(),
             safe_to_delete: safety_result.safe_to_delete,
             risk_level: format!("{:?}", safety_result.risk_level),
+            confidence: assessment.confidence,
+            evidence: assessment.evidence,
+        })
+    }
+
+    /// 按包管理器清单查找 Linux 上的残留
+    async fn scan_linux_residue(
+        &self,
+        candidates: &[LinuxResidueCandidate],
+        residue_type: ResidueType,
+    ) -> Option<ResidueScanResult> {
+        let mut items = Vec::new();
+
+        for candidate in candidates.iter().filter(|c| c.residue_type == residue_type) {
+            if self.check_cancelled() {
+                return None;
+            }
+
+            self.wait_if_paused().await;
+
+            let description = candidate.description();
+            if let Some(item) = self
+                .create_residue_item(
+                    &candidate.path,
+                    0,
+                    residue_type.clone(),
+                    &candidate.app_name,
+                    &description,
+                    (candidate.confidence, candidate.evidence.clone()),
+                )
+                .await
+            {
+                self.found_count.fetch_add(1, Ordering::SeqCst);
+                items.push(item);
+            }
+
+            self.scanned_count.fetch_add(1, Ordering::SeqCst);
+        }
+
+        if items.is_empty() {
+            return None;
+        }
+
+        let total_size: u64 = items.iter().map(|i| i.size).sum();
+        let count = items.len() as u64;
+        Some(ResidueScanResult {
+            residue_type,
+            items,
+            total_size,
+            count,
         })
     }
 
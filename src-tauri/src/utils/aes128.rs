@@ -0,0 +1,278 @@
+//! AES-128 ECB 解密
+//!
+//! 仅用于解码微信新版 `.dat` 容器中加密的开头部分（通常只有 1 KB），不用于
+//! 保护任何数据。S 盒在首次使用时按 GF(2^8) 运算生成。
+
+use std::sync::OnceLock;
+
+pub const BLOCK_SIZE: usize = 16;
+const ROUNDS: usize = 10;
+
+struct Tables {
+    sbox: [u8; 256],
+    inv_sbox: [u8; 256],
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let mut sbox = [0u8; 256];
+        let mut inv_sbox = [0u8; 256];
+        for x in 0..=255u8 {
+            // 乘法逆元为 x^254，0 没有逆元按 0 处理
+            let mut inv = 1u8;
+            for _ in 0..254 {
+                inv = gf_mul(inv, x);
+            }
+            let s = inv
+                ^ inv.rotate_left(1)
+                ^ inv.rotate_left(2)
+                ^ inv.rotate_left(3)
+                ^ inv.rotate_left(4)
+                ^ 0x63;
+            sbox[x as usize] = s;
+            inv_sbox[s as usize] = x;
+        }
+        Tables { sbox, inv_sbox }
+    })
+}
+
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0u8;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        let carry = a & 0x80 != 0;
+        a <<= 1;
+        if carry {
+            a ^= 0x1B;
+        }
+        b >>= 1;
+    }
+    product
+}
+
+fn expand_key(key: &[u8; 16]) -> [[u8; 16]; ROUNDS + 1] {
+    const RCON: [u8; ROUNDS] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1B, 0x36];
+    let sbox = &tables().sbox;
+
+    let mut words = [[0u8; 4]; 4 * (ROUNDS + 1)];
+    for (i, word) in words.iter_mut().take(4).enumerate() {
+        word.copy_from_slice(&key[4 * i..4 * i + 4]);
+    }
+    for i in 4..words.len() {
+        let mut temp = words[i - 1];
+        if i % 4 == 0 {
+            temp.rotate_left(1);
+            for byte in &mut temp {
+                *byte = sbox[*byte as usize];
+            }
+            temp[0] ^= RCON[i / 4 - 1];
+        }
+        for j in 0..4 {
+            words[i][j] = words[i - 4][j] ^ temp[j];
+        }
+    }
+
+    let mut round_keys = [[0u8; 16]; ROUNDS + 1];
+    for (round, round_key) in round_keys.iter_mut().enumerate() {
+        for (i, word) in words[4 * round..4 * round + 4].iter().enumerate() {
+            round_key[4 * i..4 * i + 4].copy_from_slice(word);
+        }
+    }
+    round_keys
+}
+
+fn add_round_key(state: &mut [u8; 16], round_key: &[u8; 16]) {
+    for (byte, key) in state.iter_mut().zip(round_key) {
+        *byte ^= key;
+    }
+}
+
+fn decrypt_block(block: &mut [u8; 16], round_keys: &[[u8; 16]; ROUNDS + 1]) {
+    let inv_sbox = &tables().inv_sbox;
+
+    add_round_key(block, &round_keys[ROUNDS]);
+    for round in (0..ROUNDS).rev() {
+        // 逆行移位与逆字节替换
+        let state = *block;
+        for c in 0..4 {
+            for r in 0..4 {
+                block[r + 4 * ((c + r) % 4)] = inv_sbox[state[r + 4 * c] as usize];
+            }
+        }
+        add_round_key(block, &round_keys[round]);
+        if round > 0 {
+            for column in block.chunks_exact_mut(4) {
+                let [a0, a1, a2, a3] = [column[0], column[1], column[2], column[3]];
+                column[0] = gf_mul(a0, 14) ^ gf_mul(a1, 11) ^ gf_mul(a2, 13) ^ gf_mul(a3, 9);
+                column[1] = gf_mul(a0, 9) ^ gf_mul(a1, 14) ^ gf_mul(a2, 11) ^ gf_mul(a3, 13);
+                column[2] = gf_mul(a0, 13) ^ gf_mul(a1, 9) ^ gf_mul(a2, 14) ^ gf_mul(a3, 11);
+                column[3] = gf_mul(a0, 11) ^ gf_mul(a1, 13) ^ gf_mul(a2, 9) ^ gf_mul(a3, 14);
+            }
+        }
+    }
+}
+
+/// 按 ECB 模式逐块解密，末尾不足一块的部分忽略
+pub fn decrypt_ecb(key: &[u8; 16], data: &[u8]) -> Vec<u8> {
+    let round_keys = expand_key(key);
+    let mut output = Vec::with_capacity(data.len());
+    for chunk in data.chunks_exact(BLOCK_SIZE) {
+        let mut block = [0u8; 16];
+        block.copy_from_slice(chunk);
+        decrypt_block(&mut block, &round_keys);
+        output.extend_from_slice(&block);
+    }
+    output
+}
+
+/// 按 ECB 模式逐块加密，用于在测试中构造加密的文件
+#[cfg(test)]
+pub fn encrypt_ecb(key: &[u8; 16], data: &[u8]) -> Vec<u8> {
+    let round_keys = expand_key(key);
+    let sbox = &tables().sbox;
+    let mut output = Vec::with_capacity(data.len());
+    for chunk in data.chunks_exact(BLOCK_SIZE) {
+        let mut block = [0u8; 16];
+        block.copy_from_slice(chunk);
+        add_round_key(&mut block, &round_keys[0]);
+        for (round, round_key) in round_keys.iter().enumerate().skip(1) {
+            let state = block;
+            for c in 0..4 {
+                for r in 0..4 {
+                    block[r + 4 * c] = sbox[state[r + 4 * ((c + r) % 4)] as usize];
+                }
+            }
+            if round < ROUNDS {
+                for column in block.chunks_exact_mut(4) {
+                    let [a0, a1, a2, a3] = [column[0], column[1], column[2], column[3]];
+                    column[0] = gf_mul(a0, 2) ^ gf_mul(a1, 3) ^ a2 ^ a3;
+                    column[1] = a0 ^ gf_mul(a1, 2) ^ gf_mul(a2, 3) ^ a3;
+                    column[2] = a0 ^ a1 ^ gf_mul(a2, 2) ^ gf_mul(a3, 3);
+                    column[3] = gf_mul(a0, 3) ^ a1 ^ a2 ^ gf_mul(a3, 2);
+                }
+            }
+            add_round_key(&mut block, round_key);
+        }
+        output.extend_from_slice(&block);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sbox() {
+        let tables = tables();
+        assert_eq!(tables.sbox[0x00], 0x63);
+        assert_eq!(tables.sbox[0x53], 0xED);
+        assert_eq!(tables.inv_sbox[0x63], 0x00);
+    }
+
+    #[test]
+    fn test_fips197_vector() {
+        let key: [u8; 16] = core::array::from_fn(|i| i as u8);
+        let plaintext: Vec<u8> = (0..16u8).map(|i| i * 0x11).collect();
+        let ciphertext = [
+            0x69, 0xC4, 0xE0, 0xD8, 0x6A, 0x7B, 0x04, 0x30, 0xD8, 0xCD, 0xB7, 0x80, 0x70, 0xB4,
+            0xC5, 0x5A,
+        ];
+
+        assert_eq!(encrypt_ecb(&key, &plaintext), ciphertext);
+        assert_eq!(decrypt_ecb(&key, &ciphertext), plaintext);
+    }
+
+    fn hex(text: &str) -> Vec<u8> {
+        (0..text.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&text[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn key(text: &str) -> [u8; 16] {
+        hex(text).try_into().unwrap()
+    }
+
+    /// FIPS-197 附录 A.1 的密钥扩展
+    #[test]
+    fn test_fips197_key_expansion() {
+        let round_keys = expand_key(&key("2b7e151628aed2a6abf7158809cf4f3c"));
+        assert_eq!(
+            round_keys[1].to_vec(),
+            hex("a0fafe1788542cb123a339392a6c7605")
+        );
+        assert_eq!(
+            round_keys[10].to_vec(),
+            hex("d014f9a8c9ee2589e13f0cc8b6630ca6")
+        );
+    }
+
+    /// NIST SP 800-38A F.1.1 与 F.1.2：ECB-AES128 的多块加解密
+    #[test]
+    fn test_sp800_38a_ecb_vectors() {
+        let key = key("2b7e151628aed2a6abf7158809cf4f3c");
+        let plaintext = hex(concat!(
+            "6bc1bee22e409f96e93d7e117393172a",
+            "ae2d8a571e03ac9c9eb76fac45af8e51",
+            "30c81c46a35ce411e5fbc1191a0a52ef",
+            "f69f2445df4f9b17ad2b417be66c3710",
+        ));
+        let ciphertext = hex(concat!(
+            "3ad77bb40d7a3660a89ecaf32466ef97",
+            "f5d3d58503b9699de785895a96fdbaaf",
+            "43b1cd7f598ece23881b00e3ed030688",
+            "7b0c785e27e8ad3f8223207104725dd4",
+        ));
+
+        assert_eq!(encrypt_ecb(&key, &plaintext), ciphertext);
+        assert_eq!(decrypt_ecb(&key, &ciphertext), plaintext);
+
+        // 末尾不足一块的部分被忽略
+        let mut unaligned = ciphertext[..40].to_vec();
+        unaligned.extend_from_slice(&[0xAA; 5]);
+        assert_eq!(decrypt_ecb(&key, &unaligned), plaintext[..32]);
+        assert!(decrypt_ecb(&key, &ciphertext[..15]).is_empty());
+    }
+
+    /// AESAVS 的 GFSbox、KeySbox 与 VarKey 已知答案测试
+    #[test]
+    fn test_aesavs_known_answer_vectors() {
+        let vectors = [
+            (
+                "00000000000000000000000000000000",
+                "f34481ec3cc627bacd5dc3fb08f273e6",
+                "0336763e966d92595a567cc9ce537f5e",
+            ),
+            (
+                "10a58869d74be5a374cf867cfb473859",
+                "00000000000000000000000000000000",
+                "6d251e6944b051e04eaa6fb4dbf78465",
+            ),
+            (
+                "80000000000000000000000000000000",
+                "00000000000000000000000000000000",
+                "0edd33d3c621e546455bd8ba1418bec8",
+            ),
+        ];
+
+        for (key_hex, plaintext, ciphertext) in vectors {
+            let key = key(key_hex);
+            assert_eq!(
+                encrypt_ecb(&key, &hex(plaintext)),
+                hex(ciphertext),
+                "{}",
+                key_hex
+            );
+            assert_eq!(
+                decrypt_ecb(&key, &hex(ciphertext)),
+                hex(plaintext),
+                "{}",
+                key_hex
+            );
+        }
+    }
+}
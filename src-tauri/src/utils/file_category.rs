@@ -1,5 +1,8 @@
 use std::path::Path;
 
+use crate::models::RiskLevel;
+use crate::utils::path_match::{CaseSensitivity, NormalizedPath, SegmentPattern};
+
 #[derive(Debug, Clone)]
 pub struct FileCategoryRule {
     pub name: String,
@@ -20,33 +23,18 @@ pub enum CleanSafety {
     NotCleanable,
 }
 
-impl FileCategoryRule {
-    pub fn matches(&self, path: &Path) -> bool {
-        let path_str = path.to_string_lossy().to_lowercase();
-        let path_str_lower = path_str.as_str();
-
-        for exclude in &self.exclude_patterns {
-            if path_str_lower.contains(&exclude.to_lowercase()) {
-                return false;
-            }
-        }
-
-        for pattern in &self.path_patterns {
-            if path_str_lower.contains(&pattern.to_lowercase()) {
-                return true;
-            }
+impl CleanSafety {
+    pub fn to_risk_level(self) -> RiskLevel {
+        match self {
+            Self::Safe | Self::LowRisk => RiskLevel::Low,
+            Self::MediumRisk => RiskLevel::Medium,
+            Self::HighRisk => RiskLevel::High,
+            Self::NotCleanable => RiskLevel::Critical,
         }
-
-        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-            let ext_lower = ext.to_lowercase();
-            if self.extensions.iter().any(|e| e.to_lowercase() == ext_lower) {
-                return true;
-            }
-        }
-
-        false
     }
+}
 
+impl FileCategoryRule {
     pub fn is_cleanable(&self) -> bool {
         matches!(
             self.clean_safety,
@@ -55,10 +43,54 @@ impl FileCategoryRule {
     }
 }
 
+/// 分类规则中的路径模式，创建注册表时解析一次
+struct CategoryMatcher {
+    path_patterns: Vec<SegmentPattern>,
+    exclude_patterns: Vec<SegmentPattern>,
+    extensions: Vec<String>,
+}
+
+impl CategoryMatcher {
+    fn new(rule: &FileCategoryRule) -> Self {
+        Self {
+            path_patterns: rule.path_patterns.iter().map(|p| SegmentPattern::new(p)).collect(),
+            exclude_patterns: rule
+                .exclude_patterns
+                .iter()
+                .map(|p| SegmentPattern::new(p))
+                .collect(),
+            extensions: rule.extensions.iter().map(|e| e.to_lowercase()).collect(),
+        }
+    }
+
+    fn matches(&self, path: &Path, normalized: &NormalizedPath) -> bool {
+        if self
+            .exclude_patterns
+            .iter()
+            .any(|pattern| pattern.matches_normalized(normalized))
+        {
+            return false;
+        }
+
+        if self
+            .path_patterns
+            .iter()
+            .any(|pattern| pattern.matches_normalized(normalized))
+        {
+            return true;
+        }
+
+        path.extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|ext| self.extensions.contains(&ext.to_lowercase()))
+    }
+}
+
 pub struct FileCategoryRegistry {
     categories: Vec<FileCategoryRule>,
+    matchers: Vec<CategoryMatcher>,
     protected_extensions: Vec<String>,
-    protected_path_patterns: Vec<String>,
+    protected_path_patterns: Vec<SegmentPattern>,
 }
 
 impl FileCategoryRegistry {
@@ -219,7 +251,7 @@ impl FileCategoryRegistry {
             "efi".to_string(),
         ];
 
-        let protected_path_patterns = vec![
+        let protected_path_patterns = [
             "\\windows\\system32\\".to_string(),
             "\\windows\\syswow64\\".to_string(),
             "\\windows\\winsxs\\".to_string(),
@@ -238,9 +270,13 @@ impl FileCategoryRegistry {
         ];
 
         Self {
+            matchers: categories.iter().map(CategoryMatcher::new).collect(),
             categories,
             protected_extensions,
-            protected_path_patterns,
+            protected_path_patterns: protected_path_patterns
+                .iter()
+                .map(|p| SegmentPattern::new(p))
+                .collect(),
         }
     }
 
@@ -251,13 +287,12 @@ impl FileCategoryRegistry {
             return None;
         }
 
-        for category in &self.categories {
-            if category.matches(path) {
-                return Some(category);
-            }
-        }
-
-        None
+        let normalized = NormalizedPath::new(path, CaseSensitivity::Insensitive);
+        self.categories
+            .iter()
+            .zip(&self.matchers)
+            .find(|(_, matcher)| matcher.matches(path, &normalized))
+            .map(|(category, _)| category)
     }
 
     pub fn is_protected_path(&self, path_lower: &str) -> bool {
@@ -267,13 +302,10 @@ impl FileCategoryRegistry {
             }
         }
 
-        for pattern in &self.protected_path_patterns {
-            if path_lower.contains(&pattern.to_lowercase()) {
-                return true;
-            }
-        }
-
-        false
+        let normalized = NormalizedPath::parse(path_lower, CaseSensitivity::Insensitive);
+        self.protected_path_patterns
+            .iter()
+            .any(|pattern| pattern.matches_normalized(&normalized))
     }
 
     pub fn get_all_categories(&self) -> &[FileCategoryRule] {
@@ -0,0 +1,345 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::models::IgnoredPath;
+
+/// DiskTidy 专用的排除规则文件名
+pub const DISKTIDY_IGNORE_FILE: &str = ".disktidyignore";
+
+/// 每次扫描最多记录的被排除路径数量
+const MAX_RECORDED_IGNORED_PATHS: usize = 1000;
+
+/// gitignore 文件中的一条规则
+#[derive(Debug, Clone)]
+pub struct IgnoreRule {
+    pub pattern: String,
+    pub line: usize,
+    pub negated: bool,
+    pub dir_only: bool,
+    anchored: bool,
+    glob: String,
+}
+
+impl IgnoreRule {
+    pub fn parse(line: &str, line_number: usize) -> Option<Self> {
+        let line = trim_trailing_spaces(line);
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let negated = line.starts_with('!');
+        let escaped = line.starts_with("\\!") || line.starts_with("\\#");
+        let body = if negated || escaped { &line[1..] } else { line };
+
+        let dir_only = body.ends_with('/') && !body.ends_with("\\/");
+        let body = body.trim_end_matches('/');
+        if body.is_empty() {
+            return None;
+        }
+
+        // 开头或中间含有分隔符的模式相对于 ignore 文件所在目录锚定
+        let anchored = body.contains('/');
+        let body = body.trim_start_matches('/');
+
+        let mut glob = body.replace('{', "\\{").replace('}', "\\}");
+        if cfg!(windows) {
+            glob = glob.to_lowercase();
+        }
+
+        Some(Self {
+            pattern: line.to_string(),
+            line: line_number,
+            negated,
+            dir_only,
+            anchored,
+            glob,
+        })
+    }
+
+    /// `relative` 为相对 ignore 文件所在目录、以 `/` 分隔的路径
+    pub fn matches(&self, relative: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+
+        let relative = if cfg!(windows) {
+            relative.to_lowercase()
+        } else {
+            relative.to_string()
+        };
+
+        if self.anchored {
+            glob_match::glob_match(&self.glob, &relative)
+        } else {
+            let name = relative.rsplit('/').next().unwrap_or(&relative);
+            glob_match::glob_match(&self.glob, name)
+        }
+    }
+}
+
+fn trim_trailing_spaces(line: &str) -> &str {
+    let line = line.trim_end_matches(['\r', '\n']);
+    let trimmed = line.trim_end_matches(' ');
+    if trimmed.ends_with('\\') && trimmed.len() < line.len() {
+        &line[..trimmed.len() + 1]
+    } else {
+        trimmed
+    }
+}
+
+/// 一个 ignore 文件及其规则
+#[derive(Debug, Clone)]
+pub struct IgnoreFile {
+    pub base_dir: PathBuf,
+    pub source: PathBuf,
+    rules: Vec<IgnoreRule>,
+}
+
+impl IgnoreFile {
+    pub fn parse(base_dir: &Path, source: &Path, content: &str) -> Self {
+        Self {
+            base_dir: base_dir.to_path_buf(),
+            source: source.to_path_buf(),
+            rules: content
+                .lines()
+                .enumerate()
+                .filter_map(|(i, line)| IgnoreRule::parse(line, i + 1))
+                .collect(),
+        }
+    }
+
+    pub fn load(base_dir: &Path, source: &Path) -> Option<Self> {
+        let content = fs::read_to_string(source).ok()?;
+        Some(Self::parse(base_dir, source, &content))
+    }
+
+    pub fn rules(&self) -> &[IgnoreRule] {
+        &self.rules
+    }
+
+    /// 返回最后一条匹配的规则，同一文件内后出现的规则优先
+    pub fn matched(&self, relative: &str, is_dir: bool) -> Option<&IgnoreRule> {
+        self.rules
+            .iter()
+            .rev()
+            .find(|r| r.matches(relative, is_dir))
+    }
+}
+
+/// 决定路径是否被忽略的规则来源
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IgnoreMatch {
+    pub ignored: bool,
+    pub source: PathBuf,
+    pub pattern: String,
+    pub line: usize,
+}
+
+impl IgnoreMatch {
+    fn from_rule(file: &IgnoreFile, rule: &IgnoreRule) -> Self {
+        Self {
+            ignored: !rule.negated,
+            source: file.source.clone(),
+            pattern: rule.pattern.clone(),
+            line: rule.line,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        format!(
+            "{}:{} {}",
+            self.source.to_string_lossy(),
+            self.line,
+            self.pattern
+        )
+    }
+}
+
+/// 按 gitignore 语义在一棵目录树中查找各级 ignore 文件
+///
+/// 深层目录的文件优先于浅层目录，被忽略目录下的内容不能再被否定规则重新包含。
+pub struct IgnoreTree {
+    root: PathBuf,
+    file_name: String,
+    /// 优先级低于目录中 ignore 文件的附加规则，如 `.git/info/exclude`
+    extra: Vec<IgnoreFile>,
+    cache: Mutex<HashMap<PathBuf, Option<Arc<IgnoreFile>>>>,
+}
+
+impl IgnoreTree {
+    pub fn new(root: &Path, file_name: &str) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            file_name: file_name.to_string(),
+            extra: Vec::new(),
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_extra(mut self, file: IgnoreFile) -> Self {
+        self.extra.push(file);
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        self.matched(path, is_dir)
+            .map(|m| m.ignored)
+            .unwrap_or(false)
+    }
+
+    pub fn matched(&self, path: &Path, is_dir: bool) -> Option<IgnoreMatch> {
+        let components = self.components(path)?;
+
+        for end in 1..=components.len() {
+            let is_last = end == components.len();
+            let matched =
+                self.match_components(&components[..end], if is_last { is_dir } else { true });
+            match matched {
+                Some(m) if m.ignored || is_last => return Some(m),
+                _ => {}
+            }
+        }
+
+        None
+    }
+
+    /// 只判断路径本身，不检查上级目录
+    ///
+    /// 适用于 `filter_entry` 遍历：上级目录被排除时遍历已不会进入该目录。
+    pub fn matched_entry(&self, path: &Path, is_dir: bool) -> Option<IgnoreMatch> {
+        let components = self.components(path)?;
+        self.match_components(&components, is_dir)
+    }
+
+    fn components(&self, path: &Path) -> Option<Vec<String>> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+        let components: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        (!components.is_empty()).then_some(components)
+    }
+
+    fn match_components(&self, components: &[String], is_dir: bool) -> Option<IgnoreMatch> {
+        for depth in (0..components.len()).rev() {
+            let mut dir = self.root.clone();
+            dir.extend(&components[..depth]);
+
+            if let Some(file) = self.file_in(&dir) {
+                let relative = components[depth..].join("/");
+                if let Some(rule) = file.matched(&relative, is_dir) {
+                    return Some(IgnoreMatch::from_rule(&file, rule));
+                }
+            }
+        }
+
+        let relative = components.join("/");
+        self.extra.iter().rev().find_map(|file| {
+            file.matched(&relative, is_dir)
+                .map(|rule| IgnoreMatch::from_rule(file, rule))
+        })
+    }
+
+    fn file_in(&self, dir: &Path) -> Option<Arc<IgnoreFile>> {
+        if let Ok(cache) = self.cache.lock() {
+            if let Some(cached) = cache.get(dir) {
+                return cached.clone();
+            }
+        }
+
+        let loaded = IgnoreFile::load(dir, &dir.join(&self.file_name))
+            .filter(|f| !f.rules.is_empty())
+            .map(Arc::new);
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.insert(dir.to_path_buf(), loaded.clone());
+        }
+        loaded
+    }
+}
+
+/// 按 gitignore 语义应用路径各级目录中的 `.disktidyignore`，并记录被排除的路径
+///
+/// 规则文件可以放在扫描根目录之上，因此以路径所在文件系统的根目录为起点查找。
+#[derive(Default)]
+pub struct DisktidyIgnore {
+    trees: Mutex<HashMap<PathBuf, Arc<IgnoreTree>>>,
+    excluded: Mutex<Vec<IgnoredPath>>,
+}
+
+impl DisktidyIgnore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 判断路径是否被排除，会检查所有上级目录
+    pub fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        let matched = self
+            .tree_for(path)
+            .and_then(|tree| tree.matched(path, is_dir));
+        self.record(path, is_dir, matched)
+    }
+
+    /// 遍历过程中的判断，上级目录已由遍历器过滤
+    pub fn is_excluded_entry(&self, entry: &walkdir::DirEntry) -> bool {
+        if entry.depth() == 0 {
+            return self.is_excluded(entry.path(), entry.file_type().is_dir());
+        }
+
+        let path = entry.path();
+        let is_dir = entry.file_type().is_dir();
+        let matched = self
+            .tree_for(path)
+            .and_then(|tree| tree.matched_entry(path, is_dir));
+        self.record(path, is_dir, matched)
+    }
+
+    /// 已记录的被排除路径
+    pub fn excluded_paths(&self) -> Vec<IgnoredPath> {
+        self.excluded.lock().map(|e| e.clone()).unwrap_or_default()
+    }
+
+    /// 取出已记录的被排除路径并清空记录，用于同一实例的多次扫描
+    pub fn take_excluded_paths(&self) -> Vec<IgnoredPath> {
+        self.excluded
+            .lock()
+            .map(|mut e| std::mem::take(&mut *e))
+            .unwrap_or_default()
+    }
+
+    fn record(&self, path: &Path, is_dir: bool, matched: Option<IgnoreMatch>) -> bool {
+        let matched = match matched.filter(|m| m.ignored) {
+            Some(m) => m,
+            None => return false,
+        };
+
+        if let Ok(mut excluded) = self.excluded.lock() {
+            if excluded.len() < MAX_RECORDED_IGNORED_PATHS {
+                excluded.push(IgnoredPath {
+                    path: path.to_string_lossy().to_string(),
+                    is_dir,
+                    ignore_file: matched.source.to_string_lossy().to_string(),
+                    line: matched.line,
+                    pattern: matched.pattern,
+                });
+            }
+        }
+        true
+    }
+
+    fn tree_for(&self, path: &Path) -> Option<Arc<IgnoreTree>> {
+        let root = path.ancestors().last()?;
+        let mut trees = self.trees.lock().ok()?;
+        Some(
+            trees
+                .entry(root.to_path_buf())
+                .or_insert_with(|| Arc::new(IgnoreTree::new(root, DISKTIDY_IGNORE_FILE)))
+                .clone(),
+        )
+    }
+}
@@ -1,10 +1,13 @@
+mod aes128;
 pub mod app_path_resolver;
 pub mod app_paths_config;
 pub mod file_category;
 pub mod file_type;
 pub mod format;
+pub mod gitignore;
 pub mod hash;
 pub mod path;
+pub mod path_match;
 pub mod scan_cache;
 pub mod wechat_dat_decoder;
 
@@ -13,7 +16,9 @@ pub use app_paths_config::*;
 pub use file_category::*;
 pub use file_type::*;
 pub use format::*;
+pub use gitignore::*;
 pub use hash::*;
 pub use path::*;
+pub use path_match::*;
 pub use scan_cache::*;
 pub use wechat_dat_decoder::*;
@@ -1,5 +1,7 @@
 use std::path::{Component, Path, PathBuf};
 
+use super::path_match::{CaseSensitivity, NormalizedPath};
+
 pub struct PathUtils;
 
 impl PathUtils {
@@ -27,8 +29,11 @@ impl PathUtils {
             .to_string()
     }
 
+    /// 按平台默认的大小写规则比较路径组件，不访问文件系统；同一目录需要与大量
+    /// 路径比较时应预先创建 [`PathPrefix`](super::path_match::PathPrefix)
     pub fn is_subpath(parent: &Path, child: &Path) -> bool {
-        child.starts_with(parent)
+        let case = CaseSensitivity::platform_default();
+        NormalizedPath::new(child, case).starts_with(&NormalizedPath::new(parent, case))
     }
 
     pub fn equals_ignore_case(path1: &Path, path2: &Path) -> bool {
@@ -90,6 +95,12 @@ impl PathUtils {
     }
 }
 
+/// 类 Unix 系统中不允许清理的系统目录
+pub const UNIX_PROTECTED_ROOTS: &[&str] = &[
+    "/bin", "/boot", "/dev", "/etc", "/lib", "/lib32", "/lib64", "/proc", "/sbin", "/sys",
+    "/usr",
+];
+
 pub struct SystemPaths;
 
 impl SystemPaths {
@@ -150,7 +161,7 @@ impl SystemPaths {
     }
 
     pub fn get_protected_paths() -> Vec<PathBuf> {
-        vec![
+        let mut paths = vec![
             Self::system32_dir(),
             PathBuf::from("C:\\Windows\\SysWOW64"),
             PathBuf::from("C:\\Windows\\WinSxS"),
@@ -160,6 +171,12 @@ impl SystemPaths {
             PathBuf::from("C:\\Boot"),
             PathBuf::from("C:\\EFI"),
             PathBuf::from("C:\\Recovery"),
-        ]
+        ];
+
+        if cfg!(unix) {
+            paths.extend(UNIX_PROTECTED_ROOTS.iter().map(PathBuf::from));
+        }
+
+        paths
     }
 }
@@ -0,0 +1,308 @@
+//! 按路径组件比较路径
+//!
+//! 受保护目录、排除目录、白名单与分类规则都通过这里匹配：`/` 与 `\` 同样视为
+//! 分隔符，只在完整的组件上比较，大小写规则取自路径所在的文件系统。
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 路径比较时的大小写规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaseSensitivity {
+    Sensitive,
+    Insensitive,
+}
+
+lazy_static::lazy_static! {
+    /// 按设备号缓存文件系统的大小写规则
+    static ref FS_CASE_CACHE: Mutex<HashMap<u64, CaseSensitivity>> = Mutex::new(HashMap::new());
+}
+
+impl CaseSensitivity {
+    /// 当前平台文件系统的默认规则：Windows 与 macOS 不区分大小写
+    pub fn platform_default() -> Self {
+        if cfg!(any(windows, target_os = "macos")) {
+            Self::Insensitive
+        } else {
+            Self::Sensitive
+        }
+    }
+
+    /// 探测路径所在文件系统是否区分大小写
+    ///
+    /// 取路径中最近的已存在目录，比较其名称大小写互换后是否指向同一文件；
+    /// 路径不存在或无法判断时使用平台默认规则。
+    pub fn for_path(path: &Path) -> Self {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+
+            let existing = match path.ancestors().find(|a| a.symlink_metadata().is_ok()) {
+                Some(existing) => existing,
+                None => return Self::platform_default(),
+            };
+            let dev = match existing.symlink_metadata() {
+                Ok(metadata) => metadata.dev(),
+                Err(_) => return Self::platform_default(),
+            };
+
+            if let Some(cached) = FS_CASE_CACHE.lock().ok().and_then(|c| c.get(&dev).copied()) {
+                return cached;
+            }
+
+            let detected = existing
+                .ancestors()
+                .find_map(|a| Self::probe(a, dev))
+                .unwrap_or_else(Self::platform_default);
+            if let Ok(mut cache) = FS_CASE_CACHE.lock() {
+                cache.insert(dev, detected);
+            }
+            detected
+        }
+
+        #[cfg(not(unix))]
+        {
+            let _ = path;
+            Self::platform_default()
+        }
+    }
+
+    #[cfg(unix)]
+    fn probe(path: &Path, dev: u64) -> Option<Self> {
+        use std::os::unix::fs::MetadataExt;
+
+        let name = path.file_name()?.to_str()?;
+        let swapped: String = name
+            .chars()
+            .map(|c| {
+                if c.is_lowercase() {
+                    c.to_uppercase().next().unwrap_or(c)
+                } else {
+                    c.to_lowercase().next().unwrap_or(c)
+                }
+            })
+            .collect();
+        if swapped == name {
+            return None;
+        }
+
+        let original = path.symlink_metadata().ok()?;
+        if original.dev() != dev {
+            return None;
+        }
+        let same_file = path
+            .with_file_name(&swapped)
+            .symlink_metadata()
+            .map(|m| m.dev() == original.dev() && m.ino() == original.ino())
+            .unwrap_or(false);
+
+        Some(if same_file {
+            Self::Insensitive
+        } else {
+            Self::Sensitive
+        })
+    }
+
+    fn fold(self, component: &str) -> String {
+        match self {
+            Self::Sensitive => component.to_string(),
+            Self::Insensitive => component.to_lowercase(),
+        }
+    }
+}
+
+/// 拆分并规范化后的路径组件
+///
+/// `/` 与 `\` 都视为分隔符，去掉 `.`，按字面处理 `..`，移除 Windows 的 `\\?\`
+/// 前缀并统一盘符大小写。绝对路径的第一个组件为根标记 `/` 或盘符。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NormalizedPath {
+    components: Vec<String>,
+}
+
+impl NormalizedPath {
+    pub fn new(path: &Path, case: CaseSensitivity) -> Self {
+        Self::parse(&path.to_string_lossy(), case)
+    }
+
+    pub fn parse(path: &str, case: CaseSensitivity) -> Self {
+        let path = path
+            .strip_prefix(r"\\?\UNC\")
+            .map(|rest| format!(r"\\{}", rest))
+            .unwrap_or_else(|| path.strip_prefix(r"\\?\").unwrap_or(path).to_string());
+
+        let mut components: Vec<String> = Vec::new();
+        if path.starts_with(['/', '\\']) {
+            components.push("/".to_string());
+        }
+
+        for (index, part) in path.split(['/', '\\']).enumerate() {
+            match part {
+                "" | "." => {}
+                ".." => {
+                    let is_root = components
+                        .last()
+                        .map(|c| c == "/" || is_drive(c))
+                        .unwrap_or(false);
+                    if !is_root && components.last().is_some_and(|c| c != "..") {
+                        components.pop();
+                    } else if !is_root {
+                        components.push("..".to_string());
+                    }
+                }
+                _ if index == 0 && is_drive(part) => components.push(part.to_uppercase()),
+                _ => components.push(case.fold(part)),
+            }
+        }
+
+        Self { components }
+    }
+
+    pub fn components(&self) -> &[String] {
+        &self.components
+    }
+
+    pub fn starts_with(&self, prefix: &NormalizedPath) -> bool {
+        self.components.starts_with(&prefix.components)
+    }
+}
+
+fn is_drive(component: &str) -> bool {
+    let bytes = component.as_bytes();
+    bytes.len() == 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+}
+
+/// 按路径组件判断包含关系的前缀，如受保护目录、排除目录和白名单路径
+///
+/// `/usr` 包含 `/usr/lib`，但不包含 `/usrlocal-data`。大小写规则取自前缀
+/// 所在的文件系统；前缀存在且规范化后的路径不同（如经过符号链接）时两种
+/// 形式都会参与比较。
+#[derive(Debug, Clone)]
+pub struct PathPrefix {
+    path: PathBuf,
+    case: CaseSensitivity,
+    normalized: NormalizedPath,
+    canonical: Option<NormalizedPath>,
+}
+
+impl PathPrefix {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        Self::with_case(path, CaseSensitivity::for_path(path))
+    }
+
+    pub fn with_case(path: impl AsRef<Path>, case: CaseSensitivity) -> Self {
+        let path = path.as_ref();
+        let normalized = NormalizedPath::new(path, case);
+        let canonical = path
+            .canonicalize()
+            .ok()
+            .map(|c| NormalizedPath::new(&c, case))
+            .filter(|c| *c != normalized);
+
+        Self {
+            path: path.to_path_buf(),
+            case,
+            normalized,
+            canonical,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn case(&self) -> CaseSensitivity {
+        self.case
+    }
+
+    /// 路径等于前缀或位于前缀之下
+    pub fn contains(&self, path: &Path) -> bool {
+        let normalized = NormalizedPath::new(path, self.case);
+        normalized.starts_with(&self.normalized)
+            || self
+                .canonical
+                .as_ref()
+                .is_some_and(|c| normalized.starts_with(c))
+    }
+
+    /// 前缀位于路径之下，删除该路径会连带删除前缀
+    pub fn is_within(&self, path: &Path) -> bool {
+        let normalized = NormalizedPath::new(path, self.case);
+        if normalized.components.is_empty() {
+            return false;
+        }
+        self.normalized.starts_with(&normalized)
+            || self
+                .canonical
+                .as_ref()
+                .is_some_and(|c| c.starts_with(&normalized))
+    }
+}
+
+/// 路径片段模式，如 `\temp\`、`\chrome\user data\default\cache`、`$recycle.bin`
+///
+/// 以分隔符开头时第一段须是完整的组件，否则只需是组件的结尾；以分隔符结尾时
+/// 最后一段须是完整的目录名，否则只需是组件的开头；中间各段须完全相等。
+/// 只有一段且两端都没有分隔符时，匹配任意组件中的子串。
+#[derive(Debug, Clone)]
+pub struct SegmentPattern {
+    segments: Vec<String>,
+    leading: bool,
+    trailing: bool,
+    case: CaseSensitivity,
+}
+
+impl SegmentPattern {
+    /// 分类规则描述的是名称特征而非具体路径，默认不区分大小写
+    pub fn new(pattern: &str) -> Self {
+        Self::with_case(pattern, CaseSensitivity::Insensitive)
+    }
+
+    pub fn with_case(pattern: &str, case: CaseSensitivity) -> Self {
+        Self {
+            segments: pattern
+                .split(['/', '\\'])
+                .filter(|s| !s.is_empty())
+                .map(|s| case.fold(s))
+                .collect(),
+            leading: pattern.starts_with(['/', '\\']),
+            trailing: pattern.ends_with(['/', '\\']),
+            case,
+        }
+    }
+
+    pub fn matches(&self, path: &Path) -> bool {
+        self.matches_normalized(&NormalizedPath::new(path, self.case))
+    }
+
+    pub fn matches_normalized(&self, path: &NormalizedPath) -> bool {
+        let count = self.segments.len();
+        if count == 0 {
+            return false;
+        }
+
+        let components = path.components();
+        // 以分隔符结尾的模式要求最后一段之后还有组件，即匹配的是目录
+        let limit = components.len().saturating_sub(self.trailing as usize);
+        if limit < count {
+            return false;
+        }
+
+        (0..=limit - count).any(|start| {
+            let window = &components[start..start + count];
+            window.iter().enumerate().all(|(i, component)| {
+                let segment = &self.segments[i];
+                let first = i == 0;
+                let last = i == count - 1;
+                match (first && !self.leading, last && !self.trailing) {
+                    (true, true) => component.contains(segment.as_str()),
+                    (true, false) => component.ends_with(segment.as_str()),
+                    (false, true) => component.starts_with(segment.as_str()),
+                    (false, false) => component == segment,
+                }
+            })
+        })
+    }
+}
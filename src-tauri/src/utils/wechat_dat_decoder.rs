@@ -1,15 +1,42 @@
+//! 微信 `.dat` 图片解码
+//!
+//! 支持两类容器：
+//! - 旧版：整个文件与单字节密钥异或，密钥通过文件头与已知签名比对得到
+//! - 新版（文件头 `07 08 'V' '1' 08 07` 或 `07 08 'V' '2' 08 07`）：文件头后依次为
+//!   AES-128-ECB 加密的开头部分、未加密的中间部分和单字节异或的末尾部分。V1 使用
+//!   固定密钥；V2 的密钥因账号而异，由调用方通过 [`DatKeys`] 提供，没有密钥时只
+//!   提取未加密部分中能识别的内容
+//!
+//! 无法解码的文件通过 [`DatDecodeError`] 给出原因。
+
+use serde::{Deserialize, Serialize};
 use std::fs::File;
 use std::io::{BufReader, Read, Seek, SeekFrom};
 use std::path::Path;
 
+use super::aes128;
+
 const XOR_KEY_SEARCH_BYTES: usize = 8;
 const MIN_FILE_SIZE: u64 = 10;
 
+/// 新版容器文件头：6 字节标识、加密部分长度与异或部分长度（小端 u32）、1 字节保留
+const CONTAINER_HEADER_LEN: usize = 15;
+const V1_MAGIC: [u8; 6] = [0x07, 0x08, b'V', b'1', 0x08, 0x07];
+const V2_MAGIC: [u8; 6] = [0x07, 0x08, b'V', b'2', 0x08, 0x07];
+/// V1 容器的 AES 密钥
+const V1_AES_KEY: [u8; 16] = *b"cfcd208495d565ef";
+/// 微信私有的 wxgf 格式（HEVC 编码）的文件头
+const WXGF_MAGIC: &[u8] = b"wxgf";
+
 #[derive(Debug, Clone)]
 pub struct WeChatImageInfo {
     pub original_format: ImageFormat,
-    pub xor_key: u8,
+    /// 旧版容器的异或密钥
+    pub xor_key: Option<u8>,
     pub file_size: u64,
+    pub container: DatContainer,
+    /// 加密部分没有解密，格式由未加密部分中的内容判断
+    pub partial: bool,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -19,6 +46,10 @@ pub enum ImageFormat {
     Gif,
     Bmp,
     WebP,
+    Tiff,
+    Heic,
+    /// 视频消息的缩略图容器
+    Mp4,
     Unknown,
 }
 
@@ -30,10 +61,13 @@ impl ImageFormat {
             ImageFormat::Gif => "gif",
             ImageFormat::Bmp => "bmp",
             ImageFormat::WebP => "webp",
+            ImageFormat::Tiff => "tiff",
+            ImageFormat::Heic => "heic",
+            ImageFormat::Mp4 => "mp4",
             ImageFormat::Unknown => "dat",
         }
     }
-    
+
     pub fn mime_type(&self) -> &'static str {
         match self {
             ImageFormat::Jpeg => "image/jpeg",
@@ -41,9 +75,80 @@ impl ImageFormat {
             ImageFormat::Gif => "image/gif",
             ImageFormat::Bmp => "image/bmp",
             ImageFormat::WebP => "image/webp",
+            ImageFormat::Tiff => "image/tiff",
+            ImageFormat::Heic => "image/heic",
+            ImageFormat::Mp4 => "video/mp4",
             ImageFormat::Unknown => "application/octet-stream",
         }
     }
+
+    /// 文件最后一个字节的固定值，用于推算末尾部分的异或密钥
+    fn trailer_byte(&self) -> Option<u8> {
+        match self {
+            ImageFormat::Jpeg => Some(0xD9),
+            ImageFormat::Png => Some(0x82),
+            ImageFormat::Gif => Some(0x3B),
+            _ => None,
+        }
+    }
+}
+
+/// `.dat` 文件的容器类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DatContainer {
+    Xor,
+    V1,
+    V2,
+}
+
+impl DatContainer {
+    fn detect(data: &[u8]) -> Self {
+        if data.starts_with(&V1_MAGIC) {
+            Self::V1
+        } else if data.starts_with(&V2_MAGIC) {
+            Self::V2
+        } else {
+            Self::Xor
+        }
+    }
+}
+
+/// 无法解码的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+#[serde(rename_all = "camelCase")]
+pub enum DatDecodeError {
+    #[error("文件过小或无法读取")]
+    Unreadable,
+    #[error("无法识别的文件头")]
+    UnknownFormat,
+    #[error("文件头记录的长度超过文件大小")]
+    Truncated,
+    #[error("缺少解密密钥")]
+    MissingKey,
+    #[error("解密结果不是已知格式，密钥可能不正确")]
+    WrongKey,
+    #[error("微信私有的 wxgf 格式，需要转码")]
+    Wxgf,
+}
+
+/// 解码时使用的密钥，未提供时 V1 使用固定密钥，末尾异或密钥按文件格式推算
+///
+/// V2 的 AES 密钥需要从运行中的微信进程读取，应用目前不获取该密钥，扫描与导出
+/// 都使用默认值，V2 文件只能部分解码
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DatKeys {
+    pub aes_key: Option<[u8; 16]>,
+    pub xor_key: Option<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DecodedDat {
+    pub data: Vec<u8>,
+    pub format: ImageFormat,
+    pub container: DatContainer,
+    /// 加密部分无法解密，只提取了未加密的部分
+    pub partial: bool,
 }
 
 static IMAGE_SIGNATURES: &[(&[u8], ImageFormat)] = &[
@@ -52,6 +157,21 @@ static IMAGE_SIGNATURES: &[(&[u8], ImageFormat)] = &[
     (&[0x47, 0x49, 0x46, 0x38], ImageFormat::Gif),
     (&[0x42, 0x4D], ImageFormat::Bmp),
     (&[0x52, 0x49, 0x46, 0x46], ImageFormat::WebP),
+    (&[0x49, 0x49, 0x2A, 0x00], ImageFormat::Tiff),
+    (&[0x4D, 0x4D, 0x00, 0x2A], ImageFormat::Tiff),
+];
+
+/// ISO BMFF 文件第 4 字节起的 box 类型
+const FTYP: &[u8] = b"ftyp";
+static HEIC_BRANDS: &[&[u8]] = &[
+    b"heic", b"heix", b"hevc", b"hevx", b"heim", b"heis", b"mif1", b"msf1",
+];
+
+/// 在未加密部分中查找的内嵌图片签名
+static EMBEDDED_SIGNATURES: &[&[u8]] = &[
+    &[0xFF, 0xD8, 0xFF],
+    &[0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A],
+    b"GIF8",
 ];
 
 pub struct WeChatDatDecoder;
@@ -63,16 +183,22 @@ impl WeChatDatDecoder {
             .map(|ext| ext.to_lowercase() == "dat")
             .unwrap_or(false)
     }
-    
+
     pub fn detect_xor_key(data: &[u8]) -> Option<u8> {
         if data.len() < XOR_KEY_SEARCH_BYTES {
             return None;
         }
-        
+
+        // 先于图片签名检查，否则 `wxgf` 会按两字节的 BMP 签名匹配到错误的密钥
+        let potential_key = data[0] ^ WXGF_MAGIC[0];
+        if data.iter().zip(WXGF_MAGIC).all(|(&b, &m)| b ^ potential_key == m) {
+            return Some(potential_key);
+        }
+
         for (signature, _) in IMAGE_SIGNATURES {
             if signature.len() <= data.len() {
                 let potential_key = data[0] ^ signature[0];
-                
+
                 let mut matches = true;
                 for (i, &sig_byte) in signature.iter().enumerate() {
                     if data[i] ^ potential_key != sig_byte {
@@ -80,18 +206,24 @@ impl WeChatDatDecoder {
                         break;
                     }
                 }
-                
+
                 if matches {
                     return Some(potential_key);
                 }
             }
         }
-        
+
+        // ISO BMFF（HEIC、MP4）开头是 box 长度，类型 `ftyp` 在第 4 字节
+        let potential_key = data[4] ^ FTYP[0];
+        if data[4..8].iter().zip(FTYP).all(|(&b, &f)| b ^ potential_key == f) {
+            return Some(potential_key);
+        }
+
         if data.len() >= 2 {
             for test_key in 0x00..=0xFF {
                 let decoded_first = data[0] ^ test_key;
                 let decoded_second = data[1] ^ test_key;
-                
+
                 if decoded_first == 0xFF && decoded_second == 0xD8 {
                     return Some(test_key);
                 }
@@ -106,108 +238,299 @@ impl WeChatDatDecoder {
                 }
             }
         }
-        
+
         None
     }
-    
+
     pub fn detect_image_format(decrypted_header: &[u8]) -> ImageFormat {
         for (signature, format) in IMAGE_SIGNATURES {
-            if signature.len() <= decrypted_header.len() {
-                if decrypted_header.starts_with(signature) {
-                    return format.clone();
-                }
+            if decrypted_header.starts_with(signature) {
+                return format.clone();
             }
         }
+        if decrypted_header.len() >= 12 && &decrypted_header[4..8] == FTYP {
+            let brand = &decrypted_header[8..12];
+            return if HEIC_BRANDS.contains(&brand) {
+                ImageFormat::Heic
+            } else {
+                ImageFormat::Mp4
+            };
+        }
         ImageFormat::Unknown
     }
-    
-    pub fn analyze_dat_file(path: &Path) -> Option<WeChatImageInfo> {
-        let file = File::open(path).ok()?;
-        let mut reader = BufReader::new(file);
-        
-        let metadata = std::fs::metadata(path).ok()?;
-        let file_size = metadata.len();
-        
+
+    /// 读取文件头判断容器与格式，不解密整个文件
+    pub fn inspect_dat_file(path: &Path) -> Result<WeChatImageInfo, DatDecodeError> {
+        Self::inspect_dat_file_with(path, &DatKeys::default())
+    }
+
+    /// 使用指定的密钥检查文件，没有 V2 密钥时从未加密部分判断格式
+    pub fn inspect_dat_file_with(
+        path: &Path,
+        keys: &DatKeys,
+    ) -> Result<WeChatImageInfo, DatDecodeError> {
+        let file_size = std::fs::metadata(path)
+            .map_err(|_| DatDecodeError::Unreadable)?
+            .len();
         if file_size < MIN_FILE_SIZE {
-            return None;
+            return Err(DatDecodeError::Unreadable);
         }
-        
-        let mut header = [0u8; XOR_KEY_SEARCH_BYTES];
-        reader.read_exact(&mut header).ok()?;
-        
-        let xor_key = Self::detect_xor_key(&header)?;
-        
-        let decrypted_header: Vec<u8> = header.iter().map(|&b| b ^ xor_key).collect();
-        let original_format = Self::detect_image_format(&decrypted_header);
-        
-        Some(WeChatImageInfo {
+
+        let mut file = File::open(path).map_err(|_| DatDecodeError::Unreadable)?;
+        let mut header = Vec::with_capacity(CONTAINER_HEADER_LEN + aes128::BLOCK_SIZE);
+        (&mut file)
+            .take((CONTAINER_HEADER_LEN + aes128::BLOCK_SIZE) as u64)
+            .read_to_end(&mut header)
+            .map_err(|_| DatDecodeError::Unreadable)?;
+
+        let container = DatContainer::detect(&header);
+        let mut partial = false;
+        let (original_format, xor_key) = match container {
+            DatContainer::Xor => {
+                let xor_key = keys
+                    .xor_key
+                    .or_else(|| Self::detect_xor_key(&header))
+                    .ok_or(DatDecodeError::UnknownFormat)?;
+                let decrypted: Vec<u8> = header.iter().map(|&b| b ^ xor_key).collect();
+                match Self::checked_format(&decrypted)? {
+                    ImageFormat::Unknown => return Err(DatDecodeError::WrongKey),
+                    format => (format, Some(xor_key)),
+                }
+            }
+            DatContainer::V1 | DatContainer::V2 => {
+                let layout = ContainerLayout::parse(&header, file_size as usize)?;
+                let aes_key = match container {
+                    DatContainer::V1 => Some(keys.aes_key.unwrap_or(V1_AES_KEY)),
+                    _ => keys.aes_key,
+                };
+                match aes_key {
+                    Some(aes_key) => {
+                        let body = &header[CONTAINER_HEADER_LEN..];
+                        let first_block = aes128::decrypt_ecb(&aes_key, body);
+                        let first_block = &first_block[..layout.aes_size.min(first_block.len())];
+                        match Self::checked_format(first_block)? {
+                            ImageFormat::Unknown => return Err(DatDecodeError::WrongKey),
+                            format => (format, None),
+                        }
+                    }
+                    None => {
+                        let plain = Self::read_plain_section(&mut file, &layout, file_size)?;
+                        let offset =
+                            Self::embedded_offset(&plain).ok_or(DatDecodeError::MissingKey)?;
+                        partial = true;
+                        (Self::detect_image_format(&plain[offset..]), None)
+                    }
+                }
+            }
+        };
+
+        Ok(WeChatImageInfo {
             original_format,
             xor_key,
             file_size,
+            container,
+            partial,
         })
     }
-    
-    pub fn decrypt_dat_file(path: &Path) -> Option<(Vec<u8>, ImageFormat)> {
-        let file = File::open(path).ok()?;
-        let mut reader = BufReader::new(file);
-        
-        let metadata = std::fs::metadata(path).ok()?;
-        let file_size = metadata.len() as usize;
-        
-        if file_size < MIN_FILE_SIZE as usize {
-            return None;
+
+    /// 读取新版容器中未加密的中间部分
+    fn read_plain_section(
+        file: &mut File,
+        layout: &ContainerLayout,
+        file_size: u64,
+    ) -> Result<Vec<u8>, DatDecodeError> {
+        let start = (CONTAINER_HEADER_LEN + layout.encrypted_len()) as u64;
+        let len = file_size - start - layout.xor_size as u64;
+        let mut plain = Vec::with_capacity(len as usize);
+        file.seek(SeekFrom::Start(start))
+            .and_then(|_| file.take(len).read_to_end(&mut plain))
+            .map_err(|_| DatDecodeError::Unreadable)?;
+        Ok(plain)
+    }
+
+    pub fn analyze_dat_file(path: &Path) -> Option<WeChatImageInfo> {
+        Self::inspect_dat_file(path).ok()
+    }
+
+    /// 解码 `.dat` 文件的内容
+    pub fn decode(data: &[u8], keys: &DatKeys) -> Result<DecodedDat, DatDecodeError> {
+        if (data.len() as u64) < MIN_FILE_SIZE {
+            return Err(DatDecodeError::Unreadable);
         }
-        
-        let mut data = Vec::with_capacity(file_size);
-        reader.read_to_end(&mut data).ok()?;
-        
-        let xor_key = Self::detect_xor_key(&data)?;
-        
-        let decrypted: Vec<u8> = data.iter().map(|&b| b ^ xor_key).collect();
-        let format = Self::detect_image_format(&decrypted);
-        
-        Some((decrypted, format))
+
+        match DatContainer::detect(data) {
+            DatContainer::Xor => {
+                let xor_key = keys
+                    .xor_key
+                    .or_else(|| Self::detect_xor_key(data))
+                    .ok_or(DatDecodeError::UnknownFormat)?;
+                let decrypted: Vec<u8> = data.iter().map(|&b| b ^ xor_key).collect();
+                let format = Self::checked_format(&decrypted)?;
+                if format == ImageFormat::Unknown {
+                    return Err(DatDecodeError::WrongKey);
+                }
+                Ok(DecodedDat {
+                    data: decrypted,
+                    format,
+                    container: DatContainer::Xor,
+                    partial: false,
+                })
+            }
+            container => Self::decode_container(data, container, keys),
+        }
+    }
+
+    pub fn decode_file(path: &Path, keys: &DatKeys) -> Result<DecodedDat, DatDecodeError> {
+        let data = std::fs::read(path).map_err(|_| DatDecodeError::Unreadable)?;
+        Self::decode(&data, keys)
+    }
+
+    fn decode_container(
+        data: &[u8],
+        container: DatContainer,
+        keys: &DatKeys,
+    ) -> Result<DecodedDat, DatDecodeError> {
+        let layout = ContainerLayout::parse(data, data.len())?;
+        let body = &data[CONTAINER_HEADER_LEN..];
+        let (encrypted, rest) = body.split_at(layout.encrypted_len());
+        let (plain, tail) = rest.split_at(rest.len() - layout.xor_size);
+
+        let aes_key = match container {
+            DatContainer::V1 => Some(keys.aes_key.unwrap_or(V1_AES_KEY)),
+            _ => keys.aes_key,
+        };
+        let Some(aes_key) = aes_key else {
+            return Self::extract_plain_payload(plain, tail, container, keys);
+        };
+
+        let mut decoded = aes128::decrypt_ecb(&aes_key, encrypted);
+        decoded.truncate(layout.aes_size);
+        let format = Self::checked_format(&decoded)?;
+        if format == ImageFormat::Unknown {
+            return Err(DatDecodeError::WrongKey);
+        }
+
+        decoded.extend_from_slice(plain);
+        if !tail.is_empty() {
+            let xor_key = keys
+                .xor_key
+                .or_else(|| Self::trailer_key(&format, tail))
+                .ok_or(DatDecodeError::MissingKey)?;
+            decoded.extend(tail.iter().map(|&b| b ^ xor_key));
+        }
+
+        Ok(DecodedDat {
+            data: decoded,
+            format,
+            container,
+            partial: false,
+        })
+    }
+
+    /// 没有 AES 密钥时，从未加密部分中第一个可识别的签名开始提取
+    fn extract_plain_payload(
+        plain: &[u8],
+        tail: &[u8],
+        container: DatContainer,
+        keys: &DatKeys,
+    ) -> Result<DecodedDat, DatDecodeError> {
+        let offset = Self::embedded_offset(plain).ok_or(DatDecodeError::MissingKey)?;
+
+        let mut data = plain[offset..].to_vec();
+        let format = Self::detect_image_format(&data);
+        if let Some(xor_key) = keys
+            .xor_key
+            .or_else(|| Self::trailer_key(&format, tail))
+        {
+            data.extend(tail.iter().map(|&b| b ^ xor_key));
+        }
+
+        Ok(DecodedDat {
+            data,
+            format,
+            container,
+            partial: true,
+        })
+    }
+
+    /// 未加密部分中第一个可识别的内嵌图片签名的位置
+    fn embedded_offset(plain: &[u8]) -> Option<usize> {
+        (0..plain.len()).find(|&i| {
+            EMBEDDED_SIGNATURES
+                .iter()
+                .any(|signature| plain[i..].starts_with(signature))
+        })
+    }
+
+    /// 解密后的内容为 wxgf 时返回错误，否则返回检测到的格式
+    fn checked_format(decrypted: &[u8]) -> Result<ImageFormat, DatDecodeError> {
+        if decrypted.starts_with(WXGF_MAGIC) {
+            return Err(DatDecodeError::Wxgf);
+        }
+        Ok(Self::detect_image_format(decrypted))
+    }
+
+    fn trailer_key(format: &ImageFormat, tail: &[u8]) -> Option<u8> {
+        Some(tail.last()? ^ format.trailer_byte()?)
+    }
+
+    /// 解码整个文件，无法完整解码时返回 `None`
+    pub fn decrypt_dat_file(path: &Path) -> Option<(Vec<u8>, ImageFormat)> {
+        Self::decode_file(path, &DatKeys::default())
+            .ok()
+            .filter(|decoded| !decoded.partial)
+            .map(|decoded| (decoded.data, decoded.format))
     }
-    
-    pub fn decrypt_dat_file_chunked(path: &Path, chunk_size: usize) -> Option<(Vec<u8>, ImageFormat)> {
+
+    pub fn decrypt_dat_file_chunked(
+        path: &Path,
+        chunk_size: usize,
+    ) -> Option<(Vec<u8>, ImageFormat)> {
         let file = File::open(path).ok()?;
         let metadata = std::fs::metadata(path).ok()?;
         let file_size = metadata.len();
-        
+
         if file_size < MIN_FILE_SIZE {
             return None;
         }
-        
+
         let mut reader = BufReader::new(file);
-        
+
         let mut header = [0u8; XOR_KEY_SEARCH_BYTES];
         reader.read_exact(&mut header).ok()?;
-        
+
+        // 新版容器各部分的处理方式不同，整体读入后解码
+        if DatContainer::detect(&header) != DatContainer::Xor {
+            return Self::decrypt_dat_file(path);
+        }
+
         let xor_key = Self::detect_xor_key(&header)?;
-        
+
         let decrypted_header: Vec<u8> = header.iter().map(|&b| b ^ xor_key).collect();
-        let format = Self::detect_image_format(&decrypted_header);
-        
+        let format = Self::checked_format(&decrypted_header).ok()?;
+
         let mut decrypted = Vec::with_capacity(file_size as usize);
         decrypted.extend_from_slice(&decrypted_header);
-        
-        reader.seek(SeekFrom::Start(XOR_KEY_SEARCH_BYTES as u64)).ok()?;
-        
+
+        reader
+            .seek(SeekFrom::Start(XOR_KEY_SEARCH_BYTES as u64))
+            .ok()?;
+
         let mut buffer = vec![0u8; chunk_size];
         loop {
             let bytes_read = reader.read(&mut buffer).ok()?;
             if bytes_read == 0 {
                 break;
             }
-            
+
             for byte in &buffer[..bytes_read] {
                 decrypted.push(*byte ^ xor_key);
             }
         }
-        
+
         Some((decrypted, format))
     }
-    
+
     pub fn decrypt_to_file(source: &Path, target: &Path) -> Option<ImageFormat> {
         let (decrypted, format) = Self::decrypt_dat_file(source)?;
         std::fs::write(target, decrypted).ok()?;
@@ -215,6 +538,44 @@ impl WeChatDatDecoder {
     }
 }
 
+/// 新版容器各部分的长度
+struct ContainerLayout {
+    aes_size: usize,
+    xor_size: usize,
+}
+
+impl ContainerLayout {
+    /// 解析文件头，`file_size` 为整个文件的大小
+    fn parse(header: &[u8], file_size: usize) -> Result<Self, DatDecodeError> {
+        if header.len() < CONTAINER_HEADER_LEN {
+            return Err(DatDecodeError::Truncated);
+        }
+        let read_u32 = |offset: usize| {
+            u32::from_le_bytes([
+                header[offset],
+                header[offset + 1],
+                header[offset + 2],
+                header[offset + 3],
+            ]) as usize
+        };
+        let layout = Self {
+            aes_size: read_u32(6),
+            xor_size: read_u32(10),
+        };
+
+        let body_len = file_size.saturating_sub(CONTAINER_HEADER_LEN);
+        match layout.encrypted_len().checked_add(layout.xor_size) {
+            Some(len) if len <= body_len => Ok(layout),
+            _ => Err(DatDecodeError::Truncated),
+        }
+    }
+
+    /// 加密部分按 PKCS#7 填充到整块后的长度
+    fn encrypted_len(&self) -> usize {
+        (self.aes_size / aes128::BLOCK_SIZE + 1) * aes128::BLOCK_SIZE
+    }
+}
+
 pub fn is_wechat_encrypted_image(path: &Path) -> bool {
     WeChatDatDecoder::is_dat_file(path) && WeChatDatDecoder::analyze_dat_file(path).is_some()
 }
@@ -226,32 +587,186 @@ pub fn get_decrypted_image_format(path: &Path) -> Option<ImageFormat> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    
+
+    const JPEG: &[u8] = &[
+        0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, 0x4A, 0x46, 0x49, 0x46, 0x00, 0x01,
+    ];
+
+    /// 构造一张 `len` 字节、以 JPEG 文件头开始并以 `FF D9` 结束的图片
+    fn jpeg(len: usize) -> Vec<u8> {
+        let mut image = JPEG.to_vec();
+        image.extend((0..len - JPEG.len() - 2).map(|i| (i % 251) as u8));
+        image.extend_from_slice(&[0xFF, 0xD9]);
+        image
+    }
+
+    /// 按新版容器格式加密图片：开头 `aes_size` 字节 AES 加密，末尾 `xor_size` 字节异或
+    fn container(
+        magic: [u8; 6],
+        aes_key: &[u8; 16],
+        xor_key: u8,
+        image: &[u8],
+        aes_size: usize,
+        xor_size: usize,
+    ) -> Vec<u8> {
+        let mut head = image[..aes_size].to_vec();
+        let padding = aes128::BLOCK_SIZE - aes_size % aes128::BLOCK_SIZE;
+        head.extend(std::iter::repeat(padding as u8).take(padding));
+
+        let mut data = magic.to_vec();
+        data.extend_from_slice(&(aes_size as u32).to_le_bytes());
+        data.extend_from_slice(&(xor_size as u32).to_le_bytes());
+        data.push(0x01);
+        data.extend(aes128::encrypt_ecb(aes_key, &head));
+        data.extend_from_slice(&image[aes_size..image.len() - xor_size]);
+        data.extend(image[image.len() - xor_size..].iter().map(|&b| b ^ xor_key));
+        data
+    }
+
     #[test]
     fn test_detect_xor_key_jpeg() {
         let jpeg_header = [0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, 0x4A, 0x46];
         let key = 0xAB;
         let encrypted: Vec<u8> = jpeg_header.iter().map(|&b| b ^ key).collect();
-        
+
         let detected = WeChatDatDecoder::detect_xor_key(&encrypted);
         assert_eq!(detected, Some(key));
     }
-    
+
     #[test]
     fn test_detect_xor_key_png() {
         let png_header = [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
         let key = 0x55;
         let encrypted: Vec<u8> = png_header.iter().map(|&b| b ^ key).collect();
-        
+
         let detected = WeChatDatDecoder::detect_xor_key(&encrypted);
         assert_eq!(detected, Some(key));
     }
-    
+
     #[test]
     fn test_detect_image_format() {
         assert_eq!(WeChatDatDecoder::detect_image_format(&[0xFF, 0xD8, 0xFF]), ImageFormat::Jpeg);
         assert_eq!(WeChatDatDecoder::detect_image_format(&[0x89, 0x50, 0x4E, 0x47]), ImageFormat::Png);
         assert_eq!(WeChatDatDecoder::detect_image_format(&[0x47, 0x49, 0x46, 0x38]), ImageFormat::Gif);
         assert_eq!(WeChatDatDecoder::detect_image_format(&[0x42, 0x4D]), ImageFormat::Bmp);
+        assert_eq!(WeChatDatDecoder::detect_image_format(b"II*\0\x08\0\0\0"), ImageFormat::Tiff);
+        assert_eq!(WeChatDatDecoder::detect_image_format(b"MM\0*\0\0\0\x08"), ImageFormat::Tiff);
+        assert_eq!(WeChatDatDecoder::detect_image_format(b"\0\0\0\x18ftypheic\0\0\0\0"), ImageFormat::Heic);
+        assert_eq!(WeChatDatDecoder::detect_image_format(b"\0\0\0\x1cftypisom\0\0\x02\0"), ImageFormat::Mp4);
+    }
+
+    #[test]
+    fn test_decode_xor_heic_and_mp4() {
+        for (header, format) in [
+            (&b"\0\0\0\x18ftypmif1\0\0\0\0mif1heic"[..], ImageFormat::Heic),
+            (&b"\0\0\0\x1cftypmp42\0\0\0\0isommp42"[..], ImageFormat::Mp4),
+        ] {
+            let encrypted: Vec<u8> = header.iter().map(|&b| b ^ 0x3C).collect();
+            assert_eq!(WeChatDatDecoder::detect_xor_key(&encrypted), Some(0x3C));
+
+            let decoded = WeChatDatDecoder::decode(&encrypted, &DatKeys::default()).unwrap();
+            assert_eq!(decoded.format, format);
+            assert_eq!(decoded.data, header);
+            assert_eq!(decoded.container, DatContainer::Xor);
+        }
+    }
+
+    #[test]
+    fn test_decode_v1_container() {
+        let image = jpeg(3000);
+        let data = container(V1_MAGIC, &V1_AES_KEY, 0x9E, &image, 1024, 100);
+
+        let decoded = WeChatDatDecoder::decode(&data, &DatKeys::default()).unwrap();
+        assert_eq!(decoded.container, DatContainer::V1);
+        assert_eq!(decoded.format, ImageFormat::Jpeg);
+        assert!(!decoded.partial);
+        assert_eq!(decoded.data, image);
+    }
+
+    #[test]
+    fn test_decode_v1_block_aligned_without_tail() {
+        let image = jpeg(64);
+        let data = container(V1_MAGIC, &V1_AES_KEY, 0, &image, 32, 0);
+
+        let decoded = WeChatDatDecoder::decode(&data, &DatKeys::default()).unwrap();
+        assert_eq!(decoded.data, image);
+    }
+
+    #[test]
+    fn test_decode_v2_container_with_key() {
+        let key = *b"0123456789abcdef";
+        let image = jpeg(2048);
+        let data = container(V2_MAGIC, &key, 0x21, &image, 1024, 16);
+
+        let keys = DatKeys {
+            aes_key: Some(key),
+            xor_key: None,
+        };
+        let decoded = WeChatDatDecoder::decode(&data, &keys).unwrap();
+        assert_eq!(decoded.container, DatContainer::V2);
+        assert_eq!(decoded.data, image);
+
+        let wrong = DatKeys {
+            aes_key: Some(V1_AES_KEY),
+            xor_key: None,
+        };
+        assert_eq!(
+            WeChatDatDecoder::decode(&data, &wrong).unwrap_err(),
+            DatDecodeError::WrongKey
+        );
+    }
+
+    #[test]
+    fn test_decode_v2_without_key_extracts_plain_payload() {
+        // 开头加密部分之后是一张完整的内嵌图片
+        let key = *b"0123456789abcdef";
+        let mut image = vec![0u8; 1024];
+        image[..JPEG.len()].copy_from_slice(JPEG);
+        let embedded = jpeg(500);
+        image.extend_from_slice(&embedded);
+        let data = container(V2_MAGIC, &key, 0x47, &image, 1024, 8);
+
+        let decoded = WeChatDatDecoder::decode(&data, &DatKeys::default()).unwrap();
+        assert!(decoded.partial);
+        assert_eq!(decoded.format, ImageFormat::Jpeg);
+        assert_eq!(decoded.data, embedded);
+
+        let opaque = container(V2_MAGIC, &key, 0x47, &[0u8; 1100], 1024, 8);
+        assert_eq!(
+            WeChatDatDecoder::decode(&opaque, &DatKeys::default()).unwrap_err(),
+            DatDecodeError::MissingKey
+        );
+    }
+
+    #[test]
+    fn test_decode_failure_reasons() {
+        let mut truncated = container(V1_MAGIC, &V1_AES_KEY, 0x10, &jpeg(2000), 1024, 100);
+        truncated.truncate(1000);
+        assert_eq!(
+            WeChatDatDecoder::decode(&truncated, &DatKeys::default()).unwrap_err(),
+            DatDecodeError::Truncated
+        );
+
+        let mut wxgf = b"wxgf".to_vec();
+        wxgf.extend_from_slice(&[0u8; 60]);
+        let xored: Vec<u8> = wxgf.iter().map(|&b| b ^ 0x5D).collect();
+        assert_eq!(
+            WeChatDatDecoder::decode(&xored, &DatKeys::default()).unwrap_err(),
+            DatDecodeError::Wxgf
+        );
+        let in_container = container(V1_MAGIC, &V1_AES_KEY, 0, &wxgf, 32, 0);
+        assert_eq!(
+            WeChatDatDecoder::decode(&in_container, &DatKeys::default()).unwrap_err(),
+            DatDecodeError::Wxgf
+        );
+
+        assert_eq!(
+            WeChatDatDecoder::decode(&[0u8; 32], &DatKeys::default()).unwrap_err(),
+            DatDecodeError::UnknownFormat
+        );
+        assert_eq!(
+            WeChatDatDecoder::decode(&[0u8; 4], &DatKeys::default()).unwrap_err(),
+            DatDecodeError::Unreadable
+        );
     }
 }
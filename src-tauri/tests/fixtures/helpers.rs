@@ -0,0 +1,63 @@
+//! 集成测试共用的文件与数据构造函数
+
+use std::fs::{self, File, FileTimes};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use disktidy_lib::modules::software_residue::InstalledSoftware;
+
+/// 写入文件，自动创建上级目录
+pub fn write(path: &Path, content: &[u8]) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+pub fn days_ago(days: u64) -> SystemTime {
+    SystemTime::now() - Duration::from_secs(days * 24 * 60 * 60)
+}
+
+/// 把修改时间设为 Unix 时间戳 `secs`
+pub fn set_mtime(path: &Path, secs: u64) {
+    File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(UNIX_EPOCH + Duration::from_secs(secs))
+        .unwrap();
+}
+
+pub fn set_times(path: &Path, accessed: SystemTime, modified: SystemTime) {
+    let times = FileTimes::new()
+        .set_accessed(accessed)
+        .set_modified(modified);
+    File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_times(times)
+        .unwrap();
+}
+
+/// 把文件或目录及其中的文件的修改时间设为若干天前
+pub fn age(path: &Path, days: u64) {
+    let time = days_ago(days);
+    for entry in walkdir::WalkDir::new(path).contents_first(true) {
+        let entry = entry.unwrap();
+        File::open(entry.path())
+            .unwrap()
+            .set_modified(time)
+            .unwrap();
+    }
+}
+
+pub fn software(name: &str, publisher: &str, location: Option<&Path>) -> InstalledSoftware {
+    InstalledSoftware {
+        name: name.to_string(),
+        publisher: Some(publisher.to_string()),
+        install_location: location.map(|p| p.to_string_lossy().to_string()),
+        uninstall_string: None,
+        version: None,
+        install_date: None,
+        installed_size: None,
+    }
+}
@@ -1,3 +1,5 @@
+pub mod helpers;
 pub mod test_fixture;
 
+pub use helpers::*;
 pub use test_fixture::*;
@@ -1,10 +1,11 @@
 #![allow(dead_code)]
 
 use std::path::{Path, PathBuf};
-use std::fs::{self, File};
-use std::io::Write;
+use std::fs;
 use tempfile::TempDir;
 
+use super::helpers::write;
+
 pub struct TestFixture {
     pub temp_dir: TempDir,
     pub test_files: Vec<PathBuf>,
@@ -20,14 +21,8 @@ impl TestFixture {
 
     pub fn create_file(&mut self, name: &str, content: &[u8]) -> PathBuf {
         let path = self.temp_dir.path().join(name);
-        
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent).unwrap();
-        }
-        
-        let mut file = File::create(&path).unwrap();
-        file.write_all(content).unwrap();
-        
+        write(&path, content);
+
         self.test_files.push(path.clone());
         path
     }
@@ -0,0 +1,199 @@
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+use disktidy_lib::modules::app_cache::{
+    conversation_files, summarize_conversations, AppCacheEngine, AppCacheFile,
+    AppDefinitionRegistry, CleanCategory, ScanControl,
+};
+
+use crate::fixtures::{set_mtime, write};
+
+fn write_at(path: &Path, content: &[u8], secs: u64) {
+    write(path, content);
+    set_mtime(path, secs);
+}
+
+fn scan_app(app: &str, root: &Path, categories: &[CleanCategory]) -> Vec<AppCacheFile> {
+    let registry = AppDefinitionRegistry::builtin();
+    let engine = AppCacheEngine::new(registry.get(app).unwrap(), categories);
+    let mut files = Vec::new();
+    let mut skipped = 0;
+    for account in engine.resolve(Some(root)) {
+        engine.scan_account(&account, &mut files, &mut skipped, &mut |_, _| {
+            ScanControl::Continue
+        });
+    }
+    files
+}
+
+fn scan_wechat(root: &Path) -> Vec<AppCacheFile> {
+    scan_app(
+        "wechat",
+        root,
+        &[CleanCategory::ChatImages, CleanCategory::ThumbCache],
+    )
+}
+
+fn owners(files: &[AppCacheFile]) -> Vec<(&str, &str)> {
+    let mut owners: Vec<(&str, &str)> = files
+        .iter()
+        .map(|f| (f.name.as_str(), f.chat_object.as_str()))
+        .collect();
+    owners.sort();
+    owners
+}
+
+#[test]
+fn test_wechat_files_grouped_by_conversation() {
+    let dir = TempDir::new().unwrap();
+    let new_account = dir.path().join("wxid_new");
+    write_at(
+        &new_account.join("msg/attach/group_a/2024-01/Img/1.dat"),
+        &[0u8; 300],
+        1_700_000_000,
+    );
+    write_at(
+        &new_account.join("msg/attach/group_a/2024-03/Img/2.dat"),
+        &[0u8; 200],
+        1_710_000_000,
+    );
+    write_at(
+        &new_account.join("msg/attach/friend_b/2024-02/Img/3.dat"),
+        &[0u8; 50],
+        1_705_000_000,
+    );
+    fs::create_dir_all(new_account.join("Msg")).unwrap();
+
+    let old_account = dir.path().join("wxid_old");
+    write(
+        &old_account.join("FileStorage/MsgAttach/group_c/Image/2023-12/4.dat"),
+        &[0u8; 100],
+    );
+    write(
+        &old_account.join("FileStorage/MsgAttach/group_c/Thumb/2023-12/4_t.dat"),
+        &[0u8; 10],
+    );
+
+    let files = scan_wechat(dir.path());
+    assert_eq!(files.len(), 5);
+
+    let summaries = summarize_conversations(&files);
+    let keys: Vec<(&str, &str)> = summaries
+        .iter()
+        .map(|s| (s.account_id.as_deref().unwrap(), s.conversation.as_str()))
+        .collect();
+    assert_eq!(
+        keys,
+        vec![
+            ("wxid_new", "group_a"),
+            ("wxid_old", "group_c"),
+            ("wxid_new", "friend_b")
+        ]
+    );
+
+    let group_a = &summaries[0];
+    assert_eq!(group_a.stats.file_count, 2);
+    assert_eq!(group_a.stats.total_size, 500);
+    assert_eq!(group_a.stats.earliest_modified, 1_700_000_000_000);
+    assert_eq!(group_a.stats.latest_modified, 1_710_000_000_000);
+
+    let group_c = &summaries[1];
+    assert_eq!(group_c.categories.len(), 2);
+    assert_eq!(group_c.categories[0].category, CleanCategory::ChatImages);
+    assert_eq!(group_c.categories[0].stats.total_size, 100);
+    assert_eq!(group_c.categories[1].category, CleanCategory::ThumbCache);
+
+    let selected = conversation_files(
+        &files,
+        "wechat",
+        Some("wxid_old"),
+        "group_c",
+        &[CleanCategory::ThumbCache],
+    );
+    assert_eq!(selected.len(), 1);
+    assert_eq!(selected[0].name, "4_t.dat");
+    assert!(conversation_files(&files, "wechat", Some("wxid_new"), "group_c", &[]).is_empty());
+}
+
+#[test]
+fn test_qq_files_grouped_by_conversation() {
+    let dir = TempDir::new().unwrap();
+    let account = dir.path().join("12345");
+    write(&account.join("Msg3.0.db"), b"db");
+    write(&account.join("Image/Group2/group_a/AB/1.jpg"), &[0u8; 30]);
+    write(&account.join("Image/C2C/friend_b/2.png"), &[0u8; 20]);
+    write(&account.join("Image/Image2/3.jpg"), &[0u8; 10]);
+    write(&account.join("Video/Group/group_a/4.mp4"), &[0u8; 40]);
+    write(&account.join("Audio/C2C/friend_b/5.amr"), &[0u8; 5]);
+
+    let files = scan_app(
+        "qq",
+        dir.path(),
+        &[
+            CleanCategory::ChatImages,
+            CleanCategory::VideoFiles,
+            CleanCategory::VoiceFiles,
+        ],
+    );
+    // 会话目录也位于 Image 之下，每个文件只记入一次
+    assert_eq!(
+        owners(&files),
+        vec![
+            ("1.jpg", "group_a"),
+            ("2.png", "friend_b"),
+            ("3.jpg", "12345"),
+            ("4.mp4", "group_a"),
+            ("5.amr", "friend_b"),
+        ]
+    );
+
+    let summaries = summarize_conversations(&files);
+    let group_a = summaries
+        .iter()
+        .find(|s| s.conversation == "group_a")
+        .unwrap();
+    assert_eq!(group_a.stats.file_count, 2);
+    assert_eq!(group_a.stats.total_size, 70);
+    assert_eq!(group_a.categories.len(), 2);
+}
+
+#[test]
+fn test_wework_files_grouped_by_conversation() {
+    let dir = TempDir::new().unwrap();
+    let account = dir.path().join("1688850000000000");
+    let attach = account.join("FileStorage/MsgAttach");
+    write(&attach.join("room_a/Image/2024-05/1.jpg"), &[0u8; 30]);
+    write(&attach.join("room_a/File/2024-05/plan.pdf"), &[0u8; 60]);
+    write(&attach.join("user_b/Video/2024-05/2.mp4"), &[0u8; 90]);
+    write(&account.join("Image/3.png"), &[0u8; 10]);
+
+    let files = scan_app(
+        "wework",
+        dir.path(),
+        &[
+            CleanCategory::ChatImages,
+            CleanCategory::VideoFiles,
+            CleanCategory::DocumentFiles,
+        ],
+    );
+    assert_eq!(
+        owners(&files),
+        vec![
+            ("1.jpg", "room_a"),
+            ("2.mp4", "user_b"),
+            ("3.png", "1688850000000000"),
+            ("plan.pdf", "room_a"),
+        ]
+    );
+
+    let selected = conversation_files(
+        &files,
+        "wework",
+        Some("1688850000000000"),
+        "room_a",
+        &[CleanCategory::DocumentFiles],
+    );
+    assert_eq!(selected.len(), 1);
+    assert_eq!(selected[0].name, "plan.pdf");
+}
@@ -0,0 +1,349 @@
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+use disktidy_lib::models::RiskLevel;
+use disktidy_lib::modules::app_cache::{
+    AppCacheEngine, AppCacheFile, AppDefinition, AppDefinitionRegistry, CleanCategory, ScanControl,
+};
+use disktidy_lib::utils::PathSource;
+
+use crate::fixtures::write;
+
+fn xor_jpeg(key: u8) -> Vec<u8> {
+    [
+        0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, 0x4A, 0x46, 0x49, 0x46, 0x00, 0x01,
+    ]
+    .iter()
+    .map(|b| b ^ key)
+    .collect()
+}
+
+fn scan(
+    definition: &AppDefinition,
+    root: &Path,
+    categories: &[CleanCategory],
+) -> Vec<AppCacheFile> {
+    let engine = AppCacheEngine::new(definition, categories);
+    let mut files = Vec::new();
+    let mut skipped = 0;
+    for account in engine.resolve(Some(root)) {
+        engine.scan_account(&account, &mut files, &mut skipped, &mut |_, _| {
+            ScanControl::Continue
+        });
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files
+}
+
+fn names(files: &[AppCacheFile], category: CleanCategory) -> Vec<String> {
+    files
+        .iter()
+        .filter(|f| f.category == category)
+        .map(|f| f.name.clone())
+        .collect()
+}
+
+#[test]
+fn test_builtin_definitions_are_valid() {
+    let registry = AppDefinitionRegistry::builtin();
+    assert_eq!(
+        registry.ids(),
+        vec![
+            "wechat",
+            "dingtalk",
+            "qq",
+            "wework",
+            "slack",
+            "discord",
+            "telegram",
+            "vscode",
+            "jetbrains",
+            "spotify",
+            "steam",
+            "flatpak",
+            "electron"
+        ]
+    );
+    assert!(registry.errors.is_empty());
+    for definition in registry.definitions() {
+        definition.validate().unwrap();
+        assert!(!definition.categories().is_empty());
+    }
+    assert!(registry.get("WeChat").is_some());
+}
+
+#[test]
+fn test_user_definitions_override_and_report_errors() {
+    let dir = TempDir::new().unwrap();
+    write(
+        &dir.path().join("a_qq.json"),
+        br#"{"id":"qq","name":"QQ Lite","accounts":{"patterns":[]},"rules":[{"category":"cache_data","paths":["Cache"]}]}"#,
+    );
+    write(
+        &dir.path().join("b_matrix.json"),
+        br#"{"id":"matrix","name":"Element","rules":[{"category":"cache_data","paths":["cache"],"risk":"low"}]}"#,
+    );
+    write(
+        &dir.path().join("c_bad.json"),
+        br#"{"id":"bad","name":"Bad","rules":[{"category":"cache_data","paths":["../etc"]}]}"#,
+    );
+    write(&dir.path().join("d_broken.json"), b"{");
+    write(&dir.path().join("notes.txt"), b"ignored");
+
+    let mut registry = AppDefinitionRegistry::builtin();
+    registry.load_dir(dir.path());
+
+    assert_eq!(registry.ids().last().map(String::as_str), Some("matrix"));
+    assert_eq!(registry.ids().iter().filter(|id| *id == "qq").count(), 1);
+    assert_eq!(registry.get("qq").unwrap().name, "QQ Lite");
+    assert_eq!(
+        registry.get("matrix").unwrap().rules[0].risk,
+        Some(RiskLevel::Low)
+    );
+    assert_eq!(registry.errors.len(), 2);
+}
+
+#[test]
+fn test_wechat_definition_scans_account_tree() {
+    let dir = TempDir::new().unwrap();
+    let account = dir.path().join("wxid_abc");
+    fs::create_dir_all(account.join("FileStorage")).unwrap();
+    write(
+        &account.join("msg/attach/hash1/2024-01/Img/a.dat"),
+        &xor_jpeg(0x3A),
+    );
+    write(
+        &account.join("msg/attach/hash1/2024-01/Img/notes.txt"),
+        b"skip",
+    );
+    write(&account.join("msg/video/2024-01/clip.mp4"), b"video");
+    write(&account.join("msg/file/2024-01/report.pdf"), b"pdf");
+    write(&account.join("msg/file/2024-01/setup.exe"), b"exe");
+    write(&account.join("temp/scratch.tmp"), b"temp");
+    write(&account.join("cache/1/HttpResource/res.bin"), b"cache");
+    fs::create_dir_all(dir.path().join("Applet/FileStorage")).unwrap();
+    write(&dir.path().join("Applet/msg/file/x/other.pdf"), b"pdf");
+
+    let registry = AppDefinitionRegistry::builtin();
+    let wechat = registry.get("wechat").unwrap();
+    let files = scan(
+        wechat,
+        dir.path(),
+        &[
+            CleanCategory::ChatImages,
+            CleanCategory::VideoFiles,
+            CleanCategory::DocumentFiles,
+            CleanCategory::InstallPackages,
+            CleanCategory::CacheData,
+        ],
+    );
+
+    assert_eq!(names(&files, CleanCategory::ChatImages), vec!["a.dat"]);
+    assert_eq!(names(&files, CleanCategory::VideoFiles), vec!["clip.mp4"]);
+    assert_eq!(
+        names(&files, CleanCategory::DocumentFiles),
+        vec!["report.pdf"]
+    );
+    assert_eq!(
+        names(&files, CleanCategory::InstallPackages),
+        vec!["setup.exe"]
+    );
+    assert_eq!(names(&files, CleanCategory::CacheData), vec!["res.bin"]);
+    assert!(names(&files, CleanCategory::TempFiles).is_empty());
+
+    let image = files.iter().find(|f| f.name == "a.dat").unwrap();
+    assert!(image.is_encrypted);
+    assert_eq!(image.original_format.as_deref(), Some("jpg"));
+    assert_eq!(image.chat_object, "hash1");
+    assert!(files
+        .iter()
+        .all(|f| f.app == "wechat" && f.account_id.as_deref() == Some("wxid_abc")));
+    assert!(files
+        .iter()
+        .filter(|f| f.name != "a.dat")
+        .all(|f| f.chat_object == "wxid_abc"));
+}
+
+#[test]
+fn test_qq_accounts_require_marker_and_skip_excluded() {
+    let dir = TempDir::new().unwrap();
+    write(&dir.path().join("12345/Msg3.0.db"), b"db");
+    write(&dir.path().join("12345/Cache/a.bin"), b"a");
+    write(&dir.path().join("67890/Cache/b.bin"), b"b");
+    write(&dir.path().join("Registry/Image/c.png"), b"c");
+    write(&dir.path().join("QQTemp/Image/d.png"), b"d");
+
+    let registry = AppDefinitionRegistry::builtin();
+    let qq = registry.get("qq").unwrap();
+    let categories = [CleanCategory::CacheData];
+    let engine = AppCacheEngine::new(qq, &categories);
+    let accounts: Vec<String> = engine
+        .resolve_accounts(dir.path(), PathSource::UserConfig)
+        .into_iter()
+        .filter_map(|a| a.account_id)
+        .collect();
+    assert_eq!(accounts, vec!["12345", "QQTemp"]);
+
+    let files = scan(qq, dir.path(), &categories);
+    assert_eq!(names(&files, CleanCategory::CacheData), vec!["a.bin"]);
+}
+
+#[test]
+fn test_missing_configured_path_and_stop_control() {
+    let dir = TempDir::new().unwrap();
+    for i in 0..5 {
+        write(&dir.path().join(format!("user/Cache/{}.bin", i)), b"x");
+    }
+
+    let registry = AppDefinitionRegistry::builtin();
+    let dingtalk = registry.get("dingtalk").unwrap();
+    let categories = [CleanCategory::CacheData];
+    let engine = AppCacheEngine::new(dingtalk, &categories);
+    assert!(engine.resolve(Some(&dir.path().join("missing"))).is_empty());
+
+    let accounts = engine.resolve(Some(dir.path()));
+    assert_eq!(accounts.len(), 1);
+    let mut files = Vec::new();
+    let mut skipped = 0;
+    let control = engine.scan_account(&accounts[0], &mut files, &mut skipped, &mut |_, files| {
+        if files.len() >= 2 {
+            ScanControl::Stop
+        } else {
+            ScanControl::Continue
+        }
+    });
+    assert_eq!(control, ScanControl::Stop);
+    assert_eq!(files.len(), 2);
+}
+
+#[test]
+fn test_vscode_definition_categorises_linux_layout() {
+    let dir = TempDir::new().unwrap();
+    let code = dir.path().join("Code");
+    write(&code.join("Cache/Cache_Data/f_000001"), b"cache");
+    write(&code.join("CachedData/abc/chrome/js/index"), b"js");
+    write(
+        &code.join("CachedExtensionVSIXs/ms-python.python-2024"),
+        b"vsix",
+    );
+    write(
+        &code.join("User/workspaceStorage/123/state.vscdb"),
+        b"state",
+    );
+    write(&code.join("User/settings.json"), b"{}");
+    write(&code.join("logs/20240101/main.log"), b"log");
+
+    let registry = AppDefinitionRegistry::builtin();
+    let vscode = registry.get("vscode").unwrap();
+    let files = scan(
+        vscode,
+        &code,
+        &[
+            CleanCategory::CacheData,
+            CleanCategory::InstallPackages,
+            CleanCategory::LogFiles,
+        ],
+    );
+
+    assert_eq!(
+        names(&files, CleanCategory::CacheData),
+        vec!["f_000001", "index", "state.vscdb"]
+    );
+    assert_eq!(
+        names(&files, CleanCategory::InstallPackages),
+        vec!["ms-python.python-2024"]
+    );
+    assert_eq!(names(&files, CleanCategory::LogFiles), vec!["main.log"]);
+    assert!(files.iter().all(|f| f.chat_object == "Code"));
+
+    let workspace = files.iter().find(|f| f.name == "state.vscdb").unwrap();
+    assert_eq!(workspace.risk_level, Some(RiskLevel::Medium));
+    let cache = files.iter().find(|f| f.name == "f_000001").unwrap();
+    assert_eq!(cache.risk_level, None);
+}
+
+#[test]
+fn test_electron_apps_detected_by_cache_signature() {
+    let dir = TempDir::new().unwrap();
+    write(&dir.path().join("Signal/GPUCache/data_0"), b"gpu");
+    write(&dir.path().join("Signal/Code Cache/js/index"), b"js");
+    write(&dir.path().join("Signal/config.json"), b"{}");
+    write(&dir.path().join("Slack/Code Cache/js/index"), b"js");
+    write(&dir.path().join("gtk-3.0/Cache/settings.ini"), b"ini");
+
+    let registry = AppDefinitionRegistry::builtin();
+    let electron = registry.get("electron").unwrap();
+    let files = scan(electron, dir.path(), &[CleanCategory::CacheData]);
+
+    assert_eq!(
+        names(&files, CleanCategory::CacheData),
+        vec!["index", "data_0"]
+    );
+    assert!(files.iter().all(|f| f.chat_object == "Signal"));
+}
+
+#[test]
+fn test_flatpak_and_steam_definitions() {
+    let dir = TempDir::new().unwrap();
+    let apps = dir.path().join(".var/app");
+    write(&apps.join("org.gimp.GIMP/cache/gegl/swap"), b"swap");
+    write(&apps.join("org.gimp.GIMP/config/gimprc"), b"rc");
+    write(&apps.join("org.example.NoCache/data/file"), b"data");
+    let steam = dir.path().join("Steam");
+    write(
+        &steam.join("steamapps/shadercache/570/fozpipelinesv6/cache.foz"),
+        b"foz",
+    );
+    write(&steam.join("steamapps/common/Game/game.bin"), b"game");
+    write(&steam.join("logs/content_log.txt"), b"log");
+
+    let registry = AppDefinitionRegistry::builtin();
+    let flatpak_files = scan(
+        registry.get("flatpak").unwrap(),
+        &apps,
+        &[CleanCategory::CacheData],
+    );
+    assert_eq!(
+        names(&flatpak_files, CleanCategory::CacheData),
+        vec!["swap"]
+    );
+    assert_eq!(flatpak_files[0].chat_object, "org.gimp.GIMP");
+
+    let steam_files = scan(
+        registry.get("steam").unwrap(),
+        &steam,
+        &[CleanCategory::ShaderCache, CleanCategory::LogFiles],
+    );
+    assert_eq!(
+        names(&steam_files, CleanCategory::ShaderCache),
+        vec!["cache.foz"]
+    );
+    assert_eq!(
+        names(&steam_files, CleanCategory::LogFiles),
+        vec!["content_log.txt"]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_default_roots_pointing_to_same_directory_scanned_once() {
+    let dir = TempDir::new().unwrap();
+    let real = dir.path().join("real");
+    write(&real.join("cache/a.bin"), b"a");
+    std::os::unix::fs::symlink(&real, dir.path().join("link")).unwrap();
+
+    let definition = AppDefinition::from_json(&format!(
+        r#"{{"id":"linked","name":"Linked","roots":["{}","{}"],"accounts":{{"patterns":[]}},"rules":[{{"category":"cache_data","paths":["cache"]}}]}}"#,
+        real.display(),
+        dir.path().join("link").display()
+    ))
+    .unwrap();
+    let categories = [CleanCategory::CacheData];
+    let engine = AppCacheEngine::new(&definition, &categories);
+    let accounts = engine.resolve(None);
+
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0].path, real);
+    assert_eq!(accounts[0].source, PathSource::Default);
+}
@@ -0,0 +1,201 @@
+use chrono::{Local, TimeZone};
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::time::{Duration, UNIX_EPOCH};
+use tempfile::TempDir;
+
+use disktidy_lib::modules::app_cache::export::{
+    ExportManifest, ExportSummary, ImageExporter, EXPORT_MANIFEST_FILE,
+};
+use disktidy_lib::modules::app_cache::{AppCacheFile, CleanCategory};
+
+use crate::fixtures::write;
+
+const JPEG: &[u8] = &[
+    0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, 0x4A, 0x46, 0x49, 0x46, 0x00, 0x01,
+];
+const PNG: &[u8] = &[
+    0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D,
+];
+
+fn write_dat(path: &Path, image: &[u8], key: u8) {
+    let encrypted: Vec<u8> = image.iter().map(|b| b ^ key).collect();
+    write(path, &encrypted);
+}
+
+fn file(
+    account: &Path,
+    path: &Path,
+    conversation: &str,
+    encrypted: bool,
+    modified_at: i64,
+) -> AppCacheFile {
+    AppCacheFile {
+        id: path.to_string_lossy().to_string(),
+        path: path.to_string_lossy().to_string(),
+        name: path.file_name().unwrap().to_string_lossy().to_string(),
+        size: fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        category: CleanCategory::ChatImages,
+        app: "wechat".to_string(),
+        chat_object: conversation.to_string(),
+        account_id: Some("wxid_abc".to_string()),
+        account_path: Some(account.to_string_lossy().to_string()),
+        created_at: modified_at,
+        modified_at,
+        selected: true,
+        is_encrypted: encrypted,
+        original_format: None,
+        decode_error: None,
+        risk_level: None,
+    }
+}
+
+fn export(destination: &Path, files: &[AppCacheFile]) -> ExportSummary {
+    let cancelled = AtomicBool::new(false);
+    let paused = AtomicBool::new(false);
+    ImageExporter::new(destination)
+        .run(files, &cancelled, &paused, &mut |_| {})
+        .unwrap()
+}
+
+fn local_ms(y: i32, m: u32, d: u32) -> i64 {
+    Local
+        .with_ymd_and_hms(y, m, d, 12, 0, 0)
+        .earliest()
+        .unwrap()
+        .timestamp_millis()
+}
+
+#[test]
+fn test_export_decodes_and_organises_by_month() {
+    let source = TempDir::new().unwrap();
+    let dest = TempDir::new().unwrap();
+
+    let dat = source.path().join("msg/attach/group1/2024-05/Img/a1b2.dat");
+    write_dat(&dat, JPEG, 0x5A);
+    let mtime = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    fs::File::options()
+        .write(true)
+        .open(&dat)
+        .unwrap()
+        .set_modified(mtime)
+        .unwrap();
+    let plain = source.path().join("FileStorage/Image/photo.png");
+    write(&plain, PNG);
+
+    let files = vec![
+        file(source.path(), &dat, "group1", true, 0),
+        file(
+            source.path(),
+            &plain,
+            "wxid_abc",
+            false,
+            local_ms(2023, 11, 3),
+        ),
+    ];
+    let summary = export(dest.path(), &files);
+
+    assert_eq!(summary.exported_files, 2);
+    assert!(summary.errors.is_empty());
+
+    let jpg = dest.path().join("wechat/wxid_abc/group1/2024-05/a1b2.jpg");
+    assert_eq!(fs::read(&jpg).unwrap(), JPEG);
+    assert_eq!(fs::metadata(&jpg).unwrap().modified().unwrap(), mtime);
+    let png = dest
+        .path()
+        .join("wechat/wxid_abc/wxid_abc/2023-11/photo.png");
+    assert_eq!(fs::read(&png).unwrap(), PNG);
+
+    assert_eq!(
+        summary.manifest_path,
+        dest.path().join(EXPORT_MANIFEST_FILE)
+    );
+    let manifest = ExportManifest::load(dest.path()).unwrap();
+    assert_eq!(manifest.entries.len(), 2);
+    assert_eq!(
+        manifest.entries[0].output,
+        "wechat/wxid_abc/group1/2024-05/a1b2.jpg"
+    );
+    assert_eq!(manifest.entries[0].source, dat.to_string_lossy());
+    assert_eq!(manifest.entries[0].size, JPEG.len() as u64);
+}
+
+#[test]
+fn test_export_skips_already_exported_content() {
+    let source = TempDir::new().unwrap();
+    let dest = TempDir::new().unwrap();
+
+    // 同一张图片用不同的密钥加密，解码后内容相同
+    let first = source.path().join("attach/g/2024-01/Img/first.dat");
+    let second = source.path().join("attach/g/2024-01/Thumb/second.dat");
+    write_dat(&first, JPEG, 0x11);
+    write_dat(&second, JPEG, 0x77);
+    let files = vec![
+        file(source.path(), &first, "g", true, 0),
+        file(source.path(), &second, "g", true, 0),
+    ];
+
+    let summary = export(dest.path(), &files);
+    assert_eq!(summary.exported_files, 1);
+    assert_eq!(summary.skipped_files, 1);
+
+    let other = source.path().join("attach/g/2024-01/Img/other.dat");
+    write_dat(&other, PNG, 0x11);
+    let mut again = files.clone();
+    again.push(file(source.path(), &other, "g", true, 0));
+
+    let summary = export(dest.path(), &again);
+    assert_eq!(summary.exported_files, 1);
+    assert_eq!(summary.skipped_files, 2);
+    assert_eq!(ExportManifest::load(dest.path()).unwrap().entries.len(), 2);
+}
+
+#[test]
+fn test_export_reports_undecodable_files() {
+    let source = TempDir::new().unwrap();
+    let dest = TempDir::new().unwrap();
+
+    let broken = source.path().join("attach/g/2024-02/Img/broken.dat");
+    write(&broken, &[0u8; 16]);
+    let good = source.path().join("attach/g/2024-02/Img/good.dat");
+    write_dat(&good, PNG, 0x33);
+    let files = vec![
+        file(source.path(), &broken, "g", true, 0),
+        file(source.path(), &good, "g", true, 0),
+    ];
+
+    let summary = export(dest.path(), &files);
+    assert_eq!(summary.exported_files, 1);
+    assert_eq!(summary.errors.len(), 1);
+    assert_eq!(summary.errors[0].path, broken.to_string_lossy());
+    assert_eq!(summary.errors[0].error_code, "E024");
+    assert!(dest
+        .path()
+        .join("wechat/wxid_abc/g/2024-02/good.png")
+        .exists());
+}
+
+#[test]
+fn test_export_cancelled_before_start() {
+    let source = TempDir::new().unwrap();
+    let dest = TempDir::new().unwrap();
+    let dat = source.path().join("attach/g/2024-03/Img/a.dat");
+    write_dat(&dat, JPEG, 0x42);
+
+    let cancelled = AtomicBool::new(true);
+    let paused = AtomicBool::new(false);
+    let summary = ImageExporter::new(dest.path())
+        .run(
+            &[file(source.path(), &dat, "g", true, 0)],
+            &cancelled,
+            &paused,
+            &mut |_| {},
+        )
+        .unwrap();
+
+    assert!(summary.cancelled);
+    assert_eq!(summary.exported_files, 0);
+    assert!(!dest.path().join("wechat").exists());
+    assert!(dest.path().join(EXPORT_MANIFEST_FILE).exists());
+}
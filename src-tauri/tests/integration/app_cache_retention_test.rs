@@ -0,0 +1,231 @@
+use chrono::{Local, TimeZone};
+use std::path::Path;
+use tempfile::TempDir;
+
+use disktidy_lib::modules::app_cache::retention::{
+    apply_retention, directory_date, preview_retention, AgeSource, RetentionAction,
+    RetentionPolicy, RetentionSchedule,
+};
+use disktidy_lib::modules::app_cache::{AppCacheFile, CleanCategory};
+
+use crate::fixtures::write;
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+fn local_ms(y: i32, m: u32, d: u32) -> i64 {
+    Local
+        .with_ymd_and_hms(y, m, d, 0, 0, 0)
+        .earliest()
+        .unwrap()
+        .timestamp_millis()
+}
+
+fn file(path: &str, category: CleanCategory, conversation: &str, modified_at: i64) -> AppCacheFile {
+    AppCacheFile {
+        id: path.to_string(),
+        path: path.to_string(),
+        name: Path::new(path)
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .to_string(),
+        size: 100,
+        category,
+        app: "wechat".to_string(),
+        chat_object: conversation.to_string(),
+        account_id: Some("wxid".to_string()),
+        account_path: Some("/w".to_string()),
+        created_at: modified_at,
+        modified_at,
+        selected: false,
+        is_encrypted: false,
+        original_format: None,
+        decode_error: None,
+        risk_level: None,
+    }
+}
+
+fn policy(id: &str, categories: Vec<CleanCategory>, keep_days: Option<u32>) -> RetentionPolicy {
+    RetentionPolicy {
+        id: id.to_string(),
+        name: id.to_string(),
+        enabled: true,
+        apps: vec!["wechat".to_string()],
+        categories,
+        conversations: Vec::new(),
+        keep_days,
+        action: RetentionAction::Delete,
+        archive: None,
+    }
+}
+
+#[test]
+fn test_directory_date_uses_end_of_period() {
+    let w = Path::new("/w");
+    assert_eq!(
+        directory_date(Path::new("/w/msg/attach/abc/2024-05/Img/a.dat"), w),
+        Some(local_ms(2024, 6, 1) - 1)
+    );
+    assert_eq!(
+        directory_date(Path::new("/w/msg/video/2024-12/clip.mp4"), w),
+        Some(local_ms(2025, 1, 1) - 1)
+    );
+    assert_eq!(
+        directory_date(Path::new("/w/2024-05-17/photo.jpg"), w),
+        Some(local_ms(2024, 5, 18) - 1)
+    );
+    assert_eq!(
+        directory_date(Path::new("/qq/20240105/Image/a.png"), Path::new("/qq")),
+        None
+    );
+    assert_eq!(directory_date(Path::new("/w/2024-13/a.png"), w), None);
+    assert_eq!(directory_date(Path::new("/w/2024-05/"), w), None);
+
+    // 账号目录及其上级目录的名称不作为日期
+    assert_eq!(
+        directory_date(
+            Path::new("/backup/2023-01/wxid/msg/a.dat"),
+            Path::new("/backup/2023-01/wxid")
+        ),
+        None
+    );
+    assert_eq!(
+        directory_date(
+            Path::new("/data/2024-05/Image/a.png"),
+            Path::new("/data/2024-05")
+        ),
+        None
+    );
+}
+
+#[test]
+fn test_preview_applies_first_matching_policy() {
+    let now = local_ms(2024, 9, 1);
+    let old_mtime = now - 400 * DAY_MS;
+    let files = vec![
+        file(
+            "/w/msg/attach/g1/2024-01/Img/old.dat",
+            CleanCategory::ChatImages,
+            "g1",
+            now,
+        ),
+        file(
+            "/w/msg/attach/g1/2024-08/Img/new.dat",
+            CleanCategory::ChatImages,
+            "g1",
+            old_mtime,
+        ),
+        file(
+            "/w/msg/video/clip.mp4",
+            CleanCategory::VideoFiles,
+            "wxid",
+            old_mtime,
+        ),
+        file(
+            "/w/msg/file/2023-01/report.pdf",
+            CleanCategory::DocumentFiles,
+            "wxid",
+            old_mtime,
+        ),
+        file("/w/temp/x.tmp", CleanCategory::TempFiles, "wxid", old_mtime),
+    ];
+    let policies = vec![
+        policy("docs", vec![CleanCategory::DocumentFiles], None),
+        policy(
+            "media",
+            vec![CleanCategory::ChatImages, CleanCategory::VideoFiles],
+            Some(90),
+        ),
+        policy("all", Vec::new(), Some(0)),
+    ];
+
+    let preview = preview_retention(&files, &policies, now);
+
+    assert_eq!(preview.policies.len(), 3);
+    assert_eq!(preview.policies[0].file_count, 0);
+
+    let media = &preview.policies[1];
+    let names: Vec<&str> = media.files.iter().map(|c| c.file.name.as_str()).collect();
+    assert_eq!(names, vec!["old.dat", "clip.mp4"]);
+    assert_eq!(media.files[0].age_source, AgeSource::DirectoryDate);
+    assert_eq!(media.files[1].age_source, AgeSource::ModifiedTime);
+    assert_eq!(media.files[1].age_days, 400);
+    assert_eq!(media.total_size, 200);
+
+    assert_eq!(preview.policies[2].file_count, 1);
+    assert_eq!(preview.policies[2].files[0].file.name, "x.tmp");
+    assert_eq!(preview.kept_files, 2);
+}
+
+#[test]
+fn test_conversation_scoped_policy() {
+    let now = local_ms(2024, 9, 1);
+    let old = now - 200 * DAY_MS;
+    let files = vec![
+        file(
+            "/w/a/noisy.dat",
+            CleanCategory::ChatImages,
+            "noisy_group",
+            old,
+        ),
+        file("/w/a/family.dat", CleanCategory::ChatImages, "family", old),
+    ];
+    let mut noisy = policy("noisy", Vec::new(), Some(30));
+    noisy.conversations = vec!["noisy_group".to_string()];
+    let mut other_app = policy("qq", Vec::new(), Some(0));
+    other_app.apps = vec!["qq".to_string()];
+
+    let preview = preview_retention(&files, &[noisy, other_app], now);
+    assert_eq!(preview.policies[0].files.len(), 1);
+    assert_eq!(preview.policies[0].files[0].file.name, "noisy.dat");
+    assert_eq!(preview.policies[1].file_count, 0);
+    assert_eq!(preview.kept_files, 1);
+}
+
+#[test]
+fn test_schedule_due() {
+    let mut schedule = RetentionSchedule::default();
+    assert!(!schedule.is_due(1_000_000));
+    schedule.enabled = true;
+    assert!(schedule.is_due(1_000_000));
+    schedule.last_run_at = Some(1_000_000);
+    assert!(!schedule.is_due(1_000_000 + 23 * 3600));
+    assert!(schedule.is_due(1_000_000 + 24 * 3600));
+}
+
+#[tokio::test]
+async fn test_apply_deletes_expired_files() {
+    let dir = TempDir::new().unwrap();
+    let expired = dir.path().join("msg/video/2023-01/old.mp4");
+    let recent = dir.path().join("msg/video/2024-08/new.mp4");
+    write(&expired, b"old");
+    write(&recent, b"new");
+
+    let now = local_ms(2024, 9, 1);
+    let mut files = vec![
+        file(
+            &expired.to_string_lossy(),
+            CleanCategory::VideoFiles,
+            "wxid",
+            now,
+        ),
+        file(
+            &recent.to_string_lossy(),
+            CleanCategory::VideoFiles,
+            "wxid",
+            now,
+        ),
+    ];
+    for file in &mut files {
+        file.account_path = Some(dir.path().to_string_lossy().to_string());
+    }
+    let policies = vec![policy("video", vec![CleanCategory::VideoFiles], Some(90))];
+    let preview = preview_retention(&files, &policies, now);
+    let results = apply_retention(&preview, &policies).await;
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].processed_files, 1);
+    assert!(results[0].errors.is_empty());
+    assert!(!expired.exists());
+    assert!(recent.exists());
+}
@@ -0,0 +1,228 @@
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tempfile::TempDir;
+
+use disktidy_lib::models::{ArchiveFormat, ArchiveOptions, CleanOptions};
+use disktidy_lib::modules::cleaner::archive::{
+    create_archive, entry_name, prune_empty_dirs, read_manifest, remove_archived_files,
+    restore_archive, verify_archive,
+};
+use disktidy_lib::modules::cleaner::{CleanerExecutor, SafetyChecker};
+
+use crate::fixtures::{set_mtime, write};
+
+fn mtime(path: &Path) -> SystemTime {
+    fs::metadata(path).unwrap().modified().unwrap()
+}
+
+fn executor() -> CleanerExecutor {
+    CleanerExecutor::with_options(CleanOptions {
+        move_to_recycle_bin: false,
+        ..Default::default()
+    })
+}
+
+#[tokio::test]
+async fn test_archive_removes_originals_and_restores() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write(&root.join("media/2019/a.jpg"), &[1u8; 50_000]);
+    write(&root.join("media/2019/b.mp4"), b"video");
+    write(&root.join("old.iso"), &[7u8; 3000]);
+    set_mtime(&root.join("media/2019/a.jpg"), 1_550_000_000);
+
+    let options = ArchiveOptions {
+        destination: root.join("backup").display().to_string(),
+        format: ArchiveFormat::TarZst,
+        ..Default::default()
+    };
+    let result = executor()
+        .archive(vec![root.join("media"), root.join("old.iso")], &options)
+        .await
+        .unwrap();
+
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(result.archived_files, 3);
+    assert_eq!(result.archived_size, 50_000 + 5 + 3000);
+    assert_eq!(result.removed_paths, 2);
+    assert!(!root.join("media").exists());
+    assert!(!root.join("old.iso").exists());
+
+    let archive = Path::new(&result.archive_path);
+    assert!(result.archive_path.ends_with(".tar.zst"));
+    assert_eq!(fs::read_dir(root.join("backup")).unwrap().count(), 1);
+    let manifest = read_manifest(archive).unwrap();
+    let entry = manifest
+        .entries
+        .iter()
+        .find(|e| e.original_path.ends_with("a.jpg"))
+        .unwrap();
+    assert_eq!(entry.modified_time, 1_550_000_000);
+    assert_eq!(
+        entry.archive_path,
+        entry_name(&root.join("media/2019/a.jpg"))
+    );
+
+    let restored = restore_archive(archive, None, false, &SafetyChecker::new()).unwrap();
+    assert_eq!(restored.restored_files, 3);
+    assert!(restored.errors.is_empty(), "{:?}", restored.errors);
+    assert_eq!(fs::read(root.join("media/2019/b.mp4")).unwrap(), b"video");
+    assert_eq!(
+        mtime(&root.join("media/2019/a.jpg")),
+        UNIX_EPOCH + Duration::from_secs(1_550_000_000)
+    );
+}
+
+#[test]
+fn test_zip_restore_selected_entries() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    let files = vec![
+        root.join("chat/a.dat"),
+        root.join("chat/b.dat"),
+        root.join("docs/c.txt"),
+    ];
+    for (i, file) in files.iter().enumerate() {
+        write(file, format!("content {}", i).as_bytes());
+    }
+
+    let (archive, manifest) =
+        create_archive(&root.join("out"), ArchiveFormat::Zip, 6, &files).unwrap();
+    verify_archive(&archive, &manifest).unwrap();
+    for file in &files {
+        fs::remove_file(file).unwrap();
+    }
+    write(&root.join("chat/b.dat"), b"newer");
+
+    let selection = vec![root.join("chat").display().to_string()];
+    let checker = SafetyChecker::new();
+    let restored = restore_archive(&archive, Some(&selection), false, &checker).unwrap();
+    assert_eq!(restored.restored_files, 1);
+    assert_eq!(restored.skipped_files, 1);
+    assert_eq!(fs::read(root.join("chat/a.dat")).unwrap(), b"content 0");
+    assert_eq!(fs::read(root.join("chat/b.dat")).unwrap(), b"newer");
+    assert!(!root.join("docs/c.txt").exists());
+
+    let restored = restore_archive(&archive, Some(&selection), true, &checker).unwrap();
+    assert_eq!(restored.restored_files, 2);
+    assert_eq!(fs::read(root.join("chat/b.dat")).unwrap(), b"content 1");
+}
+
+#[tokio::test]
+async fn test_verification_failure_keeps_originals() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write(&root.join("data/x.bin"), &[9u8; 100]);
+
+    let (archive, mut manifest) = create_archive(
+        &root.join("out"),
+        ArchiveFormat::TarZst,
+        3,
+        &[root.join("data/x.bin")],
+    )
+    .unwrap();
+    manifest.entries[0].sha256 = "0".repeat(64);
+    assert!(verify_archive(&archive, &manifest).is_err());
+
+    // 归档位置在要归档的目录中时拒绝归档
+    let options = ArchiveOptions {
+        destination: root.join("data/backup").display().to_string(),
+        format: ArchiveFormat::Zip,
+        ..Default::default()
+    };
+    let result = executor()
+        .archive(vec![root.join("data")], &options)
+        .await
+        .unwrap();
+    assert_eq!(result.errors.len(), 1);
+    assert_eq!(result.removed_paths, 0);
+    assert!(result.archive_path.is_empty());
+    assert!(root.join("data/x.bin").exists());
+}
+
+#[test]
+fn test_remove_keeps_files_changed_after_archiving() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    let files = vec![
+        root.join("logs/old/a.log"),
+        root.join("logs/b.log"),
+        root.join("logs/c.log"),
+    ];
+    for file in &files {
+        write(file, b"archived");
+    }
+
+    let (archive, manifest) =
+        create_archive(&root.join("out"), ArchiveFormat::TarZst, 3, &files).unwrap();
+    verify_archive(&archive, &manifest).unwrap();
+
+    // 归档之后修改了一个文件，又新增了一个文件
+    write(&root.join("logs/b.log"), b"written after archiving");
+    write(&root.join("logs/new.log"), b"new");
+
+    let failures = remove_archived_files(&manifest);
+    prune_empty_dirs(&root.join("logs"));
+
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].0, root.join("logs/b.log"));
+    assert!(!root.join("logs/old").exists());
+    assert!(!root.join("logs/c.log").exists());
+    assert_eq!(
+        fs::read(root.join("logs/b.log")).unwrap(),
+        b"written after archiving"
+    );
+    assert!(root.join("logs/new.log").exists());
+}
+
+#[tokio::test]
+async fn test_overlapping_selection_is_archived_once() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write(&root.join("cache/a.bin"), &[1u8; 100]);
+    write(&root.join("cache/sub/b.bin"), &[2u8; 200]);
+
+    let options = ArchiveOptions {
+        destination: root.join("backup").display().to_string(),
+        format: ArchiveFormat::Zip,
+        ..Default::default()
+    };
+    let result = executor()
+        .archive(
+            vec![root.join("cache"), root.join("cache/sub/b.bin")],
+            &options,
+        )
+        .await
+        .unwrap();
+
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(result.archived_files, 2);
+    assert_eq!(result.archived_size, 300);
+    assert_eq!(result.removed_paths, 2);
+    assert!(!root.join("cache").exists());
+}
+
+#[test]
+fn test_restore_rejects_protected_targets() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    let files = vec![root.join("protected/config.ini"), root.join("plain/d.txt")];
+    for file in &files {
+        write(file, b"data");
+    }
+    let (archive, _) = create_archive(&root.join("out"), ArchiveFormat::Zip, 6, &files).unwrap();
+    for file in &files {
+        fs::remove_file(file).unwrap();
+    }
+
+    let mut checker = SafetyChecker::new();
+    checker.add_protected_path(root.join("protected"));
+    let restored = restore_archive(&archive, None, false, &checker).unwrap();
+
+    assert_eq!(restored.restored_files, 1);
+    assert_eq!(restored.errors.len(), 1);
+    assert!(restored.errors[0].path.ends_with("config.ini"));
+    assert!(!root.join("protected/config.ini").exists());
+    assert!(root.join("plain/d.txt").exists());
+}
@@ -0,0 +1,104 @@
+use std::path::Path;
+use tempfile::TempDir;
+
+use disktidy_lib::modules::build_artifact::{
+    staleness_score, BuildArtifactDetector, BuildArtifactScanOptions, ProjectKind,
+};
+
+use crate::fixtures::write;
+
+fn detector_for(root: &Path) -> BuildArtifactDetector {
+    BuildArtifactDetector::with_options(BuildArtifactScanOptions {
+        scan_paths: vec![root.to_string_lossy().to_string()],
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_detect_cargo_and_node_artifacts() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+
+    write(&root.join("rust-app/Cargo.toml"), b"[package]\nname = \"app\"\n");
+    write(&root.join("rust-app/target/CACHEDIR.TAG"), b"Signature: 8a477f597d28d172789f06886806bc55");
+    write(&root.join("rust-app/target/debug/app"), &[0u8; 2048]);
+
+    write(&root.join("web/package.json"), br#"{"name": "web"}"#);
+    write(&root.join("web/node_modules/left-pad/index.js"), &[0u8; 1024]);
+    write(&root.join("web/node_modules/left-pad/package.json"), br#"{"name": "left-pad"}"#);
+    write(&root.join("web/dist/bundle.js"), &[0u8; 512]);
+
+    let projects = detector_for(root).detect();
+
+    // node_modules 内部的 package.json 不应被识别为项目
+    assert_eq!(projects.len(), 2);
+
+    let rust = projects.iter().find(|p| p.kinds.contains(&ProjectKind::Cargo)).unwrap();
+    assert_eq!(rust.artifacts.len(), 1);
+    assert!(rust.artifacts[0].regenerable);
+    assert!(rust.cleanable_size >= 2048);
+
+    let web = projects.iter().find(|p| p.kinds.contains(&ProjectKind::Node)).unwrap();
+    let dist = web.artifacts.iter().find(|a| a.name == "dist").unwrap();
+    assert!(!dist.regenerable);
+    let node_modules = web.artifacts.iter().find(|a| a.name == "node_modules").unwrap();
+    assert!(node_modules.regenerable);
+    assert_eq!(web.cleanable_size, node_modules.size);
+}
+
+#[test]
+fn test_detect_python_pycache_and_cmake_build() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+
+    write(&root.join("py/pyproject.toml"), b"[project]\nname = \"py\"\n");
+    write(&root.join("py/pkg/__pycache__/mod.cpython-311.pyc"), &[0u8; 100]);
+    write(&root.join("py/.venv/lib/site.py"), &[0u8; 100]);
+
+    write(&root.join("native/CMakeLists.txt"), b"project(native)\n");
+    write(&root.join("native/cmake-build-debug/CMakeCache.txt"), b"");
+    write(&root.join("native/docs/readme.txt"), b"not a build tree");
+
+    let projects = detector_for(root).detect();
+
+    let py = projects.iter().find(|p| p.kinds.contains(&ProjectKind::Python)).unwrap();
+    // 缺少 pyvenv.cfg 的 .venv 不是虚拟环境
+    assert_eq!(py.artifacts.len(), 1);
+    assert_eq!(py.artifacts[0].name, "__pycache__");
+
+    let native = projects.iter().find(|p| p.kinds.contains(&ProjectKind::CMake)).unwrap();
+    assert_eq!(native.artifacts.len(), 1);
+    assert_eq!(native.artifacts[0].name, "cmake-build-debug");
+}
+
+#[test]
+fn test_gradle_build_requires_build_script() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+
+    write(&root.join("lib/build.gradle.kts"), b"plugins { java }\n");
+    write(&root.join("lib/build/libs/lib.jar"), &[0u8; 300]);
+
+    // 只有 settings.gradle 的多项目根目录，build 下可能是其他内容
+    write(&root.join("multi/settings.gradle"), b"include 'app'\n");
+    write(&root.join("multi/build/notes.txt"), &[0u8; 200]);
+
+    let projects = detector_for(root).detect();
+
+    let lib = projects.iter().find(|p| p.name == "lib").unwrap();
+    let build = lib.artifacts.iter().find(|a| a.name == "build").unwrap();
+    assert!(build.regenerable);
+
+    let multi = projects.iter().find(|p| p.name == "multi").unwrap();
+    let build = multi.artifacts.iter().find(|a| a.name == "build").unwrap();
+    assert!(!build.regenerable);
+    assert_eq!(multi.cleanable_size, 0);
+}
+
+#[test]
+fn test_staleness_score() {
+    assert_eq!(staleness_score(0), 0);
+    assert_eq!(staleness_score(7), 0);
+    assert!(staleness_score(180) > 0 && staleness_score(180) < 100);
+    assert_eq!(staleness_score(400), 100);
+}
@@ -0,0 +1,203 @@
+use std::fs;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+use disktidy_lib::models::{CleanIoOptions, CleanOptions, CleanProgress};
+use disktidy_lib::modules::cleaner::size::{bytes_per_second, measure_entry};
+use disktidy_lib::modules::cleaner::throttle::IoThrottle;
+use disktidy_lib::modules::cleaner::{CleanReportGenerator, CleanerExecutor};
+
+use crate::fixtures::write;
+
+fn executor_with(io: CleanIoOptions) -> CleanerExecutor {
+    CleanerExecutor::with_options(CleanOptions {
+        move_to_recycle_bin: false,
+        secure_delete: false,
+        secure_pass_count: 1,
+        io,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_throttle_reserves_by_rate() {
+    let unlimited = IoThrottle::new(0, 0);
+    assert!(unlimited.is_unlimited());
+    assert_eq!(unlimited.reserve(u64::MAX), Duration::ZERO);
+
+    // 桶中初始有一秒的量，超出部分按速率等待
+    let bytes = IoThrottle::new(1000, 0);
+    assert_eq!(bytes.reserve(1000), Duration::ZERO);
+    let wait = bytes.reserve(500);
+    assert!(wait > Duration::from_millis(400) && wait <= Duration::from_millis(500));
+
+    let ops = IoThrottle::new(0, 2);
+    assert_eq!(ops.reserve(1 << 30), Duration::ZERO);
+    assert_eq!(ops.reserve(0), Duration::ZERO);
+    assert!(ops.reserve(0) > Duration::from_millis(400));
+}
+
+#[tokio::test]
+async fn test_concurrent_clean_reports_in_input_order() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let mut files = Vec::new();
+    for i in 0..40 {
+        let path = root.join(format!("dir{}/file{}.tmp", i % 4, i));
+        write(&path, &[0u8; 10]);
+        files.push(path);
+    }
+    files.insert(5, root.join("missing-a"));
+    files.insert(20, root.join("missing-b"));
+
+    let executor = executor_with(CleanIoOptions {
+        max_concurrency: 6,
+        per_device_concurrency: 3,
+        ..Default::default()
+    });
+    let progress: Arc<Mutex<Vec<CleanProgress>>> = Arc::new(Mutex::new(Vec::new()));
+    let recorded = progress.clone();
+    let result = executor
+        .clean_with_progress(
+            files,
+            Some(Arc::new(move |p: CleanProgress| {
+                recorded.lock().unwrap().push(p)
+            })),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(result.total_files, 42);
+    assert_eq!(result.cleaned_files, 40);
+    assert_eq!(result.failed_files, 2);
+    assert_eq!(result.cleaned_size, 400);
+    assert!(result.errors[0].path.ends_with("missing-a"));
+    assert!(result.errors[1].path.ends_with("missing-b"));
+    assert!(fs::read_dir(root.join("dir0")).unwrap().next().is_none());
+
+    let progress = progress.lock().unwrap();
+    assert_eq!(progress.len(), 42);
+    assert!(progress.windows(2).all(|w| w[0].percent <= w[1].percent));
+    assert_eq!(progress.last().unwrap().percent, 100.0);
+}
+
+#[tokio::test]
+async fn test_ops_limit_slows_clean() {
+    let temp_dir = TempDir::new().unwrap();
+    let files: Vec<_> = (0..4)
+        .map(|i| {
+            let path = temp_dir.path().join(format!("{}.tmp", i));
+            write(&path, b"x");
+            path
+        })
+        .collect();
+
+    // 每秒 2 次：前两次消耗初始令牌，之后每次等待约 0.5 秒
+    let executor = executor_with(CleanIoOptions {
+        max_ops_per_sec: 2,
+        ..Default::default()
+    });
+    let start = Instant::now();
+    let result = executor.clean(files).await.unwrap();
+
+    assert_eq!(result.cleaned_files, 4);
+    assert!(start.elapsed() >= Duration::from_millis(900));
+}
+
+#[tokio::test]
+async fn test_cancel_skips_remaining_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("keep.tmp");
+    write(&path, b"x");
+
+    let executor = executor_with(CleanIoOptions::default());
+    *executor.cancel_flag().write().await = true;
+    let result = executor.clean(vec![path.clone()]).await.unwrap();
+
+    assert_eq!(result.cleaned_files, 0);
+    assert_eq!(result.skipped_files, 1);
+    assert!(path.exists());
+}
+
+#[tokio::test]
+async fn test_background_priority_cleans_on_worker_threads() {
+    let temp_dir = TempDir::new().unwrap();
+    write(&temp_dir.path().join("a/b.tmp"), b"data");
+    write(&temp_dir.path().join("c.tmp"), b"data");
+
+    let executor = executor_with(CleanIoOptions {
+        background_priority: true,
+        max_concurrency: 2,
+        ..Default::default()
+    });
+    let result = executor
+        .clean(vec![
+            temp_dir.path().join("a"),
+            temp_dir.path().join("c.tmp"),
+        ])
+        .await
+        .unwrap();
+
+    assert_eq!(result.cleaned_files, 2, "{:?}", result.errors);
+    assert_eq!(result.cleaned_size, 8);
+    assert!(!temp_dir.path().join("a").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_measure_counts_contents_and_hard_links() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write(&root.join("tree/a.bin"), &[1u8; 5000]);
+    write(&root.join("tree/sub/b.bin"), &[2u8; 100]);
+    write(&root.join("tree/shared.bin"), &[3u8; 300]);
+    write(&root.join("tree/outside.bin"), &[4u8; 700]);
+    fs::hard_link(
+        root.join("tree/shared.bin"),
+        root.join("tree/sub/shared-link.bin"),
+    )
+    .unwrap();
+    fs::hard_link(root.join("tree/outside.bin"), root.join("elsewhere.bin")).unwrap();
+
+    let size = measure_entry(&root.join("tree")).unwrap();
+    // 同一文件的两个链接只统计一次，目录自身的大小不计入表观大小
+    assert_eq!(size.apparent, 5000 + 100 + 300 + 700);
+    assert_eq!(size.file_count, 4);
+    assert!(size.allocated >= 8192);
+    // 在树外仍有链接的文件删除后不会释放空间
+    let outside = measure_entry(&root.join("tree/outside.bin")).unwrap();
+    assert_eq!(outside.reclaimable, 0);
+    assert_eq!(size.reclaimable, size.allocated - outside.allocated);
+
+    assert_eq!(bytes_per_second(500, Duration::from_millis(250)), 2000);
+    assert_eq!(bytes_per_second(500, Duration::ZERO), 0);
+}
+
+#[tokio::test]
+async fn test_directory_clean_reports_content_size() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write(&root.join("cache/x/1.tmp"), &[0u8; 3000]);
+    write(&root.join("cache/2.tmp"), &[0u8; 1000]);
+    write(&root.join("secret.txt"), &[0u8; 10]);
+
+    let executor = executor_with(CleanIoOptions::default());
+    let result = executor
+        .clean(vec![root.join("cache"), root.join("secret.txt")])
+        .await
+        .unwrap();
+
+    assert_eq!(result.cleaned_files, 1);
+    assert_eq!(result.failed_files, 1);
+    assert_eq!(result.cleaned_size, 4000);
+    // 受保护而未删除的文件计入总大小，但不计入释放的空间
+    assert_eq!(result.total_size, 4010);
+    assert_eq!(result.trashed_size, 0);
+    #[cfg(unix)]
+    assert!(result.freed_size >= 4096);
+
+    let report = CleanReportGenerator::with_id(result.scan_id.clone()).generate(&result);
+    assert_eq!(report.freed_size, result.freed_size);
+    assert_eq!(report.summary.average_speed, result.bytes_per_sec);
+}
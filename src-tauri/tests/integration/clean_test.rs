@@ -15,6 +15,7 @@ async fn test_cleaner_executor_with_options() {
         move_to_recycle_bin: true,
         secure_delete: false,
         secure_pass_count: 3,
+        ..Default::default()
     };
     
     let executor = CleanerExecutor::with_options(options);
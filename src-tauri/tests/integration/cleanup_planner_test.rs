@@ -0,0 +1,191 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use disktidy_lib::models::{
+    CleanupCandidate, CleanupSource, LargeFile, LargeFileAnalysisResult, RiskLevel,
+};
+use disktidy_lib::modules::app_cache::{
+    AppCacheFile, AppCacheScanResult, AppCacheScanStatus, CleanCategory,
+};
+use disktidy_lib::modules::cleanup_planner::{
+    normalize_findings, CleanupPlanInput, CleanupPlanOptions, CleanupPlanner,
+};
+use disktidy_lib::modules::file_analyzer::{JunkFile, JunkFileType, JunkScanResult};
+
+const GB: u64 = 1024 * 1024 * 1024;
+
+fn candidate(path: &str, size: u64, risk_level: RiskLevel, regenerable: bool) -> CleanupCandidate {
+    CleanupCandidate {
+        path: path.to_string(),
+        size,
+        source: CleanupSource::Garbage,
+        risk_level,
+        regenerable,
+        modified_time: 0,
+        reason: "测试".to_string(),
+        also_reported_by: Vec::new(),
+        risk_score: 0,
+        risk_factors: Vec::new(),
+    }
+}
+
+#[test]
+fn test_plan_prefers_lowest_risk() {
+    let planner = CleanupPlanner::new(2 * GB);
+    let plan = planner.plan_candidates(vec![
+        candidate("/tmp/medium", 3 * GB, RiskLevel::Medium, false),
+        candidate("/tmp/low-user", GB, RiskLevel::Low, false),
+        candidate("/tmp/low-cache", GB, RiskLevel::Low, true),
+    ]);
+
+    assert!(plan.target_reached);
+    assert_eq!(plan.planned_bytes, 2 * GB);
+    assert_eq!(plan.items.len(), 2);
+    assert_eq!(plan.items[0].candidate.path, "/tmp/low-cache");
+    assert_eq!(plan.items[1].cumulative_size, 2 * GB);
+    assert!(plan.paths().iter().all(|p| !p.ends_with("medium")));
+}
+
+#[test]
+fn test_plan_reports_unreachable_target() {
+    let planner = CleanupPlanner::with_options(CleanupPlanOptions {
+        target_bytes: 5 * GB,
+        max_risk: RiskLevel::Low,
+        ..Default::default()
+    });
+    let plan = planner.plan_candidates(vec![
+        candidate("/tmp/low", GB, RiskLevel::Low, true),
+        candidate("/tmp/high", 10 * GB, RiskLevel::High, false),
+        candidate("/tmp/empty", 0, RiskLevel::Low, true),
+    ]);
+
+    assert!(!plan.target_reached);
+    assert_eq!(plan.planned_bytes, GB);
+    assert_eq!(plan.shortfall_bytes, 4 * GB);
+    assert_eq!(plan.items.len(), 1);
+    assert_eq!(plan.available_by_risk.len(), 2);
+    assert!(plan.message.contains("更高风险"));
+}
+
+fn junk_file(path: &str, size: u64, risk_level: &str) -> JunkFile {
+    JunkFile {
+        id: path.to_string(),
+        path: path.to_string(),
+        size,
+        file_type: JunkFileType::OldLogs,
+        description: "旧日志".to_string(),
+        modified_time: 0,
+        created_time: 0,
+        safe_to_delete: true,
+        risk_level: risk_level.to_string(),
+    }
+}
+
+#[test]
+fn test_plan_splits_by_risk_before_merging() {
+    let input = CleanupPlanInput {
+        junk: vec![JunkScanResult {
+            file_type: JunkFileType::OldLogs,
+            items: vec![
+                junk_file("/var/tmp/held", 1000, "High"),
+                junk_file("/var/tmp/held/a.log", 100, "Low"),
+                junk_file("/var/tmp/outer", 500, "Low"),
+                junk_file("/var/tmp/outer/b.log", 50, "High"),
+            ],
+            total_size: 1650,
+            count: 4,
+            ignored_paths: vec![],
+        }],
+        ..Default::default()
+    };
+    let planner = CleanupPlanner::with_options(CleanupPlanOptions {
+        target_bytes: 10 * GB,
+        max_risk: RiskLevel::Medium,
+        ..Default::default()
+    });
+
+    let plan = planner.plan(&input);
+
+    // 保留的目录不带走其中的低风险文件，含有保留项的目录不进入计划
+    let paths: Vec<&str> = plan.items.iter().map(|i| i.candidate.path.as_str()).collect();
+    assert_eq!(paths, vec!["/var/tmp/held/a.log"]);
+    assert_eq!(plan.planned_bytes, 100);
+    assert!(plan.message.contains("更高风险"));
+}
+
+fn large_file(path: &str, file_type: &str, extension: &str, idle_days: i64) -> LargeFile {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+    let last_used = now - idle_days * 86400;
+    LargeFile {
+        path: path.to_string(),
+        name: path.rsplit('/').next().unwrap().to_string(),
+        size: GB,
+        modified_time: last_used,
+        accessed_time: last_used,
+        created_time: last_used,
+        file_type: file_type.to_string(),
+        extension: extension.to_string(),
+        git_status: None,
+    }
+}
+
+#[test]
+fn test_normalize_findings_times_and_large_file_risk() {
+    let app_file = AppCacheFile {
+        id: "1".to_string(),
+        path: "/data/wechat/video.mp4".to_string(),
+        name: "video.mp4".to_string(),
+        size: GB,
+        category: CleanCategory::VideoFiles,
+        app: "wechat".to_string(),
+        chat_object: "friend".to_string(),
+        account_id: None,
+        account_path: None,
+        created_at: 1_700_000_000_000,
+        modified_at: 1_700_000_000_123,
+        selected: false,
+        is_encrypted: false,
+        original_format: None,
+        decode_error: None,
+        risk_level: None,
+    };
+    let files = vec![
+        large_file("/data/recent.mkv", "视频", "mkv", 3),
+        large_file("/data/stale.mkv", "视频", "mkv", 200),
+        large_file("/data/old.iso", "磁盘镜像", "iso", 500),
+        // 类型名称不影响风险判断
+        large_file("/data/old.vmdk", "Disk image", "vmdk", 500),
+    ];
+    let input = CleanupPlanInput {
+        app_cache: Some(AppCacheScanResult {
+            scan_id: "a".to_string(),
+            files: vec![app_file],
+            total_files: 1,
+            total_size: GB,
+            duration_ms: 0,
+            status: AppCacheScanStatus::Completed,
+            incremental: false,
+            skipped_files: 0,
+        }),
+        large_files: Some(LargeFileAnalysisResult {
+            scan_id: "l".to_string(),
+            total_files: files.len() as u64,
+            total_size: 4 * GB,
+            files,
+            threshold: GB,
+            duration_ms: 0,
+            ignored_paths: Vec::new(),
+        }),
+        ..Default::default()
+    };
+
+    let candidates = normalize_findings(&input);
+    let find = |path: &str| candidates.iter().find(|c| c.path == path).unwrap();
+
+    // 应用缓存的毫秒时间换算为秒
+    assert_eq!(find("/data/wechat/video.mp4").modified_time, 1_700_000_000);
+
+    assert_eq!(find("/data/recent.mkv").risk_level, RiskLevel::High);
+    assert_eq!(find("/data/stale.mkv").risk_level, RiskLevel::Medium);
+    assert_eq!(find("/data/old.iso").risk_level, RiskLevel::Low);
+    assert_eq!(find("/data/old.vmdk").risk_level, RiskLevel::Low);
+}
@@ -0,0 +1,133 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use tempfile::TempDir;
+use walkdir::WalkDir;
+
+use disktidy_lib::models::OverwritePattern;
+use disktidy_lib::modules::free_space_wipe::{
+    FreeSpaceWipeConfig, FreeSpaceWiper, SpaceProbe, VolumeSpace, WIPE_DIR_PREFIX,
+};
+
+const MIB: u64 = 1024 * 1024;
+
+/// 可用空间 = 初始值 - 目录中已写入的字节数 - 被其他程序占用的字节数
+struct FakeSpace {
+    total: u64,
+    available: u64,
+    taken: Arc<AtomicU64>,
+}
+
+impl SpaceProbe for FakeSpace {
+    fn space(&self, path: &Path) -> io::Result<VolumeSpace> {
+        let written: u64 = WalkDir::new(path)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter_map(|e| e.metadata().ok())
+            .filter(|m| m.is_file())
+            .map(|m| m.len())
+            .sum();
+        let used = written + self.taken.load(Ordering::Relaxed);
+        Ok(VolumeSpace {
+            total: self.total,
+            available: self.available.saturating_sub(used),
+        })
+    }
+}
+
+fn new_wiper(root: &Path, available: u64, taken: Arc<AtomicU64>) -> FreeSpaceWiper {
+    let config = FreeSpaceWipeConfig {
+        path: root.display().to_string(),
+        pattern: OverwritePattern::Byte(0xAA),
+        min_free_bytes: 4 * MIB,
+        min_free_percent: 1.0,
+        max_file_size: 2 * MIB,
+    };
+    let probe = FakeSpace {
+        total: 100 * MIB,
+        available,
+        taken,
+    };
+    FreeSpaceWiper::with_probe(config, Box::new(probe))
+}
+
+#[test]
+fn test_wipe_fills_to_margin_and_removes_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let wiper = new_wiper(temp_dir.path(), 9 * MIB, Arc::default());
+
+    let mut max_seen = 0;
+    let summary = wiper
+        .run(
+            &AtomicBool::new(false),
+            &AtomicBool::new(false),
+            &mut |stats| {
+                max_seen = stats.written_bytes;
+                assert!(stats.current_path.starts_with(temp_dir.path()));
+            },
+        )
+        .unwrap();
+
+    // 余量取 4 MiB 与容量 1% 中的较大者
+    assert_eq!(summary.safety_margin, 4 * MIB);
+    assert_eq!(summary.written_bytes, 5 * MIB);
+    assert_eq!(summary.files_created, 3);
+    assert_eq!(max_seen, 5 * MIB);
+    assert!(!summary.cancelled);
+    assert_eq!(summary.available_after, 9 * MIB);
+    assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn test_wipe_stops_when_space_is_taken_elsewhere() {
+    let temp_dir = TempDir::new().unwrap();
+    let taken = Arc::new(AtomicU64::new(0));
+    let wiper = new_wiper(temp_dir.path(), 500 * MIB, taken.clone());
+
+    // 写入开始后其他程序占用了大部分空间
+    let summary = wiper
+        .run(
+            &AtomicBool::new(false),
+            &AtomicBool::new(false),
+            &mut |_| taken.store(400 * MIB, Ordering::Relaxed),
+        )
+        .unwrap();
+
+    assert!(summary.written_bytes < 496 * MIB);
+    assert!(summary.written_bytes >= 64 * MIB);
+    assert!(fs::read_dir(temp_dir.path()).unwrap().all(|e| !e
+        .unwrap()
+        .file_name()
+        .to_string_lossy()
+        .starts_with(WIPE_DIR_PREFIX)));
+}
+
+#[test]
+fn test_cancelled_wipe_cleans_up() {
+    let temp_dir = TempDir::new().unwrap();
+    let wiper = new_wiper(temp_dir.path(), 50 * MIB, Arc::default());
+    let cancelled = AtomicBool::new(false);
+
+    let summary = wiper
+        .run(&cancelled, &AtomicBool::new(false), &mut |stats| {
+            if stats.written_bytes >= 3 * MIB {
+                cancelled.store(true, Ordering::Relaxed);
+            }
+        })
+        .unwrap();
+
+    assert!(summary.cancelled);
+    assert_eq!(summary.written_bytes, 3 * MIB);
+    assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+
+    let missing = new_wiper(&temp_dir.path().join("missing"), 50 * MIB, Arc::default());
+    assert!(missing
+        .run(
+            &AtomicBool::new(false),
+            &AtomicBool::new(false),
+            &mut |_| {}
+        )
+        .is_err());
+}
@@ -0,0 +1,250 @@
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use tempfile::TempDir;
+
+use disktidy_lib::models::{GitPathStatus, RiskLevel};
+use disktidy_lib::modules::cleaner::SafetyChecker;
+use disktidy_lib::modules::git_repo::{
+    analyze_repository, parse_git_index, GitRepoCache, GitRepository,
+};
+
+use crate::fixtures::write;
+
+/// 构造 v2 或 v4 格式的 `.git/index`
+fn build_index(version: u32, paths: &[&str]) -> Vec<u8> {
+    let mut data = b"DIRC".to_vec();
+    data.extend_from_slice(&version.to_be_bytes());
+    data.extend_from_slice(&(paths.len() as u32).to_be_bytes());
+
+    let mut previous: &str = "";
+    for path in paths {
+        let entry_start = data.len();
+        data.extend_from_slice(&[0u8; 40]);
+        data.extend_from_slice(&[0xab; 20]);
+        data.extend_from_slice(&(path.len().min(0xfff) as u16).to_be_bytes());
+
+        if version == 4 {
+            let common = previous
+                .bytes()
+                .zip(path.bytes())
+                .take_while(|(a, b)| a == b)
+                .count();
+            data.push((previous.len() - common) as u8);
+            data.extend_from_slice(&path.as_bytes()[common..]);
+            data.push(0);
+        } else {
+            data.extend_from_slice(path.as_bytes());
+            let entry_len = data.len() - entry_start;
+            data.resize(entry_start + (entry_len / 8 + 1) * 8, 0);
+        }
+        previous = path;
+    }
+
+    data.extend_from_slice(&[0u8; 20]);
+    data
+}
+
+/// 在校验和之前追加一个索引扩展
+fn with_extension(mut index: Vec<u8>, signature: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+    let checksum = index.split_off(index.len() - 20);
+    index.extend_from_slice(signature);
+    index.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    index.extend_from_slice(payload);
+    index.extend_from_slice(&checksum);
+    index
+}
+
+fn touch_later(path: &Path) {
+    fs::File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(SystemTime::now() + Duration::from_secs(60))
+        .unwrap();
+}
+
+fn init_repo(root: &Path, tracked: &[&str]) {
+    for path in tracked {
+        write(&root.join(path), b"source");
+    }
+    write(&root.join(".git/HEAD"), b"ref: refs/heads/main\n");
+    write(&root.join(".git/index"), &build_index(2, tracked));
+}
+
+#[test]
+fn test_parse_git_index_versions() {
+    let paths = ["Cargo.toml", "src/lib.rs", "src/main.rs", "src/modules/mod.rs"];
+
+    for version in [2, 4] {
+        let parsed = parse_git_index(&build_index(version, &paths)).unwrap();
+        assert_eq!(parsed, paths.to_vec(), "index v{}", version);
+    }
+
+    assert!(parse_git_index(b"not an index").is_none());
+
+    // 可选扩展（大写签名）不影响条目，拆分索引与稀疏索引无法得到完整列表
+    let tree = with_extension(build_index(2, &paths), b"TREE", &[0u8; 12]);
+    assert_eq!(parse_git_index(&tree).unwrap(), paths.to_vec());
+    for signature in [b"link", b"sdir"] {
+        let index = with_extension(build_index(4, &paths), signature, &[0u8; 20]);
+        assert!(parse_git_index(&index).is_none());
+    }
+}
+
+#[test]
+fn test_unsupported_index_protects_everything() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().join("project");
+    init_repo(&root, &["src/main.rs", ".gitignore"]);
+    write(&root.join(".gitignore"), b"*.log\n");
+    write(&root.join("notes.txt"), b"notes");
+    write(&root.join("build.log"), b"log");
+    write(
+        &root.join(".git/index"),
+        &with_extension(build_index(2, &["src/main.rs"]), b"link", &[0u8; 20]),
+    );
+
+    let repo = GitRepository::open(&root).unwrap();
+    assert!(!repo.index_supported());
+    assert_eq!(repo.status(&root.join("notes.txt")), GitPathStatus::Tracked);
+    assert_eq!(repo.status(&root.join("build.log")), GitPathStatus::Tracked);
+    assert_eq!(repo.status(&root), GitPathStatus::Tracked);
+
+    let report = analyze_repository(&repo, 0);
+    assert!(report.large_untracked_files.is_empty());
+    assert_eq!(report.ignored_size, 0);
+    assert!(report.suggestions[0].contains("拆分索引"));
+
+    assert!(!SafetyChecker::new().check(&root.join("notes.txt")).safe_to_delete);
+}
+
+#[test]
+fn test_tracked_files_protected_and_ignored_output_regenerable() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().join("project");
+    init_repo(&root, &["src/main.rs", "README.md", ".gitignore"]);
+    write(&root.join(".gitignore"), b"/target/\n*.log\n!keep.log\n");
+    write(&root.join("target/debug/app"), &[0u8; 4096]);
+    write(&root.join("logs/build.log"), b"log");
+    write(&root.join("logs/keep.log"), b"log");
+    write(&root.join("notes.txt"), b"notes");
+
+    let repo = GitRepository::discover(&root.join("src/main.rs")).unwrap();
+    assert_eq!(repo.root, root);
+    assert_eq!(repo.tracked_count(), 3);
+
+    assert_eq!(repo.status(&root.join("src/main.rs")), GitPathStatus::Tracked);
+    assert_eq!(repo.status(&root.join("src")), GitPathStatus::Tracked);
+    assert_eq!(repo.status(&root.join("target/debug/app")), GitPathStatus::Ignored);
+    assert_eq!(repo.status(&root.join("logs/build.log")), GitPathStatus::Ignored);
+    assert_eq!(repo.status(&root.join("logs/keep.log")), GitPathStatus::Untracked);
+    assert_eq!(repo.status(&root.join("notes.txt")), GitPathStatus::Untracked);
+    assert_eq!(repo.status(&root.join(".git/HEAD")), GitPathStatus::GitDir);
+
+    let cache = GitRepoCache::new();
+    assert_eq!(cache.status(&root.join("README.md")), Some(GitPathStatus::Tracked));
+    assert_eq!(cache.status(temp_dir.path()), None);
+
+    let checker = SafetyChecker::new();
+    let tracked = checker.check(&root.join("src/main.rs"));
+    assert!(!tracked.safe_to_delete);
+    assert_eq!(tracked.git_status, Some(GitPathStatus::Tracked));
+
+    // 被忽略但不是 Cargo 项目的构建输出，不直接判定为低风险
+    let ignored = checker.check(&root.join("target"));
+    assert_eq!(ignored.git_status, Some(GitPathStatus::Ignored));
+    assert!(ignored.reason.is_none());
+
+    write(&root.join("Cargo.toml"), b"[package]");
+    let ignored = checker.check(&root.join("target/debug/app"));
+    assert!(ignored.safe_to_delete);
+    assert_eq!(ignored.risk_level, RiskLevel::Low);
+    assert_eq!(ignored.git_status, Some(GitPathStatus::Ignored));
+
+    let log = checker.check(&root.join("logs/build.log"));
+    assert_eq!(log.git_status, Some(GitPathStatus::Ignored));
+    assert!(log.reason.is_none());
+    assert!(log.risk_factors.iter().any(|f| f.description.contains(".gitignore")));
+
+    assert!(!checker.is_safe_to_delete(&root.join(".git")));
+}
+
+#[test]
+fn test_cache_reloads_changed_gitignore() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().join("project");
+    init_repo(&root, &["src/main.rs", ".gitignore"]);
+    write(&root.join(".gitignore"), b"*.log\n");
+    write(&root.join("app.log"), b"log");
+
+    let cache = GitRepoCache::new();
+    assert_eq!(cache.status(&root.join("app.log")), Some(GitPathStatus::Ignored));
+
+    write(&root.join(".gitignore"), b"/target/\n");
+    touch_later(&root.join(".gitignore"));
+    assert_eq!(cache.status(&root.join("app.log")), Some(GitPathStatus::Untracked));
+
+    // 子目录中的 .gitignore 同样参与缓存判断
+    write(&root.join("sub/.gitignore"), b"*.tmp\n");
+    write(&root.join("sub/deep/a.tmp"), b"tmp");
+    assert_eq!(cache.status(&root.join("sub/deep/a.tmp")), Some(GitPathStatus::Ignored));
+    write(&root.join("sub/.gitignore"), b"*.bak\n");
+    touch_later(&root.join("sub/.gitignore"));
+    assert_eq!(cache.status(&root.join("sub/deep/a.tmp")), Some(GitPathStatus::Untracked));
+}
+
+#[test]
+fn test_cache_notices_new_and_removed_repositories() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().join("project");
+    write(&root.join("src/main.rs"), b"source");
+
+    let cache = GitRepoCache::with_dir_ttl(Duration::ZERO);
+    assert_eq!(cache.status(&root.join("src/main.rs")), None);
+    init_repo(&root, &["src/main.rs"]);
+    assert_eq!(cache.status(&root.join("src/main.rs")), Some(GitPathStatus::Tracked));
+
+    // 缓存未过期时，仓库被删除也会重新查找
+    let cache = GitRepoCache::new();
+    assert!(cache.status(&root.join("src/main.rs")).is_some());
+    fs::remove_dir_all(root.join(".git")).unwrap();
+    assert_eq!(cache.status(&root.join("src/main.rs")), None);
+}
+
+#[test]
+fn test_repository_report() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    init_repo(root, &["app.py", ".gitignore"]);
+    write(&root.join(".gitignore"), b"__pycache__/\ndist\n");
+    write(&root.join("dist/app.whl"), &[0u8; 3000]);
+    write(&root.join("pkg/__pycache__/app.cpython-311.pyc"), &[0u8; 500]);
+    write(&root.join("dataset.bin"), &[0u8; 8000]);
+    write(&root.join("small.txt"), b"x");
+
+    for i in 0..3 {
+        write(&root.join(format!(".git/objects/a{}/{:038}", i, i)), &[0u8; 100]);
+    }
+    write(&root.join(".git/objects/pack/pack-1.pack"), &[0u8; 1000]);
+    write(&root.join(".git/objects/pack/pack-1.idx"), &[0u8; 50]);
+
+    let repo = GitRepository::open(root).unwrap();
+    let report = analyze_repository(&repo, 4096);
+
+    assert_eq!(report.tracked_files, 2);
+    assert_eq!(report.loose_object_count, 3);
+    assert_eq!(report.loose_object_size, 300);
+    assert_eq!(report.pack_count, 1);
+    assert_eq!(report.pack_size, 1000);
+    assert!(report.git_dir_size >= 1350);
+
+    assert_eq!(report.ignored_size, 3500);
+    assert_eq!(report.ignored_paths.len(), 2);
+    assert!(report.ignored_paths[0].path.ends_with("dist"));
+    assert!(report.ignored_paths[0].rule.contains(".gitignore:2 dist"));
+
+    assert_eq!(report.large_untracked_files.len(), 1);
+    assert!(report.large_untracked_files[0].path.ends_with("dataset.bin"));
+    assert_eq!(report.untracked_size, 8001);
+}
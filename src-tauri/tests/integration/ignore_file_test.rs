@@ -0,0 +1,139 @@
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+use disktidy_lib::models::LargeFileAnalyzerOptions;
+use disktidy_lib::modules::file_analyzer::LargeFileAnalyzer;
+use disktidy_lib::modules::scanner_framework::{FileWalker, FilterOptions, StandardFileFilter};
+use disktidy_lib::utils::gitignore::{DisktidyIgnore, IgnoreTree, DISKTIDY_IGNORE_FILE};
+
+use crate::fixtures::write;
+
+#[test]
+fn test_gitignore_semantics() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+
+    write(
+        &root.join(DISKTIDY_IGNORE_FILE),
+        b"# comment\n*.iso\n!keep.iso\nbuild/\n/top.bin\ndocs/*.pdf\n",
+    );
+    write(&root.join("sub").join(DISKTIDY_IGNORE_FILE), b"!build/\nlocal.dat\n");
+    for file in [
+        "a.iso",
+        "keep.iso",
+        "nested/b.iso",
+        "build/out.o",
+        "top.bin",
+        "nested/top.bin",
+        "docs/manual.pdf",
+        "docs/deep/manual.pdf",
+        "sub/build/out.o",
+        "sub/local.dat",
+        "local.dat",
+    ] {
+        write(&root.join(file), b"x");
+    }
+
+    let tree = IgnoreTree::new(root, DISKTIDY_IGNORE_FILE);
+    let ignored = |p: &str| tree.is_ignored(&root.join(p), root.join(p).is_dir());
+
+    // 不含分隔符的模式匹配任意层级
+    assert!(ignored("a.iso"));
+    assert!(ignored("nested/b.iso"));
+    // 否定规则
+    assert!(!ignored("keep.iso"));
+    // 仅目录的模式及其内容
+    assert!(ignored("build"));
+    assert!(ignored("build/out.o"));
+    // 锚定到 ignore 文件所在目录
+    assert!(ignored("top.bin"));
+    assert!(!ignored("nested/top.bin"));
+    assert!(ignored("docs/manual.pdf"));
+    assert!(!ignored("docs/deep/manual.pdf"));
+    // 深层目录的 ignore 文件优先
+    assert!(!ignored("sub/build/out.o"));
+    assert!(ignored("sub/local.dat"));
+    assert!(!ignored("local.dat"));
+
+    let matched = tree.matched(&root.join("nested/b.iso"), false).unwrap();
+    assert_eq!(matched.source, root.join(DISKTIDY_IGNORE_FILE));
+    assert_eq!(matched.line, 2);
+    assert_eq!(matched.pattern, "*.iso");
+}
+
+#[test]
+fn test_excluded_directory_cannot_be_reincluded() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write(&root.join(DISKTIDY_IGNORE_FILE), b"cache/\n!cache/important.txt\n");
+    write(&root.join("cache/important.txt"), b"x");
+
+    let ignore = DisktidyIgnore::new();
+    assert!(ignore.is_excluded(&root.join("cache/important.txt"), false));
+
+    let excluded = ignore.excluded_paths();
+    assert_eq!(excluded.len(), 1);
+    assert_eq!(excluded[0].pattern, "cache/");
+}
+
+#[test]
+fn test_file_walker_applies_ignore_files_and_reports_source() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().join("scan");
+    write(&root.join("project").join(DISKTIDY_IGNORE_FILE), b"vm-images/\n");
+    write(&root.join("project/vm-images/disk.img"), &[0u8; 64]);
+    write(&root.join("project/src/main.rs"), b"fn main() {}");
+
+    let filter = StandardFileFilter::new(&FilterOptions {
+        include_hidden: true,
+        ..Default::default()
+    });
+    let files: Vec<PathBuf> = FileWalker::new(&filter)
+        .walk_files(&root)
+        .map(|e| e.path().to_path_buf())
+        .collect();
+
+    assert!(files.contains(&root.join("project/src/main.rs")));
+    assert!(!files.contains(&root.join("project/vm-images/disk.img")));
+
+    let ignored = filter.ignored_paths();
+    assert_eq!(ignored.len(), 1);
+    assert!(ignored[0].is_dir);
+    assert_eq!(PathBuf::from(&ignored[0].path), root.join("project/vm-images"));
+    assert_eq!(
+        PathBuf::from(&ignored[0].ignore_file),
+        root.join("project").join(DISKTIDY_IGNORE_FILE)
+    );
+    assert_eq!(ignored[0].line, 1);
+
+    let unfiltered = StandardFileFilter::new(&FilterOptions {
+        include_hidden: true,
+        respect_ignore_files: false,
+        ..Default::default()
+    });
+    assert!(FileWalker::new(&unfiltered)
+        .walk_files(&root)
+        .any(|e| e.path().ends_with("disk.img")));
+}
+
+#[test]
+fn test_standalone_large_file_analyzer_honours_ignore_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    // 规则文件位于扫描目录的上级
+    write(&root.join(DISKTIDY_IGNORE_FILE), b"*.vdi\n");
+    write(&root.join("data/vm.vdi"), &[0u8; 4096]);
+    write(&root.join("data/video.mp4"), &[0u8; 4096]);
+
+    let analyzer = LargeFileAnalyzer::with_options(LargeFileAnalyzerOptions {
+        threshold: 1024,
+        ..Default::default()
+    });
+    let result = analyzer.analyze(&[root.join("data")]);
+
+    assert_eq!(result.total_files, 1);
+    assert!(result.files[0].path.ends_with("video.mp4"));
+    assert_eq!(result.ignored_paths.len(), 1);
+    assert!(result.ignored_paths[0].path.ends_with("vm.vdi"));
+    assert_eq!(result.ignored_paths[0].pattern, "*.vdi");
+}
@@ -0,0 +1,198 @@
+#![cfg(unix)]
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+use disktidy_lib::models::{CleanOptions, FileHoldKind};
+use disktidy_lib::modules::cleaner::in_use::{
+    parse_maps, OpenFileIndex, ProcFs, ProcessFiles, ProcessTable,
+};
+use disktidy_lib::modules::cleaner::CleanerExecutor;
+
+use crate::fixtures::write;
+
+/// 固定的进程表，前 `held_snapshots` 次读取时进程持有文件，之后视为已关闭
+struct FakeTable {
+    process: ProcessFiles,
+    held_snapshots: usize,
+    reads: Arc<AtomicUsize>,
+}
+
+impl ProcessTable for FakeTable {
+    fn processes(&self) -> io::Result<Vec<ProcessFiles>> {
+        let read = self.reads.fetch_add(1, Ordering::SeqCst);
+        if read < self.held_snapshots {
+            Ok(vec![self.process.clone()])
+        } else {
+            Ok(Vec::new())
+        }
+    }
+}
+
+fn executor_with(
+    process: ProcessFiles,
+    held_snapshots: usize,
+) -> (CleanerExecutor, Arc<AtomicUsize>) {
+    let reads = Arc::new(AtomicUsize::new(0));
+    let mut executor = CleanerExecutor::with_options(CleanOptions {
+        move_to_recycle_bin: false,
+        secure_delete: false,
+        secure_pass_count: 1,
+        ..Default::default()
+    });
+    executor.set_process_table(Box::new(FakeTable {
+        process,
+        held_snapshots,
+        reads: reads.clone(),
+    }));
+    (executor, reads)
+}
+
+fn canonical_root(temp_dir: &TempDir) -> PathBuf {
+    temp_dir.path().canonicalize().unwrap()
+}
+
+#[test]
+fn test_procfs_reads_fd_and_maps() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = canonical_root(&temp_dir);
+    let proc_root = root.join("proc");
+    let process_dir = proc_root.join("4242");
+    write(&root.join("data/open.db"), b"x");
+    write(&process_dir.join("comm"), b"editor\n");
+    write(
+        &process_dir.join("maps"),
+        format!(
+            "7f00-7f01 r-xp 00000000 fd:01 1234 {}\n\
+             7f01-7f02 rw-p 00000000 00:00 0\n\
+             7f02-7f03 r--p 00000000 00:00 0 [heap]\n",
+            root.join("lib/libfoo so.1").display()
+        )
+        .as_bytes(),
+    );
+    fs::create_dir_all(process_dir.join("fd")).unwrap();
+    std::os::unix::fs::symlink(root.join("data/open.db"), process_dir.join("fd/3")).unwrap();
+    std::os::unix::fs::symlink("socket:[1234]", process_dir.join("fd/4")).unwrap();
+    fs::create_dir_all(proc_root.join("self")).unwrap();
+
+    let processes = ProcFs::with_root(&proc_root).processes().unwrap();
+    assert_eq!(processes.len(), 1);
+    assert_eq!(processes[0].pid, 4242);
+    assert_eq!(processes[0].name, "editor");
+    assert_eq!(processes[0].open_files, vec![root.join("data/open.db")]);
+    assert_eq!(
+        processes[0].mapped_files,
+        vec![root.join("lib/libfoo so.1")]
+    );
+
+    let index = OpenFileIndex::from_processes(processes);
+    let holders = index.holders_of(&root.join("data"));
+    assert_eq!(holders.len(), 1);
+    assert_eq!(holders[0].kind, FileHoldKind::Open);
+    assert!(index.holders_of(&root.join("data-other")).is_empty());
+    assert_eq!(
+        index.holders_of(&root.join("lib/libfoo so.1"))[0].kind,
+        FileHoldKind::Mapped
+    );
+
+    assert!(parse_maps("7f00-7f01 r-xp 00000000 00:00 0 [vdso]\n").is_empty());
+}
+
+#[tokio::test]
+async fn test_in_use_files_are_skipped_with_process_info() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = canonical_root(&temp_dir);
+    write(&root.join("free.tmp"), b"x");
+    write(&root.join("held.log"), b"x");
+    write(&root.join("cache/lib.so"), b"x");
+
+    let (executor, _) = executor_with(
+        ProcessFiles {
+            pid: 42,
+            name: "viewer".to_string(),
+            open_files: vec![root.join("held.log")],
+            mapped_files: vec![root.join("cache/lib.so")],
+        },
+        usize::MAX,
+    );
+    let result = executor
+        .clean(vec![
+            root.join("free.tmp"),
+            root.join("held.log"),
+            root.join("cache"),
+        ])
+        .await
+        .unwrap();
+
+    assert_eq!(result.cleaned_files, 1);
+    assert_eq!(result.skipped_files, 2);
+    assert_eq!(result.failed_files, 0);
+    assert!(!root.join("free.tmp").exists());
+    assert!(root.join("held.log").exists());
+    assert!(root.join("cache/lib.so").exists());
+
+    let error = &result.errors[0];
+    assert_eq!(error.error_code, "E009");
+    assert!(error.error_message.contains("viewer (PID 42)"));
+    assert_eq!(error.processes[0].pid, 42);
+    assert_eq!(result.errors[1].processes[0].kind, FileHoldKind::Mapped);
+    assert_eq!(
+        result.in_use_paths(),
+        vec![
+            root.join("held.log").to_string_lossy().to_string(),
+            root.join("cache").to_string_lossy().to_string(),
+        ]
+    );
+
+    let error = executor
+        .clean_single(&root.join("held.log"))
+        .await
+        .unwrap_err();
+    assert_eq!(error.error_code(), "E009");
+}
+
+#[tokio::test]
+async fn test_deferred_file_cleaned_once_released() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = canonical_root(&temp_dir);
+    write(&root.join("held.log"), b"x");
+    write(&root.join("other.tmp"), b"x");
+
+    // 第一次检测时被占用，处理完其他文件后重新检测时已关闭
+    let (executor, reads) = executor_with(
+        ProcessFiles {
+            pid: 7,
+            name: "writer".to_string(),
+            open_files: vec![root.join("held.log")],
+            mapped_files: vec![],
+        },
+        1,
+    );
+    let result = executor
+        .clean(vec![root.join("held.log"), root.join("other.tmp")])
+        .await
+        .unwrap();
+
+    assert_eq!(reads.load(Ordering::SeqCst), 2);
+    assert_eq!(result.cleaned_files, 2, "{:?}", result.errors);
+    assert!(result.errors.is_empty());
+    assert!(!root.join("held.log").exists());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_procfs_sees_open_file_of_current_process() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = canonical_root(&temp_dir);
+    let path = root.join("open.bin");
+    write(&path, b"x");
+    let _file = fs::File::open(&path).unwrap();
+
+    let index = OpenFileIndex::from_processes(ProcFs::new().processes().unwrap());
+    let holders = index.holders_of(&path);
+    assert!(holders.iter().any(|h| h.pid == std::process::id()));
+}
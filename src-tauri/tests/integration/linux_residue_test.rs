@@ -0,0 +1,278 @@
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+use disktidy_lib::modules::software_residue::linux::{
+    find_linux_residue, parse_dpkg_status, parse_pacman_desc, LinuxInventory, LinuxRoots,
+    PackageManager, PackageState,
+};
+use disktidy_lib::modules::software_residue::rpmdb::{read_ndb_packages, read_sqlite_packages};
+use disktidy_lib::modules::software_residue::{ResidueEvidence, ResidueType};
+
+use crate::fixtures::{age, write};
+
+const RPMDB_SQLITE: &[u8] = include_bytes!("../fixtures/rpmdb.sqlite");
+
+const DPKG_STATUS: &str = "\
+Package: vim
+Status: install ok installed
+Installed-Size: 3848
+Version: 2:9.0.1378-2
+
+Package: audacity
+Status: deinstall ok config-files
+Version: 3.2.4
+Conffiles:
+ /etc/audacity/audacity.conf 0d1f6a3e5c1b7e2f9a0c4d8b2e6f1a3c
+ /etc/audacity/presets.xml obsolete
+
+Package: nano
+Status: purge ok not-installed
+";
+
+/// 构造 rpm 软件包头部，只包含名称、版本与大小
+fn rpm_header(name: &str, version: &str, size: u32) -> Vec<u8> {
+    let mut index = Vec::new();
+    let mut data = Vec::new();
+    let mut add = |tag: u32, kind: u32, value: &[u8]| {
+        index.extend_from_slice(&tag.to_be_bytes());
+        index.extend_from_slice(&kind.to_be_bytes());
+        index.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        index.extend_from_slice(&1u32.to_be_bytes());
+        data.extend_from_slice(value);
+    };
+    add(1009, 4, &size.to_be_bytes());
+    add(1000, 6, format!("{}\0", name).as_bytes());
+    add(1001, 6, format!("{}\0", version).as_bytes());
+    let mut header = Vec::new();
+    header.extend_from_slice(&3u32.to_be_bytes());
+    header.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    header.extend_from_slice(&index);
+    header.extend_from_slice(&data);
+    header
+}
+
+#[test]
+fn test_parse_dpkg_status() {
+    let packages = parse_dpkg_status(DPKG_STATUS);
+
+    assert_eq!(packages.len(), 2);
+    assert_eq!(packages[0].name, "vim");
+    assert_eq!(packages[0].state, PackageState::Installed);
+    assert_eq!(packages[0].version.as_deref(), Some("2:9.0.1378-2"));
+    assert_eq!(packages[0].installed_size, Some(3848 * 1024));
+    assert_eq!(packages[1].name, "audacity");
+    assert_eq!(packages[1].state, PackageState::ConfigRemaining);
+    assert_eq!(packages[1].installed_size, None);
+    assert_eq!(
+        packages[1].conffiles,
+        vec!["/etc/audacity/audacity.conf", "/etc/audacity/presets.xml"]
+    );
+}
+
+#[test]
+fn test_parse_pacman_desc() {
+    let desc = "%NAME%\nlinux-firmware\n\n%VERSION%\n20240312-1\n\n%BASE%\nlinux-firmware\n\n%SIZE%\n4456448\n";
+    let package = parse_pacman_desc(desc).unwrap();
+
+    assert_eq!(package.name, "linux-firmware");
+    assert_eq!(package.version.as_deref(), Some("20240312-1"));
+    assert_eq!(package.manager, PackageManager::Pacman);
+    assert_eq!(package.installed_size, Some(4456448));
+    assert!(parse_pacman_desc("%VERSION%\n1.0\n").is_none());
+}
+
+#[test]
+fn test_read_rpm_sqlite_database() {
+    let packages = read_sqlite_packages(RPMDB_SQLITE).unwrap();
+
+    // 300 个小软件包占满多个叶子页，httpd 的头部超过一页，需要读取溢出页
+    assert_eq!(packages.len(), 302);
+    assert!(packages
+        .iter()
+        .any(|p| p.name == "pkg150" && p.version.as_deref() == Some("1.150")));
+    assert!(packages
+        .iter()
+        .any(|p| p.name == "httpd" && p.version.as_deref() == Some("2.4.57")));
+    assert!(packages.iter().any(|p| p.name == "vim-enhanced"));
+
+    assert!(read_sqlite_packages(b"not a database").is_none());
+}
+
+#[test]
+fn test_read_rpm_ndb_database() {
+    let mut data = vec![0u8; 64];
+    for (index, name) in [(1u32, "bash"), (2u32, "coreutils")] {
+        let header = rpm_header(name, "1.0", index * 1000);
+        let mut blob = Vec::new();
+        blob.extend_from_slice(b"BlbS");
+        blob.extend_from_slice(&index.to_le_bytes());
+        blob.extend_from_slice(&1u32.to_le_bytes());
+        blob.extend_from_slice(&(header.len() as u32).to_le_bytes());
+        blob.extend_from_slice(&header);
+        blob.resize(blob.len().div_ceil(16) * 16, 0);
+        data.extend_from_slice(&blob);
+    }
+
+    let packages: Vec<(String, Option<u64>)> = read_ndb_packages(&data)
+        .into_iter()
+        .map(|p| (p.name, p.size))
+        .collect();
+    assert_eq!(
+        packages,
+        vec![
+            ("bash".to_string(), Some(1000)),
+            ("coreutils".to_string(), Some(2000))
+        ]
+    );
+}
+
+#[test]
+fn test_load_inventory_from_all_managers() {
+    let root = TempDir::new().unwrap();
+    let home = TempDir::new().unwrap();
+    let r = root.path();
+
+    write(&r.join("var/lib/dpkg/status"), DPKG_STATUS.as_bytes());
+    write(&r.join("var/lib/rpm/rpmdb.sqlite"), RPMDB_SQLITE);
+    write(
+        &r.join("var/lib/pacman/local/ripgrep-14.1.0-1/desc"),
+        b"%NAME%\nripgrep\n\n%VERSION%\n14.1.0-1\n",
+    );
+    fs::create_dir_all(r.join("var/lib/flatpak/app/org.gimp.GIMP/current")).unwrap();
+    fs::create_dir_all(
+        home.path()
+            .join(".local/share/flatpak/app/org.videolan.VLC"),
+    )
+    .unwrap();
+    fs::create_dir_all(r.join("snap/firefox/current")).unwrap();
+    fs::create_dir_all(r.join("snap/bin")).unwrap();
+    write(&r.join("var/lib/snapd/snaps/core22_1380.snap"), b"");
+
+    let roots = LinuxRoots {
+        root: r.to_path_buf(),
+        home: Some(home.path().to_path_buf()),
+    };
+    let inventory = LinuxInventory::load(&roots);
+    let find = |name: &str| inventory.packages.iter().find(|p| p.name == name);
+
+    assert_eq!(find("vim").unwrap().manager, PackageManager::Dpkg);
+    assert_eq!(find("httpd").unwrap().manager, PackageManager::Rpm);
+    assert_eq!(find("ripgrep").unwrap().manager, PackageManager::Pacman);
+    assert_eq!(
+        find("org.gimp.GIMP").unwrap().manager,
+        PackageManager::Flatpak
+    );
+    assert_eq!(
+        find("org.videolan.VLC").unwrap().manager,
+        PackageManager::Flatpak
+    );
+    assert_eq!(find("firefox").unwrap().manager, PackageManager::Snap);
+    assert_eq!(find("core22").unwrap().manager, PackageManager::Snap);
+    assert!(find("bin").is_none());
+
+    let installed = inventory.installed_software();
+    let vim = installed.iter().find(|s| s.name == "vim").unwrap();
+    assert_eq!(vim.installed_size, Some(3848 * 1024));
+    assert!(installed.iter().any(|s| s.name == "ripgrep"));
+    assert!(!installed.iter().any(|s| s.name == "audacity"));
+}
+
+#[test]
+fn test_find_linux_residue() {
+    let root = TempDir::new().unwrap();
+    let home = TempDir::new().unwrap();
+    let r = root.path();
+    let h = home.path();
+
+    write(&r.join("var/lib/dpkg/status"), DPKG_STATUS.as_bytes());
+    fs::create_dir_all(r.join("var/lib/flatpak/app/org.gimp.GIMP")).unwrap();
+    fs::create_dir_all(r.join("snap/firefox")).unwrap();
+
+    write(&r.join("etc/audacity/audacity.conf"), b"");
+    write(&r.join("etc/vim/vimrc"), b"");
+    write(&r.join("etc/httpd/httpd.conf.rpmsave"), b"");
+    write(&h.join(".config/audacity/audacity.cfg"), b"");
+    write(&h.join(".config/vim/vimrc"), b"");
+    write(&h.join(".cache/fontconfig/cache"), b"");
+    // 目录名与软件包名不同（firefox 的缓存目录为 mozilla），不按已知应用名判定
+    write(&h.join(".cache/mozilla/firefox/cache2/data"), b"");
+    write(&h.join(".local/share/oldtool/state"), b"");
+    age(&h.join(".local/share/oldtool"), 400);
+    write(&h.join(".local/share/newtool/state"), b"");
+    fs::create_dir_all(h.join(".var/app/org.gimp.GIMP")).unwrap();
+    fs::create_dir_all(h.join(".var/app/com.spotify.Client")).unwrap();
+    fs::create_dir_all(h.join("snap/firefox")).unwrap();
+    fs::create_dir_all(h.join("snap/telegram-desktop")).unwrap();
+
+    let roots = LinuxRoots {
+        root: r.to_path_buf(),
+        home: Some(h.to_path_buf()),
+    };
+    let inventory = LinuxInventory::load(&roots);
+    let candidates = find_linux_residue(&inventory, &roots);
+    let find = |path: &Path| candidates.iter().find(|c| c.path == path);
+
+    let etc = find(&r.join("etc/audacity")).unwrap();
+    assert_eq!(etc.residue_type, ResidueType::ConfigFile);
+    assert_eq!(etc.app_name, "audacity");
+    assert_eq!(
+        etc.evidence[0],
+        ResidueEvidence::OwnedByRemovedPackage {
+            manager: PackageManager::Dpkg,
+            package: "audacity".to_string(),
+        }
+    );
+
+    let config = find(&h.join(".config/audacity")).unwrap();
+    assert!(config.confidence < etc.confidence);
+    assert!(config.evidence.contains(&ResidueEvidence::NotInInventory));
+
+    let saved = find(&r.join("etc/httpd/httpd.conf.rpmsave")).unwrap();
+    assert_eq!(
+        saved.evidence,
+        vec![ResidueEvidence::SavedConfig {
+            manager: PackageManager::Rpm
+        }]
+    );
+
+    let flatpak = find(&h.join(".var/app/com.spotify.Client")).unwrap();
+    assert_eq!(flatpak.residue_type, ResidueType::LeftoverFolder);
+    assert!(flatpak.confidence >= 0.9);
+    assert!(find(&h.join("snap/telegram-desktop")).is_some());
+
+    let stale = find(&h.join(".local/share/oldtool")).unwrap();
+    assert!(stale.confidence < config.confidence);
+    assert!(matches!(
+        stale.evidence[0],
+        ResidueEvidence::LastWrite { days } if days >= 399
+    ));
+
+    for kept in [
+        r.join("etc/vim"),
+        h.join(".config/vim"),
+        h.join(".cache/fontconfig"),
+        h.join(".cache/mozilla"),
+        h.join(".local/share/newtool"),
+        h.join(".var/app/org.gimp.GIMP"),
+        h.join("snap/firefox"),
+    ] {
+        assert!(find(&kept).is_none(), "{} should be kept", kept.display());
+    }
+}
+
+#[test]
+fn test_find_linux_residue_without_inventory() {
+    let root = TempDir::new().unwrap();
+    let home = TempDir::new().unwrap();
+    write(&home.path().join(".cache/discord/data"), b"");
+
+    let roots = LinuxRoots {
+        root: root.path().to_path_buf(),
+        home: Some(home.path().to_path_buf()),
+    };
+    let inventory = LinuxInventory::load(&roots);
+
+    assert!(inventory.is_empty());
+    assert!(find_linux_residue(&inventory, &roots).is_empty());
+}
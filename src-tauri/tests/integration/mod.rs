@@ -4,3 +4,26 @@ pub mod duplicate_test;
 pub mod clean_test;
 pub mod safety_test;
 pub mod settings_test;
+pub mod cleanup_planner_test;
+pub mod smart_clean_test;
+pub mod build_artifact_test;
+pub mod package_cache_test;
+pub mod git_repo_test;
+pub mod ignore_file_test;
+pub mod safe_remove_test;
+pub mod path_match_test;
+pub mod risk_score_test;
+pub mod in_use_test;
+pub mod clean_executor_test;
+pub mod secure_erase_test;
+pub mod free_space_wipe_test;
+pub mod archive_test;
+pub mod app_cache_definition_test;
+pub mod app_cache_conversation_test;
+pub mod app_cache_retention_test;
+pub mod app_cache_export_test;
+pub mod linux_residue_test;
+pub mod registry_backend_test;
+pub mod residue_confidence_test;
+pub mod software_inventory_test;
+pub mod wechat_dat_test;
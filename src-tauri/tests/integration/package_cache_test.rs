@@ -0,0 +1,233 @@
+use std::cmp::Ordering;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use tempfile::TempDir;
+
+use disktidy_lib::modules::package_cache::{
+    compare_versions, split_name_version, PackageCacheAnalyzer, PackageCacheOptions,
+    PackageCacheRoot, PackageManager,
+};
+
+use crate::fixtures::write;
+
+fn analyzer_for(manager: PackageManager, cache: &Path, projects: &Path) -> PackageCacheAnalyzer {
+    PackageCacheAnalyzer::with_options(PackageCacheOptions {
+        managers: vec![manager],
+        project_paths: vec![projects.to_string_lossy().to_string()],
+        keep_recent: 1,
+        cache_roots: vec![PackageCacheRoot {
+            manager,
+            path: cache.to_string_lossy().to_string(),
+        }],
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_version_helpers() {
+    assert_eq!(split_name_version("serde-1.0.197"), Some(("serde", "1.0.197")));
+    assert_eq!(
+        split_name_version("wasm-bindgen-0.2.92"),
+        Some(("wasm-bindgen", "0.2.92"))
+    );
+    assert_eq!(split_name_version("no-version"), None);
+
+    assert_eq!(compare_versions("1.10.0", "1.9.3"), Ordering::Greater);
+    assert_eq!(compare_versions("v0.3.0", "v0.3.0"), Ordering::Equal);
+    assert_eq!(compare_versions("2.0.0-beta.1", "2.0.0"), Ordering::Less);
+    assert_eq!(compare_versions("1.2.3.1", "1.2.3"), Ordering::Greater);
+}
+
+#[test]
+fn test_cargo_registry_cross_referenced_with_lockfile() {
+    let temp_dir = TempDir::new().unwrap();
+    let cargo_home = temp_dir.path().join("cargo");
+    let projects = temp_dir.path().join("projects");
+
+    let registry = cargo_home.join("registry");
+    for version in ["1.0.100", "1.0.150", "1.0.197"] {
+        write(
+            &registry.join(format!("cache/index.crates.io-6f17d22bba15001f/serde-{}.crate", version)),
+            &[0u8; 1000],
+        );
+        write(
+            &registry.join(format!("src/index.crates.io-6f17d22bba15001f/serde-{}/src/lib.rs", version)),
+            &[0u8; 4000],
+        );
+    }
+    write(&registry.join("index/index.crates.io-6f17d22bba15001f/config.json"), b"{}");
+
+    write(
+        &projects.join("app/Cargo.lock"),
+        b"version = 3\n\n[[package]]\nname = \"serde\"\nversion = \"1.0.100\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n",
+    );
+
+    let result = analyzer_for(PackageManager::Cargo, &cargo_home, &projects).analyze();
+    assert_eq!(result.lockfiles_scanned, 1);
+    assert_eq!(result.managers.len(), 1);
+
+    let cache = &result.managers[0];
+    assert_eq!(cache.entries.len(), 3);
+    assert_eq!(cache.package_count, 1);
+    assert_eq!(cache.other.len(), 1);
+
+    let entry = |version: &str| cache.entries.iter().find(|e| e.version == version).unwrap();
+
+    // 被锁文件引用
+    assert!(entry("1.0.100").referenced);
+    assert!(!entry("1.0.100").removable);
+    // 最新版本保留
+    assert!(!entry("1.0.197").removable);
+    // 既无引用也不是最新版本
+    let stale = entry("1.0.150");
+    assert!(stale.removable);
+    assert_eq!(stale.size, 5000);
+    assert_eq!(stale.paths.len(), 2);
+
+    assert_eq!(cache.removable_size, 5000);
+    assert_eq!(result.removable_paths().len(), 2);
+}
+
+#[test]
+fn test_npm_cacache_and_maven_layouts() {
+    let temp_dir = TempDir::new().unwrap();
+    let projects = temp_dir.path().join("projects");
+    fs::create_dir_all(&projects).unwrap();
+
+    let cacache = temp_dir.path().join("_cacache");
+    write(
+        &cacache.join("index-v5/aa/bb/cafebabe"),
+        b"0123abcd\t{\"key\":\"make-fetch-happen:request-cache:https://registry.npmjs.org/left-pad/-/left-pad-1.3.0.tgz\",\"integrity\":\"sha1-FsOFpsvXxq0GzWpxlar65JMvzz0=\",\"time\":1700000000000,\"size\":2048}\n",
+    );
+    write(
+        &cacache.join("content-v2/sha1/16/c3/85a6cbd7c6ad06cd6a7195aafae4932fcf3d"),
+        &[0u8; 2048],
+    );
+
+    let npm = analyzer_for(PackageManager::Npm, &cacache, &projects).analyze();
+    let npm_cache = &npm.managers[0];
+    assert_eq!(npm_cache.entries.len(), 1);
+    assert_eq!(npm_cache.entries[0].name, "left-pad");
+    assert_eq!(npm_cache.entries[0].version, "1.3.0");
+    assert_eq!(npm_cache.entries[0].size, 2048);
+
+    let m2 = temp_dir.path().join("repository");
+    for version in ["5.3.0", "6.1.0"] {
+        write(
+            &m2.join(format!("org/junit/junit-bom/{v}/junit-bom-{v}.pom", v = version)),
+            &[0u8; 300],
+        );
+    }
+
+    let maven = analyzer_for(PackageManager::Maven, &m2, &projects).analyze();
+    let maven_cache = &maven.managers[0];
+    assert_eq!(maven_cache.entries.len(), 2);
+    assert!(maven_cache.entries.iter().all(|e| e.name == "org.junit:junit-bom"));
+
+    let old = maven_cache.entries.iter().find(|e| e.version == "5.3.0").unwrap();
+    assert!(old.removable);
+    assert_eq!(maven_cache.removable_size, 300);
+}
+
+#[test]
+fn test_cargo_git_checkouts_matched_by_rev() {
+    let temp_dir = TempDir::new().unwrap();
+    let cargo_home = temp_dir.path().join("cargo");
+    let projects = temp_dir.path().join("projects");
+
+    let checkouts = cargo_home.join("git/checkouts/tokio-9a8b7c6d5e4f3a2b");
+    for rev in ["0a1b2c3", "ffeeddc", "7d8e9f0"] {
+        write(&checkouts.join(rev).join("Cargo.toml"), &[0u8; 100]);
+    }
+    // 最近检出的版本保留，哈希的字典序与新旧无关
+    fs::File::open(checkouts.join("0a1b2c3"))
+        .unwrap()
+        .set_modified(SystemTime::now() + Duration::from_secs(60))
+        .unwrap();
+
+    write(
+        &projects.join("app/Cargo.lock"),
+        b"version = 3\n\n[[package]]\nname = \"tokio-util\"\nversion = \"0.7.10\"\nsource = \"git+https://github.com/tokio-rs/tokio?branch=master#7d8e9f0a1b2c3d4e5f60718293a4b5c6d7e8f901\"\n",
+    );
+
+    let result = analyzer_for(PackageManager::Cargo, &cargo_home, &projects).analyze();
+    let cache = &result.managers[0];
+    let entry = |rev: &str| cache.entries.iter().find(|e| e.version == rev).unwrap();
+
+    assert!(cache.entries.iter().all(|e| e.git_checkout && e.name == "tokio"));
+    assert!(entry("7d8e9f0").referenced);
+    assert!(!entry("7d8e9f0").removable);
+    assert!(!entry("0a1b2c3").removable);
+    assert!(entry("ffeeddc").removable);
+}
+
+#[test]
+fn test_maven_and_conda_reference_sources() {
+    let temp_dir = TempDir::new().unwrap();
+    let projects = temp_dir.path().join("projects");
+
+    write(
+        &projects.join("service/pom.xml"),
+        br#"<project>
+  <properties>
+    <guava.version>32.1.3-jre</guava.version>
+  </properties>
+  <dependencies>
+    <dependency>
+      <groupId>com.google.guava</groupId>
+      <artifactId>guava</artifactId>
+      <version>${guava.version}</version>
+    </dependency>
+  </dependencies>
+</project>
+"#,
+    );
+    let m2 = temp_dir.path().join("repository");
+    for version in ["31.0-jre", "32.1.3-jre", "33.0.0-jre"] {
+        write(
+            &m2.join(format!("com/google/guava/guava/{v}/guava-{v}.pom", v = version)),
+            &[0u8; 100],
+        );
+    }
+
+    let maven = analyzer_for(PackageManager::Maven, &m2, &projects).analyze();
+    let maven_cache = &maven.managers[0];
+    assert_eq!(maven_cache.lockfiles_found, 1);
+    let guava = |version: &str| {
+        maven_cache
+            .entries
+            .iter()
+            .find(|e| e.version == version)
+            .unwrap()
+    };
+    assert!(guava("32.1.3-jre").referenced);
+    assert!(!guava("33.0.0-jre").removable);
+    assert!(guava("31.0-jre").removable);
+
+    write(
+        &projects.join("analysis/environment.yml"),
+        b"name: analysis\nchannels:\n  - conda-forge\ndependencies:\n  - python=3.11\n  - conda-forge::numpy=1.25.2=py311h64a7726_0\n  - pip\n  - pip:\n      - requests==2.31.0\n",
+    );
+    let pkgs = temp_dir.path().join("pkgs");
+    for version in ["1.24.4", "1.25.2", "1.26.4"] {
+        write(
+            &pkgs.join(format!("numpy-{}-py311h64a7726_0/info/index.json", version)),
+            &[0u8; 100],
+        );
+    }
+
+    let conda = analyzer_for(PackageManager::Conda, &pkgs, &projects).analyze();
+    let conda_cache = &conda.managers[0];
+    assert_eq!(conda_cache.lockfiles_found, 1);
+    let numpy = |version: &str| {
+        conda_cache
+            .entries
+            .iter()
+            .find(|e| e.version == version)
+            .unwrap()
+    };
+    assert!(numpy("1.25.2").referenced);
+    assert!(numpy("1.24.4").removable);
+    assert!(!numpy("1.26.4").removable);
+}
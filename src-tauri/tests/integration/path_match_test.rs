@@ -0,0 +1,111 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+use disktidy_lib::models::settings::WhitelistSettings;
+use disktidy_lib::modules::cleaner::SafetyChecker;
+use disktidy_lib::modules::cleanup_planner::path_key;
+use disktidy_lib::utils::file_category::FileCategoryRegistry;
+use disktidy_lib::utils::path_match::{
+    CaseSensitivity, NormalizedPath, PathPrefix, SegmentPattern,
+};
+
+#[test]
+fn test_prefix_compares_whole_components() {
+    let usr = PathPrefix::with_case("/usr", CaseSensitivity::Sensitive);
+    assert!(usr.contains(Path::new("/usr")));
+    assert!(usr.contains(Path::new("/usr/lib/libc.so")));
+    assert!(!usr.contains(Path::new("/usrlocal-data/file")));
+    assert!(!usr.contains(Path::new("/home/usr")));
+    assert!(usr.contains(Path::new("/home/../usr/./bin")));
+
+    let windows = PathPrefix::with_case(r"C:\Windows\System32", CaseSensitivity::Insensitive);
+    assert!(windows.contains(Path::new(r"c:\windows\system32\drivers")));
+    assert!(windows.contains(Path::new("C:/Windows/System32/kernel32.dll")));
+    assert!(windows.contains(Path::new(r"\\?\C:\WINDOWS\System32")));
+    assert!(!windows.contains(Path::new(r"C:\Windows\System32Backup")));
+    assert!(windows.is_within(Path::new(r"C:\Windows")));
+    assert!(!windows.is_within(Path::new(r"C:\Users")));
+
+    assert_eq!(
+        NormalizedPath::parse("/a/b/../c/", CaseSensitivity::Sensitive).components(),
+        ["/", "a", "c"]
+    );
+}
+
+#[test]
+fn test_case_sensitivity_follows_filesystem() {
+    let temp_dir = TempDir::new().unwrap();
+    let data = temp_dir.path().join("Data");
+    fs::create_dir_all(&data).unwrap();
+
+    let case = CaseSensitivity::for_path(&data);
+    let prefix = PathPrefix::new(&data);
+    assert_eq!(prefix.case(), case);
+
+    let other_case = temp_dir.path().join("data").join("file.txt");
+    assert_eq!(
+        prefix.contains(&other_case),
+        case == CaseSensitivity::Insensitive
+    );
+    assert!(prefix.contains(&data.join("file.txt")));
+}
+
+#[test]
+fn test_segment_patterns() {
+    let temp = SegmentPattern::new(r"\temp\");
+    assert!(temp.matches(Path::new(r"C:\Users\a\AppData\Local\Temp\x.tmp")));
+    assert!(temp.matches(Path::new("/var/temp/x.tmp")));
+    assert!(!temp.matches(Path::new("/var/temp")));
+    assert!(!temp.matches(Path::new("/var/contemporary/x")));
+
+    let chrome = SegmentPattern::new(r"\chrome\user data\default\cache");
+    assert!(chrome.matches(Path::new(
+        r"C:\Google\Chrome\User Data\Default\Cache\f_0001"
+    )));
+    assert!(chrome.matches(Path::new("/g/chrome/User Data/Default/Cache2/x")));
+    assert!(!chrome.matches(Path::new("/g/chrome/User Data/Profile 1/Cache/x")));
+
+    let recycle = SegmentPattern::new("$recycle.bin");
+    assert!(recycle.matches(Path::new(r"D:\$RECYCLE.BIN\S-1-5\$R1.txt")));
+}
+
+#[test]
+fn test_existing_checks_use_component_matching() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    let protected = root.join("srv");
+    fs::create_dir_all(&protected).unwrap();
+    fs::create_dir_all(root.join("srvlocal-data")).unwrap();
+    fs::write(root.join("srvlocal-data/cache.tmp"), b"x").unwrap();
+
+    let mut checker = SafetyChecker::new();
+    checker.add_protected_path(protected.clone());
+    assert!(!checker.check(&protected.join("app.tmp")).safe_to_delete);
+    assert!(
+        checker
+            .check(&root.join("srvlocal-data/cache.tmp"))
+            .safe_to_delete
+    );
+    // 删除受保护目录的上级同样不允许
+    assert!(!checker.check(&root).safe_to_delete);
+
+    let mut whitelist = WhitelistSettings::new();
+    whitelist.add_path(protected.to_string_lossy().to_string(), String::new());
+    assert!(whitelist.is_path_whitelisted(&protected.join("a").to_string_lossy()));
+    assert!(!whitelist.is_path_whitelisted(&root.join("srvlocal-data").to_string_lossy()));
+
+    let registry = FileCategoryRegistry::new();
+    let log = PathBuf::from("/home/user/.local/share/app/logs/output.txt");
+    assert_eq!(registry.categorize_file(&log).unwrap().name, "log_files");
+    let catalog = PathBuf::from("/home/user/catalogs/output.txt");
+    assert!(registry.categorize_file(&catalog).is_none());
+}
+
+#[test]
+fn test_path_key_uses_normalized_components() {
+    assert_eq!(path_key("/home/u/./cache//a/"), "/home/u/cache/a");
+    assert_eq!(path_key("/home/u/cache/../a"), "/home/u/a");
+    assert_eq!(path_key(r"c:\Users\u\a"), "C:/Users/u/a");
+    assert_eq!(path_key("/"), "/");
+}
@@ -0,0 +1,269 @@
+use std::fs;
+use tempfile::TempDir;
+
+use disktidy_lib::modules::software_residue::registry::{
+    delete_keys_with_backup, export_reg, find_leftover_keys, import_reg, parse_reg,
+    read_installed_software, read_reg_file, LeftoverReason, MemoryRegistry, RegBackup,
+    RegOperation, RegistryBackend, RegistryValue,
+};
+
+const SAMPLE_REG: &str = r#"Windows Registry Editor Version 5.00
+
+[HKEY_LOCAL_MACHINE\SOFTWARE\Microsoft\Windows\CurrentVersion\Uninstall\7-Zip]
+"DisplayName"="7-Zip 23.01 (x64)"
+"Publisher"="Igor Pavlov"
+"InstallLocation"="C:\\Program Files\\7-Zip\\"
+
+[HKEY_LOCAL_MACHINE\SOFTWARE\Microsoft\Windows\CurrentVersion\Uninstall\{GUID-1}]
+"DisplayName"="Google Chrome"
+"Publisher"="Google LLC"
+
+[HKEY_CURRENT_USER\SOFTWARE\7-Zip\Options]
+"MenuIcons"=dword:00000001
+
+[HKEY_CURRENT_USER\SOFTWARE\Google\Chrome]
+
+[HKEY_CURRENT_USER\SOFTWARE\Tencent\WeChat]
+"InstallPath"="C:\\Program Files\\Tencent\\WeChat"
+"Version"=dword:63090a1b
+"Flags"=hex(b):01,00,00,00,00,00,00,00
+"Path"=hex(2):25,00,41,00,50,00,50,00,44,00,41,00,54,00,41,00,25,00,00,00
+"Servers"=hex(7):61,00,00,00,62,00,00,00,00,00
+"Blob"=hex:de,ad,be,\
+  ef
+@="default"
+
+[HKEY_CURRENT_USER\SOFTWARE\EmptyVendor]
+"#;
+
+fn sample() -> MemoryRegistry {
+    MemoryRegistry::from_reg(SAMPLE_REG).unwrap()
+}
+
+#[test]
+fn test_parse_reg_values() {
+    let registry = sample();
+    let key = "HKCU\\SOFTWARE\\Tencent\\WeChat";
+
+    assert_eq!(
+        registry.value(key, "InstallPath"),
+        Some(RegistryValue::String(
+            "C:\\Program Files\\Tencent\\WeChat".to_string()
+        ))
+    );
+    assert_eq!(
+        registry.value(key, "version"),
+        Some(RegistryValue::Dword(0x63090a1b))
+    );
+    assert_eq!(registry.value(key, "Flags"), Some(RegistryValue::Qword(1)));
+    assert_eq!(
+        registry.value(key, "Path"),
+        Some(RegistryValue::ExpandString("%APPDATA%".to_string()))
+    );
+    assert_eq!(
+        registry.value(key, "Servers"),
+        Some(RegistryValue::MultiString(vec![
+            "a".to_string(),
+            "b".to_string()
+        ]))
+    );
+    assert_eq!(
+        registry.value(key, "Blob"),
+        Some(RegistryValue::Binary(vec![0xde, 0xad, 0xbe, 0xef]))
+    );
+    assert_eq!(
+        registry.value(key, ""),
+        Some(RegistryValue::String("default".to_string()))
+    );
+
+    assert!(registry.key_exists("HKEY_CURRENT_USER\\Software\\tencent"));
+    assert_eq!(
+        registry.subkeys("HKCU\\SOFTWARE").unwrap(),
+        vec!["7-Zip", "EmptyVendor", "Google", "Tencent"]
+    );
+}
+
+#[test]
+fn test_parse_reg_deletions_and_errors() {
+    let operations = parse_reg(
+        "REGEDIT4\r\n\r\n[-HKEY_CURRENT_USER\\SOFTWARE\\Old]\r\n[HKCU\\SOFTWARE\\App]\r\n\"Gone\"=-\r\n",
+    )
+    .unwrap();
+    assert_eq!(
+        operations,
+        vec![
+            RegOperation::DeleteKey("HKEY_CURRENT_USER\\SOFTWARE\\Old".to_string()),
+            RegOperation::CreateKey("HKEY_CURRENT_USER\\SOFTWARE\\App".to_string()),
+            RegOperation::DeleteValue {
+                key: "HKEY_CURRENT_USER\\SOFTWARE\\App".to_string(),
+                name: "Gone".to_string(),
+            },
+        ]
+    );
+
+    let error = parse_reg("[HKCU\\SOFTWARE\\App]\n").unwrap_err();
+    assert_eq!(error.error_code(), "E026");
+    let error = parse_reg("Windows Registry Editor Version 5.00\n\n[HKCU\\X]\n\"A\"=dword:zz\n")
+        .unwrap_err();
+    assert!(error.to_string().contains("第 4 行"));
+
+    // 格式错误时不做任何修改
+    let registry = sample();
+    assert!(import_reg(
+        &registry,
+        "Windows Registry Editor Version 5.00\n[-HKCU\\SOFTWARE\\Tencent]\n[BAD\\Key]\n"
+    )
+    .is_err());
+    assert!(registry.key_exists("HKCU\\SOFTWARE\\Tencent"));
+}
+
+#[test]
+fn test_export_round_trip() {
+    let registry = sample();
+    let text = export_reg(&registry, &["HKCU\\SOFTWARE\\Tencent".to_string()]).unwrap();
+
+    assert!(text.starts_with("Windows Registry Editor Version 5.00\r\n"));
+    assert!(text.contains("[HKEY_CURRENT_USER\\SOFTWARE\\Tencent\\WeChat]"));
+    assert!(text.contains("\"Version\"=dword:63090a1b"));
+    assert!(text.contains("\"InstallPath\"=\"C:\\\\Program Files\\\\Tencent\\\\WeChat\""));
+    assert!(text.lines().all(|line| line.len() <= 80));
+
+    let copy = MemoryRegistry::from_reg(&text).unwrap();
+    let key = "HKCU\\SOFTWARE\\Tencent\\WeChat";
+    assert_eq!(copy.values(key).unwrap(), registry.values(key).unwrap());
+    assert!(!copy.key_exists("HKCU\\SOFTWARE\\7-Zip"));
+}
+
+#[test]
+fn test_installed_software_and_leftover_keys() {
+    let registry = sample();
+    let installed = read_installed_software(&registry);
+
+    assert_eq!(installed.len(), 2);
+    assert_eq!(installed[0].name, "7-Zip 23.01 (x64)");
+    assert_eq!(
+        installed[0].install_location.as_deref(),
+        Some("C:\\Program Files\\7-Zip\\")
+    );
+
+    let leftovers = find_leftover_keys(&registry, &installed);
+    let paths: Vec<&str> = leftovers.iter().map(|l| l.path.as_str()).collect();
+
+    // 7-Zip 与 Google 仍有已安装的软件，Microsoft 为系统项
+    assert_eq!(
+        paths,
+        vec!["HKCU\\SOFTWARE\\EmptyVendor", "HKCU\\SOFTWARE\\Tencent"]
+    );
+    assert_eq!(leftovers[0].reason, LeftoverReason::EmptyKey);
+    assert_eq!(leftovers[1].reason, LeftoverReason::KnownApp);
+    assert_eq!(leftovers[1].app_name, "腾讯");
+}
+
+#[test]
+fn test_delete_with_backup_and_restore() {
+    let registry = sample();
+    let backup_dir = TempDir::new().unwrap();
+    let backup = RegBackup::new(backup_dir.path());
+
+    let outcome = delete_keys_with_backup(
+        &registry,
+        &backup,
+        &[
+            "HKCU\\SOFTWARE\\Tencent".to_string(),
+            "HKCU\\SOFTWARE\\Missing".to_string(),
+        ],
+    );
+
+    assert_eq!(outcome.deleted, vec!["HKCU\\SOFTWARE\\Tencent"]);
+    assert_eq!(outcome.failed.len(), 1);
+    assert_eq!(outcome.failed[0].0, "HKCU\\SOFTWARE\\Missing");
+    assert!(!registry.key_exists("HKCU\\SOFTWARE\\Tencent\\WeChat"));
+
+    let backup_path = outcome.backup_path.unwrap();
+    let name = backup_path
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .to_string();
+    assert!(name.starts_with("registry-backup-") && name.ends_with(".reg"));
+    assert_eq!(&fs::read(&backup_path).unwrap()[..2], &[0xFF, 0xFE]);
+    assert!(read_reg_file(&backup_path)
+        .unwrap()
+        .contains("[HKEY_CURRENT_USER\\SOFTWARE\\Tencent\\WeChat]"));
+    assert_eq!(backup.list().len(), 1);
+
+    let summary = backup.restore(&registry, &backup_path).unwrap();
+    assert_eq!(summary.keys, 2);
+    assert_eq!(summary.values, 7);
+    assert_eq!(
+        registry.value("HKCU\\SOFTWARE\\Tencent\\WeChat", "Version"),
+        Some(RegistryValue::Dword(0x63090a1b))
+    );
+}
+
+#[test]
+fn test_backup_restores_multiline_string() {
+    let registry = sample();
+    let key = "HKCU\\SOFTWARE\\Tencent\\WeChat";
+    let notes = RegistryValue::String("第一行\r\n第二行\n\"引号\"".to_string());
+    registry.set_value(key, "Notes", &notes).unwrap();
+
+    let text = export_reg(&registry, &[key.to_string()]).unwrap();
+    assert!(text.contains("\"Notes\"=hex(1):"));
+    assert!(parse_reg(&text).is_ok());
+
+    let backup_dir = TempDir::new().unwrap();
+    let backup = RegBackup::new(backup_dir.path());
+    let outcome = delete_keys_with_backup(&registry, &backup, &[key.to_string()]);
+    assert!(!registry.key_exists(key));
+
+    backup
+        .restore(&registry, &outcome.backup_path.unwrap())
+        .unwrap();
+    assert_eq!(registry.value(key, "Notes"), Some(notes));
+}
+
+#[test]
+fn test_restore_only_accepts_unmodified_backups() {
+    let registry = sample();
+    let backup_dir = TempDir::new().unwrap();
+    let backup = RegBackup::new(backup_dir.path());
+
+    let outside = TempDir::new().unwrap();
+    let foreign = outside.path().join("registry-backup-foreign.reg");
+    fs::write(
+        &foreign,
+        "Windows Registry Editor Version 5.00\r\n\r\n[HKEY_CURRENT_USER\\SOFTWARE\\Evil]\r\n",
+    )
+    .unwrap();
+    assert!(backup.restore(&registry, &foreign).is_err());
+    assert!(!registry.key_exists("HKCU\\SOFTWARE\\Evil"));
+
+    let tampered = backup_dir.path().join("registry-backup-tampered.reg");
+    fs::write(
+        &tampered,
+        "Windows Registry Editor Version 5.00\r\n\r\n[-HKEY_CURRENT_USER\\SOFTWARE\\Google]\r\n",
+    )
+    .unwrap();
+    assert!(backup.restore(&registry, &tampered).is_err());
+    assert!(registry.key_exists("HKCU\\SOFTWARE\\Google"));
+}
+
+#[test]
+fn test_nothing_deleted_when_backup_fails() {
+    let registry = sample();
+    let dir = TempDir::new().unwrap();
+    let blocker = dir.path().join("file");
+    fs::write(&blocker, b"").unwrap();
+
+    let outcome = delete_keys_with_backup(
+        &registry,
+        &RegBackup::new(blocker.join("backups")),
+        &["HKCU\\SOFTWARE\\Tencent".to_string()],
+    );
+
+    assert!(outcome.backup_path.is_none());
+    assert!(outcome.deleted.is_empty());
+    assert_eq!(outcome.failed.len(), 1);
+    assert!(registry.key_exists("HKCU\\SOFTWARE\\Tencent"));
+}
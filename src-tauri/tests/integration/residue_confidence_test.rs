@@ -0,0 +1,150 @@
+use std::fs;
+use tempfile::TempDir;
+
+use disktidy_lib::modules::software_residue::confidence::{
+    name_similarity, ProcessRef, ResidueScorer, UninstalledProduct, HIGH_CONFIDENCE,
+};
+use disktidy_lib::modules::software_residue::ResidueEvidence;
+
+use crate::fixtures::{age, software, write};
+
+fn scorer() -> ResidueScorer {
+    ResidueScorer::new(
+        vec![software("Google Chrome", "Google LLC", None)],
+        vec![UninstalledProduct {
+            name: "FooEditor 2.1 (x64)".to_string(),
+            publisher: None,
+        }],
+    )
+}
+
+#[test]
+fn test_name_similarity() {
+    assert_eq!(name_similarity("7-Zip", "7-Zip 23.01 (x64)"), 1.0);
+    assert_eq!(name_similarity("Google", "Google LLC"), 1.0);
+    assert_eq!(name_similarity("Notepad++", "Notepad++ (x64)"), 1.0);
+    // 过短的名称不按包含关系匹配
+    assert!(name_similarity("360", "360 Total Security") < 0.8);
+    assert!(name_similarity("FooEdit", "FooEditor") >= 0.8);
+    assert!(name_similarity("WeChat", "Spotify") < 0.5);
+    assert_eq!(name_similarity("", "Anything"), 0.0);
+}
+
+#[test]
+fn test_matched_uninstalled_product_is_high_confidence() {
+    let dir = TempDir::new().unwrap();
+    let folder = dir.path().join("FooEditor");
+    write(&folder.join("settings.ini"), b"[ui]");
+    age(&folder, 400);
+
+    let assessment = scorer().assess_path(
+        &folder,
+        "FooEditor",
+        0.3,
+        vec![ResidueEvidence::NotInInventory],
+    );
+
+    assert!(assessment.is_high_confidence(), "{:?}", assessment);
+    assert!(assessment
+        .evidence
+        .contains(&ResidueEvidence::UninstalledProduct {
+            product: "FooEditor 2.1 (x64)".to_string(),
+            similarity: 1.0,
+        }));
+    assert!(assessment
+        .evidence
+        .iter()
+        .any(|e| matches!(e, ResidueEvidence::LastWrite { days } if *days >= 399)));
+    assert!(assessment.evidence.iter().all(|e| e.supports_removal()));
+}
+
+#[test]
+fn test_shared_vendor_folder_is_low_confidence() {
+    let dir = TempDir::new().unwrap();
+    let folder = dir.path().join("Google");
+    write(&folder.join("Update/log.txt"), b"");
+    age(&folder, 400);
+
+    let assessment = scorer().assess_path(&folder, "Google", 0.5, Vec::new());
+
+    assert!(assessment.confidence < 0.5);
+    assert!(assessment
+        .evidence
+        .contains(&ResidueEvidence::PublisherMatch {
+            publisher: "Google LLC".to_string(),
+        }));
+    assert!(assessment.evidence.iter().any(|e| !e.supports_removal()));
+}
+
+#[test]
+fn test_references_and_executables_lower_confidence() {
+    let dir = TempDir::new().unwrap();
+    let folder = dir.path().join("FooEditor");
+    write(&folder.join("bin/foo.exe"), b"MZ");
+    age(&folder, 400);
+
+    let assessment = scorer().assess_path(&folder, "FooEditor", 0.5, Vec::new());
+    assert!(assessment
+        .evidence
+        .contains(&ResidueEvidence::ContainsExecutables { count: 1 }));
+    assert!(assessment.confidence < HIGH_CONFIDENCE);
+
+    let installed = ResidueScorer::new(
+        vec![software("Bar Tool", "Bar", Some(&folder.join("bin")))],
+        Vec::new(),
+    );
+    let assessment = installed.assess_path(&folder, "FooEditor", 0.9, Vec::new());
+    assert!(assessment.confidence <= 0.1);
+    assert!(assessment
+        .evidence
+        .contains(&ResidueEvidence::ReferencedByInstalled {
+            product: "Bar Tool".to_string(),
+        }));
+
+    let running = scorer().with_processes(vec![ProcessRef {
+        pid: 4242,
+        name: "foo.exe".to_string(),
+        paths: vec![folder.join("bin/foo.exe")],
+    }]);
+    let assessment = running.assess_path(&folder, "FooEditor", 0.9, Vec::new());
+    assert!(assessment.confidence <= 0.05);
+    assert!(assessment
+        .evidence
+        .contains(&ResidueEvidence::ReferencedByProcess {
+            process: "foo.exe".to_string(),
+            pid: 4242,
+        }));
+}
+
+#[test]
+fn test_recent_and_empty_folders() {
+    let dir = TempDir::new().unwrap();
+    let recent = dir.path().join("RecentTool");
+    write(&recent.join("state.json"), b"{}");
+    let assessment = scorer().assess_path(&recent, "RecentTool", 0.5, Vec::new());
+    assert!(assessment.confidence < 0.5);
+    assert!(assessment
+        .evidence
+        .iter()
+        .any(|e| matches!(e, ResidueEvidence::LastWrite { days: 0 })));
+
+    let empty = dir.path().join("EmptyTool");
+    fs::create_dir_all(&empty).unwrap();
+    age(&empty, 400);
+    let assessment = scorer().assess_path(&empty, "EmptyTool", 0.5, Vec::new());
+    assert!(assessment.evidence.contains(&ResidueEvidence::Empty));
+    assert!(assessment.confidence > 0.5);
+}
+
+#[test]
+fn test_registry_assessment_uses_names_only() {
+    let assessment = scorer().assess_registry("Google", 0.5, Vec::new());
+    assert!(assessment.confidence < 0.5);
+
+    let assessment = scorer().assess_registry(
+        "FooEditor",
+        0.5,
+        vec![ResidueEvidence::Empty, ResidueEvidence::NotInInventory],
+    );
+    assert!(assessment.is_high_confidence());
+}
@@ -0,0 +1,220 @@
+use std::path::Path;
+use tempfile::TempDir;
+
+use disktidy_lib::models::{
+    CleanupCandidate, CleanupSource, GitPathStatus, RiskLevel, RiskSignal, RiskWeights,
+};
+use disktidy_lib::modules::cleaner::in_use::{OpenFileIndex, ProcessFiles};
+use disktidy_lib::modules::cleaner::SafetyChecker;
+use disktidy_lib::modules::risk_score::{
+    install_saved_config, LocationClass, RiskInput, RiskModel, RiskModelConfig,
+};
+
+use crate::fixtures::write;
+
+const DAY: i64 = 86400;
+const NOW: i64 = 1_700_000_000;
+
+fn signals(model: &RiskModel, input: &RiskInput) -> Vec<RiskSignal> {
+    model
+        .assess_at(input, NOW)
+        .factors
+        .iter()
+        .map(|f| f.signal)
+        .collect()
+}
+
+#[test]
+fn test_signals_explain_score() {
+    let model = RiskModel::new();
+
+    let mut cache = RiskInput::new("/srv/app/cache/blob.tmp");
+    cache.modified_time = NOW - 400 * DAY;
+    cache.accessed_time = NOW - 200 * DAY;
+    cache.regenerable = true;
+    let assessment = model.assess_at(&cache, NOW);
+    assert_eq!(assessment.risk_level, RiskLevel::Low);
+    assert_eq!(
+        model.classify_location(Path::new("/srv/app/cache/blob.tmp")),
+        LocationClass::Cache
+    );
+    assert!(assessment.factors.iter().all(|f| f.contribution < 0));
+    let used = signals(&model, &cache);
+    for signal in [
+        RiskSignal::Location,
+        RiskSignal::Age,
+        RiskSignal::LastAccess,
+        RiskSignal::FileType,
+        RiskSignal::Regenerable,
+    ] {
+        assert!(used.contains(&signal), "{:?}", signal);
+    }
+
+    let mut report = RiskInput::new("/srv/share/Documents/report.docx");
+    report.modified_time = NOW - 3600;
+    report.in_use = Some(true);
+    report.git_status = Some(GitPathStatus::Untracked);
+    let assessment = model.assess_at(&report, NOW);
+    assert_eq!(assessment.risk_level, RiskLevel::High);
+    assert_eq!(assessment.score, 100);
+    // 因素按影响大小排序
+    assert_eq!(assessment.factors[0].signal, RiskSignal::InUse);
+    assert!(assessment
+        .factors
+        .windows(2)
+        .all(|w| w[0].contribution.abs() >= w[1].contribution.abs()));
+    assert!(signals(&model, &report).contains(&RiskSignal::RecentModification));
+}
+
+#[test]
+fn test_weights_and_whitelist_are_configurable() {
+    let input = RiskInput::new("/srv/projects/keep-sibling/data.bin");
+    let plain = RiskModel::new().assess_at(&input, NOW);
+    assert!(plain.factors.is_empty());
+    assert_eq!(plain.score, 40);
+
+    let model = RiskModel::with_config(RiskModelConfig {
+        whitelist_paths: vec!["/srv/projects/keep".to_string()],
+        ..Default::default()
+    });
+    let near = model.assess_at(&input, NOW);
+    assert_eq!(near.factors[0].signal, RiskSignal::Whitelist);
+    assert_eq!(near.score, 60);
+    let inside = model.assess_at(&RiskInput::new("/srv/projects/keep/a.bin"), NOW);
+    assert!(inside.score > near.score);
+    let parent = model.assess_at(&RiskInput::new("/srv/projects"), NOW);
+    assert_eq!(parent.score, 80);
+    assert_eq!(parent.risk_level, RiskLevel::High);
+
+    let mut regenerable = RiskInput::new("/srv/build/out.o");
+    regenerable.regenerable = true;
+    let muted = RiskModel::with_config(RiskModelConfig {
+        weights: RiskWeights {
+            regenerable: 0,
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    assert!(muted.assess_at(&regenerable, NOW).factors.is_empty());
+    assert_eq!(
+        RiskModel::new().assess_at(&regenerable, NOW).risk_level,
+        RiskLevel::Low
+    );
+}
+
+#[test]
+fn test_category_level_is_a_floor() {
+    let model = RiskModel::new();
+    for level in [RiskLevel::Critical, RiskLevel::High, RiskLevel::Low] {
+        let mut candidate = CleanupCandidate {
+            path: "/var/tmp/old/build.log".to_string(),
+            size: 100,
+            source: CleanupSource::Junk,
+            risk_level: level.clone(),
+            regenerable: true,
+            modified_time: NOW - 400 * DAY,
+            reason: String::new(),
+            also_reported_by: Vec::new(),
+            risk_score: 0,
+            risk_factors: Vec::new(),
+        };
+        model.score_candidate(&mut candidate);
+
+        // 临时目录中可重新生成的旧日志分数很低，但不会低于分析器给出的等级
+        assert!(candidate.risk_score < 40);
+        assert_eq!(candidate.risk_level, level);
+    }
+}
+
+#[test]
+fn test_candidate_in_use_from_open_file_index() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write(&root.join("held.log"), b"x");
+    write(&root.join("free.log"), b"x");
+
+    let candidate = |path: &Path| CleanupCandidate {
+        path: path.to_string_lossy().to_string(),
+        size: 1,
+        source: CleanupSource::Junk,
+        risk_level: RiskLevel::Low,
+        regenerable: false,
+        modified_time: 0,
+        reason: String::new(),
+        also_reported_by: Vec::new(),
+        risk_score: 0,
+        risk_factors: Vec::new(),
+    };
+
+    let mut model = RiskModel::new();
+    let mut held = candidate(&root.join("held.log"));
+    model.score_candidate(&mut held);
+    assert!(!held.risk_factors.iter().any(|f| f.signal == RiskSignal::InUse));
+
+    model.set_open_files(OpenFileIndex::from_processes(vec![ProcessFiles {
+        pid: 42,
+        name: "viewer".to_string(),
+        open_files: vec![root.join("held.log")],
+        mapped_files: Vec::new(),
+    }]));
+    let mut free = candidate(&root.join("free.log"));
+    model.score_candidate(&mut held);
+    model.score_candidate(&mut free);
+    assert!(held.risk_factors.iter().any(|f| f.signal == RiskSignal::InUse));
+    assert!(!free.risk_factors.iter().any(|f| f.signal == RiskSignal::InUse));
+    assert!(held.risk_score > free.risk_score);
+}
+
+#[test]
+fn test_safety_checker_attaches_factors() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    let file = root.join("work/old.tmp");
+    write(&file, b"x");
+
+    let checker = SafetyChecker::new();
+    let result = checker.check(&file);
+    assert!(result.safe_to_delete);
+    assert!(!result.risk_factors.is_empty());
+    assert!(result
+        .risk_factors
+        .iter()
+        .any(|f| f.signal == RiskSignal::FileType));
+
+    let blocked = checker.check(&root.join("work/secret.txt"));
+    assert!(!blocked.safe_to_delete);
+    assert_eq!(blocked.risk_factors.len(), 1);
+    assert_eq!(blocked.risk_factors[0].signal, RiskSignal::Protection);
+    assert_eq!(blocked.reason.as_deref(), Some("敏感文件"));
+}
+
+#[test]
+fn test_checkers_use_saved_whitelist() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    let file = root.join("projects/keep/report.tmp");
+    write(&file, b"x");
+
+    let has_whitelist = |checker: &SafetyChecker| {
+        checker
+            .check(&file)
+            .risk_factors
+            .iter()
+            .any(|f| f.signal == RiskSignal::Whitelist)
+    };
+    let config = RiskModelConfig {
+        whitelist_paths: vec![root.join("projects/keep").to_string_lossy().to_string()],
+        ..Default::default()
+    };
+
+    let mut checker = SafetyChecker::new();
+    assert!(!has_whitelist(&checker));
+    checker.set_risk_model(RiskModel::with_config(config.clone()));
+    assert!(has_whitelist(&checker));
+
+    // 白名单只包含这个临时目录中的路径，不影响其他测试
+    install_saved_config(config);
+    assert!(has_whitelist(&SafetyChecker::new()));
+    let planned = RiskModelConfig::default().with_saved_whitelist();
+    assert_eq!(planned.whitelist_paths.len(), 1);
+}
@@ -0,0 +1,191 @@
+#![cfg(unix)]
+
+use std::fs;
+use std::os::unix::fs::{symlink, PermissionsExt};
+use tempfile::TempDir;
+
+use disktidy_lib::models::{CleanOptions, DiskTidyError};
+use disktidy_lib::modules::cleaner::safe_remove::{
+    is_symlink_error, remove_file_if, remove_nofollow, resolve_delete_target,
+};
+use disktidy_lib::modules::cleaner::{CleanerExecutor, SafetyChecker};
+
+use crate::fixtures::write;
+
+fn permanent_executor() -> CleanerExecutor {
+    CleanerExecutor::with_options(CleanOptions {
+        move_to_recycle_bin: false,
+        secure_delete: false,
+        secure_pass_count: 1,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_recursive_delete_does_not_follow_symlinks() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write(&root.join("outside/important.txt"), b"keep");
+    write(&root.join("work/a.tmp"), b"x");
+    write(&root.join("work/sub/b.tmp"), b"x");
+    symlink(root.join("outside"), root.join("work/sub/dir-link")).unwrap();
+    symlink(
+        root.join("outside/important.txt"),
+        root.join("work/file-link"),
+    )
+    .unwrap();
+
+    let target = resolve_delete_target(&root.join("work")).unwrap();
+    remove_nofollow(&target).unwrap();
+
+    assert!(!root.join("work").exists());
+    assert_eq!(
+        fs::read(root.join("outside/important.txt")).unwrap(),
+        b"keep"
+    );
+}
+
+#[test]
+fn test_ancestor_swapped_for_symlink_after_check_is_refused() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write(&root.join("data/cache/app.tmp"), b"cache");
+    write(&root.join("victim/cache/app.tmp"), b"precious");
+
+    // 扫描与检查阶段看到的是真实目录
+    let target = resolve_delete_target(&root.join("data/cache/app.tmp")).unwrap();
+
+    // 删除之前目录被替换为指向其他位置的符号链接
+    fs::rename(root.join("data"), root.join("data-old")).unwrap();
+    symlink(root.join("victim"), root.join("data")).unwrap();
+
+    let error = remove_nofollow(&target).unwrap_err();
+    assert!(is_symlink_error(&error));
+    assert_eq!(
+        fs::read(root.join("victim/cache/app.tmp")).unwrap(),
+        b"precious"
+    );
+    assert!(root.join("data-old/cache/app.tmp").exists());
+}
+
+#[test]
+fn test_remove_file_if_checks_without_opening_contents() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+
+    // 没有任何权限的文件也能检查并删除
+    let locked = root.join("locked.bin");
+    write(&locked, b"data");
+    fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+    assert!(remove_file_if(&locked, |m| m.len() == 4).unwrap());
+    assert!(!locked.exists());
+
+    let kept = root.join("kept.bin");
+    write(&kept, b"data");
+    assert!(!remove_file_if(&kept, |m| m.len() == 0).unwrap());
+    assert!(kept.exists());
+
+    let link = root.join("link.bin");
+    symlink(&kept, &link).unwrap();
+    assert!(remove_file_if(&link, |_| true).is_err());
+    assert!(fs::symlink_metadata(&link).is_ok());
+    assert!(kept.exists());
+}
+
+#[test]
+fn test_resolved_paths_checked_against_protected_roots() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write(&root.join("protected/system.db"), b"x");
+    write(&root.join("work/cache.tmp"), b"x");
+    symlink(root.join("protected"), root.join("work/link")).unwrap();
+
+    let mut checker = SafetyChecker::new();
+    checker.add_protected_path(root.join("protected"));
+
+    assert!(checker.check(&root.join("work/cache.tmp")).safe_to_delete);
+    assert!(
+        !checker
+            .check(&root.join("work/../protected/system.db"))
+            .safe_to_delete
+    );
+    assert!(
+        !checker
+            .check(&root.join("work/link/system.db"))
+            .safe_to_delete
+    );
+    // 删除链接本身不会影响受保护目录
+    assert!(checker.check(&root.join("work/link")).safe_to_delete);
+}
+
+#[tokio::test]
+async fn test_executor_removes_symlink_not_target() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write(&root.join("outside/important.txt"), b"keep");
+    write(&root.join("work/build/out.o"), b"x");
+    symlink(root.join("outside"), root.join("work/link")).unwrap();
+    symlink(root.join("outside"), root.join("work/build/nested-link")).unwrap();
+    symlink(root.join("missing"), root.join("work/dangling")).unwrap();
+
+    let executor = permanent_executor();
+    let result = executor
+        .clean(vec![
+            root.join("work/link"),
+            root.join("work/build"),
+            root.join("work/dangling"),
+        ])
+        .await
+        .unwrap();
+
+    assert_eq!(result.cleaned_files, 3, "{:?}", result.errors);
+    assert!(fs::symlink_metadata(root.join("work/link")).is_err());
+    assert!(fs::symlink_metadata(root.join("work/dangling")).is_err());
+    assert!(!root.join("work/build").exists());
+    assert_eq!(
+        fs::read(root.join("outside/important.txt")).unwrap(),
+        b"keep"
+    );
+
+    let secure = CleanerExecutor::with_options(CleanOptions {
+        move_to_recycle_bin: false,
+        secure_delete: true,
+        secure_pass_count: 1,
+        ..Default::default()
+    });
+    symlink(
+        root.join("outside/important.txt"),
+        root.join("work/file-link"),
+    )
+    .unwrap();
+    secure
+        .clean_single(&root.join("work/file-link"))
+        .await
+        .unwrap();
+    assert_eq!(
+        fs::read(root.join("outside/important.txt")).unwrap(),
+        b"keep"
+    );
+}
+
+#[tokio::test]
+async fn test_foreign_symlink_in_ancestors_is_refused() {
+    use std::os::unix::fs::{lchown, MetadataExt};
+
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write(&root.join("real/file.tmp"), b"x");
+    // 只有 root 才能把链接的属主改成其他用户
+    if fs::metadata(root.join("real/file.tmp")).unwrap().uid() != 0 {
+        return;
+    }
+    symlink(root.join("real"), root.join("shared")).unwrap();
+    lchown(root.join("shared"), Some(65534), Some(65534)).unwrap();
+
+    let error = permanent_executor()
+        .permanent_delete(&root.join("shared/file.tmp"))
+        .await
+        .unwrap_err();
+    assert!(matches!(error, DiskTidyError::SymlinkRefused { .. }));
+    assert!(root.join("real/file.tmp").exists());
+}
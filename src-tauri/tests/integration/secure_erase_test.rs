@@ -0,0 +1,165 @@
+#![cfg(unix)]
+
+use std::fs;
+use std::os::unix::fs::symlink;
+use tempfile::TempDir;
+
+use disktidy_lib::models::{
+    CleanOptions, OverwritePattern, SecureDeleteOptions, SecureDeleteScheme,
+};
+use disktidy_lib::modules::cleaner::secure_erase::{
+    overwrite_passes, shrink_file, storage_warnings, SecureEraser, DOD_5220_22_M,
+};
+use disktidy_lib::modules::cleaner::CleanerExecutor;
+
+use crate::fixtures::write;
+
+fn options(scheme: SecureDeleteScheme) -> SecureDeleteOptions {
+    SecureDeleteOptions {
+        scheme,
+        verify: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_schemes_expand_to_passes() {
+    assert_eq!(
+        overwrite_passes(&options(SecureDeleteScheme::Random), 7),
+        vec![OverwritePattern::Random]
+    );
+    assert_eq!(
+        overwrite_passes(&options(SecureDeleteScheme::Dod5220), 1),
+        DOD_5220_22_M.to_vec()
+    );
+    // 未指定数据的自定义方案保持原来的循环方式
+    assert_eq!(
+        overwrite_passes(&options(SecureDeleteScheme::Custom), 4),
+        vec![
+            OverwritePattern::Byte(0x00),
+            OverwritePattern::Byte(0xFF),
+            OverwritePattern::Byte(0xAA),
+            OverwritePattern::Byte(0x00),
+        ]
+    );
+    let custom = SecureDeleteOptions {
+        patterns: vec![OverwritePattern::Byte(0x55), OverwritePattern::Random],
+        ..Default::default()
+    };
+    assert_eq!(overwrite_passes(&custom, 9), custom.patterns);
+}
+
+#[test]
+fn test_erase_overwrites_truncates_and_scrubs_name() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    let file = root.join("secret-report.pdf");
+    write(&file, &[7u8; 200_000]);
+
+    let eraser = SecureEraser::new(&options(SecureDeleteScheme::Dod5220), 1);
+    eraser.overwrite(&file, 200_000).unwrap();
+    assert_eq!(fs::metadata(&file).unwrap().len(), 0);
+
+    let renamed = eraser.scrub_name(&file).unwrap();
+    assert!(!file.exists());
+    assert_eq!(
+        renamed.file_name().unwrap().len(),
+        "secret-report.pdf".len()
+    );
+    assert_eq!(renamed.parent(), Some(root.as_path()));
+
+    write(&file, b"again");
+    eraser.erase(&file).unwrap();
+    assert_eq!(fs::read_dir(&root).unwrap().count(), 1);
+}
+
+#[test]
+fn test_erase_directory_recursively_without_following_links() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write(&root.join("outside/keep.txt"), b"keep");
+    write(&root.join("outside/linked.txt"), b"linked");
+    write(&root.join("target/a/b/data.bin"), &[1u8; 4096]);
+    write(&root.join("target/top.txt"), b"top");
+    symlink(root.join("outside"), root.join("target/a/dir-link")).unwrap();
+    symlink(root.join("outside/keep.txt"), root.join("target/file-link")).unwrap();
+    fs::hard_link(
+        root.join("outside/linked.txt"),
+        root.join("target/hard.txt"),
+    )
+    .unwrap();
+
+    let eraser = SecureEraser::new(&options(SecureDeleteScheme::Random), 1);
+    eraser.erase(&root.join("target")).unwrap();
+
+    assert!(!root.join("target").exists());
+    assert_eq!(fs::read(root.join("outside/keep.txt")).unwrap(), b"keep");
+    // 有其他硬链接的文件不覆写
+    assert_eq!(
+        fs::read(root.join("outside/linked.txt")).unwrap(),
+        b"linked"
+    );
+    assert_eq!(fs::read_dir(&root).unwrap().count(), 1);
+}
+
+#[tokio::test]
+async fn test_executor_secure_deletes_directories() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write(&root.join("cache/x.tmp"), &[3u8; 1000]);
+    write(&root.join("cache/y/z.tmp"), &[4u8; 10]);
+
+    let executor = CleanerExecutor::with_options(CleanOptions {
+        move_to_recycle_bin: false,
+        secure_delete: true,
+        secure_pass_count: 1,
+        secure: options(SecureDeleteScheme::Dod5220),
+        ..Default::default()
+    });
+    let result = executor.clean(vec![root.join("cache")]).await.unwrap();
+
+    assert_eq!(result.cleaned_files, 1, "{:?}", result.errors);
+    assert_eq!(result.cleaned_size, 1010);
+    assert!(!root.join("cache").exists());
+
+    // 同一设备上的多个目标只提示一次
+    let single = storage_warnings(std::slice::from_ref(&root));
+    let repeated = storage_warnings(&[root.clone(), root.join("other")]);
+    assert_eq!(single.len(), repeated.len());
+    assert_eq!(result.secure_delete_warnings.len(), single.len());
+}
+
+#[test]
+fn test_shrink_file_wipes_truncated_tail() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("log.txt");
+    let mut content = b"keep".to_vec();
+    content.extend_from_slice(&[9u8; 10_000]);
+    write(&file, &content);
+
+    shrink_file(&file, 4, true).unwrap();
+    assert_eq!(fs::read(&file).unwrap(), b"keep");
+
+    shrink_file(&file, 2, false).unwrap();
+    assert_eq!(fs::read(&file).unwrap(), b"ke");
+    // 不需要缩短时保持原样
+    shrink_file(&file, 10, true).unwrap();
+    assert_eq!(fs::read(&file).unwrap(), b"ke");
+}
+
+#[test]
+fn test_erase_with_slack_removes_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("tail.bin");
+    write(&file, &[5u8; 5000]);
+
+    let slack = SecureDeleteOptions {
+        wipe_slack: true,
+        ..options(SecureDeleteScheme::Random)
+    };
+    let eraser = SecureEraser::new(&slack, 1);
+    eraser.overwrite(&file, 5000).unwrap();
+    assert_eq!(fs::metadata(&file).unwrap().len(), 0);
+    eraser.erase(&file).unwrap();
+    assert!(!file.exists());
+}
@@ -1,6 +1,6 @@
 use tempfile::TempDir;
 use disktidy_lib::modules::settings::{SettingsManager, RuleEngine};
-use disktidy_lib::models::settings::CleanRule;
+use disktidy_lib::models::settings::{CleanRule, WhitelistSettings};
 use disktidy_lib::models::file_analyzer::JunkCategory;
 
 #[test]
@@ -145,3 +145,31 @@ fn test_settings_export_import() {
     let imported = manager2.get_settings();
     assert!(imported.auto_scan);
 }
+
+#[test]
+fn test_whitelist_is_saved_and_feeds_risk_model() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("settings.json");
+    let manager = SettingsManager::with_path(config_path.clone());
+
+    let mut whitelist = WhitelistSettings::new();
+    whitelist.add_path("/srv/keep".to_string(), String::new());
+    whitelist.add_path("/srv/old".to_string(), String::new());
+    whitelist.toggle_path("/srv/old");
+    manager.update_whitelist(whitelist).unwrap();
+
+    let reloaded = SettingsManager::with_path(config_path);
+    assert_eq!(reloaded.get_whitelist().paths.len(), 2);
+    assert_eq!(reloaded.risk_model_config().whitelist_paths, vec!["/srv/keep"]);
+
+    let mut disabled = reloaded.get_whitelist();
+    disabled.enabled = false;
+    reloaded.update_whitelist(disabled).unwrap();
+    assert!(reloaded.risk_model_config().whitelist_paths.is_empty());
+
+    // 旧版本的配置文件没有白名单字段
+    let legacy = temp_dir.path().join("legacy.json");
+    let exported = manager.export_settings().unwrap();
+    std::fs::write(&legacy, exported.replace("\"whitelist\"", "\"unused\"")).unwrap();
+    assert!(SettingsManager::with_path(legacy).get_whitelist().paths.is_empty());
+}
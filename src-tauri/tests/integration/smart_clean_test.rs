@@ -0,0 +1,184 @@
+use std::path::PathBuf;
+
+use disktidy_lib::models::{CleanupCandidate, CleanupSource, RiskLevel};
+use disktidy_lib::modules::cleanup_planner::{merge_candidates, CleanupPlanInput};
+use disktidy_lib::modules::file_analyzer::{JunkFile, JunkFileType, JunkScanResult};
+use disktidy_lib::modules::smart_clean::build_smart_clean_plan;
+
+fn candidate(path: &str, size: u64, source: CleanupSource, risk_level: RiskLevel) -> CleanupCandidate {
+    CleanupCandidate {
+        path: path.to_string(),
+        size,
+        source,
+        risk_level,
+        regenerable: false,
+        modified_time: 0,
+        reason: String::new(),
+        also_reported_by: Vec::new(),
+        risk_score: 0,
+        risk_factors: Vec::new(),
+    }
+}
+
+fn junk_file(path: &str, size: u64, risk_level: &str) -> JunkFile {
+    JunkFile {
+        id: path.to_string(),
+        path: path.to_string(),
+        size,
+        file_type: JunkFileType::OldLogs,
+        description: "旧日志".to_string(),
+        modified_time: 0,
+        created_time: 0,
+        safe_to_delete: true,
+        risk_level: risk_level.to_string(),
+    }
+}
+
+#[test]
+fn test_merge_same_path_keeps_highest_risk() {
+    let result = merge_candidates(vec![
+        candidate("/home/u/a.log", 10, CleanupSource::LargeFile, RiskLevel::High),
+        candidate("/home/u/a.log/", 10, CleanupSource::Junk, RiskLevel::Low),
+    ]);
+
+    assert_eq!(result.candidates.len(), 1);
+    assert_eq!(result.candidates[0].source, CleanupSource::LargeFile);
+    assert_eq!(result.candidates[0].risk_level, RiskLevel::High);
+    assert_eq!(result.candidates[0].also_reported_by, vec![CleanupSource::Junk]);
+    assert!(result.merged.is_empty());
+}
+
+#[test]
+fn test_merge_files_inside_selected_directory() {
+    let result = merge_candidates(vec![
+        candidate("/home/u/.cache/app/a.bin", 5, CleanupSource::Garbage, RiskLevel::Low),
+        candidate("/home/u/.cache/app", 100, CleanupSource::Residue, RiskLevel::Medium),
+        candidate("/home/u/.cache/app/sub/b.bin", 7, CleanupSource::Duplicate, RiskLevel::Low),
+        candidate("/home/u/.cache/application.log", 3, CleanupSource::Junk, RiskLevel::Low),
+    ]);
+
+    assert_eq!(result.candidates.len(), 2);
+    assert_eq!(result.merged.len(), 2);
+    assert!(result
+        .merged
+        .iter()
+        .all(|m| m.merged_into == "/home/u/.cache/app"));
+}
+
+#[test]
+fn test_build_plan_holds_back_risky_items() {
+    let input = CleanupPlanInput {
+        junk: vec![JunkScanResult {
+            file_type: JunkFileType::OldLogs,
+            items: vec![
+                junk_file("/var/tmp/a.log", 100, "Low"),
+                junk_file("/var/tmp/b.log", 300, "High"),
+                junk_file("/var/tmp/c.log", 200, "Medium"),
+                junk_file("/var/tmp/empty.log", 0, "Low"),
+            ],
+            total_size: 600,
+            count: 4,
+            ignored_paths: vec![],
+        }],
+        ..Default::default()
+    };
+
+    let plan = build_smart_clean_plan("scan", &input, RiskLevel::Medium);
+
+    assert_eq!(plan.total_count, 2);
+    assert_eq!(plan.total_size, 300);
+    assert_eq!(plan.candidates[0].path, "/var/tmp/a.log");
+    assert_eq!(plan.held_back.len(), 1);
+    assert_eq!(plan.paths(&["/var/tmp/a.log".to_string()]).len(), 1);
+}
+
+#[test]
+fn test_build_plan_partitions_before_merging() {
+    let input = CleanupPlanInput {
+        junk: vec![JunkScanResult {
+            file_type: JunkFileType::OldLogs,
+            items: vec![
+                // 保留的目录不吸收其中低风险的文件
+                junk_file("/var/tmp/held", 1000, "High"),
+                junk_file("/var/tmp/held/a.log", 100, "Low"),
+                // 待清理目录中有保留项时，目录本身也保留
+                junk_file("/var/tmp/outer", 500, "Low"),
+                junk_file("/var/tmp/outer/b.log", 50, "High"),
+                junk_file("/var/tmp/cache", 400, "Low"),
+                junk_file("/var/tmp/cache/c.log", 40, "Low"),
+            ],
+            total_size: 2090,
+            count: 6,
+            ignored_paths: vec![],
+        }],
+        ..Default::default()
+    };
+
+    let plan = build_smart_clean_plan("scan", &input, RiskLevel::Medium);
+
+    let mut paths: Vec<&str> = plan.candidates.iter().map(|c| c.path.as_str()).collect();
+    paths.sort();
+    assert_eq!(paths, vec!["/var/tmp/cache", "/var/tmp/held/a.log"]);
+    assert_eq!(plan.merged.len(), 1);
+    assert_eq!(plan.merged[0].merged_into, "/var/tmp/cache");
+
+    let mut held: Vec<&str> = plan.held_back.iter().map(|c| c.path.as_str()).collect();
+    held.sort();
+    assert_eq!(
+        held,
+        vec!["/var/tmp/held", "/var/tmp/outer", "/var/tmp/outer/b.log"]
+    );
+}
+
+#[test]
+fn test_excluding_merged_child_splits_parent() {
+    let input = CleanupPlanInput {
+        junk: vec![JunkScanResult {
+            file_type: JunkFileType::OldLogs,
+            items: vec![
+                junk_file("/var/tmp/cache", 400, "Low"),
+                junk_file("/var/tmp/cache/a.log", 40, "Low"),
+                junk_file("/var/tmp/cache/sub", 100, "Low"),
+                junk_file("/var/tmp/cache/sub/b.log", 60, "Low"),
+                junk_file("/var/tmp/other.log", 10, "Low"),
+            ],
+            total_size: 610,
+            count: 5,
+            ignored_paths: vec![],
+        }],
+        ..Default::default()
+    };
+    let plan = build_smart_clean_plan("scan", &input, RiskLevel::Medium);
+    assert_eq!(plan.merged.len(), 3);
+
+    let mut paths = plan.paths(&[]);
+    paths.sort();
+    assert_eq!(
+        paths,
+        vec![
+            PathBuf::from("/var/tmp/cache"),
+            PathBuf::from("/var/tmp/other.log")
+        ]
+    );
+
+    // 取消勾选目录中的文件时，只删除目录中其余的路径
+    let mut paths = plan.paths(&["/var/tmp/cache/./sub//b.log".to_string()]);
+    paths.sort();
+    assert_eq!(
+        paths,
+        vec![
+            PathBuf::from("/var/tmp/cache/a.log"),
+            PathBuf::from("/var/tmp/other.log")
+        ]
+    );
+
+    let mut paths = plan.paths(&["/var/tmp/cache/sub/".to_string()]);
+    paths.sort();
+    assert_eq!(
+        paths,
+        vec![
+            PathBuf::from("/var/tmp/cache/a.log"),
+            PathBuf::from("/var/tmp/other.log")
+        ]
+    );
+}
@@ -0,0 +1,132 @@
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+use tempfile::TempDir;
+
+use disktidy_lib::modules::software_residue::inventory::{
+    build_inventory, FootprintKind, FootprintLocation, LastUsedSource,
+};
+use disktidy_lib::modules::software_residue::InstalledSoftware;
+
+use crate::fixtures::{days_ago, set_times, software, write};
+
+#[test]
+fn test_build_inventory() {
+    let dir = TempDir::new().unwrap();
+    let d = dir.path();
+    let programs = d.join("programs");
+    let config = d.join("config");
+    let data = d.join("data");
+
+    write(&programs.join("FooEditor/foo.exe"), &[0; 1000]);
+    write(&programs.join("FooEditor/resources.pak"), &[0; 3000]);
+    write(&config.join("FooEditor/settings.ini"), &[0; 100]);
+    set_times(
+        &programs.join("FooEditor/foo.exe"),
+        days_ago(10),
+        days_ago(200),
+    );
+    set_times(
+        &config.join("FooEditor/settings.ini"),
+        days_ago(400),
+        days_ago(400),
+    );
+
+    write(&programs.join("Chrome/chrome.exe"), &[0; 600]);
+    write(&config.join("Google/Chrome/Preferences"), &[0; 200]);
+    write(&config.join("Google/Update/log"), &[0; 50]);
+
+    write(&data.join("Acme/state.json"), &[0; 10]);
+
+    // 包管理器安装的软件没有安装目录，使用登记的安装大小
+    write(&config.join("pkgtool/pkgtool.conf"), &[0; 30]);
+
+    // 安装在数据目录之下的软件，数据目录只统计安装目录以外的部分
+    write(&data.join("Bar/app-1.0/bar.exe"), &[0; 2000]);
+    write(&data.join("Bar/settings.json"), &[0; 20]);
+
+    // 包含其他软件安装目录的上级目录不计入
+    write(&data.join("Suite/Baz/baz.exe"), &[0; 700]);
+
+    let installed = vec![
+        software(
+            "FooEditor 2.1 (x64)",
+            "Foo Ltd",
+            Some(&programs.join("FooEditor")),
+        ),
+        software("FooEditor 2.1 (x64)", "Foo Ltd", None),
+        software(
+            "Google Chrome",
+            "Google LLC",
+            Some(&programs.join("Chrome")),
+        ),
+        software("Acme Widget", "Acme Inc", None),
+        software("Bar", "Bar", Some(&data.join("Bar/app-1.0"))),
+        software("Suite", "Suite", None),
+        software("Baz", "Baz", Some(&data.join("Suite/Baz"))),
+        software("Unused Tool", "Nobody", Some(&d.join("missing"))),
+        InstalledSoftware {
+            installed_size: Some(1500),
+            ..software("pkgtool", "", None)
+        },
+    ];
+    let locations = vec![
+        FootprintLocation::new(&config, FootprintKind::Config),
+        FootprintLocation::new(&data, FootprintKind::Data),
+    ];
+
+    let inventory = build_inventory(&installed, &locations);
+    let names: Vec<&str> = inventory.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "FooEditor 2.1 (x64)",
+            "Bar",
+            "pkgtool",
+            "Google Chrome",
+            "Baz",
+            "Acme Widget",
+            "Suite",
+            "Unused Tool",
+        ]
+    );
+
+    let foo = &inventory[0];
+    assert_eq!(foo.install_size, 4000);
+    assert_eq!(foo.data_size, 100);
+    assert_eq!(foo.total_size, 4100);
+    assert_eq!(foo.directories[1].kind, FootprintKind::Config);
+    assert_eq!(
+        foo.last_used_source,
+        Some(LastUsedSource::ExecutableAccessed)
+    );
+    let accessed = days_ago(10).duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+    assert!((foo.last_used.unwrap() - accessed).abs() <= 5);
+
+    let bar = &inventory[1];
+    assert_eq!(bar.install_size, 2000);
+    assert_eq!(bar.data_size, 20);
+
+    let pkgtool = &inventory[2];
+    assert_eq!(pkgtool.install_size, 1500);
+    assert_eq!(pkgtool.data_size, 30);
+    assert_eq!(pkgtool.total_size, 1530);
+
+    let chrome = &inventory[3];
+    assert_eq!(chrome.total_size, 800);
+    assert!(chrome
+        .directories
+        .iter()
+        .any(|d| Path::new(&d.path) == config.join("Google/Chrome")));
+
+    let acme = &inventory[5];
+    assert_eq!(acme.total_size, 10);
+    assert_eq!(
+        acme.last_used_source,
+        Some(LastUsedSource::StateFileModified)
+    );
+
+    assert_eq!(inventory[6].total_size, 0);
+    let unused = &inventory[7];
+    assert!(unused.directories.is_empty());
+    assert_eq!(unused.last_used, None);
+}
@@ -0,0 +1,152 @@
+use std::fs;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+use disktidy_lib::utils::{DatContainer, DatDecodeError, DatKeys, ImageFormat, WeChatDatDecoder};
+
+const WECHAT_V1: &[u8] = include_bytes!("../fixtures/wechat_v1.dat");
+const WECHAT_V2: &[u8] = include_bytes!("../fixtures/wechat_v2.dat");
+const WECHAT_XOR_JPEG: &[u8] = include_bytes!("../fixtures/wechat_xor_jpeg.dat");
+const WECHAT_XOR_HEIC: &[u8] = include_bytes!("../fixtures/wechat_xor_heic.dat");
+const WECHAT_XOR_TIFF: &[u8] = include_bytes!("../fixtures/wechat_xor_tiff.dat");
+const WECHAT_XOR_MP4: &[u8] = include_bytes!("../fixtures/wechat_xor_mp4.dat");
+const WECHAT_WXGF: &[u8] = include_bytes!("../fixtures/wechat_wxgf.dat");
+/// V2 容器样本使用的账号密钥
+const V2_AES_KEY: [u8; 16] = *b"0123456789abcdef";
+
+fn fixture(dir: &TempDir, content: &[u8]) -> PathBuf {
+    let path = dir.path().join("image.dat");
+    fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn test_inspect_and_decode_legacy_xor_fixtures() {
+    let cases: [(&[u8], u8, ImageFormat, &[u8]); 4] = [
+        (
+            WECHAT_XOR_JPEG,
+            0x6B,
+            ImageFormat::Jpeg,
+            &[0xFF, 0xD8, 0xFF, 0xE0],
+        ),
+        (
+            WECHAT_XOR_HEIC,
+            0x3C,
+            ImageFormat::Heic,
+            b"\0\0\0\x18ftypheic",
+        ),
+        (WECHAT_XOR_TIFF, 0xA5, ImageFormat::Tiff, b"II*\0"),
+        (
+            WECHAT_XOR_MP4,
+            0x91,
+            ImageFormat::Mp4,
+            b"\0\0\0\x1cftypisom",
+        ),
+    ];
+
+    for (content, key, format, header) in cases {
+        let dir = TempDir::new().unwrap();
+        let path = fixture(&dir, content);
+
+        let info = WeChatDatDecoder::inspect_dat_file(&path).unwrap();
+        assert_eq!(info.container, DatContainer::Xor);
+        assert_eq!(info.xor_key, Some(key), "{:?}", format);
+        assert_eq!(info.original_format, format);
+        assert!(!info.partial);
+
+        let decoded = WeChatDatDecoder::decode_file(&path, &DatKeys::default()).unwrap();
+        assert_eq!(decoded.format, format);
+        assert_eq!(decoded.data.len(), content.len());
+        assert!(decoded.data.starts_with(header), "{:?}", format);
+    }
+}
+
+#[test]
+fn test_wxgf_fixture_is_reported() {
+    let dir = TempDir::new().unwrap();
+    let path = fixture(&dir, WECHAT_WXGF);
+
+    assert_eq!(
+        WeChatDatDecoder::inspect_dat_file(&path).unwrap_err(),
+        DatDecodeError::Wxgf
+    );
+    assert_eq!(
+        WeChatDatDecoder::decode_file(&path, &DatKeys::default()).unwrap_err(),
+        DatDecodeError::Wxgf
+    );
+}
+
+#[test]
+fn test_inspect_and_decode_v1_fixture() {
+    let dir = TempDir::new().unwrap();
+    let path = fixture(&dir, WECHAT_V1);
+
+    let info = WeChatDatDecoder::inspect_dat_file(&path).unwrap();
+    assert_eq!(info.container, DatContainer::V1);
+    assert_eq!(info.original_format, ImageFormat::Jpeg);
+    assert!(!info.partial);
+
+    let decoded = WeChatDatDecoder::decode_file(&path, &DatKeys::default()).unwrap();
+    assert!(!decoded.partial);
+    assert_eq!(decoded.data.len(), 3000);
+    assert!(decoded.data.starts_with(&[0xFF, 0xD8, 0xFF]));
+    assert!(decoded.data.ends_with(&[0xFF, 0xD9]));
+}
+
+#[test]
+fn test_inspect_v2_fixture_with_and_without_key() {
+    let dir = TempDir::new().unwrap();
+    let path = fixture(&dir, WECHAT_V2);
+
+    // 没有密钥时从未加密部分判断格式
+    let info = WeChatDatDecoder::inspect_dat_file(&path).unwrap();
+    assert_eq!(info.container, DatContainer::V2);
+    assert_eq!(info.original_format, ImageFormat::Jpeg);
+    assert!(info.partial);
+
+    let keys = DatKeys {
+        aes_key: Some(V2_AES_KEY),
+        xor_key: None,
+    };
+    let info = WeChatDatDecoder::inspect_dat_file_with(&path, &keys).unwrap();
+    assert!(!info.partial);
+    let decoded = WeChatDatDecoder::decode_file(&path, &keys).unwrap();
+    assert!(!decoded.partial);
+    assert!(decoded.data.ends_with(&[0xFF, 0xD9]));
+
+    let wrong = DatKeys {
+        aes_key: Some(*b"cfcd208495d565ef"),
+        xor_key: None,
+    };
+    assert_eq!(
+        WeChatDatDecoder::inspect_dat_file_with(&path, &wrong).unwrap_err(),
+        DatDecodeError::WrongKey
+    );
+}
+
+#[test]
+fn test_inspect_xor_with_wrong_key() {
+    let dir = TempDir::new().unwrap();
+    let image = [
+        0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, 0x4A, 0x46, 0x49, 0x46, 0x00, 0x01, 0xFF, 0xD9,
+    ];
+    let xored: Vec<u8> = image.iter().map(|&b| b ^ 0x6B).collect();
+    let path = fixture(&dir, &xored);
+
+    let info = WeChatDatDecoder::inspect_dat_file(&path).unwrap();
+    assert_eq!(info.xor_key, Some(0x6B));
+    assert_eq!(info.original_format, ImageFormat::Jpeg);
+
+    let wrong = DatKeys {
+        aes_key: None,
+        xor_key: Some(0x12),
+    };
+    assert_eq!(
+        WeChatDatDecoder::inspect_dat_file_with(&path, &wrong).unwrap_err(),
+        DatDecodeError::WrongKey
+    );
+    assert_eq!(
+        WeChatDatDecoder::decode_file(&path, &wrong).unwrap_err(),
+        DatDecodeError::WrongKey
+    );
+}
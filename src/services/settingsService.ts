@@ -4,6 +4,7 @@ import type {
   CleanRule,
   SettingsUpdate,
   CleanRuleInput,
+  WhitelistSettings,
 } from '../types';
 
 export const settingsService = {
@@ -25,6 +26,12 @@ export const settingsService = {
   import: (json: string): Promise<void> =>
     invoke<void>('settings_import', { json }),
 
+  getWhitelist: (): Promise<WhitelistSettings> =>
+    invoke<WhitelistSettings>('whitelist_get'),
+
+  updateWhitelist: (whitelist: WhitelistSettings): Promise<WhitelistSettings> =>
+    invoke<WhitelistSettings>('whitelist_update', { whitelist }),
+
   getRules: (): Promise<CleanRule[]> =>
     invoke<CleanRule[]>('rule_list'),
 
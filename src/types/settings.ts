@@ -23,6 +23,32 @@ export interface CleanRule {
   enabled: boolean;
 }
 
+export interface WhitelistPath {
+  path: string;
+  description: string;
+  enabled: boolean;
+  added_time: number;
+}
+
+export interface WhitelistExtension {
+  extension: string;
+  description: string;
+  enabled: boolean;
+}
+
+export interface WhitelistPattern {
+  pattern: string;
+  description: string;
+  enabled: boolean;
+}
+
+export interface WhitelistSettings {
+  enabled: boolean;
+  paths: WhitelistPath[];
+  extensions: WhitelistExtension[];
+  patterns: WhitelistPattern[];
+}
+
 export interface SettingsUpdate {
   auto_scan?: boolean;
   scan_on_startup?: boolean;